@@ -1,8 +1,12 @@
 use crate::logging;
 use crate::managers::{create_manager, MANAGER_NAMES};
 use crate::{AppState, TaskStore};
+use boxy_brew::{BrewManager, BrewScope, BrewTap};
 use boxy_cache::Cache;
-use boxy_core::{Job, JobStatus, ManagerStatus, Operation, Package};
+use boxy_core::{
+  search_all, snapshot_installed, DiskUsage, Job, JobStatus, ManagerStatus, Operation, Package,
+  PackageDiff,
+};
 use tauri_plugin_opener::OpenerExt;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -61,12 +65,20 @@ pub async fn scan_managers(state: State<'_, AppState>) -> Result<Vec<ManagerStat
             .unwrap_or(None)
             .unwrap_or_default();
           let outdated_count = cached_packages.iter().filter(|pkg| pkg.outdated).count();
+          let sizes: Vec<u64> = cached_packages.iter().filter_map(|pkg| pkg.size).collect();
+          let installed_size = (!sizes.is_empty()).then(|| sizes.iter().sum());
+          let cache_size = DiskUsage::new(cache.clone())
+            .cached_cache_usage(&manager_name)
+            .await
+            .and_then(|usage| usage.reclaimable);
           ManagerStatus {
             name: manager_name,
             version: String::new(),
             available,
             package_count: cached_packages.len(),
             outdated_count,
+            installed_size,
+            cache_size,
           }
         } else {
           ManagerStatus {
@@ -75,6 +87,8 @@ pub async fn scan_managers(state: State<'_, AppState>) -> Result<Vec<ManagerStat
             available: false,
             package_count: 0,
             outdated_count: 0,
+            installed_size: None,
+            cache_size: None,
           }
         }
       })
@@ -160,35 +174,19 @@ pub async fn search_packages(
     MANAGER_NAMES.iter().map(|name| name.to_string()).collect()
   };
 
-  let cache = state.cache.clone();
-  let tasks: Vec<_> = manager_list
+  // 与 CLI、TUI 共用合并搜索：同一工具的各生态结果相邻，整体按相关度排序
+  let managers = manager_list
     .iter()
-    .map(|name| {
-      let cache = cache.clone();
-      let manager_name = name.to_string();
-      let query = query.clone();
-      tokio::spawn(async move {
-        let manager = create_manager(&manager_name, cache.clone(), true, None);
-        if let Some(mgr) = manager {
-          if !mgr.check_available().await.unwrap_or(false) {
-            return Vec::new();
-          }
-          mgr.search(&query).await.unwrap_or_default()
-        } else {
-          Vec::new()
-        }
-      })
-    })
+    .filter_map(|name| create_manager(name, state.cache.clone(), true, None))
     .collect();
-
-  let mut results = Vec::new();
-  for task in tasks {
-    if let Ok(mut packages) = task.await {
-      results.append(&mut packages);
-    }
-  }
-
-  Ok(results)
+  let outcome = search_all(managers, &query, Duration::from_secs(30)).await;
+  Ok(
+    outcome
+      .results
+      .into_iter()
+      .flat_map(|result| result.packages)
+      .collect(),
+  )
 }
 
 #[tauri::command]
@@ -209,6 +207,50 @@ pub async fn get_package_info(
     .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn list_brew_taps(state: State<'_, AppState>) -> Result<Vec<BrewTap>, String> {
+  BrewManager::new(state.cache.clone(), BrewScope::All)
+    .taps()
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_brew_tap(
+  name: String,
+  url: Option<String>,
+  state: State<'_, AppState>,
+) -> Result<(), String> {
+  let cache = state.cache.clone();
+  state
+    .executor
+    .execute("brew", || async {
+      BrewManager::new(cache.clone(), BrewScope::All)
+        .tap(&name, url.as_deref())
+        .await
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_brew_tap(
+  name: String,
+  force: Option<bool>,
+  state: State<'_, AppState>,
+) -> Result<(), String> {
+  let cache = state.cache.clone();
+  state
+    .executor
+    .execute("brew", || async {
+      BrewManager::new(cache.clone(), BrewScope::All)
+        .untap(&name, force.unwrap_or(false))
+        .await
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn install_package(
   manager: String,
@@ -453,6 +495,30 @@ async fn fetch_packages(
   Ok((packages, outdated_map))
 }
 
+/// 与操作前的快照对比，返回写入任务日志的差异行（含顺带安装、移除的依赖）
+async fn change_logs(
+  cache: &Arc<Cache>,
+  manager: &str,
+  global: bool,
+  workdir: Option<PathBuf>,
+  before: Option<Vec<Package>>,
+) -> Vec<String> {
+  let Some(before) = before else {
+    return Vec::new();
+  };
+  let Some(mgr) = create_manager(manager, cache.clone(), global, workdir) else {
+    return Vec::new();
+  };
+  let Ok(after) = snapshot_installed(mgr.as_ref(), cache).await else {
+    return Vec::new();
+  };
+  let lines = PackageDiff::between(manager, &before, &after).lines();
+  if lines.is_empty() {
+    return lines;
+  }
+  std::iter::once("变更:".to_string()).chain(lines).collect()
+}
+
 async fn spawn_task(
   app: AppHandle,
   state: &AppState,
@@ -501,6 +567,11 @@ async fn spawn_task(
       .as_ref()
       .map(|mgr| mgr.cache_key().to_string())
       .unwrap_or_else(|| manager.clone());
+    // 记录操作前的已安装包，完成后写入差异
+    let before = match manager_impl {
+      Some(ref mgr) => snapshot_installed(mgr.as_ref(), &cache).await.ok(),
+      None => None,
+    };
     let operation_task = executor.execute(&manager, || async {
       let manager_impl = create_manager(&manager, cache.clone(), global, workdir.clone());
       if let Some(mgr) = manager_impl {
@@ -510,8 +581,10 @@ async fn spawn_task(
           Operation::Uninstall => {
             // 执行卸载
             mgr.uninstall(&package, force).await?;
-            // 自动清理缓存（忽略错误，不中断卸载）
-            let _ = mgr.clean_cache().await;
+            // 自动清理缓存（忽略错误，不中断卸载）；共享缓存只在显式清理时删除
+            if mgr.clean_cache_on_uninstall() {
+              let _ = mgr.clean_cache().await;
+            }
             Ok(())
           },
         }
@@ -550,6 +623,7 @@ async fn spawn_task(
       Ok(()) => (JobStatus::Succeeded, None),
       Err(err) => (JobStatus::Failed, Some(err.to_string())),
     };
+    let changes = change_logs(&cache, &manager, global, workdir.clone(), before).await;
 
     let mut store = tasks.lock().await;
     if let Some(job) = store.tasks.iter_mut().find(|job| job.id == task_id_for_worker) {
@@ -571,6 +645,7 @@ async fn spawn_task(
       } else {
         logs.push("Completed".to_string());
       }
+      logs.extend(changes);
     }
 
     store.handles.remove(&task_id_for_worker);
@@ -717,6 +792,10 @@ async fn spawn_batch_update(
       return;
     }
 
+    let before = match manager_impl {
+      Some(ref mgr) => snapshot_installed(mgr.as_ref(), &cache).await.ok(),
+      None => None,
+    };
     let total = outdated.len().max(1) as f64;
     for (index, pkg) in outdated.iter().enumerate() {
       let result = executor
@@ -749,7 +828,13 @@ async fn spawn_batch_update(
       }));
 
       if result.is_err() {
+        drop(store);
+        // 失败前已更新的包同样写入差异
+        let changes = change_logs(&cache, &manager, global, workdir.clone(), before).await;
         let mut store = tasks.lock().await;
+        if let Some(logs) = store.logs.get_mut(&task_id_for_worker) {
+          logs.extend(changes);
+        }
         if let Some(job) = store.tasks.iter_mut().find(|job| job.id == task_id_for_worker) {
           job.status = JobStatus::Failed;
           job.finished_at = Some(Utc::now());
@@ -772,6 +857,7 @@ async fn spawn_batch_update(
       }
     }
 
+    let changes = change_logs(&cache, &manager, global, workdir.clone(), before).await;
     let mut store = tasks.lock().await;
     if let Some(job) = store.tasks.iter_mut().find(|job| job.id == task_id_for_worker) {
       job.status = JobStatus::Succeeded;
@@ -779,6 +865,9 @@ async fn spawn_batch_update(
       job.progress = Some(100.0);
       job.step = Some("completed".to_string());
     }
+    if let Some(logs) = store.logs.get_mut(&task_id_for_worker) {
+      logs.extend(changes);
+    }
     store.handles.remove(&task_id_for_worker);
     drop(store);
 
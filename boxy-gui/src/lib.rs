@@ -57,6 +57,9 @@ pub fn build() -> tauri::Builder<Wry> {
       commands::uninstall_package,
       commands::search_packages,
       commands::get_package_info,
+      commands::list_brew_taps,
+      commands::add_brew_tap,
+      commands::remove_brew_tap,
       commands::get_tasks,
       commands::get_task_logs,
       commands::cancel_task,
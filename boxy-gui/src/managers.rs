@@ -1,8 +1,9 @@
-use boxy_brew::BrewManager;
+use boxy_brew::{BrewManager, BrewScope};
 use boxy_bun::BunManager;
 use boxy_cache::Cache;
 use boxy_cargo::CargoManager;
 use boxy_core::manager::PackageManager;
+use boxy_core::python::PythonScope;
 use boxy_mas::MasManager;
 use boxy_npm::{NpmManager, NpmScope};
 use boxy_pip::PipManager;
@@ -16,6 +17,14 @@ pub const MANAGER_NAMES: [&str; 10] = [
   "brew", "npm", "pnpm", "yarn", "bun", "pip", "pipx", "uv", "cargo", "mas",
 ];
 
+/// 本地范围的目录中存在虚拟环境时，pip、uv 使用该环境
+fn python_scope(workdir: Option<&PathBuf>) -> PythonScope {
+  match workdir {
+    Some(dir) => PythonScope::detect(dir),
+    None => PythonScope::Default,
+  }
+}
+
 pub fn create_manager(
   name: &str,
   cache: Arc<Cache>,
@@ -23,7 +32,7 @@ pub fn create_manager(
   workdir: Option<PathBuf>,
 ) -> Option<Box<dyn PackageManager>> {
   match name {
-    "brew" => Some(Box::new(BrewManager::new(cache))),
+    "brew" => Some(Box::new(BrewManager::new(cache, BrewScope::All))),
     "npm" => Some(Box::new(NpmManager::new(
       cache,
       if global { NpmScope::Global } else { NpmScope::Local },
@@ -32,10 +41,14 @@ pub fn create_manager(
     "pnpm" => Some(Box::new(PnpmManager::new(cache, global, workdir))),
     "yarn" => Some(Box::new(YarnManager::new(cache, global, workdir))),
     "bun" => Some(Box::new(BunManager::new(cache, global, workdir))),
-    "pip" => Some(Box::new(PipManager::new(cache, false))),
+    "pip" => Some(Box::new(PipManager::new(cache, python_scope(workdir.as_ref())))),
     "pipx" => Some(Box::new(PipxManager::new(cache))),
-    "uv" => Some(Box::new(UvManager::new(cache, false))),
-    "cargo" => Some(Box::new(CargoManager::new(cache, false))),
+    "uv" => Some(Box::new(UvManager::new(
+      cache,
+      python_scope(workdir.as_ref()),
+      workdir,
+    ))),
+    "cargo" => Some(Box::new(CargoManager::new(cache, workdir))),
     "mas" => Some(Box::new(MasManager::new(cache))),
     _ => None,
   }
@@ -0,0 +1,360 @@
+use crate::osv::Vulnerability;
+use boxy_core::disk::normalize_dist_name;
+use boxy_error::{BoxyError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
+
+/// 支持离线审计的 OSV 生态
+pub const ECOSYSTEMS: [&str; 3] = ["npm", "PyPI", "crates.io"];
+
+const OSV_BASE_URL: &str = "https://osv-vulnerabilities.storage.googleapis.com";
+const INDEX_SUFFIX: &str = ".boxy-index.json";
+const INDEX_VERSION: u32 = 1;
+/// 数据包较大（npm 约数百 MB），只限制连接超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// boxy 数据目录下的默认 OSV 数据库位置
+pub fn default_db_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("boxy").join("osv"))
+}
+
+/// 包管理器对应的 OSV 生态，brew、mas 没有对应的 OSV 数据
+pub fn ecosystem_for(manager: &str) -> Option<&'static str> {
+    match manager {
+        "npm" | "pnpm" | "yarn" | "bun" => Some("npm"),
+        "pip" | "pipx" | "uv" => Some("PyPI"),
+        "cargo" => Some("crates.io"),
+        _ => None,
+    }
+}
+
+/// 用于索引的包键，PyPI 包名按 PEP 503 规范化，其余生态忽略大小写
+pub fn package_key(ecosystem: &str, name: &str) -> String {
+    let name = if ecosystem == "PyPI" {
+        normalize_dist_name(name)
+    } else {
+        name.to_lowercase()
+    };
+    format!("{}:{}", ecosystem, name)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    version: u32,
+    source_mtime: i64,
+    entries: HashMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct IndexRecord {
+    #[serde(default)]
+    affected: Vec<IndexAffected>,
+}
+
+#[derive(Deserialize)]
+struct IndexAffected {
+    package: Option<IndexPackage>,
+}
+
+#[derive(Deserialize)]
+struct IndexPackage {
+    ecosystem: String,
+    name: String,
+}
+
+/// 本地 OSV 数据库
+///
+/// 目录中每个 JSON 文件是一条 OSV 记录（官方 `all.zip` 解压后的格式），
+/// 首次打开时建立「包 → 记录文件」索引，之后的查询只读取相关文件。
+pub struct OsvDatabase {
+    root: PathBuf,
+    index: Index,
+}
+
+impl OsvDatabase {
+    pub fn open(root: &Path) -> Result<Self> {
+        if !root.is_dir() {
+            return Err(BoxyError::CacheError {
+                message: format!("OSV 数据库目录不存在: {}", root.display()),
+            });
+        }
+
+        let source_mtime = source_mtime(root);
+        let index_path = index_path(root);
+        let cached = index_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<Index>(&content).ok())
+            .filter(|index| index.version == INDEX_VERSION && index.source_mtime == source_mtime);
+        let index = match cached {
+            Some(index) => index,
+            None => {
+                info!("建立 OSV 索引: {}", root.display());
+                let index = build_index(root, source_mtime);
+                // 只读目录无法写入索引时，每次打开都在内存中重建
+                if let Some(path) = &index_path {
+                    match serde_json::to_string(&index) {
+                        Ok(content) => {
+                            if let Err(err) = fs::write(path, content) {
+                                warn!("写入 OSV 索引失败: {}", err);
+                            }
+                        }
+                        Err(err) => warn!("序列化 OSV 索引失败: {}", err),
+                    }
+                }
+                index
+            }
+        };
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            index,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.entries.is_empty()
+    }
+
+    /// 查询指定包的全部漏洞记录
+    pub fn lookup(&self, ecosystem: &str, name: &str) -> Vec<Vulnerability> {
+        let Some(files) = self.index.entries.get(&package_key(ecosystem, name)) else {
+            return Vec::new();
+        };
+        files
+            .iter()
+            .filter_map(|file| {
+                let content = fs::read_to_string(self.root.join(file)).ok()?;
+                match serde_json::from_str::<Vulnerability>(&content) {
+                    Ok(record) => Some(record),
+                    Err(err) => {
+                        debug!("解析 OSV 记录 {} 失败: {}", file, err);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// 索引文件放在数据库目录旁（`<目录名>.boxy-index.json`），
+/// 写入索引不会改变数据库目录自身的修改时间
+fn index_path(root: &Path) -> Option<PathBuf> {
+    let name = root.file_name()?.to_string_lossy();
+    Some(root.with_file_name(format!("{}{}", name, INDEX_SUFFIX)))
+}
+
+/// 下载指定生态的 OSV 数据（`<生态>/all.zip`）并解压到数据库目录
+pub async fn download(root: &Path, ecosystems: &[&str]) -> Result<()> {
+    fs::create_dir_all(root)?;
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .user_agent(concat!("boxy/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default();
+    for ecosystem in ecosystems {
+        let url = format!("{}/{}/all.zip", OSV_BASE_URL, ecosystem);
+        let archive = root.join(format!(".{}.zip", ecosystem));
+        info!("下载 OSV 数据: {}", url);
+        if let Err(err) = fetch_to_file(&client, &url, &archive).await {
+            let _ = fs::remove_file(&archive);
+            return Err(err);
+        }
+
+        // 先解压到临时目录，成功后再替换旧数据
+        let target = root.join(ecosystem);
+        let staging = root.join(format!(".{}.tmp", ecosystem));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        let extracted = {
+            let archive = archive.clone();
+            let staging = staging.clone();
+            tokio::task::spawn_blocking(move || extract_zip(&archive, &staging))
+                .await
+                .map_err(|err| BoxyError::CacheError {
+                    message: format!("解压任务执行失败: {}", err),
+                })?
+        };
+        let _ = fs::remove_file(&archive);
+        if let Err(err) = extracted {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+        fs::rename(&staging, &target)?;
+    }
+
+    // 数据变化后重建索引
+    if let Some(path) = index_path(root) {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+/// 以流式写入的方式下载到文件，避免把整个数据包读入内存
+async fn fetch_to_file(client: &reqwest::Client, url: &str, path: &Path) -> Result<()> {
+    let network_error = |err: reqwest::Error| BoxyError::NetworkError {
+        message: format!("下载 {} 失败: {}", url, err),
+    };
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(network_error)?;
+    let mut file = tokio::fs::File::create(path).await?;
+    while let Some(chunk) = response.chunk().await.map_err(network_error)? {
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+    Ok(())
+}
+
+fn extract_zip(archive: &Path, target: &Path) -> Result<()> {
+    let zip_error = |err: zip::result::ZipError| BoxyError::CacheError {
+        message: format!("解压 {} 失败: {}", archive.display(), err),
+    };
+    let file = fs::File::open(archive)?;
+    zip::ZipArchive::new(file)
+        .and_then(|mut zip| zip.extract(target))
+        .map_err(zip_error)
+}
+
+/// 数据库目录及其直接子目录的最新修改时间，用于判断索引是否过期
+fn source_mtime(root: &Path) -> i64 {
+    let mut dirs = vec![root.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(root) {
+        dirs.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir()),
+        );
+    }
+    dirs.iter()
+        .filter_map(|dir| fs::metadata(dir).ok()?.modified().ok())
+        .filter_map(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as i64)
+        .max()
+        .unwrap_or_default()
+}
+
+fn build_index(root: &Path, source_mtime: i64) -> Index {
+    let mut files = Vec::new();
+    collect_json_files(root, &mut files);
+
+    // 记录文件数量较多（npm 超过十万条），按 CPU 数量分片并行解析
+    let workers = std::thread::available_parallelism()
+        .map(|value| value.get())
+        .unwrap_or(4);
+    let chunk_size = files.len().div_ceil(workers).max(1);
+    let partials: Vec<HashMap<String, Vec<String>>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || index_chunk(root, chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect()
+    });
+
+    let mut entries: HashMap<String, Vec<String>> = HashMap::new();
+    for partial in partials {
+        for (key, mut paths) in partial {
+            entries.entry(key).or_default().append(&mut paths);
+        }
+    }
+    Index {
+        version: INDEX_VERSION,
+        source_mtime,
+        entries,
+    }
+}
+
+fn index_chunk(root: &Path, files: &[PathBuf]) -> HashMap<String, Vec<String>> {
+    let mut entries: HashMap<String, Vec<String>> = HashMap::new();
+    for path in files {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let Ok(record) = serde_json::from_str::<IndexRecord>(&content) else {
+            continue;
+        };
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().to_string();
+        let mut keys: Vec<String> = record
+            .affected
+            .into_iter()
+            .filter_map(|affected| affected.package)
+            .map(|package| package_key(&package.ecosystem, &package.name))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            entries.entry(key).or_default().push(relative.clone());
+        }
+    }
+    entries
+}
+
+fn collect_json_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_json_files(&path, files);
+        } else if name.ends_with(".json") {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_open_and_lookup() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("osv");
+        let pypi = root.join("PyPI");
+        fs::create_dir_all(&pypi).unwrap();
+        let record = serde_json::json!({
+            "id": "PYSEC-2023-1",
+            "affected": [{
+                "package": { "ecosystem": "PyPI", "name": "Requests" },
+                "ranges": [{ "type": "ECOSYSTEM", "events": [{ "introduced": "0" }, { "fixed": "2.31.0" }] }]
+            }]
+        });
+        fs::write(pypi.join("PYSEC-2023-1.json"), record.to_string()).unwrap();
+
+        let database = OsvDatabase::open(&root).unwrap();
+        // 索引写在数据库目录旁，写入后仍与数据目录的修改时间一致
+        let index_file = temp_dir.path().join("osv.boxy-index.json");
+        let index: Index = serde_json::from_str(&fs::read_to_string(index_file).unwrap()).unwrap();
+        assert_eq!(index.source_mtime, source_mtime(&root));
+        let records = database.lookup("PyPI", "requests");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, "PYSEC-2023-1");
+        assert!(database.lookup("npm", "requests").is_empty());
+    }
+}
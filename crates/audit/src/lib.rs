@@ -0,0 +1,119 @@
+pub mod database;
+pub mod osv;
+pub mod sarif;
+pub mod sbom;
+
+pub use database::{default_db_dir, download, ecosystem_for, OsvDatabase, ECOSYSTEMS};
+pub use osv::{Severity, Vulnerability};
+pub use sbom::SbomEntry;
+
+use boxy_core::Package;
+use serde::{Deserialize, Serialize};
+
+/// 一条审计结果：某个已安装包命中某个漏洞
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub manager: String,
+    pub package: String,
+    pub version: String,
+    pub ecosystem: String,
+    pub id: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    pub severity: Severity,
+    pub fixed_versions: Vec<String>,
+    /// 升级到 `check_outdated` 给出的最新版本即可修复
+    pub upgrade_fixes: bool,
+    pub latest_version: Option<String>,
+    pub installed_path: Option<String>,
+    pub url: String,
+}
+
+/// 将已安装包与 OSV 数据库比对
+///
+/// `packages` 中的 `outdated` / `latest_version` 应已由 `check_outdated` 填充，
+/// 用于判断升级到最新版本能否修复漏洞。
+pub fn audit_packages(database: &OsvDatabase, packages: &[Package]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for pkg in packages {
+        let Some(ecosystem) = ecosystem_for(&pkg.manager) else {
+            continue;
+        };
+        if pkg.version.is_empty() {
+            continue;
+        }
+        for vulnerability in database.lookup(ecosystem, &pkg.name) {
+            let Some(affected) = vulnerability.affected_entry(ecosystem, &pkg.name, &pkg.version)
+            else {
+                continue;
+            };
+            let latest_version = pkg.latest_version.clone().filter(|_| pkg.outdated);
+            let upgrade_fixes = latest_version
+                .as_deref()
+                .is_some_and(|latest| !affected.is_affected(latest));
+            findings.push(Finding {
+                manager: pkg.manager.clone(),
+                package: pkg.name.clone(),
+                version: pkg.version.clone(),
+                ecosystem: ecosystem.to_string(),
+                id: vulnerability.id.clone(),
+                aliases: vulnerability.aliases.clone(),
+                summary: vulnerability.summary.clone(),
+                severity: vulnerability.severity_for(affected),
+                fixed_versions: affected.fixed_versions(),
+                upgrade_fixes,
+                latest_version,
+                installed_path: pkg.installed_path.clone(),
+                url: vulnerability.advisory_url(),
+            });
+        }
+    }
+    findings.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.package.cmp(&b.package))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_audit_packages() {
+        let temp_dir = tempdir().unwrap();
+        let record = serde_json::json!({
+            "id": "GHSA-35jh-r3h4-6jhm",
+            "summary": "Command Injection in lodash",
+            "affected": [{
+                "package": { "ecosystem": "npm", "name": "lodash" },
+                "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "4.17.21" }] }],
+                "database_specific": { "severity": "HIGH" }
+            }]
+        });
+        fs::write(temp_dir.path().join("GHSA-35jh-r3h4-6jhm.json"), record.to_string()).unwrap();
+        let database = OsvDatabase::open(temp_dir.path()).unwrap();
+
+        let packages = vec![
+            Package {
+                outdated: true,
+                latest_version: Some("4.17.21".to_string()),
+                ..Package::new("npm", "lodash", "4.17.20")
+            },
+            Package::new("npm", "left-pad", "1.3.0"),
+        ];
+        let findings = audit_packages(&database, &packages);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].fixed_versions, vec!["4.17.21".to_string()]);
+        assert!(findings[0].upgrade_fixes);
+
+        let findings = audit_packages(&database, &[Package::new("npm", "lodash", "4.17.21")]);
+        assert!(findings.is_empty());
+    }
+}
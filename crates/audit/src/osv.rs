@@ -0,0 +1,405 @@
+use boxy_core::compare_versions;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// OSV 格式的漏洞记录（https://ossf.github.io/osv-schema/）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vulnerability {
+    pub id: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub details: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub withdrawn: Option<String>,
+    #[serde(default)]
+    pub severity: Vec<SeverityScore>,
+    #[serde(default)]
+    pub affected: Vec<Affected>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+    #[serde(default)]
+    pub database_specific: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeverityScore {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub score: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Affected {
+    #[serde(default)]
+    pub package: Option<AffectedPackage>,
+    #[serde(default)]
+    pub ranges: Vec<Range>,
+    #[serde(default)]
+    pub versions: Vec<String>,
+    #[serde(default)]
+    pub severity: Vec<SeverityScore>,
+    #[serde(default)]
+    pub database_specific: Option<Value>,
+    #[serde(default)]
+    pub ecosystem_specific: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AffectedPackage {
+    pub ecosystem: String,
+    pub name: String,
+    #[serde(default)]
+    pub purl: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Range {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    #[serde(default)]
+    pub introduced: Option<String>,
+    #[serde(default)]
+    pub fixed: Option<String>,
+    #[serde(default)]
+    pub last_affected: Option<String>,
+    #[serde(default)]
+    pub limit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reference {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub url: String,
+}
+
+/// 漏洞严重程度，按从低到高排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// 按 CVSS 分数划分等级
+    pub fn from_score(score: f64) -> Self {
+        if score >= 9.0 {
+            Severity::Critical
+        } else if score >= 7.0 {
+            Severity::High
+        } else if score >= 4.0 {
+            Severity::Medium
+        } else if score > 0.0 {
+            Severity::Low
+        } else {
+            Severity::Unknown
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Unknown => "unknown",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "low" => Ok(Severity::Low),
+            "medium" | "moderate" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            "unknown" => Ok(Severity::Unknown),
+            other => Err(format!("未知的严重程度: {}", other)),
+        }
+    }
+}
+
+impl Vulnerability {
+    /// 找到匹配指定包与版本的 affected 条目
+    pub fn affected_entry(&self, ecosystem: &str, name: &str, version: &str) -> Option<&Affected> {
+        if self.withdrawn.is_some() {
+            return None;
+        }
+        let key = crate::database::package_key(ecosystem, name);
+        self.affected.iter().find(|affected| {
+            affected.package.as_ref().is_some_and(|package| {
+                crate::database::package_key(&package.ecosystem, &package.name) == key
+            }) && affected.is_affected(version)
+        })
+    }
+
+    /// 漏洞的严重程度
+    ///
+    /// 优先使用数据库给出的等级（GHSA 的 database_specific.severity），
+    /// 其次根据 CVSS v3 向量计算。
+    pub fn severity_for(&self, affected: &Affected) -> Severity {
+        let labeled = [
+            affected.database_specific.as_ref(),
+            affected.ecosystem_specific.as_ref(),
+            self.database_specific.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|value| value.get("severity").and_then(|value| value.as_str()))
+        .find_map(|value| value.parse::<Severity>().ok());
+        if let Some(severity) = labeled {
+            return severity;
+        }
+
+        affected
+            .severity
+            .iter()
+            .chain(self.severity.iter())
+            .filter_map(|score| score_value(&score.score))
+            .map(Severity::from_score)
+            .max()
+            .unwrap_or(Severity::Unknown)
+    }
+
+    pub fn advisory_url(&self) -> String {
+        self.references
+            .iter()
+            .find(|reference| reference.kind == "ADVISORY")
+            .map(|reference| reference.url.clone())
+            .unwrap_or_else(|| format!("https://osv.dev/vulnerability/{}", self.id))
+    }
+}
+
+impl Affected {
+    /// 判断版本是否落在受影响范围内
+    pub fn is_affected(&self, version: &str) -> bool {
+        if self
+            .versions
+            .iter()
+            .any(|value| compare_versions(value, version) == Ordering::Equal)
+        {
+            return true;
+        }
+        self.ranges
+            .iter()
+            .filter(|range| range.kind != "GIT")
+            .any(|range| range.contains(version))
+    }
+
+    /// 修复该漏洞的版本
+    pub fn fixed_versions(&self) -> Vec<String> {
+        let mut fixed: Vec<String> = self
+            .ranges
+            .iter()
+            .flat_map(|range| range.events.iter())
+            .filter_map(|event| event.fixed.clone())
+            .collect();
+        fixed.sort_by(|a, b| compare_versions(a, b));
+        fixed.dedup();
+        fixed
+    }
+}
+
+impl Range {
+    /// 按 OSV 规范依次应用事件：introduced 开始受影响，fixed / last_affected 之后结束
+    pub fn contains(&self, version: &str) -> bool {
+        let mut events: Vec<(&str, &Event)> = self
+            .events
+            .iter()
+            .filter_map(|event| event_version(event).map(|value| (value, event)))
+            .collect();
+        events.sort_by(|(a, _), (b, _)| compare_event_versions(a, b));
+
+        let mut affected = false;
+        for (value, event) in events {
+            let ordering = compare_event_versions(value, version);
+            if event.introduced.is_some() {
+                if ordering != Ordering::Greater {
+                    affected = true;
+                }
+            } else if event.fixed.is_some() || event.limit.is_some() {
+                if ordering != Ordering::Greater {
+                    affected = false;
+                }
+            } else if event.last_affected.is_some() && ordering == Ordering::Less {
+                affected = false;
+            }
+        }
+        affected
+    }
+}
+
+fn event_version(event: &Event) -> Option<&str> {
+    event
+        .introduced
+        .as_deref()
+        .or(event.fixed.as_deref())
+        .or(event.last_affected.as_deref())
+        .or(event.limit.as_deref())
+}
+
+/// introduced 中的 "0" 表示所有版本
+fn compare_event_versions(left: &str, right: &str) -> Ordering {
+    match (left, right) {
+        ("0", "0") => Ordering::Equal,
+        ("0", _) => Ordering::Less,
+        (_, "0") => Ordering::Greater,
+        _ => compare_versions(left, right),
+    }
+}
+
+/// 解析严重程度分数：数字或 CVSS v3 向量
+fn score_value(score: &str) -> Option<f64> {
+    if let Ok(value) = score.parse::<f64>() {
+        return Some(value);
+    }
+    cvss3_base_score(score)
+}
+
+/// 根据 CVSS v3.x 向量计算基础分
+pub fn cvss3_base_score(vector: &str) -> Option<f64> {
+    if !vector.starts_with("CVSS:3") {
+        return None;
+    }
+    let metric = |name: &str| {
+        vector
+            .split('/')
+            .filter_map(|part| part.split_once(':'))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    };
+    let scope_changed = metric("S")? == "C";
+    let attack_vector = match metric("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let attack_complexity = match metric("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let privileges = match (metric("PR")?, scope_changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let interaction = match metric("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact_value = |name: &str| match metric(name) {
+        Some("H") => Some(0.56),
+        Some("L") => Some(0.22),
+        Some("N") => Some(0.0),
+        _ => None,
+    };
+    let iss = 1.0
+        - (1.0 - impact_value("C")?) * (1.0 - impact_value("I")?) * (1.0 - impact_value("A")?);
+    let impact = if scope_changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * attack_vector * attack_complexity * privileges * interaction;
+    let base = if scope_changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+    Some(round_up(base))
+}
+
+/// CVSS 规范中的向上取整到一位小数
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        ((scaled / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_range(events: Value) -> Range {
+        serde_json::from_value(serde_json::json!({ "type": "ECOSYSTEM", "events": events }))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_range_contains() {
+        let range = make_range(serde_json::json!([{ "introduced": "0" }, { "fixed": "4.17.21" }]));
+        assert!(range.contains("4.17.20"));
+        assert!(!range.contains("4.17.21"));
+        assert!(!range.contains("5.0.0"));
+
+        let range = make_range(serde_json::json!([
+            { "introduced": "2.0.0" },
+            { "last_affected": "2.3.1" }
+        ]));
+        assert!(!range.contains("1.9.0"));
+        assert!(range.contains("2.3.1"));
+        assert!(!range.contains("2.3.2"));
+    }
+
+    #[test]
+    fn test_cvss3_base_score() {
+        let critical = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H";
+        assert_eq!(cvss3_base_score(critical), Some(9.8));
+        let medium = "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N";
+        assert_eq!(cvss3_base_score(medium), Some(6.1));
+        assert_eq!(cvss3_base_score("CVSS:4.0/AV:N"), None);
+    }
+
+    #[test]
+    fn test_severity_prefers_database_label() {
+        let vulnerability: Vulnerability = serde_json::from_value(serde_json::json!({
+            "id": "GHSA-xxxx",
+            "severity": [{ "type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H" }],
+            "affected": [{ "package": { "ecosystem": "npm", "name": "lodash" } }],
+            "database_specific": { "severity": "MODERATE" }
+        }))
+        .unwrap();
+        assert_eq!(
+            vulnerability.severity_for(&vulnerability.affected[0]),
+            Severity::Medium
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use crate::osv::Severity;
+use crate::Finding;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// 将审计结果转换为 SARIF 2.1.0，供 CI 的代码扫描面板展示
+pub fn to_sarif(findings: &[Finding]) -> Value {
+    // 每个漏洞 ID 对应一条规则
+    let mut rules: BTreeMap<&str, &Finding> = BTreeMap::new();
+    for finding in findings {
+        rules.entry(finding.id.as_str()).or_insert(finding);
+    }
+    let rules: Vec<Value> = rules
+        .values()
+        .map(|finding| {
+            json!({
+              "id": finding.id,
+              "shortDescription": { "text": finding.summary.clone().unwrap_or_else(|| finding.id.clone()) },
+              "helpUri": finding.url,
+              "properties": {
+                "security-severity": security_severity(finding.severity),
+                "tags": ["security", "vulnerability", finding.ecosystem],
+              },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let fixed = if finding.fixed_versions.is_empty() {
+                "暂无修复版本".to_string()
+            } else {
+                format!("修复版本: {}", finding.fixed_versions.join(", "))
+            };
+            let mut result = json!({
+              "ruleId": finding.id,
+              "level": level(finding.severity),
+              "message": {
+                "text": format!(
+                    "{} {}@{} 存在漏洞 {}（{}）",
+                    finding.manager, finding.package, finding.version, finding.id, fixed
+                ),
+              },
+              "logicalLocations": [{
+                "name": finding.package,
+                "fullyQualifiedName": format!("{}:{}@{}", finding.manager, finding.package, finding.version),
+                "kind": "package",
+              }],
+              "properties": {
+                "severity": finding.severity,
+                "fixedVersions": finding.fixed_versions,
+                "upgradeFixes": finding.upgrade_fixes,
+              },
+            });
+            if let Some(path) = &finding.installed_path {
+                result["locations"] = json!([{
+                  "physicalLocation": { "artifactLocation": { "uri": path } },
+                }]);
+            }
+            result
+        })
+        .collect();
+
+    json!({
+      "$schema": SARIF_SCHEMA,
+      "version": "2.1.0",
+      "runs": [{
+        "tool": {
+          "driver": {
+            "name": "boxy-audit",
+            "version": env!("CARGO_PKG_VERSION"),
+            "informationUri": "https://osv.dev",
+            "rules": rules,
+          },
+        },
+        "results": results,
+      }],
+    })
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Unknown => "note",
+    }
+}
+
+/// GitHub 代码扫描使用的数值等级
+fn security_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "9.5",
+        Severity::High => "7.5",
+        Severity::Medium => "5.0",
+        Severity::Low => "2.0",
+        Severity::Unknown => "0.0",
+    }
+}
@@ -0,0 +1,400 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use boxy_core::license::is_spdx_like;
+use boxy_core::Package;
+use chrono::{SecondsFormat, Utc};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const CYCLONEDX_SPEC_VERSION: &str = "1.5";
+const SPDX_VERSION: &str = "SPDX-2.3";
+const NOASSERTION: &str = "NOASSERTION";
+
+/// SBOM 中的一个组件：已安装包以及安装它的管理器范围
+#[derive(Debug, Clone)]
+pub struct SbomEntry {
+    pub package: Package,
+    /// `global`、`local`，pip/uv 的 `user`，或 brew 的 `formula`、`cask`
+    pub scope: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    fn cyclonedx_name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha1 => "SHA-1",
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Sha512 => "SHA-512",
+        }
+    }
+
+    fn spdx_name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha1 => "SHA1",
+            HashAlgorithm::Sha256 => "SHA256",
+            HashAlgorithm::Sha512 => "SHA512",
+        }
+    }
+}
+
+/// 十六进制小写摘要
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub algorithm: HashAlgorithm,
+    pub value: String,
+}
+
+/// 收集已安装包在本地可获得的摘要
+///
+/// npm 系取自 `node_modules/.package-lock.json` 的 integrity，
+/// cargo 取 registry 缓存中 `.crate` 文件的 SHA-256（即 crates.io 发布的校验和）。
+#[derive(Default)]
+pub struct ChecksumResolver {
+    lockfiles: HashMap<PathBuf, Option<Value>>,
+}
+
+impl ChecksumResolver {
+    pub fn resolve(&mut self, pkg: &Package) -> Vec<Checksum> {
+        match pkg.manager.as_str() {
+            "npm" | "pnpm" | "yarn" | "bun" => self.npm_integrity(pkg).into_iter().collect(),
+            "cargo" => crate_checksum(&pkg.name, &pkg.version)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn npm_integrity(&mut self, pkg: &Package) -> Option<Checksum> {
+        let installed = pkg.installed_path.as_deref()?;
+        let root = installed.strip_suffix(pkg.name.as_str())?;
+        let root = PathBuf::from(root.trim_end_matches(['/', '\\']));
+        let lockfile = self.lockfiles.entry(root.clone()).or_insert_with(|| {
+            let content = fs::read_to_string(root.join(".package-lock.json")).ok()?;
+            serde_json::from_str(&content).ok()
+        });
+        let integrity = lockfile
+            .as_ref()?
+            .get("packages")?
+            .get(format!("node_modules/{}", pkg.name))?
+            .get("integrity")?
+            .as_str()?;
+        parse_integrity(integrity)
+    }
+}
+
+/// 解析 Subresource Integrity 字符串（`sha512-<base64>`），多个摘要时取最强的
+fn parse_integrity(integrity: &str) -> Option<Checksum> {
+    integrity
+        .split_whitespace()
+        .filter_map(|item| {
+            let (algorithm, digest) = item.split_once('-')?;
+            let algorithm = match algorithm {
+                "sha512" => HashAlgorithm::Sha512,
+                "sha256" => HashAlgorithm::Sha256,
+                "sha1" => HashAlgorithm::Sha1,
+                _ => return None,
+            };
+            let bytes = STANDARD.decode(digest).ok()?;
+            Some(Checksum {
+                algorithm,
+                value: to_hex(&bytes),
+            })
+        })
+        .max_by_key(|checksum| match checksum.algorithm {
+            HashAlgorithm::Sha1 => 1,
+            HashAlgorithm::Sha256 => 2,
+            HashAlgorithm::Sha512 => 3,
+        })
+}
+
+fn crate_checksum(name: &str, version: &str) -> Option<Checksum> {
+    let cargo_home = match env::var("CARGO_HOME") {
+        Ok(home) => PathBuf::from(home),
+        Err(_) => dirs::home_dir()?.join(".cargo"),
+    };
+    let file_name = format!("{}-{}.crate", name, version.trim_start_matches('v'));
+    let archive = fs::read_dir(cargo_home.join("registry").join("cache"))
+        .ok()?
+        .flatten()
+        .map(|index| index.path().join(&file_name))
+        .find(|path| path.is_file())?;
+    Some(Checksum {
+        algorithm: HashAlgorithm::Sha256,
+        value: sha256_file(&archive)?,
+    })
+}
+
+fn sha256_file(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    Some(to_hex(&Sha256::digest(bytes)))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 许可证是否为合法的 SPDX 表达式（由不含空格的标识组成）
+fn spdx_expression(license: Option<&str>) -> Option<String> {
+    let license = license.map(str::trim).filter(|value| !value.is_empty())?;
+    is_spdx_like(license).then(|| license.to_string())
+}
+
+/// 作为可执行程序安装的管理器，其余视为库
+fn is_application(manager: &str) -> bool {
+    matches!(manager, "pipx" | "cargo" | "mas" | "brew")
+}
+
+/// 生成 CycloneDX 1.5 JSON
+pub fn to_cyclonedx(entries: &[SbomEntry]) -> Value {
+    let mut resolver = ChecksumResolver::default();
+    let mut refs = HashSet::new();
+    let components: Vec<Value> = entries
+        .iter()
+        .filter_map(|entry| {
+            let pkg = &entry.package;
+            // 同一个包可能同时出现在多个管理器中，bom-ref 需要唯一
+            let bom_ref = format!("{}:{}@{}", pkg.manager, pkg.name, pkg.version);
+            if !refs.insert(bom_ref.clone()) {
+                return None;
+            }
+            let mut component = json!({
+              "type": if is_application(&pkg.manager) { "application" } else { "library" },
+              "bom-ref": bom_ref,
+              "name": pkg.name,
+              "purl": pkg.purl(),
+              "properties": [
+                { "name": "boxy:manager", "value": pkg.manager },
+                { "name": "boxy:scope", "value": entry.scope },
+              ],
+            });
+            if !pkg.version.is_empty() {
+                component["version"] = json!(pkg.version);
+            }
+            if let Some(description) = &pkg.description {
+                component["description"] = json!(description);
+            }
+            if let Some(license) = pkg.license.as_deref() {
+                component["licenses"] = match spdx_expression(Some(license)) {
+                    Some(expression) => json!([{ "expression": expression }]),
+                    None => json!([{ "license": { "name": license } }]),
+                };
+            }
+            let hashes: Vec<Value> = resolver
+                .resolve(pkg)
+                .into_iter()
+                .map(|checksum| {
+                    json!({ "alg": checksum.algorithm.cyclonedx_name(), "content": checksum.value })
+                })
+                .collect();
+            if !hashes.is_empty() {
+                component["hashes"] = json!(hashes);
+            }
+            if let Some(homepage) = &pkg.homepage {
+                component["externalReferences"] = json!([{ "type": "website", "url": homepage }]);
+            }
+            if let Some(path) = &pkg.installed_path {
+                component["properties"]
+                    .as_array_mut()
+                    .expect("properties 为数组")
+                    .push(json!({ "name": "boxy:installed_path", "value": path }));
+            }
+            Some(component)
+        })
+        .collect();
+
+    json!({
+      "bomFormat": "CycloneDX",
+      "specVersion": CYCLONEDX_SPEC_VERSION,
+      "serialNumber": format!("urn:uuid:{}", Uuid::new_v4()),
+      "version": 1,
+      "metadata": {
+        "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "tools": {
+          "components": [{
+            "type": "application",
+            "name": "boxy",
+            "version": env!("CARGO_PKG_VERSION"),
+          }],
+        },
+      },
+      "components": components,
+    })
+}
+
+/// 生成 SPDX 2.3 JSON
+pub fn to_spdx(entries: &[SbomEntry]) -> Value {
+    let mut resolver = ChecksumResolver::default();
+    let mut ids = HashSet::new();
+    let mut packages = Vec::new();
+    let mut relationships = Vec::new();
+    for entry in entries {
+        let pkg = &entry.package;
+        let base = format!(
+            "SPDXRef-Package-{}-{}-{}",
+            pkg.manager,
+            spdx_id_part(&pkg.name),
+            spdx_id_part(&pkg.version)
+        );
+        if !ids.insert(base.clone()) {
+            continue;
+        }
+
+        let license =
+            spdx_expression(pkg.license.as_deref()).unwrap_or_else(|| NOASSERTION.to_string());
+        let mut package = json!({
+          "name": pkg.name,
+          "SPDXID": base,
+          "downloadLocation": NOASSERTION,
+          "filesAnalyzed": false,
+          "licenseConcluded": NOASSERTION,
+          "licenseDeclared": license,
+          "copyrightText": NOASSERTION,
+          "primaryPackagePurpose": if is_application(&pkg.manager) { "APPLICATION" } else { "LIBRARY" },
+          "externalRefs": [{
+            "referenceCategory": "PACKAGE-MANAGER",
+            "referenceType": "purl",
+            "referenceLocator": pkg.purl(),
+          }],
+          // SPDX 没有自定义属性，管理器与范围写入注释
+          "comment": format!("boxy:manager={}; boxy:scope={}", pkg.manager, entry.scope),
+        });
+        if !pkg.version.is_empty() {
+            package["versionInfo"] = json!(pkg.version);
+        }
+        if let Some(description) = &pkg.description {
+            package["summary"] = json!(description);
+        }
+        if let Some(homepage) = &pkg.homepage {
+            package["homepage"] = json!(homepage);
+        }
+        let checksums: Vec<Value> = resolver
+            .resolve(pkg)
+            .into_iter()
+            .map(|checksum| {
+                json!({ "algorithm": checksum.algorithm.spdx_name(), "checksumValue": checksum.value })
+            })
+            .collect();
+        if !checksums.is_empty() {
+            package["checksums"] = json!(checksums);
+        }
+        relationships.push(json!({
+          "spdxElementId": "SPDXRef-DOCUMENT",
+          "relationshipType": "DESCRIBES",
+          "relatedSpdxElement": base,
+        }));
+        packages.push(package);
+    }
+
+    json!({
+      "spdxVersion": SPDX_VERSION,
+      "dataLicense": "CC0-1.0",
+      "SPDXID": "SPDXRef-DOCUMENT",
+      "name": "boxy-inventory",
+      "documentNamespace": format!("https://spdx.org/spdxdocs/boxy-{}", Uuid::new_v4()),
+      "creationInfo": {
+        "created": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "creators": [format!("Tool: boxy-{}", env!("CARGO_PKG_VERSION"))],
+      },
+      "packages": packages,
+      "relationships": relationships,
+    })
+}
+
+/// SPDXID 只允许字母、数字、`.` 与 `-`
+fn spdx_id_part(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '.' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_npm_integrity() {
+        let temp_dir = tempdir().unwrap();
+        let lockfile = json!({
+            "packages": {
+                "node_modules/left-pad": { "version": "1.3.0", "integrity": "sha1-AAEC sha512-AAECAw==" }
+            }
+        });
+        fs::write(
+            temp_dir.path().join(".package-lock.json"),
+            lockfile.to_string(),
+        )
+        .unwrap();
+        let mut pkg = Package::new("npm", "left-pad", "1.3.0");
+        pkg.installed_path = Some(
+            temp_dir
+                .path()
+                .join("left-pad")
+                .to_string_lossy()
+                .to_string(),
+        );
+
+        let checksums = ChecksumResolver::default().resolve(&pkg);
+        assert_eq!(
+            checksums,
+            vec![Checksum {
+                algorithm: HashAlgorithm::Sha512,
+                value: "00010203".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_documents() {
+        let entries = vec![
+            SbomEntry {
+                package: Package {
+                    license: Some("MIT".to_string()),
+                    ..Package::new("npm", "typescript", "5.4.0")
+                },
+                scope: "global".to_string(),
+            },
+            SbomEntry {
+                package: Package {
+                    license: Some("BSD License".to_string()),
+                    ..Package::new("pip", "black", "24.1.0")
+                },
+                scope: "global".to_string(),
+            },
+        ];
+
+        let bom = to_cyclonedx(&entries);
+        assert_eq!(bom["components"].as_array().unwrap().len(), 2);
+        assert_eq!(bom["components"][0]["licenses"][0]["expression"], "MIT");
+        assert_eq!(
+            bom["components"][1]["licenses"][0]["license"]["name"],
+            "BSD License"
+        );
+        assert_eq!(bom["components"][1]["purl"], "pkg:pypi/black@24.1.0");
+
+        let spdx = to_spdx(&entries);
+        assert_eq!(
+            spdx["packages"][0]["SPDXID"],
+            "SPDXRef-Package-npm-typescript-5.4.0"
+        );
+        assert_eq!(spdx["packages"][1]["licenseDeclared"], NOASSERTION);
+        assert_eq!(spdx["relationships"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::context::{resolve_manager_names, CliContext};
+use crate::inventory::collect_installed;
+use crate::{EXIT_ERROR, EXIT_USAGE};
+use anyhow::{Context, Result};
+use boxy_audit::{Finding, OsvDatabase, Severity};
+use boxy_core::Package;
+use colored::*;
+use std::path::PathBuf;
+
+pub async fn cmd_audit(
+    ctx: &CliContext,
+    manager_name: Option<&str>,
+    db: Option<&str>,
+    update_db: bool,
+    format: &str,
+    fail_on: Option<&str>,
+) -> Result<()> {
+    if !matches!(format, "table" | "json" | "sarif") {
+        eprintln!(
+            "{}",
+            format!("错误: 不支持的输出格式 {}（table、json、sarif）", format).bright_red()
+        );
+        std::process::exit(EXIT_USAGE);
+    }
+    let fail_on = match fail_on.map(|value| value.parse::<Severity>()) {
+        None => None,
+        Some(Ok(severity)) => Some(severity),
+        Some(Err(err)) => {
+            eprintln!("{}", format!("错误: {}", err).bright_red());
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+    let table = format == "table";
+
+    // 数据库：--db 指定的本地目录，或 boxy 数据目录下自动下载的数据
+    let db_dir = match db {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            boxy_audit::default_db_dir().ok_or_else(|| anyhow::anyhow!("无法获取 boxy 数据目录"))?
+        }
+    };
+    let missing = !db_dir.is_dir();
+    if update_db || (missing && db.is_none()) {
+        eprintln!(
+            "{}",
+            format!("正在下载 OSV 数据库到 {}...", db_dir.display()).bright_cyan()
+        );
+        boxy_audit::download(&db_dir, &boxy_audit::ECOSYSTEMS)
+            .await
+            .context("下载 OSV 数据库失败")?;
+    }
+    let database = {
+        let db_dir = db_dir.clone();
+        tokio::task::spawn_blocking(move || OsvDatabase::open(&db_dir))
+            .await
+            .map_err(|err| anyhow::anyhow!("任务执行失败: {}", err))?
+            .context("打开 OSV 数据库失败")?
+    };
+    if database.is_empty() {
+        eprintln!(
+            "{}",
+            format!("警告: OSV 数据库为空: {}", db_dir.display()).bright_yellow()
+        );
+    }
+
+    let scope_config = ctx.resolve_scope(manager_name)?;
+    let manager_names: Vec<String> = resolve_manager_names(manager_name)
+        .into_iter()
+        .filter(|name| boxy_audit::ecosystem_for(name).is_some())
+        .collect();
+    let installed = collect_installed(ctx, &scope_config, &manager_names, true, false).await?;
+    let incomplete = !installed.failed.is_empty();
+    let packages: Vec<Package> = installed
+        .lists
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect();
+    let scanned = packages.len();
+
+    let findings: Vec<Finding> =
+        tokio::task::spawn_blocking(move || boxy_audit::audit_packages(&database, &packages))
+            .await
+            .map_err(|err| anyhow::anyhow!("任务执行失败: {}", err))?;
+
+    match format {
+        "json" => {
+            let output = serde_json::json!({
+              "scanned": scanned,
+              "findings": findings,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        "sarif" => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&boxy_audit::sarif::to_sarif(&findings))?
+            );
+        }
+        _ => print_audit_table(&findings, scanned),
+    }
+
+    let failed = fail_on
+        .is_some_and(|threshold| findings.iter().any(|finding| finding.severity >= threshold));
+    if failed {
+        if table {
+            eprintln!(
+                "{}",
+                format!(
+                    "✗ 存在 {} 及以上等级的漏洞",
+                    fail_on.unwrap_or(Severity::Unknown)
+                )
+                .bright_red()
+            );
+        }
+        std::process::exit(EXIT_ERROR);
+    }
+    // 有管理器未能列出包时审计结果不完整，不能当作通过
+    if incomplete {
+        eprintln!(
+            "{}",
+            format!(
+                "✗ 以下包管理器的包列表获取失败，审计结果不完整: {}",
+                installed.failed.join(", ")
+            )
+            .bright_red()
+        );
+        std::process::exit(EXIT_ERROR);
+    }
+    Ok(())
+}
+
+fn print_audit_table(findings: &[Finding], scanned: usize) {
+    if findings.is_empty() {
+        println!(
+            "{}",
+            format!("✓ 已检查 {} 个包，未发现已知漏洞", scanned).bright_green()
+        );
+        return;
+    }
+
+    for finding in findings {
+        let severity = match finding.severity {
+            Severity::Critical => "CRITICAL".bright_red().bold(),
+            Severity::High => "HIGH".bright_red(),
+            Severity::Medium => "MEDIUM".bright_yellow(),
+            Severity::Low => "LOW".bright_blue(),
+            Severity::Unknown => "UNKNOWN".dimmed(),
+        };
+        println!(
+            "  {:<10} {} {} {}",
+            severity,
+            finding.package.bright_white(),
+            finding.version.dimmed(),
+            format!("({})", finding.manager).dimmed()
+        );
+        println!(
+            "    {} {}",
+            finding.id.bright_cyan(),
+            finding.summary.as_deref().unwrap_or_default()
+        );
+        if !finding.fixed_versions.is_empty() {
+            println!(
+                "    修复版本: {}",
+                finding.fixed_versions.join(", ").bright_green()
+            );
+        }
+        if finding.upgrade_fixes {
+            if let Some(latest) = &finding.latest_version {
+                println!(
+                    "    {} 升级到 {} 即可修复",
+                    "→".bright_blue(),
+                    latest.bright_green()
+                );
+            }
+        }
+        println!("    {}", finding.url.dimmed());
+    }
+
+    let fixable = findings
+        .iter()
+        .filter(|finding| finding.upgrade_fixes)
+        .count();
+    println!();
+    println!(
+        "已检查 {} 个包，发现 {} 个漏洞，其中 {} 个可通过升级修复",
+        scanned,
+        findings.len().to_string().bright_red(),
+        fixable.to_string().bright_green()
+    );
+}
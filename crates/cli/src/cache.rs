@@ -0,0 +1,253 @@
+use crate::context::{resolve_manager_names, CliContext};
+use crate::managers::create_manager;
+use crate::{EXIT_ERROR, EXIT_USAGE, SCAN_CONCURRENCY};
+use anyhow::Result;
+use boxy_core::{format_size, parse_age, DiskUsage};
+use boxy_error::BoxyError;
+use colored::*;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+pub async fn cmd_cache_size(ctx: &CliContext, manager_name: Option<&str>) -> Result<()> {
+    let manager_names = resolve_manager_names(manager_name);
+
+    // 缓存目录与包范围无关，统一按全局范围创建管理器
+    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+    let tasks: Vec<_> = manager_names
+        .iter()
+        .map(|name| {
+            let manager_name = name.clone();
+            let cache_clone = ctx.cache.clone();
+            let semaphore = semaphore.clone();
+            let options = ctx.options.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.ok()?;
+                let manager =
+                    create_manager(&manager_name, cache_clone.clone(), true, None, &options)?;
+                if !manager.check_available().await.unwrap_or(false) {
+                    return None;
+                }
+                Some(
+                    DiskUsage::new(cache_clone)
+                        .cache_usage(manager.as_ref())
+                        .await,
+                )
+            })
+        })
+        .collect();
+
+    let mut usages = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(Some(usage)) if !usage.dirs.is_empty() => usages.push(usage),
+            Ok(_) => {}
+            Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+        }
+    }
+    usages.sort_by_key(|usage| std::cmp::Reverse(usage.total));
+
+    if ctx.json {
+        println!("{}", serde_json::to_string_pretty(&usages)?);
+        return Ok(());
+    }
+
+    if usages.is_empty() {
+        println!("{}", "没有找到包管理器缓存".bright_yellow());
+        return Ok(());
+    }
+
+    for usage in &usages {
+        println!(
+            "{} {}",
+            usage.manager.bright_cyan().bold(),
+            format_size(usage.total).bright_yellow()
+        );
+        for dir in &usage.dirs {
+            println!(
+                "  {} {:>10}  {}",
+                "•".bright_blue(),
+                format_size(dir.size),
+                dir.path.display()
+            );
+        }
+    }
+    let total: u64 = usages.iter().map(|usage| usage.total).sum();
+    println!();
+    println!("合计: {}", format_size(total).bright_yellow().bold());
+
+    Ok(())
+}
+
+enum CleanOutcome {
+    /// 释放（dry_run 时为预计释放）的字节数，无法得知时为 None
+    Freed(Option<u64>),
+    Unsupported,
+    Failed(String),
+}
+
+pub async fn cmd_cache_clean(
+    ctx: &CliContext,
+    manager_name: Option<&str>,
+    older_than: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let older_than = match older_than.map(|value| (value, parse_age(value))) {
+        None => None,
+        Some((_, Some(age))) => Some(age),
+        Some((value, None)) => {
+            eprintln!(
+                "{}",
+                format!("错误: 无法解析时长 {}，示例: 30d、12h、2w", value).bright_red()
+            );
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+    let manager_names = resolve_manager_names(manager_name);
+
+    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+    let tasks: Vec<_> = manager_names
+        .iter()
+        .map(|name| {
+            let manager_name = name.clone();
+            let cache_clone = ctx.cache.clone();
+            let semaphore = semaphore.clone();
+            let options = ctx.options.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.ok()?;
+                let manager =
+                    create_manager(&manager_name, cache_clone.clone(), true, None, &options)?;
+                if !manager.check_available().await.unwrap_or(false) {
+                    return None;
+                }
+                let usage = DiskUsage::new(cache_clone);
+                if dry_run {
+                    let outcome = match older_than {
+                        Some(age) => match manager.prune_cache(age, true).await {
+                            Ok(bytes) => CleanOutcome::Freed(bytes),
+                            Err(BoxyError::UnsupportedOperation { .. }) => {
+                                CleanOutcome::Unsupported
+                            }
+                            Err(err) => CleanOutcome::Failed(err.to_string()),
+                        },
+                        // 只有会清空缓存目录的包管理器，缓存大小才等于将释放的空间
+                        None => CleanOutcome::Freed(
+                            usage.cache_usage(manager.as_ref()).await.reclaimable,
+                        ),
+                    };
+                    return Some((manager_name, outcome));
+                }
+
+                // 实际释放的空间以清理前后缓存目录的大小之差为准，
+                // 清理后的统计同时刷新缓存占用记录，TUI 仪表盘会直接读取
+                let before = usage.cache_usage(manager.as_ref()).await.total;
+                let result = match older_than {
+                    Some(age) => manager.prune_cache(age, false).await.map(|_| ()),
+                    None => manager.clean_cache().await,
+                };
+                let outcome = match result {
+                    Ok(()) => {
+                        let after = usage.cache_usage(manager.as_ref()).await.total;
+                        CleanOutcome::Freed(Some(before.saturating_sub(after)))
+                    }
+                    Err(BoxyError::UnsupportedOperation { .. }) => CleanOutcome::Unsupported,
+                    Err(err) => CleanOutcome::Failed(err.to_string()),
+                };
+                Some((manager_name, outcome))
+            })
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(Some(result)) => results.push(result),
+            Ok(None) => {}
+            Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+        }
+    }
+
+    let has_failure = results
+        .iter()
+        .any(|(_, outcome)| matches!(outcome, CleanOutcome::Failed(_)));
+    let total: u64 = results
+        .iter()
+        .map(|(_, outcome)| match outcome {
+            CleanOutcome::Freed(bytes) => bytes.unwrap_or_default(),
+            _ => 0,
+        })
+        .sum();
+    let has_unknown = results
+        .iter()
+        .any(|(_, outcome)| matches!(outcome, CleanOutcome::Freed(None)));
+
+    if ctx.json {
+        let output: Vec<serde_json::Value> = results
+            .iter()
+            .map(|(manager, outcome)| match outcome {
+                CleanOutcome::Freed(bytes) => serde_json::json!({
+                  "manager": manager,
+                  "status": if dry_run { "dry_run" } else { "cleaned" },
+                  "bytes": bytes,
+                }),
+                CleanOutcome::Unsupported => serde_json::json!({
+                  "manager": manager,
+                  "status": "unsupported",
+                }),
+                CleanOutcome::Failed(error) => serde_json::json!({
+                  "manager": manager,
+                  "status": "failed",
+                  "error": error,
+                }),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        let verb = if dry_run { "将释放" } else { "已释放" };
+        for (manager, outcome) in &results {
+            match outcome {
+                CleanOutcome::Freed(bytes) => println!(
+                    "  {} {} {} {}",
+                    "✓".bright_green(),
+                    manager.bright_white(),
+                    verb,
+                    match bytes {
+                        Some(bytes) => format_size(*bytes).bright_yellow(),
+                        None => "未知".dimmed(),
+                    }
+                ),
+                CleanOutcome::Unsupported => println!(
+                    "  {} {} {}",
+                    "⚠".bright_yellow(),
+                    manager.bright_white(),
+                    if older_than.is_some() {
+                        "不支持按时间清理缓存"
+                    } else {
+                        "不支持缓存清理"
+                    }
+                ),
+                CleanOutcome::Failed(error) => eprintln!(
+                    "  {} {} {}",
+                    "✗".bright_red(),
+                    manager.bright_white(),
+                    format!("缓存清理失败: {}", error).bright_red()
+                ),
+            }
+        }
+        println!();
+        println!(
+            "合计{}: {}{}",
+            verb,
+            format_size(total).bright_yellow().bold(),
+            if has_unknown {
+                "（不含无法估算的包管理器）".dimmed()
+            } else {
+                "".normal()
+            }
+        );
+    }
+
+    if has_failure {
+        std::process::exit(EXIT_ERROR);
+    }
+    Ok(())
+}
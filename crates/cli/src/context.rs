@@ -0,0 +1,176 @@
+use crate::managers::{supports_global, supports_local, ManagerOptions, MANAGER_NAMES};
+use crate::READ_COMMAND_TIMEOUT;
+use anyhow::Result;
+use boxy_cache::Cache;
+use boxy_core::python::{find_venv, venv_python, PythonScope};
+use boxy_core::ManagerExecutor;
+use colored::*;
+use std::{path::PathBuf, sync::Arc};
+use tokio::time::timeout;
+
+/// 各子命令共用的运行环境：缓存、执行器与全局命令行参数
+pub struct CliContext {
+    pub cache: Arc<Cache>,
+    pub executor: Arc<ManagerExecutor>,
+    pub global: bool,
+    pub scope: Option<String>,
+    pub directory: Option<String>,
+    pub json: bool,
+    pub no_cache: bool,
+    /// --python、--user 与 --scope=formula|cask 决定的 pip、uv 与 brew 范围
+    pub options: ManagerOptions,
+}
+
+impl CliContext {
+    /// 按包管理器解析 --global、--scope 与 --dir
+    pub fn resolve_scope(&self, manager_name: Option<&str>) -> Result<ScopeConfig> {
+        resolve_scope(
+            manager_name,
+            self.global,
+            self.scope.as_deref(),
+            self.directory.as_deref(),
+        )
+    }
+}
+
+pub struct ScopeConfig {
+    pub global: bool,
+    pub workdir: Option<PathBuf>,
+}
+
+pub async fn run_with_timeout<F>(message: &'static str, fut: F) -> Result<()>
+where
+    F: std::future::Future<Output = Result<()>>,
+{
+    timeout(READ_COMMAND_TIMEOUT, fut)
+        .await
+        .map_err(|_| anyhow::anyhow!(message))?
+}
+
+pub fn resolve_scope(
+    manager_name: Option<&str>,
+    global: bool,
+    scope: Option<&str>,
+    directory: Option<&str>,
+) -> Result<ScopeConfig> {
+    let scope_value = scope.map(|value| value.to_lowercase());
+    if directory.is_some() && scope_value.as_deref() != Some("local") {
+        return Err(anyhow::anyhow!("--dir 仅能与 --scope=local 一起使用"));
+    }
+
+    let (mut global, workdir) = match scope_value.as_deref() {
+        None => (global, None),
+        Some("global") => (true, None),
+        Some("formula" | "cask") => {
+            if manager_name != Some("brew") {
+                return Err(anyhow::anyhow!(
+                    "--scope=formula、--scope=cask 仅适用于 brew，请同时指定 -m brew"
+                ));
+            }
+            (global, None)
+        }
+        Some("local") => {
+            let dir = directory.unwrap_or("").trim();
+            if dir.is_empty() {
+                return Err(anyhow::anyhow!("缺少本地目录，请使用 --dir 指定"));
+            }
+            let path = expand_home(dir)?;
+            if !path.is_dir() {
+                return Err(anyhow::anyhow!("目录不存在或不可访问"));
+            }
+            (false, Some(path))
+        }
+        Some(other) => return Err(anyhow::anyhow!("不支持的 scope: {}", other)),
+    };
+
+    if let Some(name) = manager_name {
+        if global && !supports_global(name) {
+            eprintln!(
+                "{}",
+                format!("警告: {} 不支持全局范围，忽略 --global 参数", name).bright_yellow()
+            );
+            global = false;
+        }
+        if workdir.is_some() && !supports_local(name) {
+            return Err(anyhow::anyhow!("{} 不支持本地范围", name));
+        }
+        if let Some(dir) = workdir.as_ref().filter(|_| name == "cargo") {
+            if !dir.join("Cargo.toml").is_file() {
+                return Err(anyhow::anyhow!("{} 中未找到 Cargo.toml", dir.display()));
+            }
+        }
+        // uv 项目在首次 `uv add` 时才会创建 .venv
+        let uv_project = name == "uv"
+            && workdir
+                .as_ref()
+                .is_some_and(|dir| dir.join("pyproject.toml").is_file());
+        if let Some(dir) = workdir
+            .as_ref()
+            .filter(|_| matches!(name, "pip" | "uv") && !uv_project)
+        {
+            if find_venv(dir).is_none() {
+                return Err(anyhow::anyhow!(
+                    "{} 中未找到 Python 虚拟环境（pyvenv.cfg 或 .venv）",
+                    dir.display()
+                ));
+            }
+        }
+    }
+
+    Ok(ScopeConfig { global, workdir })
+}
+
+/// 展开路径开头的 `~/`，避免用户手动展开路径
+pub fn expand_home(path: &str) -> Result<PathBuf> {
+    match path.strip_prefix("~/") {
+        Some(rest) => {
+            let home = std::env::var("HOME").map_err(|_| anyhow::anyhow!("无法解析用户目录"))?;
+            Ok(PathBuf::from(home).join(rest))
+        }
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+/// 解析 --python 与 --user，未指定时返回 None（只在 --dir 指定的本地目录中自动发现虚拟环境）
+pub fn resolve_python_scope(
+    python: Option<&str>,
+    user: bool,
+    directory: Option<&str>,
+) -> Result<Option<PythonScope>> {
+    if (python.is_some() || user) && directory.is_some() {
+        return Err(anyhow::anyhow!("--python、--user 不能与 --dir 同时使用"));
+    }
+    match (python, user) {
+        (Some(_), true) => Err(anyhow::anyhow!("--python 与 --user 不能同时使用")),
+        (Some(python), false) => {
+            let path = match python.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir()
+                    .ok_or_else(|| anyhow::anyhow!("无法解析用户目录"))?
+                    .join(rest),
+                None => PathBuf::from(python),
+            };
+            // 不含路径分隔符时按 PATH 中的命令名处理，如 python3.12
+            if path.components().count() > 1 && !path.exists() {
+                return Err(anyhow::anyhow!("Python 解释器不存在: {}", path.display()));
+            }
+            // 环境目录：虚拟环境按 pyvenv.cfg 识别，conda 等其他环境使用其中的解释器
+            if path.is_dir() {
+                return Ok(Some(match find_venv(&path) {
+                    Some(venv) => PythonScope::Venv(venv),
+                    None => PythonScope::Interpreter(venv_python(&path)),
+                }));
+            }
+            Ok(Some(PythonScope::Interpreter(path)))
+        }
+        (None, true) => Ok(Some(PythonScope::User)),
+        (None, false) => Ok(None),
+    }
+}
+
+pub fn resolve_manager_names(manager_name: Option<&str>) -> Vec<String> {
+    if let Some(name) = manager_name {
+        vec![name.to_string()]
+    } else {
+        MANAGER_NAMES.iter().map(|name| name.to_string()).collect()
+    }
+}
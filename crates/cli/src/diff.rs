@@ -0,0 +1,79 @@
+use crate::context::CliContext;
+use crate::managers::create_manager;
+use crate::READ_COMMAND_TIMEOUT;
+use boxy_core::{snapshot_installed, Package, PackageDiff};
+use colored::*;
+use tokio::time::timeout;
+
+/// 读取包管理器当前已安装的包，用于变更前后对比，失败时返回 None
+pub async fn take_snapshot(ctx: &CliContext, manager_name: &str) -> Option<Vec<Package>> {
+    let scope_config = ctx.resolve_scope(Some(manager_name)).ok()?;
+    let manager = create_manager(
+        manager_name,
+        ctx.cache.clone(),
+        scope_config.global,
+        scope_config.workdir.as_ref(),
+        &ctx.options,
+    )?;
+    if !manager.check_available().await.unwrap_or(false) {
+        return None;
+    }
+    timeout(
+        READ_COMMAND_TIMEOUT,
+        snapshot_installed(manager.as_ref(), &ctx.cache),
+    )
+    .await
+    .ok()?
+    .ok()
+}
+
+/// 与操作前的快照对比，记录非空的差异
+pub async fn push_diff(
+    ctx: &CliContext,
+    diffs: &mut Vec<PackageDiff>,
+    manager_name: &str,
+    before: Option<Vec<Package>>,
+) {
+    let Some(before) = before else {
+        return;
+    };
+    let Some(after) = take_snapshot(ctx, manager_name).await else {
+        return;
+    };
+    let diff = PackageDiff::between(manager_name, &before, &after);
+    if !diff.is_empty() {
+        diffs.push(diff);
+    }
+}
+
+/// 输出已安装包的变化（含包管理器顺带安装、移除的依赖）
+pub fn print_changes(diffs: &[PackageDiff]) {
+    if diffs.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", "变更:".bold());
+    for diff in diffs {
+        println!("  {}", diff.manager.bright_cyan());
+        for entry in &diff.added {
+            println!(
+                "    {} {} {}",
+                "+".bright_green(),
+                entry.name,
+                entry.version
+            );
+        }
+        for entry in &diff.removed {
+            println!("    {} {} {}", "-".bright_red(), entry.name, entry.version);
+        }
+        for change in &diff.changed {
+            println!(
+                "    {} {} {} → {}",
+                "~".bright_yellow(),
+                change.name,
+                change.from,
+                change.to
+            );
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::context::{resolve_manager_names, CliContext};
+use crate::managers::create_manager;
+use crate::SCAN_CONCURRENCY;
+use anyhow::Result;
+use boxy_core::{format_size, CacheUsage, DiskUsage, Package};
+use colored::*;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+#[derive(Clone)]
+struct ManagerUsage {
+    manager: String,
+    packages: Vec<Package>,
+    cache: CacheUsage,
+}
+
+impl ManagerUsage {
+    fn installed_size(&self) -> u64 {
+        self.packages.iter().filter_map(|pkg| pkg.size).sum()
+    }
+}
+
+pub async fn cmd_du(ctx: &CliContext, manager_name: Option<&str>, top: usize) -> Result<()> {
+    let scope_config = ctx.resolve_scope(manager_name)?;
+    let global = scope_config.global;
+    let workdir = scope_config.workdir.clone();
+    let manager_names = resolve_manager_names(manager_name);
+
+    // 并行统计所有管理器
+    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+    let tasks: Vec<_> = manager_names
+        .iter()
+        .map(|name| {
+            let manager_name = name.clone();
+            let cache_clone = ctx.cache.clone();
+            let no_cache = ctx.no_cache;
+            let semaphore = semaphore.clone();
+            let workdir = workdir.clone();
+            let options = ctx.options.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.ok()?;
+                let manager = create_manager(
+                    &manager_name,
+                    cache_clone.clone(),
+                    global,
+                    workdir.as_ref(),
+                    &options,
+                )?;
+                if !manager.check_available().await.unwrap_or(false) {
+                    return None;
+                }
+                if no_cache {
+                    if let Err(err) = cache_clone.invalidate(manager.cache_key()).await {
+                        eprintln!(
+                            "{}",
+                            format!("错误: 清除 {} 缓存失败: {}", manager_name, err).bright_red()
+                        );
+                    }
+                }
+                let packages = match manager.list_installed().await {
+                    Ok(list) => list,
+                    Err(err) => {
+                        eprintln!(
+                            "{}",
+                            format!("错误: 获取 {} 包列表失败: {}", manager_name, err).bright_red()
+                        );
+                        Vec::new()
+                    }
+                };
+                let usage = DiskUsage::new(cache_clone)
+                    .cache_usage(manager.as_ref())
+                    .await;
+                Some(ManagerUsage {
+                    manager: manager_name,
+                    packages,
+                    cache: usage,
+                })
+            })
+        })
+        .collect();
+
+    let mut usages = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(Some(usage)) => usages.push(usage),
+            Ok(None) => {}
+            Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+        }
+    }
+    usages.sort_by_key(|usage| std::cmp::Reverse(usage.installed_size() + usage.cache.total));
+
+    let mut largest: Vec<&Package> = usages
+        .iter()
+        .flat_map(|usage| usage.packages.iter())
+        .filter(|pkg| pkg.size.is_some())
+        .collect();
+    largest.sort_by_key(|pkg| std::cmp::Reverse(pkg.size.unwrap_or_default()));
+    largest.truncate(top);
+
+    let total_installed: u64 = usages.iter().map(|usage| usage.installed_size()).sum();
+    // 只清理部分缓存的包管理器（如 pnpm store prune）无法得知可回收的空间，不计入合计
+    let total_reclaimable: u64 = usages
+        .iter()
+        .filter_map(|usage| usage.cache.reclaimable)
+        .sum();
+
+    if ctx.json {
+        let managers: Vec<serde_json::Value> = usages
+            .iter()
+            .map(|usage| {
+                serde_json::json!({
+                  "manager": usage.manager,
+                  "package_count": usage.packages.len(),
+                  "installed_size": usage.installed_size(),
+                  "cache_size": usage.cache.total,
+                  "reclaimable": usage.cache.reclaimable,
+                  "cache_dirs": usage.cache.dirs,
+                })
+            })
+            .collect();
+        let largest: Vec<serde_json::Value> = largest
+            .iter()
+            .map(|pkg| {
+                serde_json::json!({
+                  "name": pkg.name,
+                  "version": pkg.version,
+                  "manager": pkg.manager,
+                  "size": pkg.size,
+                  "installed_path": pkg.installed_path,
+                })
+            })
+            .collect();
+        let output = serde_json::json!({
+          "managers": managers,
+          "largest": largest,
+          "total_installed": total_installed,
+          "total_reclaimable": total_reclaimable,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if usages.is_empty() {
+        println!("{}", "没有可用的包管理器".bright_yellow());
+        return Ok(());
+    }
+
+    println!("{}", "磁盘占用".bright_cyan().bold());
+    println!(
+        "  {:<7} {:>7} {:>11} {:>9}",
+        "管理器", "包数量", "已安装", "可回收缓存"
+    );
+    for usage in &usages {
+        println!(
+            "  {:<10} {:>10} {:>14} {:>14}",
+            usage.manager.bright_white(),
+            usage.packages.len(),
+            format_size(usage.installed_size()),
+            match usage.cache.reclaimable {
+                Some(bytes) => format_size(bytes).bright_yellow(),
+                None => "未知".dimmed(),
+            },
+        );
+    }
+    println!(
+        "  {:<10} {:>10} {:>14} {:>14}",
+        "合计".bold(),
+        "",
+        format_size(total_installed).bold(),
+        format_size(total_reclaimable).bright_yellow().bold(),
+    );
+
+    if !largest.is_empty() {
+        println!();
+        println!(
+            "{}",
+            format!("占用最大的 {} 个包", largest.len())
+                .bright_cyan()
+                .bold()
+        );
+        for pkg in &largest {
+            println!(
+                "  {} {:>10}  {} {} {}",
+                "•".bright_yellow(),
+                format_size(pkg.size.unwrap_or_default()),
+                pkg.name.bright_white(),
+                pkg.version.dimmed(),
+                format!("({})", pkg.manager).dimmed(),
+            );
+        }
+    }
+
+    let cache_dirs: Vec<_> = usages
+        .iter()
+        .flat_map(|usage| {
+            usage
+                .cache
+                .dirs
+                .iter()
+                .map(move |dir| (&usage.manager, dir))
+        })
+        .filter(|(_, dir)| dir.size > 0)
+        .collect();
+    if !cache_dirs.is_empty() {
+        println!();
+        println!("{}", "下载缓存目录".bright_cyan().bold());
+        for (manager, dir) in cache_dirs {
+            println!(
+                "  {} {:>10}  {} {}",
+                "•".bright_yellow(),
+                format_size(dir.size),
+                dir.path.display(),
+                format!("({})", manager).dimmed(),
+            );
+        }
+    }
+
+    Ok(())
+}
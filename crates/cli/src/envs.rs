@@ -0,0 +1,83 @@
+use anyhow::Result;
+use boxy_core::envs::{EnvDiscovery, PythonEnv};
+use boxy_core::format_size;
+use colored::*;
+use std::path::PathBuf;
+
+pub async fn cmd_envs(roots: &[String], depth: usize, stale_only: bool, json: bool) -> Result<()> {
+    let discovery = if roots.is_empty() {
+        EnvDiscovery::from_env()
+    } else {
+        EnvDiscovery::new(roots.iter().map(PathBuf::from).collect())
+    }
+    .with_max_depth(depth);
+    // 统计大小需要遍历环境目录，放到阻塞线程中执行
+    let mut envs = tokio::task::spawn_blocking(move || discovery.discover())
+        .await
+        .map_err(|err| anyhow::anyhow!("任务执行失败: {}", err))?;
+    if stale_only {
+        envs.retain(|env| env.stale.is_some());
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&envs)?);
+        return Ok(());
+    }
+    if envs.is_empty() {
+        println!("{}", "没有发现 Python 环境".bright_yellow());
+        return Ok(());
+    }
+
+    for env in &envs {
+        print_env(env);
+    }
+    let total: u64 = envs.iter().map(|env| env.size).sum();
+    println!(
+        "共 {} 个环境，占用 {}",
+        envs.len(),
+        format_size(total).bold()
+    );
+    let stale: Vec<&PythonEnv> = envs.iter().filter(|env| env.stale.is_some()).collect();
+    if !stale.is_empty() {
+        let reclaimable: u64 = stale.iter().map(|env| env.size).sum();
+        println!(
+            "{}",
+            format!(
+                "其中 {} 个已失效，可回收 {}",
+                stale.len(),
+                format_size(reclaimable)
+            )
+            .bright_yellow()
+        );
+    }
+    println!(
+        "{}",
+        "使用 --python <环境目录> 对环境执行 list、outdated、audit 等命令".dimmed()
+    );
+    Ok(())
+}
+
+fn print_env(env: &PythonEnv) {
+    let version = env.python_version.as_deref().unwrap_or("未知版本");
+    println!(
+        "{} {} {}",
+        env.path.display().to_string().bright_cyan().bold(),
+        format!("({})", env.kind.as_str()).dimmed(),
+        format!("Python {}", version).bright_white()
+    );
+    println!(
+        "  包: {}  大小: {}",
+        env.package_count,
+        format_size(env.size)
+    );
+    if let Some(project) = &env.project {
+        println!("  项目: {}", project.display());
+    }
+    if let Some(reason) = env.stale {
+        println!(
+            "  {}",
+            format!("已失效: {}", reason.description()).bright_red()
+        );
+    }
+    println!();
+}
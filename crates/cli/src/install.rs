@@ -0,0 +1,762 @@
+use crate::context::{resolve_manager_names, CliContext};
+use crate::diff::{print_changes, push_diff, take_snapshot};
+use crate::managers::create_manager;
+use crate::transaction::{run_transaction, PlannedStep};
+use crate::{COMMAND_TIMEOUT, EXIT_ERROR, EXIT_PARTIAL, EXIT_USAGE, SCAN_CONCURRENCY};
+use anyhow::{Context, Result};
+use boxy_core::{Capability, PackageDiff, PackageSpec, StepAction};
+use boxy_error::BoxyError;
+use colored::*;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+
+pub async fn cmd_install(
+    ctx: &CliContext,
+    specs: &[PackageSpec],
+    force: bool,
+    transaction: bool,
+) -> Result<()> {
+    if transaction {
+        // 事务模式逐个执行，以便准确记录每一步及其原版本
+        let plan = specs
+            .iter()
+            .map(|spec| PlannedStep {
+                manager: spec.manager.clone().unwrap_or_default(),
+                package: spec.name.clone(),
+                action: StepAction::Install,
+                version: spec.version.clone(),
+            })
+            .collect();
+        return run_transaction(ctx, "install", plan, force).await;
+    }
+
+    let mut outcomes: Vec<Option<Result<()>>> = specs.iter().map(|_| None).collect();
+    let mut diffs = Vec::new();
+    for (manager_name, indexes) in group_by_manager(specs) {
+        let group: Vec<&PackageSpec> = indexes.iter().map(|index| &specs[*index]).collect();
+        let before = take_snapshot(ctx, &manager_name).await;
+        let batch = run_batch(ctx, &manager_name, &group, BatchAction::Install, force).await;
+        if let Some(Ok(())) = batch {
+            for index in indexes {
+                outcomes[index] = Some(Ok(()));
+            }
+            push_diff(ctx, &mut diffs, &manager_name, before).await;
+            continue;
+        }
+
+        // 不支持批量或批量调用失败时逐个安装，确定每个包的结果
+        for index in indexes {
+            let spec = &specs[index];
+            let outcome = install_package(ctx, spec, force).await;
+            if let Err(err) = &outcome {
+                if !ctx.json {
+                    eprintln!(
+                        "{}",
+                        format!("✗ 安装 {} 失败: {:#}", spec, err).bright_red()
+                    );
+                }
+            }
+            outcomes[index] = Some(outcome);
+        }
+        push_diff(ctx, &mut diffs, &manager_name, before).await;
+    }
+    let outcomes: Vec<_> = specs
+        .iter()
+        .zip(outcomes)
+        .map(|(spec, outcome)| (spec, outcome.unwrap_or(Ok(()))))
+        .collect();
+    report_batch(&outcomes, &diffs, ctx.json)
+}
+
+#[derive(Clone, Copy)]
+enum BatchAction {
+    Install,
+    Uninstall,
+}
+
+/// 按包管理器分组，保持每个管理器首次出现的顺序，返回各包在 specs 中的下标
+fn group_by_manager(specs: &[PackageSpec]) -> Vec<(String, Vec<usize>)> {
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for (index, spec) in specs.iter().enumerate() {
+        let manager = spec.manager.clone().unwrap_or_default();
+        match groups.iter_mut().find(|(name, _)| *name == manager) {
+            Some((_, indexes)) => indexes.push(index),
+            None => groups.push((manager, vec![index])),
+        }
+    }
+    groups
+}
+
+/// 对同一包管理器的多个包尝试一次原生调用（如 `npm install a b c`）
+///
+/// 只有一个包、包管理器不可用或不支持批量时返回 None；
+/// 批量调用失败时返回错误，由调用方逐个重试以确定每个包的结果。
+async fn run_batch(
+    ctx: &CliContext,
+    manager_name: &str,
+    specs: &[&PackageSpec],
+    action: BatchAction,
+    force: bool,
+) -> Option<Result<()>> {
+    if specs.len() < 2 {
+        return None;
+    }
+    let scope_config = ctx.resolve_scope(Some(manager_name)).ok()?;
+    let global = scope_config.global;
+    let workdir = scope_config.workdir.clone();
+    let manager = create_manager(
+        manager_name,
+        ctx.cache.clone(),
+        global,
+        workdir.as_ref(),
+        &ctx.options,
+    )?;
+    if !manager.supports(Capability::BatchInstall)
+        || !manager.check_available().await.unwrap_or(false)
+    {
+        return None;
+    }
+
+    let targets = specs
+        .iter()
+        .map(|spec| spec.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if !ctx.json {
+        let label = match action {
+            BatchAction::Install => "批量安装",
+            BatchAction::Uninstall => "批量卸载",
+        };
+        println!(
+            "{} {} ({})...",
+            label,
+            targets.bright_white(),
+            manager_name.bright_cyan()
+        );
+    }
+
+    let result = ctx
+        .executor
+        .execute(manager_name, || async {
+            match action {
+                BatchAction::Install => {
+                    let packages: Vec<(&str, Option<&str>)> = specs
+                        .iter()
+                        .map(|spec| (spec.name.as_str(), spec.version.as_deref()))
+                        .collect();
+                    timeout(COMMAND_TIMEOUT, manager.install_batch(&packages, force))
+                        .await
+                        .map_err(|_| BoxyError::CommandTimeout)?
+                }
+                BatchAction::Uninstall => {
+                    let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+                    timeout(COMMAND_TIMEOUT, manager.uninstall_batch(&names, force))
+                        .await
+                        .map_err(|_| BoxyError::CommandTimeout)?
+                }
+            }
+        })
+        .await;
+    let _ = ctx.cache.invalidate(manager.cache_key()).await;
+
+    match result {
+        Ok(()) => {
+            if !ctx.json {
+                println!("{}", "✓ 批量操作成功".bright_green());
+            }
+            Some(Ok(()))
+        }
+        Err(err) => {
+            if !ctx.json {
+                eprintln!(
+                    "{}",
+                    format!("⚠ 批量操作失败，改为逐个执行: {}", err).bright_yellow()
+                );
+            }
+            Some(Err(anyhow::anyhow!(err)))
+        }
+    }
+}
+
+async fn install_package(ctx: &CliContext, spec: &PackageSpec, force: bool) -> Result<()> {
+    let manager_name = spec.manager.as_deref().unwrap_or_default();
+    let package = spec.name.as_str();
+    let version = spec.version.as_deref();
+    let scope_config = ctx.resolve_scope(Some(manager_name))?;
+    let global = scope_config.global;
+    let workdir = scope_config.workdir.clone();
+
+    let manager = create_manager(
+        manager_name,
+        ctx.cache.clone(),
+        global,
+        workdir.as_ref(),
+        &ctx.options,
+    )
+    .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
+
+    if !manager.check_available().await.unwrap_or(false) {
+        return Err(anyhow::anyhow!("包管理器 '{}' 不可用", manager.name()));
+    }
+
+    if !ctx.json {
+        println!(
+            "安装 {} 到 {}...",
+            spec.to_string().bright_white(),
+            manager.name().bright_cyan()
+        );
+    }
+
+    let cache_key = manager.cache_key().to_string();
+    let manager_name = manager.name().to_string();
+    let workdir = workdir.clone();
+    ctx.executor
+        .execute(&manager_name, || async {
+            let manager = create_manager(
+                &manager_name,
+                ctx.cache.clone(),
+                global,
+                workdir.as_ref(),
+                &ctx.options,
+            )
+            .ok_or_else(|| BoxyError::ManagerNotFound {
+                name: manager_name.clone(),
+            })?;
+            timeout(COMMAND_TIMEOUT, manager.install(package, version, force))
+                .await
+                .map_err(|_| BoxyError::CommandTimeout)?
+        })
+        .await
+        .map_err(|err| anyhow::anyhow!(err))
+        .context(format!("安装 {} 失败", package))?;
+
+    ctx.cache
+        .invalidate(&cache_key)
+        .await
+        .with_context(|| format!("清除 {} 缓存失败", manager.name()))?;
+
+    if !ctx.json {
+        println!("{}", "✓ 安装成功".bright_green());
+    }
+
+    Ok(())
+}
+
+/// 输出批量安装、卸载的结果
+///
+/// 单个包时 JSON 保持 `{"status", "package"}` 的格式，多个包时输出 `results` 列表，
+/// `changes` 为各包管理器操作前后已安装包的差异。
+/// 全部失败时以 EXIT_ERROR 退出，部分失败时以 EXIT_PARTIAL 退出。
+fn report_batch(
+    outcomes: &[(&PackageSpec, Result<()>)],
+    diffs: &[PackageDiff],
+    json: bool,
+) -> Result<()> {
+    let failed = outcomes
+        .iter()
+        .filter(|(_, outcome)| outcome.is_err())
+        .count();
+    let status = if failed == 0 {
+        "success"
+    } else if failed < outcomes.len() {
+        "partial"
+    } else {
+        "failed"
+    };
+    if json {
+        let entry = |spec: &PackageSpec, outcome: &Result<()>| {
+            let mut value = serde_json::json!({
+              "status": if outcome.is_ok() { "success" } else { "failed" },
+              "package": spec.name,
+              "manager": spec.manager,
+            });
+            if let Err(err) = outcome {
+                value["error"] = serde_json::json!(format!("{:#}", err));
+            }
+            value
+        };
+        let mut output = match outcomes {
+            [(spec, outcome)] => entry(spec, outcome),
+            _ => serde_json::json!({
+              "status": status,
+              "results": outcomes
+                  .iter()
+                  .map(|(spec, outcome)| entry(spec, outcome))
+                  .collect::<Vec<_>>(),
+            }),
+        };
+        output["changes"] = serde_json::json!(diffs);
+        println!("{}", output);
+    } else {
+        print_changes(diffs);
+    }
+    if !json && outcomes.len() > 1 {
+        println!();
+        println!(
+            "{:<32} {:<8} {}",
+            "包".bold(),
+            "管理器".bold(),
+            "结果".bold()
+        );
+        for (spec, outcome) in outcomes {
+            let result = match outcome {
+                Ok(()) => "✓ 成功".bright_green().to_string(),
+                Err(err) => format!("✗ {:#}", err).bright_red().to_string(),
+            };
+            println!(
+                "{:<32} {:<8} {}",
+                spec.name,
+                spec.manager.as_deref().unwrap_or_default(),
+                result
+            );
+        }
+        println!();
+        println!(
+            "{} 个成功，{} 个失败",
+            (outcomes.len() - failed).to_string().bright_green(),
+            failed.to_string().bright_red()
+        );
+    }
+
+    match status {
+        "failed" => std::process::exit(EXIT_ERROR),
+        "partial" => std::process::exit(EXIT_PARTIAL),
+        _ => Ok(()),
+    }
+}
+
+pub async fn cmd_update(
+    ctx: &CliContext,
+    package: Option<&str>,
+    manager_name: Option<&str>,
+    transaction: bool,
+    latest: bool,
+) -> Result<()> {
+    let action = if latest {
+        StepAction::Bump
+    } else {
+        StepAction::Upgrade
+    };
+    if let Some(pkg) = package {
+        // 更新单个包
+        let manager_name = match manager_name {
+            Some(name) => name,
+            None => {
+                eprintln!("{}", "错误: 必须指定包管理器".bright_red());
+                std::process::exit(EXIT_USAGE);
+            }
+        };
+        if transaction {
+            let plan = vec![PlannedStep {
+                manager: manager_name.to_string(),
+                package: pkg.to_string(),
+                action,
+                version: None,
+            }];
+            return run_transaction(ctx, "update", plan, false).await;
+        }
+        let scope_config = ctx.resolve_scope(Some(manager_name))?;
+        let global = scope_config.global;
+        let workdir = scope_config.workdir.clone();
+
+        let manager = create_manager(
+            manager_name,
+            ctx.cache.clone(),
+            global,
+            workdir.as_ref(),
+            &ctx.options,
+        )
+        .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
+        let cache_key = manager.cache_key().to_string();
+        let manager_name = manager.name().to_string();
+        let workdir = workdir.clone();
+        let before = take_snapshot(ctx, &manager_name).await;
+        ctx.executor
+            .execute(&manager_name, || async {
+                let manager = create_manager(
+                    &manager_name,
+                    ctx.cache.clone(),
+                    global,
+                    workdir.as_ref(),
+                    &ctx.options,
+                )
+                .ok_or_else(|| BoxyError::ManagerNotFound {
+                    name: manager_name.clone(),
+                })?;
+                let command = async {
+                    if latest {
+                        manager.upgrade_latest(pkg).await
+                    } else {
+                        manager.upgrade(pkg).await
+                    }
+                };
+                timeout(COMMAND_TIMEOUT, command)
+                    .await
+                    .map_err(|_| BoxyError::CommandTimeout)?
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err))
+            .context(format!("更新 {} 失败", pkg))?;
+        ctx.cache
+            .invalidate(&cache_key)
+            .await
+            .with_context(|| format!("清除 {} 缓存失败", manager.name()))?;
+        let mut diffs = Vec::new();
+        push_diff(ctx, &mut diffs, &manager_name, before).await;
+        if !ctx.json {
+            println!("{}", "✓ 更新成功".bright_green());
+            print_changes(&diffs);
+        } else {
+            println!(
+                "{}",
+                serde_json::json!({ "status": "success", "package": pkg, "changes": diffs })
+            );
+        }
+    } else {
+        // 更新所有可更新的包
+        let scope_config = ctx.resolve_scope(manager_name)?;
+        let global = scope_config.global;
+        let workdir = scope_config.workdir.clone();
+        let manager_names = resolve_manager_names(manager_name);
+        let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+        let tasks: Vec<_> = manager_names
+            .iter()
+            .map(|name| {
+                let manager_name = name.clone();
+                let cache_clone = ctx.cache.clone();
+                let semaphore = semaphore.clone();
+                let workdir = workdir.clone();
+                let options = ctx.options.clone();
+                tokio::spawn(async move {
+                    let _permit = match semaphore.acquire().await {
+                        Ok(permit) => permit,
+                        Err(_) => return Ok((manager_name, Vec::new())),
+                    };
+                    let manager = create_manager(
+                        &manager_name,
+                        cache_clone.clone(),
+                        global,
+                        workdir.as_ref(),
+                        &options,
+                    );
+                    if let Some(m) = manager {
+                        if !m.check_available().await.unwrap_or(false) {
+                            return Ok((manager_name, Vec::new()));
+                        }
+                        let outdated = m
+                            .check_outdated()
+                            .await
+                            .with_context(|| format!("检查 {} 更新失败", manager_name))?;
+                        Ok((manager_name, outdated))
+                    } else {
+                        Ok((manager_name, Vec::new()))
+                    }
+                })
+            })
+            .collect();
+
+        let mut all_outdated = Vec::new();
+        let mut out_of_range = 0;
+        for task in tasks {
+            match task.await {
+                Ok(Ok((name, packages))) => {
+                    if latest {
+                        all_outdated.push((name, packages));
+                        continue;
+                    }
+                    // 默认只在清单的版本范围内更新，跳过 wanted 与当前版本相同的包
+                    out_of_range += packages
+                        .iter()
+                        .filter(|pkg| pkg.needs_manifest_bump())
+                        .count();
+                    let packages = packages
+                        .into_iter()
+                        .filter(|pkg| pkg.has_update_in_range())
+                        .collect();
+                    all_outdated.push((name, packages));
+                }
+                Ok(Err(err)) => return Err(err),
+                Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+            }
+        }
+        if out_of_range > 0 && !ctx.json {
+            println!(
+                "{}",
+                format!(
+                    "{} 个包的最新版本超出清单的版本范围，使用 --latest 升级并修改范围",
+                    out_of_range
+                )
+                .dimmed()
+            );
+        }
+
+        if transaction {
+            let plan = all_outdated
+                .iter()
+                .flat_map(|(manager_name, packages)| {
+                    packages.iter().map(|pkg| PlannedStep {
+                        manager: manager_name.clone(),
+                        package: pkg.name.clone(),
+                        action,
+                        version: None,
+                    })
+                })
+                .collect();
+            return run_transaction(ctx, "update", plan, false).await;
+        }
+
+        let mut updated = Vec::new();
+        let mut diffs = Vec::new();
+        for (manager_name, packages) in all_outdated {
+            if packages.is_empty() {
+                continue;
+            }
+            let manager = create_manager(
+                &manager_name,
+                ctx.cache.clone(),
+                global,
+                workdir.as_ref(),
+                &ctx.options,
+            )
+            .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
+            let cache_key = manager.cache_key().to_string();
+            let before = take_snapshot(ctx, &manager_name).await;
+            for pkg in packages {
+                let workdir = workdir.clone();
+                ctx.executor
+                    .execute(manager.name(), || async {
+                        let manager = create_manager(
+                            &manager_name,
+                            ctx.cache.clone(),
+                            global,
+                            workdir.as_ref(),
+                            &ctx.options,
+                        )
+                        .ok_or_else(|| BoxyError::ManagerNotFound {
+                            name: manager_name.clone(),
+                        })?;
+                        let command = async {
+                            if latest {
+                                manager.upgrade_latest(&pkg.name).await
+                            } else {
+                                manager.upgrade(&pkg.name).await
+                            }
+                        };
+                        timeout(COMMAND_TIMEOUT, command)
+                            .await
+                            .map_err(|_| BoxyError::CommandTimeout)?
+                    })
+                    .await
+                    .map_err(|err| anyhow::anyhow!(err))
+                    .context(format!("更新 {} 失败", pkg.name))?;
+                updated.push((manager_name.clone(), pkg.name.clone()));
+            }
+            ctx.cache
+                .invalidate(&cache_key)
+                .await
+                .with_context(|| format!("清除 {} 缓存失败", manager.name()))?;
+            push_diff(ctx, &mut diffs, &manager_name, before).await;
+        }
+
+        if updated.is_empty() {
+            if !ctx.json {
+                println!("{}", "✓ 没有可更新的包".bright_green());
+            } else {
+                println!("{}", serde_json::json!({ "updated": [], "changes": [] }));
+            }
+        } else if ctx.json {
+            let output: Vec<serde_json::Value> = updated
+                .into_iter()
+                .map(|(manager, package)| {
+                    serde_json::json!({
+                      "manager": manager,
+                      "package": package,
+                    })
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                  "updated": output,
+                  "changes": diffs,
+                }))?
+            );
+        } else {
+            println!("{}", "✓ 批量更新完成".bright_green());
+            print_changes(&diffs);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn cmd_uninstall(
+    ctx: &CliContext,
+    specs: &[PackageSpec],
+    force: bool,
+    clean_cache: bool,
+) -> Result<()> {
+    let mut outcomes: Vec<Option<Result<()>>> = specs.iter().map(|_| None).collect();
+    let mut diffs = Vec::new();
+    for (manager_name, indexes) in group_by_manager(specs) {
+        let group: Vec<&PackageSpec> = indexes.iter().map(|index| &specs[*index]).collect();
+        let before = take_snapshot(ctx, &manager_name).await;
+        let batch = run_batch(ctx, &manager_name, &group, BatchAction::Uninstall, force).await;
+        if let Some(Ok(())) = batch {
+            for index in indexes {
+                outcomes[index] = Some(Ok(()));
+            }
+            push_diff(ctx, &mut diffs, &manager_name, before).await;
+            continue;
+        }
+
+        for index in indexes {
+            let spec = &specs[index];
+            let outcome = uninstall_package(ctx, spec, force).await;
+            if let Err(err) = &outcome {
+                if !ctx.json {
+                    eprintln!(
+                        "{}",
+                        format!("✗ 卸载 {} 失败: {:#}", spec, err).bright_red()
+                    );
+                }
+            }
+            outcomes[index] = Some(outcome);
+        }
+        push_diff(ctx, &mut diffs, &manager_name, before).await;
+    }
+    let outcomes: Vec<_> = specs
+        .iter()
+        .zip(outcomes)
+        .map(|(spec, outcome)| (spec, outcome.unwrap_or(Ok(()))))
+        .collect();
+
+    // 如果指定了 --clean-cache，每个包管理器只清理一次缓存
+    if clean_cache {
+        let mut cleaned = Vec::new();
+        for (spec, outcome) in &outcomes {
+            let Some(manager_name) = spec.manager.as_deref() else {
+                continue;
+            };
+            if outcome.is_err() || cleaned.contains(&manager_name) {
+                continue;
+            }
+            cleaned.push(manager_name);
+            let Some(manager) =
+                create_manager(manager_name, ctx.cache.clone(), true, None, &ctx.options)
+            else {
+                continue;
+            };
+            // 共享缓存只在 boxy cache clean 中清理，避免影响其它项目的离线构建
+            if !manager.clean_cache_on_uninstall() {
+                if !ctx.json {
+                    println!(
+                        "{}",
+                        format!(
+                            "{} 的缓存由所有项目共享，卸载后不自动清理（可使用 boxy cache clean -m {}）",
+                            manager_name, manager_name
+                        )
+                        .dimmed()
+                    );
+                }
+                continue;
+            }
+            if !ctx.json {
+                println!(
+                    "{}",
+                    format!("正在清理 {} 缓存...", manager_name).bright_cyan()
+                );
+            }
+            match manager.clean_cache().await {
+                Ok(_) => {
+                    if !ctx.json {
+                        println!("{}", "✓ 缓存清理成功".bright_green());
+                    }
+                }
+                Err(BoxyError::UnsupportedOperation { .. }) => {
+                    if !ctx.json {
+                        println!(
+                            "{}",
+                            format!("⚠ {} 不支持缓存清理", manager_name).bright_yellow()
+                        );
+                    }
+                }
+                Err(err) => {
+                    if !ctx.json {
+                        eprintln!("{}", format!("⚠ 缓存清理失败: {}", err).bright_yellow());
+                    }
+                }
+            }
+        }
+    }
+
+    report_batch(&outcomes, &diffs, ctx.json)
+}
+
+async fn uninstall_package(ctx: &CliContext, spec: &PackageSpec, force: bool) -> Result<()> {
+    let manager_name = spec.manager.as_deref().unwrap_or_default();
+    let package = spec.name.as_str();
+    let scope_config = ctx.resolve_scope(Some(manager_name))?;
+    let global = scope_config.global;
+    let workdir = scope_config.workdir.clone();
+
+    let manager = create_manager(
+        manager_name,
+        ctx.cache.clone(),
+        global,
+        workdir.as_ref(),
+        &ctx.options,
+    )
+    .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
+
+    if !ctx.json {
+        if force {
+            println!(
+                "强制卸载 {} 从 {}...",
+                package.bright_white(),
+                manager.name().bright_cyan()
+            );
+        } else {
+            println!(
+                "卸载 {} 从 {}...",
+                package.bright_white(),
+                manager.name().bright_cyan()
+            );
+        }
+    }
+
+    let cache_key = manager.cache_key().to_string();
+    let manager_name = manager.name().to_string();
+    let workdir = workdir.clone();
+    ctx.executor
+        .execute(&manager_name, || async {
+            let manager = create_manager(
+                &manager_name,
+                ctx.cache.clone(),
+                global,
+                workdir.as_ref(),
+                &ctx.options,
+            )
+            .ok_or_else(|| BoxyError::ManagerNotFound {
+                name: manager_name.clone(),
+            })?;
+            timeout(COMMAND_TIMEOUT, manager.uninstall(package, force))
+                .await
+                .map_err(|_| BoxyError::CommandTimeout)?
+        })
+        .await
+        .map_err(|err| anyhow::anyhow!(err))
+        .context(format!("卸载 {} 失败", package))?;
+
+    ctx.cache
+        .invalidate(&cache_key)
+        .await
+        .with_context(|| format!("清除 {} 缓存失败", manager.name()))?;
+
+    if !ctx.json {
+        println!("{}", "✓ 卸载成功".bright_green());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::context::{CliContext, ScopeConfig};
+use crate::managers::create_manager;
+use crate::{READ_COMMAND_TIMEOUT, SCAN_CONCURRENCY};
+use anyhow::Result;
+use boxy_core::Package;
+use colored::*;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+
+/// 各管理器的已安装包，以及获取包列表失败的管理器
+pub struct InstalledPackages {
+    pub lists: Vec<(String, Vec<Package>)>,
+    pub failed: Vec<String>,
+}
+
+/// 并行收集各可用管理器的已安装包，按管理器名返回
+///
+/// `with_outdated` 时用 check_outdated 的结果标记可升级版本，
+/// `with_licenses` 时调用 fill_licenses 补全缺少的许可证；
+/// 列出包失败的管理器记录在 `failed` 中，由调用方决定是否视为错误。
+pub async fn collect_installed(
+    ctx: &CliContext,
+    scope_config: &ScopeConfig,
+    manager_names: &[String],
+    with_outdated: bool,
+    with_licenses: bool,
+) -> Result<InstalledPackages> {
+    let global = scope_config.global;
+    let workdir = scope_config.workdir.clone();
+
+    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+    let tasks: Vec<_> = manager_names
+        .iter()
+        .map(|name| {
+            let manager_name = name.clone();
+            let cache_clone = ctx.cache.clone();
+            let no_cache = ctx.no_cache;
+            let semaphore = semaphore.clone();
+            let workdir = workdir.clone();
+            let options = ctx.options.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.ok()?;
+                let manager = create_manager(
+                    &manager_name,
+                    cache_clone.clone(),
+                    global,
+                    workdir.as_ref(),
+                    &options,
+                )?;
+                if !manager.check_available().await.unwrap_or(false) {
+                    return None;
+                }
+                if no_cache {
+                    let _ = cache_clone.invalidate(manager.cache_key()).await;
+                }
+                let mut packages = match manager.list_installed().await {
+                    Ok(list) => list,
+                    Err(err) => {
+                        eprintln!(
+                            "{}",
+                            format!("错误: 获取 {} 包列表失败: {}", manager_name, err).bright_red()
+                        );
+                        return Some(Err(manager_name));
+                    }
+                };
+                // 检查更新需要联网，超时或失败时仍按已安装版本返回
+                if with_outdated {
+                    if let Ok(Ok(outdated)) =
+                        timeout(READ_COMMAND_TIMEOUT, manager.check_outdated()).await
+                    {
+                        for pkg in packages.iter_mut() {
+                            if let Some(latest) = outdated.iter().find(|item| item.name == pkg.name)
+                            {
+                                pkg.outdated = true;
+                                pkg.latest_version = latest.latest_version.clone();
+                            }
+                        }
+                    }
+                }
+                // 补全失败时仍按已有的许可证返回
+                if with_licenses {
+                    if let Err(err) = manager.fill_licenses(&mut packages).await {
+                        eprintln!(
+                            "{}",
+                            format!("警告: 补全 {} 的许可证失败: {}", manager_name, err)
+                                .bright_yellow()
+                        );
+                    }
+                }
+                Some(Ok((manager_name, packages)))
+            })
+        })
+        .collect();
+
+    let mut installed = InstalledPackages {
+        lists: Vec::new(),
+        failed: Vec::new(),
+    };
+    for task in tasks {
+        match task.await {
+            Ok(Some(Ok(item))) => installed.lists.push(item),
+            Ok(Some(Err(name))) => installed.failed.push(name),
+            Ok(None) => {}
+            Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+        }
+    }
+    Ok(installed)
+}
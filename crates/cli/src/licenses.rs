@@ -0,0 +1,176 @@
+use crate::context::{resolve_manager_names, CliContext};
+use crate::inventory::collect_installed;
+use crate::EXIT_ERROR;
+use anyhow::{Context, Result};
+use boxy_core::{LicensePolicy, Package, PolicyVerdict};
+use colored::*;
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// 未声明许可证的包在清单中的分组名
+const UNKNOWN_LICENSE: &str = "UNKNOWN";
+
+pub async fn cmd_licenses(
+    ctx: &CliContext,
+    manager_name: Option<&str>,
+    policy_path: Option<&str>,
+) -> Result<()> {
+    // 策略：--policy 指定的文件，或配置目录下存在的默认策略；都没有时只输出清单
+    let policy_path = match policy_path {
+        Some(path) => Some(PathBuf::from(path)),
+        None => dirs::config_dir()
+            .map(|dir| dir.join("boxy").join("licenses.toml"))
+            .filter(|path| path.is_file()),
+    };
+    let policy = match &policy_path {
+        Some(path) => Some(
+            LicensePolicy::load(path)
+                .with_context(|| format!("读取许可证策略失败: {}", path.display()))?,
+        ),
+        None => None,
+    };
+
+    let scope_config = ctx.resolve_scope(manager_name)?;
+    let manager_names = resolve_manager_names(manager_name);
+    let installed = collect_installed(ctx, &scope_config, &manager_names, false, true).await?;
+    let packages: Vec<Package> = installed
+        .lists
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect();
+
+    // 按 SPDX 表达式分组
+    let mut groups: BTreeMap<String, Vec<&Package>> = BTreeMap::new();
+    for pkg in &packages {
+        let license = pkg
+            .license
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or(UNKNOWN_LICENSE);
+        groups.entry(license.to_string()).or_default().push(pkg);
+    }
+    for list in groups.values_mut() {
+        list.sort_by(|a, b| a.manager.cmp(&b.manager).then_with(|| a.name.cmp(&b.name)));
+    }
+
+    let violations: Vec<(&Package, PolicyVerdict)> = match &policy {
+        Some(policy) => packages
+            .iter()
+            .map(|pkg| (pkg, policy.evaluate(pkg.license.as_deref())))
+            .filter(|(_, verdict)| policy.is_violation(verdict))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    if ctx.json {
+        let licenses: Vec<_> = groups
+            .iter()
+            .map(|(license, list)| {
+                serde_json::json!({
+                  "license": license,
+                  "count": list.len(),
+                  "packages": list
+                      .iter()
+                      .map(|pkg| serde_json::json!({
+                        "manager": pkg.manager,
+                        "name": pkg.name,
+                        "version": pkg.version,
+                      }))
+                      .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let output = serde_json::json!({
+          "scanned": packages.len(),
+          "policy": policy_path.as_ref().map(|path| path.display().to_string()),
+          "licenses": licenses,
+          "violations": violations
+              .iter()
+              .map(|(pkg, verdict)| serde_json::json!({
+                "manager": pkg.manager,
+                "name": pkg.name,
+                "version": pkg.version,
+                "license": pkg.license,
+                "verdict": verdict,
+              }))
+              .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        let mut sorted: Vec<_> = groups.iter().collect();
+        sorted.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+        for (license, list) in sorted {
+            let title = if license == UNKNOWN_LICENSE {
+                license.dimmed()
+            } else {
+                license.bright_cyan().bold()
+            };
+            println!("{} ({})", title, list.len());
+            for pkg in list {
+                println!(
+                    "  {} {} {}",
+                    pkg.name.bright_white(),
+                    pkg.version.dimmed(),
+                    format!("({})", pkg.manager).dimmed()
+                );
+            }
+        }
+        println!();
+        println!(
+            "已检查 {} 个包，共 {} 种许可证",
+            packages.len(),
+            groups.keys().filter(|key| *key != UNKNOWN_LICENSE).count()
+        );
+
+        if policy.is_some() {
+            if violations.is_empty() {
+                println!("{}", "✓ 所有包均符合许可证策略".bright_green());
+            } else {
+                println!();
+                println!("{}", "违反许可证策略:".bright_red().bold());
+                for (pkg, verdict) in &violations {
+                    let reason = match verdict {
+                        PolicyVerdict::Denied => "禁止使用",
+                        PolicyVerdict::NotAllowed => "不在允许列表中",
+                        PolicyVerdict::Unknown => "缺少许可证信息",
+                        PolicyVerdict::Allowed => continue,
+                    };
+                    println!(
+                        "  {} {} {} {} {}",
+                        "✗".bright_red(),
+                        pkg.name.bright_white(),
+                        format!("({})", pkg.manager).dimmed(),
+                        pkg.license
+                            .as_deref()
+                            .unwrap_or(UNKNOWN_LICENSE)
+                            .bright_yellow(),
+                        reason
+                    );
+                }
+            }
+        }
+    }
+
+    if !violations.is_empty() {
+        if !ctx.json {
+            eprintln!(
+                "{}",
+                format!("✗ {} 个包违反许可证策略", violations.len()).bright_red()
+            );
+        }
+        std::process::exit(EXIT_ERROR);
+    }
+    // 有管理器未能列出包时清单不完整，不能当作符合策略
+    if !installed.failed.is_empty() {
+        eprintln!(
+            "{}",
+            format!(
+                "✗ 以下包管理器的包列表获取失败，许可证清单不完整: {}",
+                installed.failed.join(", ")
+            )
+            .bright_red()
+        );
+        std::process::exit(EXIT_ERROR);
+    }
+    Ok(())
+}
@@ -0,0 +1,294 @@
+use crate::context::{resolve_manager_names, run_with_timeout, CliContext};
+use crate::managers::create_manager;
+use crate::{EXIT_ERROR, SCAN_CONCURRENCY};
+use anyhow::{Context, Result};
+use boxy_core::{DependencyType, Package};
+use colored::*;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+pub async fn cmd_list(ctx: &CliContext, manager_name: Option<&str>) -> Result<()> {
+    run_with_timeout("列表操作超时", async {
+        let scope_config = ctx.resolve_scope(manager_name)?;
+        let global = scope_config.global;
+        let workdir = scope_config.workdir.clone();
+        let manager_names = resolve_manager_names(manager_name);
+
+        // 并行扫描所有管理器
+        let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+        let tasks: Vec<_> = manager_names
+            .iter()
+            .map(|name| {
+                let manager_name = name.clone();
+                let cache_clone = ctx.cache.clone();
+                let no_cache = ctx.no_cache;
+                let semaphore = semaphore.clone();
+                let workdir = workdir.clone();
+                let options = ctx.options.clone();
+                tokio::spawn(async move {
+                    let _permit = match semaphore.acquire().await {
+                        Ok(permit) => permit,
+                        Err(_) => {
+                            return Ok((manager_name, Vec::new(), false));
+                        }
+                    };
+                    let result: Result<(String, Vec<boxy_core::Package>, bool)> = {
+                        let manager = create_manager(
+                            &manager_name,
+                            cache_clone.clone(),
+                            global,
+                            workdir.as_ref(),
+                            &options,
+                        );
+                        if let Some(m) = manager {
+                            let available = m.check_available().await.unwrap_or(false);
+                            if !available {
+                                Ok((manager_name, Vec::new(), false))
+                            } else {
+                                // 只有在 no_cache 为 true 时才清除缓存
+                                if no_cache {
+                                    cache_clone.invalidate(m.cache_key()).await.with_context(
+                                        || format!("清除 {} 缓存失败", manager_name),
+                                    )?;
+                                }
+                                let packages = m
+                                    .list_installed()
+                                    .await
+                                    .with_context(|| format!("获取 {} 包列表失败", manager_name))?;
+
+                                Ok((manager_name, packages, true))
+                            }
+                        } else {
+                            Ok((manager_name, Vec::new(), false))
+                        }
+                    };
+                    result
+                })
+            })
+            .collect();
+
+        let mut all_packages = Vec::new();
+        for task in tasks {
+            match task.await {
+                Ok(Ok((name, packages, available))) => {
+                    all_packages.push((name, packages, available))
+                }
+                Ok(Err(err)) => return Err(err),
+                Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+            }
+        }
+
+        if ctx.json {
+            let output: Vec<serde_json::Value> = all_packages
+                .into_iter()
+                .map(|(manager, packages, available)| {
+                    serde_json::json!({
+                      "manager": manager,
+                      "available": available,
+                      "packages": packages,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            let mut has_output = false;
+            for (manager, packages, available) in all_packages {
+                if !available {
+                    println!("{}", format!("{}: 不可用", manager.bright_cyan()).dimmed());
+                    has_output = true;
+                } else if packages.is_empty() {
+                    println!(
+                        "{}",
+                        format!("{}: 没有安装任何包", manager.bright_cyan()).dimmed()
+                    );
+                    has_output = true;
+                } else {
+                    println!(
+                        "{}",
+                        format!("{} ({})", manager.bright_cyan(), packages.len()).bold()
+                    );
+                    print_package_list(&packages);
+                    println!();
+                    has_output = true;
+                }
+            }
+            if !has_output {
+                eprintln!("{}", "错误: 没有找到任何包管理器".bright_red());
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+pub async fn cmd_info(ctx: &CliContext, package: &str, manager_name: Option<&str>) -> Result<()> {
+    run_with_timeout("查询信息超时", async {
+        let scope_config = ctx.resolve_scope(manager_name)?;
+        let global = scope_config.global;
+        let workdir = scope_config.workdir.clone();
+        let manager_names = resolve_manager_names(manager_name);
+
+        // 并行搜索所有管理器
+        let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+        let tasks: Vec<_> = manager_names
+            .iter()
+            .map(|name| {
+                let manager_name = name.clone();
+                let cache_clone = ctx.cache.clone();
+                let pkg_name = package.to_string();
+                let semaphore = semaphore.clone();
+                let workdir = workdir.clone();
+                let options = ctx.options.clone();
+                tokio::spawn(async move {
+                    let _permit = match semaphore.acquire().await {
+                        Ok(permit) => permit,
+                        Err(_) => return Ok(None),
+                    };
+                    let manager = create_manager(
+                        &manager_name,
+                        cache_clone,
+                        global,
+                        workdir.as_ref(),
+                        &options,
+                    );
+                    if let Some(m) = manager {
+                        if !m.check_available().await.unwrap_or(false) {
+                            return Ok(None);
+                        }
+
+                        let info = m
+                            .get_info(&pkg_name)
+                            .await
+                            .with_context(|| format!("获取 {} 包信息失败", manager_name))?;
+                        Ok(Some((manager_name, info)))
+                    } else {
+                        Ok(None)
+                    }
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for task in tasks {
+            match task.await {
+                Ok(Ok(Some((manager, pkg)))) => results.push((manager, pkg)),
+                Ok(Ok(None)) => {}
+                Ok(Err(err)) => return Err(err),
+                Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+            }
+        }
+
+        if results.is_empty() {
+            eprintln!("{}", format!("错误: 未找到包 '{}'", package).bright_red());
+            std::process::exit(EXIT_ERROR);
+        }
+
+        if ctx.json {
+            let output: Vec<serde_json::Value> = results
+                .into_iter()
+                .map(|(manager, pkg)| {
+                    serde_json::json!({
+                      "manager": manager,
+                      "purl": pkg.purl(),
+                      "package": pkg,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            for (manager, pkg) in results {
+                println!(
+                    "{}",
+                    format!("{} ({})", pkg.name.bright_cyan(), manager).bold()
+                );
+                if !pkg.version.is_empty() {
+                    let yanked = if pkg.yanked {
+                        format!(" {}", "(已撤回)".bright_red())
+                    } else {
+                        String::new()
+                    };
+                    println!("  版本: {}{}", pkg.version.bright_white(), yanked);
+                }
+                if let Some(latest) = pkg.latest_version.as_ref().filter(|v| **v != pkg.version) {
+                    println!("  最新: {}", latest.bright_green());
+                }
+                if let Some(source) = &pkg.source {
+                    println!("  来源: {}", source);
+                }
+                if let Some(tap) = &pkg.tap {
+                    let kind = if pkg.cask { "cask" } else { "formula" };
+                    println!("  tap: {} ({})", tap, kind);
+                }
+                if let Some(desc) = &pkg.description {
+                    println!("  描述: {}", desc);
+                }
+                if let Some(homepage) = &pkg.homepage {
+                    println!("  主页: {}", homepage.bright_blue());
+                }
+                if let Some(license) = &pkg.license {
+                    println!("  许可证: {}", license);
+                }
+                for flag in &pkg.flags {
+                    println!("  {}", flag.description().dimmed());
+                }
+                println!("  purl: {}", pkg.purl().dimmed());
+                println!();
+            }
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// 输出包列表；本地项目的依赖按清单中的类型分组
+fn print_package_list(packages: &[Package]) {
+    let print = |pkg: &Package| {
+        println!("  {} {}", "•".bright_green(), pkg.name.bright_white());
+        if !pkg.version.is_empty() {
+            println!("    {}", format!("版本: {}", pkg.version).dimmed());
+        }
+        if let Some(tap) = pkg
+            .tap
+            .as_ref()
+            .filter(|_| pkg.qualified_name() != pkg.name)
+        {
+            println!("    {}", format!("tap: {}", tap).dimmed());
+        }
+        for flag in &pkg.flags {
+            if flag.is_problem() {
+                println!(
+                    "    {}",
+                    format!("⚠ {}", flag.description()).bright_yellow()
+                );
+            } else {
+                println!("    {}", flag.description().dimmed());
+            }
+        }
+    };
+
+    if packages.iter().all(|pkg| pkg.dependency_type.is_none()) {
+        packages.iter().for_each(print);
+        return;
+    }
+    let groups = [
+        (Some(DependencyType::Prod), "依赖"),
+        (Some(DependencyType::Dev), "开发依赖"),
+        (Some(DependencyType::Optional), "可选依赖"),
+        (Some(DependencyType::Peer), "peer 依赖"),
+        (None, "未声明"),
+    ];
+    for (dependency_type, title) in groups {
+        let group: Vec<&Package> = packages
+            .iter()
+            .filter(|pkg| pkg.dependency_type == dependency_type)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        println!(" {}", format!("{} ({})", title, group.len()).bright_white());
+        group.into_iter().for_each(print);
+    }
+}
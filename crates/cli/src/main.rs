@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
+use boxy_brew::BrewScope;
 use boxy_cache::Cache;
-use boxy_core::ManagerExecutor;
-use boxy_error::BoxyError;
+use boxy_core::envs::DEFAULT_MAX_DEPTH;
+use boxy_core::project::DEFAULT_SCAN_DEPTH;
+use boxy_core::{DependencyType, ManagerExecutor, PackageSpec};
 use clap::{Parser, Subcommand};
 use colored::*;
 #[cfg(target_os = "macos")]
@@ -10,20 +12,57 @@ use std::collections::HashSet;
 use std::env;
 #[cfg(target_os = "macos")]
 use std::process::Command;
-use std::{path::PathBuf, sync::Arc};
-use tokio::sync::Semaphore;
-use tokio::time::{timeout, Duration};
-
+use std::sync::Arc;
+use tokio::time::Duration;
+
+mod audit;
+mod cache;
+mod context;
+mod diff;
+mod du;
+mod envs;
+mod install;
+mod inventory;
+mod licenses;
+mod list;
 mod managers;
-
-use managers::{create_manager, supports_global, MANAGER_NAMES};
+mod outdated;
+mod pipx;
+mod project;
+mod sbom;
+mod scan;
+mod search;
+mod taps;
+mod transaction;
+
+use audit::cmd_audit;
+use cache::{cmd_cache_clean, cmd_cache_size};
+use context::{resolve_python_scope, CliContext};
+use du::cmd_du;
+use envs::cmd_envs;
+use install::{cmd_install, cmd_uninstall, cmd_update};
+use licenses::cmd_licenses;
+use list::{cmd_info, cmd_list};
+use managers::ManagerOptions;
+use outdated::cmd_outdated;
+use pipx::cmd_pipx;
+use project::{cmd_add, cmd_project, cmd_remove};
+use sbom::cmd_sbom;
+use scan::{cmd_scan, cmd_scan_projects};
+use search::cmd_search;
+use taps::cmd_brew;
 
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
 const READ_COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+/// 合并搜索中单个包管理器的时限，需短于整体的 READ_COMMAND_TIMEOUT
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(30);
 const SCAN_CONCURRENCY: usize = 10;  // 提高并发度以加速扫描
 const EXIT_ERROR: i32 = 1;
 const EXIT_USAGE: i32 = 2;
 
+/// 批量操作中部分包失败
+const EXIT_PARTIAL: i32 = 3;
+
 #[derive(Parser, Debug)]
 #[command(name = "boxy", version = env!("CARGO_PKG_VERSION"), about = "macOS 统一包管理器")]
 struct Cli {
@@ -40,14 +79,22 @@ struct Cli {
     #[arg(long, global = true)]
     global: bool,
 
-    /// 包范围（global 或 local）
+    /// 包范围（global 或 local；brew 为 formula 或 cask）
     #[arg(long, global = true)]
     scope: Option<String>,
 
-    /// 本地范围目录（配合 --scope=local）
+    /// 本地范围目录（配合 --scope=local）；pip、uv 为虚拟环境或包含 .venv 的目录，cargo 为 Cargo 项目目录
     #[arg(long, global = true)]
     dir: Option<String>,
 
+    /// 指定 Python 解释器或环境目录（针对 pip、uv）
+    #[arg(long, global = true, value_name = "PATH")]
+    python: Option<String>,
+
+    /// 使用用户 site-packages（针对 pip）
+    #[arg(long, global = true)]
+    user: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -59,6 +106,12 @@ enum Commands {
         /// 只显示可用的包管理器
         #[arg(long)]
         available_only: bool,
+        /// 扫描目录下的全部项目，按项目列出依赖与过时情况并汇总
+        #[arg(long, value_name = "DIR")]
+        projects: Option<String>,
+        /// 查找项目的最大目录深度（配合 --projects）
+        #[arg(long, default_value_t = DEFAULT_SCAN_DEPTH)]
+        depth: usize,
     },
     /// 列出已安装的包
     List {
@@ -68,7 +121,7 @@ enum Commands {
     },
     /// 查看包详情
     Info {
-        /// 包名
+        /// 包名、包描述（npm:typescript）或 purl
         package: String,
         /// 指定包管理器
         #[arg(short, long)]
@@ -81,12 +134,16 @@ enum Commands {
         /// 指定包管理器
         #[arg(short, long)]
         manager: Option<String>,
+        /// 按包管理器分别列出结果，不合并排序
+        #[arg(long)]
+        by_manager: bool,
     },
     /// 安装包
     Install {
-        /// 包名
-        package: String,
-        /// 版本（可选）
+        /// 包描述，可指定多个（如 npm:typescript@^5、pip:black==24.*、pkg:cargo/ripgrep@14）
+        #[arg(required = true)]
+        packages: Vec<String>,
+        /// 版本（可选，仅用于单个包）
         #[arg(long)]
         version: Option<String>,
         /// 指定包管理器
@@ -95,19 +152,29 @@ enum Commands {
         /// 强制安装
         #[arg(short, long)]
         force: bool,
+        /// 事务模式：任一包失败或按下 Ctrl-C 时回滚已完成的步骤
+        #[arg(long)]
+        transaction: bool,
     },
     /// 更新包
     Update {
-        /// 包名（可选，不指定则更新所有）
+        /// 包名、包描述或 purl（可选，不指定则更新所有）
         package: Option<String>,
         /// 指定包管理器
         #[arg(short, long)]
         manager: Option<String>,
+        /// 事务模式：任一包失败或按下 Ctrl-C 时回滚已完成的步骤
+        #[arg(long)]
+        transaction: bool,
+        /// 升级到最新版本并修改项目清单中的版本范围（默认只在范围内更新）
+        #[arg(long)]
+        latest: bool,
     },
     /// 卸载包
     Uninstall {
-        /// 包名
-        package: String,
+        /// 包描述，可指定多个（如 npm:typescript、brew:wget、pkg:cargo/ripgrep）
+        #[arg(required = true)]
+        packages: Vec<String>,
         /// 指定包管理器
         #[arg(short, long)]
         manager: Option<String>,
@@ -118,12 +185,195 @@ enum Commands {
         #[arg(long)]
         keep_cache: bool,
     },
-    /// 列出可更新的包
+    /// 向当前项目添加依赖（按清单文件识别项目与包管理器，如 pnpm add、uv add、cargo add）
+    Add {
+        /// 包名，可带版本（如 lodash@^4、black==24.*、serde@1）
+        #[arg(required = true)]
+        packages: Vec<String>,
+        /// 指定包管理器（项目中有多种清单时使用）
+        #[arg(short, long)]
+        manager: Option<String>,
+        /// 添加为开发依赖
+        #[arg(short = 'D', long, conflicts_with_all = ["optional", "peer"])]
+        dev: bool,
+        /// 添加为可选依赖
+        #[arg(short = 'O', long, conflicts_with = "peer")]
+        optional: bool,
+        /// 添加为 peer 依赖
+        #[arg(long)]
+        peer: bool,
+        /// 工作区成员（目录或目录名），默认为当前目录所在的成员
+        #[arg(long)]
+        member: Option<String>,
+    },
+    /// 从当前项目的清单中移除依赖
+    Remove {
+        /// 包名，可指定多个
+        #[arg(required = true)]
+        packages: Vec<String>,
+        /// 指定包管理器（项目中有多种清单时使用）
+        #[arg(short, long)]
+        manager: Option<String>,
+        /// 工作区成员（目录或目录名），默认为当前目录所在的成员
+        #[arg(long)]
+        member: Option<String>,
+    },
+    /// 列出可更新的包（在项目目录中未指定 -m、--global、--scope 时检查项目的依赖）
     Outdated {
         /// 指定包管理器
         #[arg(short, long)]
         manager: Option<String>,
     },
+    /// 显示当前目录识别出的项目、包管理器与工作区成员
+    Project,
+    /// 统计磁盘占用（已安装包与可回收的下载缓存）
+    Du {
+        /// 指定包管理器
+        #[arg(short, long)]
+        manager: Option<String>,
+        /// 显示占用最大的前 N 个包
+        #[arg(short = 'n', long, default_value_t = 10)]
+        top: usize,
+    },
+    /// 离线漏洞审计（基于 OSV 数据库）
+    Audit {
+        /// 指定包管理器
+        #[arg(short, long)]
+        manager: Option<String>,
+        /// 使用本地 OSV 数据目录（默认为 boxy 数据目录下的 osv）
+        #[arg(long)]
+        db: Option<String>,
+        /// 重新下载 OSV 数据库
+        #[arg(long)]
+        update_db: bool,
+        /// 输出格式（table、json、sarif）
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// 存在不低于该等级的漏洞时以非零退出码结束（low、medium、high、critical）
+        #[arg(long)]
+        fail_on: Option<String>,
+    },
+    /// 许可证清单与策略检查
+    Licenses {
+        /// 指定包管理器
+        #[arg(short, long)]
+        manager: Option<String>,
+        /// 许可证策略文件（默认为配置目录下的 boxy/licenses.toml）
+        #[arg(long)]
+        policy: Option<String>,
+    },
+    /// 导出软件物料清单（SBOM）
+    Sbom {
+        /// 指定包管理器
+        #[arg(short, long)]
+        manager: Option<String>,
+        /// 输出格式（cyclonedx、spdx）
+        #[arg(long, default_value = "cyclonedx")]
+        format: String,
+        /// 写入文件而不是标准输出
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// 查看与清理包管理器的下载缓存
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Homebrew 专用操作
+    Brew {
+        #[command(subcommand)]
+        action: BrewAction,
+    },
+    /// 管理 pipx 应用的虚拟环境
+    Pipx {
+        #[command(subcommand)]
+        action: PipxAction,
+    },
+    /// 发现 Python 虚拟环境、conda、pipx 与 uv 环境
+    Envs {
+        /// 扫描的根目录，可重复指定（默认读取 BOXY_ENV_ROOTS，未设置时为用户目录）
+        #[arg(long)]
+        root: Vec<String>,
+        /// 最大扫描深度
+        #[arg(long, default_value_t = DEFAULT_MAX_DEPTH)]
+        depth: usize,
+        /// 只显示失效的环境
+        #[arg(long)]
+        stale: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BrewAction {
+    /// 管理 tap（不指定操作时列出已添加的 tap）
+    Taps {
+        #[command(subcommand)]
+        action: Option<TapAction>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TapAction {
+    /// 列出已添加的 tap 及从中安装的包
+    List,
+    /// 添加 tap
+    Add {
+        /// tap 名，如 user/repo
+        name: String,
+        /// 仓库地址（不在 GitHub 上的 user/homebrew-repo 时需要）
+        url: Option<String>,
+    },
+    /// 移除 tap
+    Remove {
+        /// tap 名，如 user/repo
+        name: String,
+        /// 仍有从该 tap 安装的包时也强制移除
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PipxAction {
+    /// 列出应用的虚拟环境、命令、注入的包与 Python 版本
+    List,
+    /// 向应用的虚拟环境注入额外的包
+    Inject {
+        /// 应用（虚拟环境）名
+        app: String,
+        /// 要注入的包
+        #[arg(required = true)]
+        packages: Vec<String>,
+    },
+    /// 重建应用的虚拟环境
+    Reinstall {
+        /// 应用（虚拟环境）名
+        app: String,
+    },
+    /// 重建所有应用的虚拟环境（如 Python 升级后）
+    ReinstallAll,
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheAction {
+    /// 查看各包管理器的缓存占用
+    Size {
+        /// 指定包管理器
+        #[arg(short, long)]
+        manager: Option<String>,
+    },
+    /// 清理缓存
+    Clean {
+        /// 指定包管理器
+        #[arg(short, long)]
+        manager: Option<String>,
+        /// 只清理早于该时长的缓存条目（如 30d、12h、2w）；npm、uv 改为回收不再被引用的内容
+        #[arg(long)]
+        older_than: Option<String>,
+        /// 只统计将释放的空间，不实际删除
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
@@ -142,126 +392,212 @@ async fn main() -> Result<()> {
             .init();
     }
 
+    let options = ManagerOptions {
+        python: resolve_python_scope(cli.python.as_deref(), cli.user, cli.dir.as_deref())?,
+        brew: match cli.scope.as_deref().map(str::to_lowercase).as_deref() {
+            Some("formula") => Some(BrewScope::Formula),
+            Some("cask") => Some(BrewScope::Cask),
+            _ => None,
+        },
+    };
+
     // 创建缓存
     let cache = Arc::new(Cache::new().context("创建缓存失败")?);
-    let executor = Arc::new(ManagerExecutor::default());
+    let ctx = CliContext {
+        cache,
+        executor: Arc::new(ManagerExecutor::default()),
+        global: cli.global,
+        scope: cli.scope,
+        directory: cli.dir,
+        json: cli.json,
+        no_cache: cli.no_cache,
+        options,
+    };
 
     // 执行命令
     match cli.command {
-        Commands::Scan { available_only } => {
-            cmd_scan(
-                cache,
-                cli.global,
-                cli.scope.as_deref(),
-                cli.dir.as_deref(),
-                available_only,
-                cli.json,
-                cli.no_cache,
-            )
-            .await
-        }
-        Commands::List { manager } => {
-            cmd_list(
-                cache,
-                cli.global,
-                cli.scope.as_deref(),
-                cli.dir.as_deref(),
-                manager.as_deref(),
-                cli.json,
-                cli.no_cache,
-            )
-            .await
-        }
+        Commands::Scan {
+            projects: Some(root),
+            depth,
+            ..
+        } => cmd_scan_projects(&ctx, &root, depth).await,
+        Commands::Scan { available_only, .. } => cmd_scan(&ctx, available_only).await,
+        Commands::List { manager } => cmd_list(&ctx, manager.as_deref()).await,
         Commands::Info { package, manager } => {
-            cmd_info(
-                cache,
-                cli.global,
-                cli.scope.as_deref(),
-                cli.dir.as_deref(),
-                &package,
-                manager.as_deref(),
-                cli.json,
-            )
-            .await
-        }
-        Commands::Search { query, manager } => {
-            cmd_search(
-                cache,
-                cli.global,
-                cli.scope.as_deref(),
-                cli.dir.as_deref(),
-                &query,
-                manager.as_deref(),
-                cli.json,
-            )
-            .await
+            let (package, manager, _) = package_target(&package, manager);
+            cmd_info(&ctx, &package, manager.as_deref()).await
         }
+        Commands::Search {
+            query,
+            manager,
+            by_manager,
+        } => cmd_search(&ctx, &query, manager.as_deref(), by_manager).await,
         Commands::Install {
-            package,
+            packages,
             version,
             manager,
             force,
+            transaction,
         } => {
-            cmd_install(
-                cache,
-                executor.clone(),
-                cli.global,
-                cli.scope.as_deref(),
-                cli.dir.as_deref(),
-                &package,
-                version.as_deref(),
-                manager.as_deref(),
-                force,
-                cli.json,
-            )
-            .await
+            let specs = package_specs(&packages, manager.as_deref(), version);
+            cmd_install(&ctx, &specs, force, transaction).await
         }
-        Commands::Update { package, manager } => {
+        Commands::Update {
+            package,
+            manager,
+            transaction,
+            latest,
+        } => {
+            let (package, manager) = match package {
+                Some(package) => {
+                    let (package, manager, _) = package_target(&package, manager);
+                    (Some(package), manager)
+                }
+                None => (None, manager),
+            };
             cmd_update(
-                cache,
-                executor.clone(),
-                cli.global,
-                cli.scope.as_deref(),
-                cli.dir.as_deref(),
+                &ctx,
                 package.as_deref(),
                 manager.as_deref(),
-                cli.json,
+                transaction,
+                latest,
             )
             .await
         }
         Commands::Uninstall {
-            package,
+            packages,
             manager,
             force,
             keep_cache,
         } => {
-            cmd_uninstall(
-                cache,
-                executor.clone(),
-                cli.global,
-                cli.scope.as_deref(),
-                cli.dir.as_deref(),
-                &package,
+            let specs = package_specs(&packages, manager.as_deref(), None);
+            // 反转逻辑：默认清理，--keep-cache 跳过
+            cmd_uninstall(&ctx, &specs, force, !keep_cache).await
+        }
+        Commands::Add {
+            packages,
+            manager,
+            dev,
+            optional,
+            peer,
+            member,
+        } => {
+            let dependency_type = if dev {
+                DependencyType::Dev
+            } else if optional {
+                DependencyType::Optional
+            } else if peer {
+                DependencyType::Peer
+            } else {
+                DependencyType::Prod
+            };
+            cmd_add(
+                &ctx,
+                &packages,
                 manager.as_deref(),
-                force,
-                !keep_cache,  // 反转逻辑：默认清理，--keep-cache 跳过
-                cli.json,
+                dependency_type,
+                member.as_deref(),
             )
             .await
         }
-        Commands::Outdated { manager } => {
-            cmd_outdated(
-                cache,
-                cli.global,
-                cli.scope.as_deref(),
-                cli.dir.as_deref(),
+        Commands::Remove {
+            packages,
+            manager,
+            member,
+        } => cmd_remove(&ctx, &packages, manager.as_deref(), member.as_deref()).await,
+        Commands::Project => cmd_project(&ctx),
+        Commands::Outdated { manager } => cmd_outdated(&ctx, manager.as_deref()).await,
+        Commands::Du { manager, top } => cmd_du(&ctx, manager.as_deref(), top).await,
+        Commands::Audit {
+            manager,
+            db,
+            update_db,
+            format,
+            fail_on,
+        } => {
+            cmd_audit(
+                &ctx,
                 manager.as_deref(),
-                cli.json,
-                cli.no_cache,
+                db.as_deref(),
+                update_db,
+                if cli.json { "json" } else { format.as_str() },
+                fail_on.as_deref(),
             )
             .await
         }
+        Commands::Licenses { manager, policy } => {
+            cmd_licenses(&ctx, manager.as_deref(), policy.as_deref()).await
+        }
+        Commands::Sbom {
+            manager,
+            format,
+            output,
+        } => cmd_sbom(&ctx, manager.as_deref(), &format, output.as_deref()).await,
+        Commands::Cache { action } => match action {
+            CacheAction::Size { manager } => cmd_cache_size(&ctx, manager.as_deref()).await,
+            CacheAction::Clean {
+                manager,
+                older_than,
+                dry_run,
+            } => cmd_cache_clean(&ctx, manager.as_deref(), older_than.as_deref(), dry_run).await,
+        },
+        Commands::Brew { action } => cmd_brew(&ctx, action).await,
+        Commands::Pipx { action } => cmd_pipx(&ctx, action).await,
+        Commands::Envs { root, depth, stale } => cmd_envs(&root, depth, stale, cli.json).await,
+    }
+}
+
+/// 解析命令行中的单个包参数
+///
+/// 支持普通包名、包描述（`npm:typescript@^5`）与 purl（`pkg:cargo/ripgrep@14`），
+/// 返回包名、管理器与版本；未指定管理器时保持为空，由调用方决定是否在所有管理器中查找。
+fn package_target(
+    package: &str,
+    manager: Option<String>,
+) -> (String, Option<String>, Option<String>) {
+    match PackageSpec::parse(package).and_then(|spec| spec.with_manager(manager.as_deref())) {
+        Ok(spec) => (spec.name, spec.manager, spec.version),
+        Err(err) => {
+            eprintln!("{}", format!("错误: {}", err).bright_red());
+            std::process::exit(EXIT_USAGE);
+        }
+    }
+}
+
+/// 解析安装、卸载的包描述列表，每个包都必须能确定包管理器
+fn package_specs(
+    inputs: &[String],
+    manager: Option<&str>,
+    version: Option<String>,
+) -> Vec<PackageSpec> {
+    if version.is_some() && inputs.len() > 1 {
+        eprintln!("{}", "错误: --version 只能用于单个包".bright_red());
+        std::process::exit(EXIT_USAGE);
     }
+    inputs
+        .iter()
+        .map(|input| {
+            let mut spec = match PackageSpec::parse(input).and_then(|spec| spec.with_manager(manager)) {
+                Ok(spec) => spec,
+                Err(err) => {
+                    eprintln!("{}", format!("错误: {}", err).bright_red());
+                    std::process::exit(EXIT_USAGE);
+                }
+            };
+            if spec.manager.is_none() {
+                eprintln!(
+                    "{}",
+                    format!("错误: 必须指定包管理器（{}，使用 manager:name 或 -m）", input)
+                        .bright_red()
+                );
+                std::process::exit(EXIT_USAGE);
+            }
+            if spec.version.is_none() {
+                spec.version = version.clone();
+            }
+            spec
+        })
+        .collect()
 }
 
 fn ensure_macos_path() {
@@ -343,993 +679,3 @@ fn resolve_login_shell_path() -> Result<String, String> {
     }
     Ok(value)
 }
-
-async fn cmd_scan(
-    cache: Arc<Cache>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-    available_only: bool,
-    json: bool,
-    no_cache: bool,
-) -> Result<()> {
-    run_with_timeout("扫描操作超时", async {
-    let scope_config = resolve_scope(None, global, scope, directory)?;
-    let global = scope_config.global;
-    let workdir = scope_config.workdir.clone();
-    if json {
-        let mut results = Vec::new();
-        for name in MANAGER_NAMES.iter() {
-            let cache_clone = cache.clone();
-            let manager_name = name.to_string();
-            let manager = create_manager(&manager_name, cache_clone.clone(), global, workdir.as_ref());
-            if let Some(m) = manager {
-                let available = m.check_available().await.unwrap_or(false);
-                if available_only && !available {
-                    continue;
-                }
-
-                let packages = if available {
-                    // 只有在 no_cache 为 true 时才清除缓存
-                    if no_cache {
-                        if let Err(err) = cache_clone.invalidate(m.cache_key()).await {
-                            eprintln!(
-                                "{}",
-                                format!("错误: 清除 {} 缓存失败: {}", manager_name, err)
-                                    .bright_red()
-                            );
-                        }
-                    }
-                    match m.list_installed().await {
-                        Ok(list) => list,
-                        Err(err) => {
-                            eprintln!(
-                                "{}",
-                                format!("错误: 获取 {} 包列表失败: {}", manager_name, err)
-                                    .bright_red()
-                            );
-                            Vec::new()
-                        }
-                    }
-                } else {
-                    Vec::new()
-                };
-
-                results.push(serde_json::json!({
-                  "manager": manager_name,
-                  "available": available,
-                  "package_count": packages.len(),
-                }));
-            }
-        }
-        println!("{}", serde_json::to_string_pretty(&results)?);
-    } else {
-        println!("{}", "扫描所有包管理器...".bright_cyan());
-        println!();
-
-        // 并行检查所有管理器
-        let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
-        let tasks: Vec<_> = MANAGER_NAMES
-            .iter()
-            .map(|name| {
-                let cache_clone = cache.clone();
-                let manager_name = name.to_string();
-                let semaphore = semaphore.clone();
-                let workdir = workdir.clone();
-                tokio::spawn(async move {
-                    let _permit = match semaphore.acquire().await {
-                        Ok(permit) => permit,
-                        Err(_) => return (manager_name, false),
-                    };
-                    let manager =
-                        create_manager(&manager_name, cache_clone, global, workdir.as_ref());
-                    if let Some(m) = manager {
-                        let available = m.check_available().await.unwrap_or(false);
-                        (manager_name, available)
-                    } else {
-                        (manager_name, false)
-                    }
-                })
-            })
-            .collect();
-
-        let mut statuses = Vec::new();
-        for task in tasks {
-            if let Ok((name, available)) = task.await {
-                statuses.push((name, available));
-            }
-        }
-
-        for (name, available) in statuses {
-            if available_only && !available {
-                continue;
-            }
-
-            let status = if available {
-                "✓".bright_green()
-            } else {
-                "✗".bright_red()
-            };
-            println!("  {} {}", status, name.bright_white());
-
-            if available {
-                let cache_clone = cache.clone();
-                let manager = create_manager(&name, cache_clone.clone(), global, workdir.as_ref());
-                if let Some(m) = manager {
-                    // 只有在 no_cache 为 true 时才清除缓存
-                    if no_cache {
-                        if let Err(err) = cache_clone.invalidate(m.cache_key()).await {
-                            eprintln!(
-                                "{}",
-                                format!("错误: 清除 {} 缓存失败: {}", name, err).bright_red()
-                            );
-                        }
-                    }
-                    if let Ok(Ok(packages)) =
-                        timeout(Duration::from_secs(5), m.list_installed()).await
-                    {
-                        println!("    {} 已安装包: {}", "→".bright_blue(), packages.len());
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(())
-    })
-    .await
-}
-
-async fn cmd_list(
-    cache: Arc<Cache>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-    manager_name: Option<&str>,
-    json: bool,
-    no_cache: bool,
-) -> Result<()> {
-    run_with_timeout("列表操作超时", async {
-    let scope_config = resolve_scope(manager_name, global, scope, directory)?;
-    let global = scope_config.global;
-    let workdir = scope_config.workdir.clone();
-    let manager_names = resolve_manager_names(manager_name);
-
-    // 并行扫描所有管理器
-    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
-    let tasks: Vec<_> = manager_names
-        .iter()
-        .map(|name| {
-            let manager_name = name.clone();
-            let cache_clone = cache.clone();
-            let semaphore = semaphore.clone();
-            let workdir = workdir.clone();
-            tokio::spawn(async move {
-                let _permit = match semaphore.acquire().await {
-                    Ok(permit) => permit,
-                    Err(_) => {
-                        return Ok((manager_name, Vec::new(), false));
-                    }
-                };
-                let result: Result<(String, Vec<boxy_core::Package>, bool)> = {
-                    let manager =
-                        create_manager(&manager_name, cache_clone.clone(), global, workdir.as_ref());
-                    if let Some(m) = manager {
-                        let available = m.check_available().await.unwrap_or(false);
-                        if !available {
-                            Ok((manager_name, Vec::new(), false))
-                        } else {
-                            // 只有在 no_cache 为 true 时才清除缓存
-                            if no_cache {
-                                cache_clone
-                                    .invalidate(m.cache_key())
-                                    .await
-                                    .with_context(|| format!("清除 {} 缓存失败", manager_name))?;
-                            }
-                            let packages = m
-                                .list_installed()
-                                .await
-                                .with_context(|| format!("获取 {} 包列表失败", manager_name))?;
-
-                            Ok((manager_name, packages, true))
-                        }
-                    } else {
-                        Ok((manager_name, Vec::new(), false))
-                    }
-                };
-                result
-            })
-        })
-        .collect();
-
-    let mut all_packages = Vec::new();
-    for task in tasks {
-        match task.await {
-            Ok(Ok((name, packages, available))) => all_packages.push((name, packages, available)),
-            Ok(Err(err)) => return Err(err),
-            Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
-        }
-    }
-
-    if json {
-        let output: Vec<serde_json::Value> = all_packages
-            .into_iter()
-            .map(|(manager, packages, available)| {
-                serde_json::json!({
-                  "manager": manager,
-                  "available": available,
-                  "packages": packages,
-                })
-            })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        let mut has_output = false;
-        for (manager, packages, available) in all_packages {
-            if !available {
-                println!(
-                    "{}",
-                    format!("{}: 不可用", manager.bright_cyan()).dimmed()
-                );
-                has_output = true;
-            } else if packages.is_empty() {
-                println!(
-                    "{}",
-                    format!("{}: 没有安装任何包", manager.bright_cyan()).dimmed()
-                );
-                has_output = true;
-            } else {
-                println!(
-                    "{}",
-                    format!("{} ({})", manager.bright_cyan(), packages.len()).bold()
-                );
-                for pkg in packages {
-                    println!("  {} {}", "•".bright_green(), pkg.name.bright_white());
-                    if !pkg.version.is_empty() {
-                        println!("    {}", format!("版本: {}", pkg.version).dimmed());
-                    }
-                }
-                println!();
-                has_output = true;
-            }
-        }
-        if !has_output {
-            eprintln!("{}", "错误: 没有找到任何包管理器".bright_red());
-            std::process::exit(EXIT_ERROR);
-        }
-    }
-
-    Ok(())
-    })
-    .await
-}
-
-async fn cmd_info(
-    cache: Arc<Cache>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-    package: &str,
-    manager_name: Option<&str>,
-    json: bool,
-) -> Result<()> {
-    run_with_timeout("查询信息超时", async {
-    let scope_config = resolve_scope(manager_name, global, scope, directory)?;
-    let global = scope_config.global;
-    let workdir = scope_config.workdir.clone();
-    let manager_names = resolve_manager_names(manager_name);
-
-    // 并行搜索所有管理器
-    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
-    let tasks: Vec<_> = manager_names
-        .iter()
-        .map(|name| {
-            let manager_name = name.clone();
-            let cache_clone = cache.clone();
-            let pkg_name = package.to_string();
-            let semaphore = semaphore.clone();
-            let workdir = workdir.clone();
-            tokio::spawn(async move {
-                let _permit = match semaphore.acquire().await {
-                    Ok(permit) => permit,
-                    Err(_) => return Ok(None),
-                };
-                let manager = create_manager(&manager_name, cache_clone, global, workdir.as_ref());
-                if let Some(m) = manager {
-                    if !m.check_available().await.unwrap_or(false) {
-                        return Ok(None);
-                    }
-
-                    let info = m
-                        .get_info(&pkg_name)
-                        .await
-                        .with_context(|| format!("获取 {} 包信息失败", manager_name))?;
-                    Ok(Some((manager_name, info)))
-                } else {
-                    Ok(None)
-                }
-            })
-        })
-        .collect();
-
-    let mut results = Vec::new();
-    for task in tasks {
-        match task.await {
-            Ok(Ok(Some((manager, pkg)))) => results.push((manager, pkg)),
-            Ok(Ok(None)) => {}
-            Ok(Err(err)) => return Err(err),
-            Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
-        }
-    }
-
-    if results.is_empty() {
-        eprintln!("{}", format!("错误: 未找到包 '{}'", package).bright_red());
-        std::process::exit(EXIT_ERROR);
-    }
-
-    if json {
-        let output: Vec<serde_json::Value> = results
-            .into_iter()
-            .map(|(manager, pkg)| {
-                serde_json::json!({
-                  "manager": manager,
-                  "package": pkg,
-                })
-            })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        for (manager, pkg) in results {
-            println!(
-                "{}",
-                format!("{} ({})", pkg.name.bright_cyan(), manager).bold()
-            );
-            if !pkg.version.is_empty() {
-                println!("  版本: {}", pkg.version.bright_white());
-            }
-            if let Some(desc) = &pkg.description {
-                println!("  描述: {}", desc);
-            }
-            if let Some(homepage) = &pkg.homepage {
-                println!("  主页: {}", homepage.bright_blue());
-            }
-            if let Some(license) = &pkg.license {
-                println!("  许可证: {}", license);
-            }
-            println!();
-        }
-    }
-
-    Ok(())
-    })
-    .await
-}
-
-async fn cmd_search(
-    cache: Arc<Cache>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-    query: &str,
-    manager_name: Option<&str>,
-    json: bool,
-) -> Result<()> {
-    run_with_timeout("搜索超时", async {
-    let scope_config = resolve_scope(manager_name, global, scope, directory)?;
-    let global = scope_config.global;
-    let workdir = scope_config.workdir.clone();
-    let manager_names = resolve_manager_names(manager_name);
-
-    // 并行搜索所有管理器
-    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
-    let tasks: Vec<_> = manager_names
-        .iter()
-        .map(|name| {
-            let manager_name = name.clone();
-            let cache_clone = cache.clone();
-            let query_str = query.to_string();
-            let semaphore = semaphore.clone();
-            let workdir = workdir.clone();
-            tokio::spawn(async move {
-                let _permit = match semaphore.acquire().await {
-                    Ok(permit) => permit,
-                    Err(_) => return Ok((manager_name, Vec::new())),
-                };
-                let manager = create_manager(&manager_name, cache_clone, global, workdir.as_ref());
-                if let Some(m) = manager {
-                    if !m.check_available().await.unwrap_or(false) {
-                        return Ok((manager_name, Vec::new()));
-                    }
-
-                    let packages = m
-                        .search(&query_str)
-                        .await
-                        .with_context(|| format!("搜索 {} 失败", manager_name))?;
-                    Ok((manager_name, packages))
-                } else {
-                    Ok((manager_name, Vec::new()))
-                }
-            })
-        })
-        .collect();
-
-    let mut all_results = Vec::new();
-    for task in tasks {
-        match task.await {
-            Ok(Ok((name, packages))) => all_results.push((name, packages)),
-            Ok(Err(err)) => return Err(err),
-            Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
-        }
-    }
-
-    if json {
-        let output: Vec<serde_json::Value> = all_results
-            .into_iter()
-            .map(|(manager, packages)| {
-                serde_json::json!({
-                  "manager": manager,
-                  "packages": packages,
-                })
-            })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        for (manager, packages) in all_results {
-            if packages.is_empty() {
-                continue;
-            }
-
-            println!(
-                "{}",
-                format!("{} ({})", manager.bright_cyan(), packages.len()).bold()
-            );
-            for pkg in packages.iter().take(10) {
-                println!("  {} {}", "•".bright_green(), pkg.name.bright_white());
-                if let Some(desc) = &pkg.description {
-                    println!("    {}", desc.dimmed());
-                }
-            }
-            if packages.len() > 10 {
-                println!("  ... 还有 {} 个结果", packages.len() - 10);
-            }
-            println!();
-        }
-    }
-
-    Ok(())
-    })
-    .await
-}
-
-async fn cmd_install(
-    cache: Arc<Cache>,
-    executor: Arc<ManagerExecutor>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-    package: &str,
-    version: Option<&str>,
-    manager_name: Option<&str>,
-    force: bool,
-    json: bool,
-) -> Result<()> {
-    let manager_name = match manager_name {
-        Some(name) => name,
-        None => {
-            eprintln!("{}", "错误: 必须指定包管理器".bright_red());
-            std::process::exit(EXIT_USAGE);
-        }
-    };
-    let scope_config = resolve_scope(Some(manager_name), global, scope, directory)?;
-    let global = scope_config.global;
-    let workdir = scope_config.workdir.clone();
-
-    let manager =
-        create_manager(manager_name, cache.clone(), global, workdir.as_ref())
-            .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
-
-    if !manager.check_available().await.unwrap_or(false) {
-        eprintln!(
-            "{}",
-            format!("错误: 包管理器 '{}' 不可用", manager.name()).bright_red()
-        );
-        std::process::exit(EXIT_ERROR);
-    }
-
-    if !json {
-        println!(
-            "安装 {} 到 {}...",
-            package.bright_white(),
-            manager.name().bright_cyan()
-        );
-    }
-
-    let cache_key = manager.cache_key().to_string();
-    let manager_name = manager.name().to_string();
-    let workdir = workdir.clone();
-    executor
-        .execute(&manager_name, || async {
-            let manager =
-                create_manager(&manager_name, cache.clone(), global, workdir.as_ref())
-            .ok_or_else(|| BoxyError::ManagerNotFound {
-                name: manager_name.clone(),
-            })?;
-            timeout(COMMAND_TIMEOUT, manager.install(package, version, force))
-                .await
-                .map_err(|_| BoxyError::CommandTimeout)?
-        })
-        .await
-        .map_err(|err| anyhow::anyhow!(err))
-        .context(format!("安装 {} 失败", package))?;
-
-    cache
-        .invalidate(&cache_key)
-        .await
-        .with_context(|| format!("清除 {} 缓存失败", manager.name()))?;
-
-    if !json {
-        println!("{}", "✓ 安装成功".bright_green());
-    } else {
-        println!(
-            "{}",
-            serde_json::json!({ "status": "success", "package": package })
-        );
-    }
-
-    Ok(())
-}
-
-async fn cmd_update(
-    cache: Arc<Cache>,
-    executor: Arc<ManagerExecutor>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-    package: Option<&str>,
-    manager_name: Option<&str>,
-    json: bool,
-) -> Result<()> {
-    if let Some(pkg) = package {
-        // 更新单个包
-        let manager_name = match manager_name {
-            Some(name) => name,
-            None => {
-                eprintln!("{}", "错误: 必须指定包管理器".bright_red());
-                std::process::exit(EXIT_USAGE);
-            }
-        };
-        let scope_config = resolve_scope(Some(manager_name), global, scope, directory)?;
-        let global = scope_config.global;
-        let workdir = scope_config.workdir.clone();
-
-        let manager =
-            create_manager(manager_name, cache.clone(), global, workdir.as_ref())
-                .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
-        let cache_key = manager.cache_key().to_string();
-        let manager_name = manager.name().to_string();
-        let workdir = workdir.clone();
-        executor
-            .execute(&manager_name, || async {
-                let manager =
-                    create_manager(&manager_name, cache.clone(), global, workdir.as_ref())
-                .ok_or_else(|| BoxyError::ManagerNotFound {
-                    name: manager_name.clone(),
-                })?;
-                timeout(COMMAND_TIMEOUT, manager.upgrade(pkg))
-                    .await
-                    .map_err(|_| BoxyError::CommandTimeout)?
-            })
-            .await
-            .map_err(|err| anyhow::anyhow!(err))
-            .context(format!("更新 {} 失败", pkg))?;
-        cache
-            .invalidate(&cache_key)
-            .await
-            .with_context(|| format!("清除 {} 缓存失败", manager.name()))?;
-        if !json {
-            println!("{}", "✓ 更新成功".bright_green());
-        } else {
-            println!(
-                "{}",
-                serde_json::json!({ "status": "success", "package": pkg })
-            );
-        }
-    } else {
-        // 更新所有可更新的包
-        let scope_config = resolve_scope(manager_name, global, scope, directory)?;
-        let global = scope_config.global;
-        let workdir = scope_config.workdir.clone();
-        let manager_names = resolve_manager_names(manager_name);
-        let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
-        let tasks: Vec<_> = manager_names
-            .iter()
-            .map(|name| {
-                let manager_name = name.clone();
-                let cache_clone = cache.clone();
-                let semaphore = semaphore.clone();
-                let workdir = workdir.clone();
-                tokio::spawn(async move {
-                    let _permit = match semaphore.acquire().await {
-                        Ok(permit) => permit,
-                        Err(_) => return Ok((manager_name, Vec::new())),
-                    };
-                    let manager = create_manager(
-                        &manager_name,
-                        cache_clone.clone(),
-                        global,
-                        workdir.as_ref(),
-                    );
-                    if let Some(m) = manager {
-                        if !m.check_available().await.unwrap_or(false) {
-                            return Ok((manager_name, Vec::new()));
-                        }
-                        let outdated = m
-                            .check_outdated()
-                            .await
-                            .with_context(|| format!("检查 {} 更新失败", manager_name))?;
-                        Ok((manager_name, outdated))
-                    } else {
-                        Ok((manager_name, Vec::new()))
-                    }
-                })
-            })
-            .collect();
-
-        let mut all_outdated = Vec::new();
-        for task in tasks {
-            match task.await {
-                Ok(Ok((name, packages))) => all_outdated.push((name, packages)),
-                Ok(Err(err)) => return Err(err),
-                Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
-            }
-        }
-
-        let mut updated = Vec::new();
-        for (manager_name, packages) in all_outdated {
-            if packages.is_empty() {
-                continue;
-            }
-            let manager =
-                create_manager(&manager_name, cache.clone(), global, workdir.as_ref())
-                    .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
-            let cache_key = manager.cache_key().to_string();
-            for pkg in packages {
-                let workdir = workdir.clone();
-                executor
-                    .execute(manager.name(), || async {
-                        let manager =
-                            create_manager(&manager_name, cache.clone(), global, workdir.as_ref())
-                        .ok_or_else(|| BoxyError::ManagerNotFound {
-                            name: manager_name.clone(),
-                        })?;
-                        timeout(COMMAND_TIMEOUT, manager.upgrade(&pkg.name))
-                            .await
-                            .map_err(|_| BoxyError::CommandTimeout)?
-                    })
-                    .await
-                    .map_err(|err| anyhow::anyhow!(err))
-                    .context(format!("更新 {} 失败", pkg.name))?;
-                updated.push((manager_name.clone(), pkg.name.clone()));
-            }
-            cache
-                .invalidate(&cache_key)
-                .await
-                .with_context(|| format!("清除 {} 缓存失败", manager.name()))?;
-        }
-
-        if updated.is_empty() {
-            if !json {
-                println!("{}", "✓ 没有可更新的包".bright_green());
-            } else {
-                println!("{}", serde_json::json!({ "updated": [] }));
-            }
-        } else if json {
-            let output: Vec<serde_json::Value> = updated
-                .into_iter()
-                .map(|(manager, package)| {
-                    serde_json::json!({
-                      "manager": manager,
-                      "package": package,
-                    })
-                })
-                .collect();
-            println!("{}", serde_json::to_string_pretty(&output)?);
-        } else {
-            println!("{}", "✓ 批量更新完成".bright_green());
-        }
-    }
-
-    Ok(())
-}
-
-async fn cmd_uninstall(
-    cache: Arc<Cache>,
-    executor: Arc<ManagerExecutor>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-    package: &str,
-    manager_name: Option<&str>,
-    force: bool,
-    clean_cache: bool,
-    json: bool,
-) -> Result<()> {
-    let manager_name = match manager_name {
-        Some(name) => name,
-        None => {
-            eprintln!("{}", "错误: 必须指定包管理器".bright_red());
-            std::process::exit(EXIT_USAGE);
-        }
-    };
-    let scope_config = resolve_scope(Some(manager_name), global, scope, directory)?;
-    let global = scope_config.global;
-    let workdir = scope_config.workdir.clone();
-
-    let manager =
-        create_manager(manager_name, cache.clone(), global, workdir.as_ref())
-            .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
-
-    if !json {
-        if force {
-            println!(
-                "强制卸载 {} 从 {}...",
-                package.bright_white(),
-                manager.name().bright_cyan()
-            );
-        } else {
-            println!(
-                "卸载 {} 从 {}...",
-                package.bright_white(),
-                manager.name().bright_cyan()
-            );
-        }
-    }
-
-    let cache_key = manager.cache_key().to_string();
-    let manager_name = manager.name().to_string();
-    let workdir = workdir.clone();
-    executor
-        .execute(&manager_name, || async {
-            let manager =
-                create_manager(&manager_name, cache.clone(), global, workdir.as_ref())
-            .ok_or_else(|| BoxyError::ManagerNotFound {
-                name: manager_name.clone(),
-            })?;
-            timeout(COMMAND_TIMEOUT, manager.uninstall(package, force))
-                .await
-                .map_err(|_| BoxyError::CommandTimeout)?
-        })
-        .await
-        .map_err(|err| anyhow::anyhow!(err))
-        .context(format!("卸载 {} 失败", package))?;
-
-    cache
-        .invalidate(&cache_key)
-        .await
-        .with_context(|| format!("清除 {} 缓存失败", manager.name()))?;
-
-    // 如果指定了 --clean-cache，清理包管理器缓存
-    if clean_cache {
-        if !json {
-            println!("{}", "正在清理包管理器缓存...".bright_cyan());
-        }
-        match manager.clean_cache().await {
-            Ok(_) => {
-                if !json {
-                    println!("{}", "✓ 缓存清理成功".bright_green());
-                }
-            }
-            Err(BoxyError::UnsupportedOperation { .. }) => {
-                if !json {
-                    println!(
-                        "{}",
-                        format!("⚠ {} 不支持缓存清理", manager_name).bright_yellow()
-                    );
-                }
-            }
-            Err(err) => {
-                if !json {
-                    eprintln!(
-                        "{}",
-                        format!("⚠ 缓存清理失败: {}", err).bright_yellow()
-                    );
-                }
-            }
-        }
-    }
-
-    if !json {
-        println!("{}", "✓ 卸载成功".bright_green());
-    } else {
-        println!(
-            "{}",
-            serde_json::json!({ "status": "success", "package": package })
-        );
-    }
-
-    Ok(())
-}
-
-async fn cmd_outdated(
-    cache: Arc<Cache>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-    manager_name: Option<&str>,
-    json: bool,
-    no_cache: bool,
-) -> Result<()> {
-    run_with_timeout("检查更新超时", async {
-    let scope_config = resolve_scope(manager_name, global, scope, directory)?;
-    let global = scope_config.global;
-    let workdir = scope_config.workdir.clone();
-    let manager_names = resolve_manager_names(manager_name);
-
-    // 并行检查所有管理器
-    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
-    let tasks: Vec<_> = manager_names
-        .iter()
-        .map(|name| {
-            let manager_name = name.clone();
-            let cache_clone = cache.clone();
-            let semaphore = semaphore.clone();
-            let workdir = workdir.clone();
-            tokio::spawn(async move {
-                let _permit = match semaphore.acquire().await {
-                    Ok(permit) => permit,
-                    Err(_) => return Ok((manager_name, Vec::new())),
-                };
-                let result: Result<(String, Vec<boxy_core::Package>)> = {
-                    let manager =
-                        create_manager(&manager_name, cache_clone.clone(), global, workdir.as_ref());
-                    if let Some(m) = manager {
-                        if !m.check_available().await.unwrap_or(false) {
-                            Ok((manager_name, Vec::new()))
-                        } else {
-                            if no_cache {
-                                cache_clone
-                                    .invalidate(m.cache_key())
-                                    .await
-                                    .with_context(|| format!("清除 {} 缓存失败", manager_name))?;
-                            }
-
-                            let outdated = m
-                                .check_outdated()
-                                .await
-                                .with_context(|| format!("检查 {} 更新失败", manager_name))?;
-                            Ok((manager_name, outdated))
-                        }
-                    } else {
-                        Ok((manager_name, Vec::new()))
-                    }
-                };
-                result
-            })
-        })
-        .collect();
-
-    let mut all_outdated = Vec::new();
-    for task in tasks {
-        match task.await {
-            Ok(Ok((name, packages))) => all_outdated.push((name, packages)),
-            Ok(Err(err)) => return Err(err),
-            Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
-        }
-    }
-
-    if json {
-        let output: Vec<serde_json::Value> = all_outdated
-            .into_iter()
-            .map(|(manager, packages)| {
-                serde_json::json!({
-                  "manager": manager,
-                  "packages": packages,
-                })
-            })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        let mut has_outdated = false;
-        for (manager, packages) in all_outdated {
-            if packages.is_empty() {
-                continue;
-            }
-
-            has_outdated = true;
-            println!(
-                "{}",
-                format!("{} ({})", manager.bright_cyan(), packages.len()).bold()
-            );
-            for pkg in packages {
-                println!("  {} {}", "•".bright_yellow(), pkg.name.bright_white());
-                println!("    当前: {}", pkg.version.dimmed());
-                if let Some(latest) = &pkg.latest_version {
-                    println!("    最新: {}", latest.bright_green());
-                }
-            }
-            println!();
-        }
-
-        if !has_outdated {
-            println!("{}", "✓ 所有包都是最新版本".bright_green());
-        }
-    }
-
-    Ok(())
-    })
-    .await
-}
-
-#[derive(Clone)]
-struct ScopeConfig {
-    global: bool,
-    workdir: Option<PathBuf>,
-}
-
-async fn run_with_timeout<F>(message: &'static str, fut: F) -> Result<()>
-where
-    F: std::future::Future<Output = Result<()>>,
-{
-    timeout(READ_COMMAND_TIMEOUT, fut)
-        .await
-        .map_err(|_| anyhow::anyhow!(message))?
-}
-
-fn resolve_scope(
-    manager_name: Option<&str>,
-    global: bool,
-    scope: Option<&str>,
-    directory: Option<&str>,
-) -> Result<ScopeConfig> {
-    let scope_value = scope.map(|value| value.to_lowercase());
-    if directory.is_some() && scope_value.as_deref() != Some("local") {
-        return Err(anyhow::anyhow!("--dir 仅能与 --scope=local 一起使用"));
-    }
-
-    let (mut global, workdir) = match scope_value.as_deref() {
-        None => (global, None),
-        Some("global") => (true, None),
-        Some("local") => {
-            let dir = directory.unwrap_or("").trim();
-            if dir.is_empty() {
-                return Err(anyhow::anyhow!("缺少本地目录，请使用 --dir 指定"));
-            }
-            // 支持 ~ 前缀，避免用户手动展开路径
-            let path = if let Some(rest) = dir.strip_prefix("~/") {
-                let home =
-                    std::env::var("HOME").map_err(|_| anyhow::anyhow!("无法解析用户目录"))?;
-                PathBuf::from(home).join(rest)
-            } else {
-                PathBuf::from(dir)
-            };
-            if !path.is_dir() {
-                return Err(anyhow::anyhow!("目录不存在或不可访问"));
-            }
-            (false, Some(path))
-        }
-        Some(other) => return Err(anyhow::anyhow!("不支持的 scope: {}", other)),
-    };
-
-    if let Some(name) = manager_name {
-        if global && !supports_global(name) {
-            eprintln!(
-                "{}",
-                format!("警告: {} 不支持全局范围，忽略 --global 参数", name).bright_yellow()
-            );
-            global = false;
-        }
-        if workdir.is_some() && !supports_global(name) {
-            return Err(anyhow::anyhow!("{} 不支持本地范围", name));
-        }
-    }
-
-    Ok(ScopeConfig { global, workdir })
-}
-
-fn resolve_manager_names(manager_name: Option<&str>) -> Vec<String> {
-    if let Some(name) = manager_name {
-        vec![name.to_string()]
-    } else {
-        MANAGER_NAMES.iter().map(|name| name.to_string()).collect()
-    }
-}
@@ -1,8 +1,9 @@
-use boxy_brew::BrewManager;
+use boxy_brew::{BrewManager, BrewScope};
 use boxy_bun::BunManager;
 use boxy_cache::Cache;
 use boxy_cargo::CargoManager;
 use boxy_core::manager::PackageManager;
+use boxy_core::python::PythonScope;
 use boxy_mas::MasManager;
 use boxy_npm::{NpmManager, NpmScope};
 use boxy_pip::PipManager;
@@ -16,6 +17,25 @@ pub const MANAGER_NAMES: [&str; 10] = [
     "brew", "npm", "pnpm", "yarn", "bun", "pip", "pipx", "uv", "cargo", "mas",
 ];
 
+/// 由命令行参数决定的 pip、uv 与 brew 范围
+#[derive(Debug, Clone, Default)]
+pub struct ManagerOptions {
+    /// 通过 --python 或 --user 指定的 Python 环境
+    pub python: Option<PythonScope>,
+    /// 通过 --scope=formula 或 --scope=cask 指定的 brew 包类型
+    pub brew: Option<BrewScope>,
+}
+
+/// pip、uv 的 Python 环境：优先使用 --python / --user，
+/// 其次只在本地范围（--scope local --dir）的目录中自动发现虚拟环境
+fn python_scope(options: &ManagerOptions, workdir: Option<&PathBuf>) -> PythonScope {
+    match (&options.python, workdir) {
+        (Some(scope), _) => scope.clone(),
+        (None, Some(dir)) => PythonScope::detect(dir),
+        (None, None) => PythonScope::Default,
+    }
+}
+
 /// 创建包管理器实例
 /// 
 /// # 参数
@@ -23,14 +43,20 @@ pub const MANAGER_NAMES: [&str; 10] = [
 /// * `name` - 包管理器名称
 /// * `cache` - 缓存实例
 /// * `global` - 是否使用全局范围（针对 npm、pnpm、yarn、bun）
+/// * `workdir` - 本地范围目录；pip、uv 在其中查找虚拟环境，uv、cargo 在其中管理项目依赖
+/// * `options` - 通过 --python、--user、--scope=formula|cask 指定的 pip、uv 与 brew 范围
 pub fn create_manager(
     name: &str,
     cache: Arc<Cache>,
     global: bool,
     workdir: Option<&PathBuf>,
+    options: &ManagerOptions,
 ) -> Option<Box<dyn PackageManager>> {
     match name {
-        "brew" => Some(Box::new(BrewManager::new(cache))),
+        "brew" => Some(Box::new(BrewManager::new(
+            cache,
+            options.brew.unwrap_or(BrewScope::All),
+        ))),
         "npm" => Some(Box::new(NpmManager::new(
             cache,
             if global { NpmScope::Global } else { NpmScope::Local },
@@ -39,10 +65,14 @@ pub fn create_manager(
         "pnpm" => Some(Box::new(PnpmManager::new(cache, global, workdir.cloned()))),
         "yarn" => Some(Box::new(YarnManager::new(cache, global, workdir.cloned()))),
         "bun" => Some(Box::new(BunManager::new(cache, global, workdir.cloned()))),
-        "pip" => Some(Box::new(PipManager::new(cache, false))),
+        "pip" => Some(Box::new(PipManager::new(cache, python_scope(options, workdir)))),
         "pipx" => Some(Box::new(PipxManager::new(cache))),
-        "uv" => Some(Box::new(UvManager::new(cache, false))),
-        "cargo" => Some(Box::new(CargoManager::new(cache, false))),
+        "uv" => Some(Box::new(UvManager::new(
+            cache,
+            python_scope(options, workdir),
+            workdir.cloned(),
+        ))),
+        "cargo" => Some(Box::new(CargoManager::new(cache, workdir.cloned()))),
         "mas" => Some(Box::new(MasManager::new(cache))),
         _ => None,
     }
@@ -52,3 +82,8 @@ pub fn create_manager(
 pub fn supports_global(name: &str) -> bool {
     matches!(name, "npm" | "pnpm" | "yarn" | "bun")
 }
+
+/// 检查包管理器是否支持 --scope=local（pip、uv 对应虚拟环境目录，cargo 对应 Cargo 项目）
+pub fn supports_local(name: &str) -> bool {
+    supports_global(name) || matches!(name, "pip" | "uv" | "cargo")
+}
@@ -0,0 +1,166 @@
+use crate::context::{resolve_manager_names, run_with_timeout, CliContext};
+use crate::managers::create_manager;
+use crate::project::{project_targets, ProjectTarget};
+use crate::SCAN_CONCURRENCY;
+use anyhow::{Context, Result};
+use boxy_core::project::detect_projects;
+use boxy_core::DependencyFlag;
+use colored::*;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+pub async fn cmd_outdated(ctx: &CliContext, manager_name: Option<&str>) -> Result<()> {
+    run_with_timeout("检查更新超时", async {
+        // 在项目目录中且未指定包管理器与范围时，按项目的包管理器检查每个本地范围
+        let projects = if manager_name.is_none()
+            && !ctx.global
+            && ctx.scope.is_none()
+            && ctx.directory.is_none()
+        {
+            std::env::current_dir()
+                .map(|dir| detect_projects(&dir))
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let (global, targets) = if projects.is_empty() {
+            let scope_config = ctx.resolve_scope(manager_name)?;
+            let targets: Vec<ProjectTarget> = resolve_manager_names(manager_name)
+                .into_iter()
+                .map(|name| ProjectTarget {
+                    label: name.clone(),
+                    manager: name,
+                    workdir: scope_config.workdir.clone(),
+                })
+                .collect();
+            (scope_config.global, targets)
+        } else {
+            (false, project_targets(&projects))
+        };
+        let project_mode = !projects.is_empty();
+
+        // 并行检查所有管理器
+        let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+        let tasks: Vec<_> = targets
+            .into_iter()
+            .map(|target| {
+                let cache_clone = ctx.cache.clone();
+                let no_cache = ctx.no_cache;
+                let semaphore = semaphore.clone();
+                let options = ctx.options.clone();
+                tokio::spawn(async move {
+                    let manager_name = target.manager.clone();
+                    let _permit = match semaphore.acquire().await {
+                        Ok(permit) => permit,
+                        Err(_) => return Ok((target, Vec::new())),
+                    };
+                    let result: Result<(ProjectTarget, Vec<boxy_core::Package>)> = {
+                        let manager = create_manager(
+                            &manager_name,
+                            cache_clone.clone(),
+                            global,
+                            target.workdir.as_ref(),
+                            &options,
+                        );
+                        if let Some(m) = manager {
+                            if !m.check_available().await.unwrap_or(false) {
+                                Ok((target, Vec::new()))
+                            } else {
+                                if no_cache {
+                                    cache_clone.invalidate(m.cache_key()).await.with_context(
+                                        || format!("清除 {} 缓存失败", manager_name),
+                                    )?;
+                                }
+
+                                let outdated = m
+                                    .check_outdated()
+                                    .await
+                                    .with_context(|| format!("检查 {} 更新失败", target.label))?;
+                                Ok((target, outdated))
+                            }
+                        } else {
+                            Ok((target, Vec::new()))
+                        }
+                    };
+                    result
+                })
+            })
+            .collect();
+
+        let mut all_outdated = Vec::new();
+        for task in tasks {
+            match task.await {
+                Ok(Ok((target, packages))) => all_outdated.push((target, packages)),
+                Ok(Err(err)) => return Err(err),
+                Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+            }
+        }
+
+        if ctx.json {
+            let output: Vec<serde_json::Value> = all_outdated
+                .into_iter()
+                .map(|(target, packages)| {
+                    let mut entry = serde_json::json!({
+                      "manager": target.manager,
+                      "packages": packages,
+                    });
+                    if project_mode {
+                        entry["scope"] = serde_json::json!(target.workdir);
+                    }
+                    entry
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            let mut has_outdated = false;
+            for (target, packages) in all_outdated {
+                if packages.is_empty() {
+                    continue;
+                }
+
+                has_outdated = true;
+                println!(
+                    "{}",
+                    format!("{} ({})", target.label.bright_cyan(), packages.len()).bold()
+                );
+                for pkg in packages {
+                    println!("  {} {}", "•".bright_yellow(), pkg.name.bright_white());
+                    println!("    当前: {}", pkg.version.dimmed());
+                    if let Some(wanted) = &pkg.wanted_version {
+                        match &pkg.range {
+                            Some(range) => {
+                                println!("    范围内: {} ({})", wanted.bright_green(), range)
+                            }
+                            None => println!("    范围内: {}", wanted.bright_green()),
+                        }
+                    }
+                    if let Some(latest) = &pkg.latest_version {
+                        if pkg.needs_manifest_bump() {
+                            println!(
+                                "    最新: {} {}",
+                                latest.bright_yellow(),
+                                "(需修改清单中的版本范围)".dimmed()
+                            );
+                        } else {
+                            println!("    最新: {}", latest.bright_green());
+                        }
+                    }
+                    if pkg.yanked {
+                        println!("    {}", "当前版本已被撤回 (yanked)".bright_red());
+                    }
+                    if pkg.flags.contains(&DependencyFlag::Pinned) {
+                        println!("    {}", "已固定版本 (brew pin)，升级时会跳过".dimmed());
+                    }
+                }
+                println!();
+            }
+
+            if !has_outdated {
+                println!("{}", "✓ 所有包都是最新版本".bright_green());
+            }
+        }
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,96 @@
+use crate::context::CliContext;
+use crate::{PipxAction, COMMAND_TIMEOUT, READ_COMMAND_TIMEOUT};
+use anyhow::{Context, Result};
+use boxy_core::PackageManager;
+use boxy_pipx::PipxManager;
+use colored::*;
+use tokio::time::timeout;
+
+pub async fn cmd_pipx(ctx: &CliContext, action: PipxAction) -> Result<()> {
+    let manager = PipxManager::new(ctx.cache.clone());
+    if !manager.check_available().await.unwrap_or(false) {
+        return Err(anyhow::anyhow!("包管理器 'pipx' 不可用"));
+    }
+
+    let done = |message: String| -> Result<()> {
+        if ctx.json {
+            println!(
+                "{}",
+                serde_json::json!({ "success": true, "message": message })
+            );
+        } else {
+            println!("{}", format!("✓ {}", message).bright_green());
+        }
+        Ok(())
+    };
+
+    match action {
+        PipxAction::List => {
+            let venvs = timeout(READ_COMMAND_TIMEOUT, manager.venvs())
+                .await
+                .map_err(|_| anyhow::anyhow!("读取 pipx 虚拟环境超时"))?
+                .context("读取 pipx 虚拟环境失败")?;
+            if ctx.json {
+                println!("{}", serde_json::to_string_pretty(&venvs)?);
+                return Ok(());
+            }
+            if venvs.is_empty() {
+                println!("{}", "没有通过 pipx 安装的应用".bright_yellow());
+                return Ok(());
+            }
+            for venv in &venvs {
+                println!(
+                    "{} {}",
+                    venv.name.bright_cyan().bold(),
+                    venv.version.bright_white()
+                );
+                if venv.package != venv.name {
+                    println!("  包: {}", venv.package);
+                }
+                if let Some(source) = venv.source() {
+                    println!("  来源: {}", source);
+                }
+                if let Some(python) = &venv.python_version {
+                    println!("  Python: {}", python);
+                }
+                if !venv.apps.is_empty() {
+                    println!("  命令: {}", venv.apps.join(", "));
+                }
+                if !venv.injected.is_empty() {
+                    let injected: Vec<String> = venv
+                        .injected
+                        .iter()
+                        .map(|pkg| format!("{} {}", pkg.name, pkg.version))
+                        .collect();
+                    println!("  注入: {}", injected.join(", "));
+                }
+                if let Some(path) = &venv.path {
+                    println!("  路径: {}", path.display().to_string().dimmed());
+                }
+            }
+            Ok(())
+        }
+        PipxAction::Inject { app, packages } => {
+            let packages: Vec<&str> = packages.iter().map(|pkg| pkg.as_str()).collect();
+            timeout(COMMAND_TIMEOUT, manager.inject(&app, &packages))
+                .await
+                .map_err(|_| anyhow::anyhow!("pipx inject 超时"))?
+                .with_context(|| format!("向 {} 注入 {} 失败", app, packages.join(" ")))?;
+            done(format!("已向 {} 注入 {}", app, packages.join(" ")))
+        }
+        PipxAction::Reinstall { app } => {
+            timeout(COMMAND_TIMEOUT, manager.reinstall(&app))
+                .await
+                .map_err(|_| anyhow::anyhow!("pipx reinstall 超时"))?
+                .with_context(|| format!("重建 {} 失败", app))?;
+            done(format!("已重建 {}", app))
+        }
+        PipxAction::ReinstallAll => {
+            timeout(COMMAND_TIMEOUT, manager.reinstall_all())
+                .await
+                .map_err(|_| anyhow::anyhow!("pipx reinstall-all 超时"))?
+                .context("重建 pipx 应用失败")?;
+            done("已重建所有 pipx 应用".to_string())
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use crate::context::{resolve_scope, CliContext};
+use crate::managers::create_manager;
+use crate::COMMAND_TIMEOUT;
+use anyhow::{Context, Result};
+use boxy_core::project::{detect_projects, Project};
+use boxy_core::python::find_venv;
+use boxy_core::{DependencyType, PackageSpec};
+use boxy_error::BoxyError;
+use colored::*;
+use std::path::PathBuf;
+use tokio::time::timeout;
+
+/// 依赖编辑与过时检查的目标：包管理器及其本地范围
+#[derive(Debug, Clone)]
+pub struct ProjectTarget {
+    /// 输出中显示的名称，工作区成员带上相对路径，如 `pnpm (packages/web)`
+    pub label: String,
+    pub manager: String,
+    pub workdir: Option<PathBuf>,
+}
+
+/// 项目的每个本地范围对应一个目标
+///
+/// pip 与 uv 只检查已有虚拟环境的范围，避免回退到系统 Python。
+pub fn project_targets(projects: &[Project]) -> Vec<ProjectTarget> {
+    projects
+        .iter()
+        .flat_map(|project| {
+            project.scopes().into_iter().map(move |dir| {
+                let label = match dir.strip_prefix(&project.root) {
+                    Ok(relative) if !relative.as_os_str().is_empty() => {
+                        format!("{} ({})", project.manager, relative.display())
+                    }
+                    _ => project.manager.clone(),
+                };
+                ProjectTarget {
+                    label,
+                    manager: project.manager.clone(),
+                    workdir: Some(dir),
+                }
+            })
+        })
+        .filter(|target| {
+            !matches!(target.manager.as_str(), "pip" | "uv")
+                || target.workdir.as_deref().and_then(find_venv).is_some()
+        })
+        .collect()
+}
+
+/// 确定 add、remove 操作的项目与本地范围
+///
+/// 从 --dir（配合 --scope=local）或当前目录向上识别项目；工作区中默认使用当前目录所在的成员。
+fn resolve_project_target(
+    manager_name: Option<&str>,
+    scope: Option<&str>,
+    directory: Option<&str>,
+    member: Option<&str>,
+) -> Result<(Project, PathBuf)> {
+    let start = match directory {
+        Some(_) => resolve_scope(None, false, scope, directory)?
+            .workdir
+            .ok_or_else(|| anyhow::anyhow!("add、remove 只能用于本地项目"))?,
+        None if scope.is_some_and(|scope| !scope.eq_ignore_ascii_case("local")) => {
+            return Err(anyhow::anyhow!("add、remove 只能用于本地项目"));
+        }
+        None => std::env::current_dir().context("无法读取当前目录")?,
+    };
+    let start = start.canonicalize().unwrap_or(start);
+
+    let mut projects: Vec<Project> = detect_projects(&start)
+        .into_iter()
+        .filter(|project| match manager_name {
+            Some(name) => project_accepts(project, name),
+            None => true,
+        })
+        .collect();
+    let mut project = match projects.len() {
+        0 => {
+            return Err(anyhow::anyhow!(
+                "{} 中未找到{}项目清单（package.json、pyproject.toml、requirements.txt 或 Cargo.toml）",
+                start.display(),
+                manager_name.map(|name| format!(" {} ", name)).unwrap_or_default()
+            ));
+        }
+        1 => projects.remove(0),
+        _ => {
+            let names: Vec<&str> = projects.iter().map(|p| p.manager.as_str()).collect();
+            return Err(anyhow::anyhow!(
+                "当前项目包含多种清单（{}），请使用 -m 指定包管理器",
+                names.join("、")
+            ));
+        }
+    };
+    if let Some(name) = manager_name {
+        project.manager = name.to_string();
+    }
+
+    let dir = match member {
+        Some(member) => {
+            let path = project.root.join(member);
+            let path = path.canonicalize().unwrap_or(path);
+            project
+                .members
+                .iter()
+                .find(|dir| **dir == path || dir.file_name().is_some_and(|name| name == member))
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow::anyhow!("{} 不是 {} 的工作区成员", member, project.root.display())
+                })?
+        }
+        None => match project
+            .members
+            .iter()
+            .filter(|dir| start.starts_with(dir))
+            .max_by_key(|dir| dir.components().count())
+        {
+            Some(dir) => dir.clone(),
+            None if project.virtual_root => {
+                return Err(anyhow::anyhow!(
+                    "{} 是虚拟工作区，请使用 --member 指定成员",
+                    project.root.display()
+                ));
+            }
+            None => project.root.clone(),
+        },
+    };
+
+    // 复用本地范围的检查：pip 需要虚拟环境，cargo 需要 Cargo.toml
+    resolve_scope(
+        Some(&project.manager),
+        false,
+        Some("local"),
+        Some(&dir.to_string_lossy()),
+    )?;
+    Ok((project, dir))
+}
+
+/// `-m` 指定的包管理器能否编辑该项目：Node 项目的包管理器可以互换，pip 与 uv 可以互换
+fn project_accepts(project: &Project, manager_name: &str) -> bool {
+    const NODE: [&str; 4] = ["npm", "pnpm", "yarn", "bun"];
+    const PYTHON: [&str; 2] = ["pip", "uv"];
+    let manager = project.manager.as_str();
+    manager == manager_name
+        || NODE.contains(&manager) && NODE.contains(&manager_name)
+        || PYTHON.contains(&manager) && PYTHON.contains(&manager_name)
+}
+
+pub async fn cmd_add(
+    ctx: &CliContext,
+    packages: &[String],
+    manager_name: Option<&str>,
+    dependency_type: DependencyType,
+    member: Option<&str>,
+) -> Result<()> {
+    let (project, dir) = resolve_project_target(
+        manager_name,
+        ctx.scope.as_deref(),
+        ctx.directory.as_deref(),
+        member,
+    )?;
+    let specs = packages
+        .iter()
+        .map(|input| {
+            PackageSpec::parse(input)
+                .and_then(|spec| spec.with_manager(Some(&project.manager)))
+                .map_err(|err| anyhow::anyhow!(err))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for spec in &specs {
+        if !ctx.json {
+            println!(
+                "添加 {} 到 {} ({})...",
+                spec.name.bright_white(),
+                dir.display(),
+                project.manager.bright_cyan()
+            );
+        }
+        let manager_name = project.manager.clone();
+        let cache = ctx.cache.clone();
+        let workdir = dir.clone();
+        ctx.executor
+            .execute(&manager_name, || async {
+                let manager = create_manager(
+                    &manager_name,
+                    cache.clone(),
+                    false,
+                    Some(&workdir),
+                    &ctx.options,
+                )
+                .ok_or_else(|| BoxyError::ManagerNotFound {
+                    name: manager_name.clone(),
+                })?;
+                timeout(
+                    COMMAND_TIMEOUT,
+                    manager.add_dependency(&spec.name, spec.version.as_deref(), dependency_type),
+                )
+                .await
+                .map_err(|_| BoxyError::CommandTimeout)?
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err))
+            .context(format!("添加 {} 失败", spec.name))?;
+    }
+
+    if !ctx.json {
+        println!("{}", "✓ 添加成功".bright_green());
+    }
+    Ok(())
+}
+
+pub async fn cmd_remove(
+    ctx: &CliContext,
+    packages: &[String],
+    manager_name: Option<&str>,
+    member: Option<&str>,
+) -> Result<()> {
+    let (project, dir) = resolve_project_target(
+        manager_name,
+        ctx.scope.as_deref(),
+        ctx.directory.as_deref(),
+        member,
+    )?;
+
+    for package in packages {
+        if !ctx.json {
+            println!(
+                "从 {} ({}) 移除 {}...",
+                dir.display(),
+                project.manager.bright_cyan(),
+                package.bright_white()
+            );
+        }
+        let manager_name = project.manager.clone();
+        let cache = ctx.cache.clone();
+        let workdir = dir.clone();
+        ctx.executor
+            .execute(&manager_name, || async {
+                let manager = create_manager(
+                    &manager_name,
+                    cache.clone(),
+                    false,
+                    Some(&workdir),
+                    &ctx.options,
+                )
+                .ok_or_else(|| BoxyError::ManagerNotFound {
+                    name: manager_name.clone(),
+                })?;
+                timeout(COMMAND_TIMEOUT, manager.remove_dependency(package))
+                    .await
+                    .map_err(|_| BoxyError::CommandTimeout)?
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err))
+            .context(format!("移除 {} 失败", package))?;
+    }
+
+    if !ctx.json {
+        println!("{}", "✓ 移除成功".bright_green());
+    }
+    Ok(())
+}
+
+pub fn cmd_project(ctx: &CliContext) -> Result<()> {
+    let dir = match ctx.directory {
+        Some(_) => resolve_scope(None, false, ctx.scope.as_deref(), ctx.directory.as_deref())?
+            .workdir
+            .ok_or_else(|| anyhow::anyhow!("缺少本地目录，请使用 --dir 指定"))?,
+        None => std::env::current_dir().context("无法读取当前目录")?,
+    };
+    let dir = dir.canonicalize().unwrap_or(dir);
+    let projects = detect_projects(&dir);
+
+    if ctx.json {
+        println!("{}", serde_json::to_string_pretty(&projects)?);
+        return Ok(());
+    }
+    if projects.is_empty() {
+        println!(
+            "{}",
+            format!("{} 中未找到项目清单", dir.display()).bright_yellow()
+        );
+        return Ok(());
+    }
+
+    for project in &projects {
+        println!(
+            "{} {}",
+            project.root.display().to_string().bright_cyan().bold(),
+            format!("({})", project.manager).dimmed()
+        );
+        println!("  清单: {}", project.manifest.display());
+        if !project.members.is_empty() {
+            println!("  工作区成员: {}", project.members.len());
+            for member in &project.members {
+                let relative = member.strip_prefix(&project.root).unwrap_or(member);
+                println!("    {} {}", "•".bright_yellow(), relative.display());
+            }
+        }
+        println!();
+    }
+    println!(
+        "{}",
+        "在项目目录中可直接使用 add、remove、outdated，无需指定 -m 与 --scope".dimmed()
+    );
+    Ok(())
+}
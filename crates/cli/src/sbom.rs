@@ -0,0 +1,97 @@
+use crate::context::{resolve_manager_names, CliContext, ScopeConfig};
+use crate::inventory::collect_installed;
+use crate::managers::{supports_global, ManagerOptions};
+use crate::{EXIT_ERROR, EXIT_USAGE};
+use anyhow::{Context, Result};
+use boxy_audit::SbomEntry;
+use boxy_brew::BrewScope;
+use boxy_core::python::PythonScope;
+use colored::*;
+
+pub async fn cmd_sbom(
+    ctx: &CliContext,
+    manager_name: Option<&str>,
+    format: &str,
+    output: Option<&str>,
+) -> Result<()> {
+    if !matches!(format, "cyclonedx" | "spdx") {
+        eprintln!(
+            "{}",
+            format!("错误: 不支持的 SBOM 格式 {}（cyclonedx、spdx）", format).bright_red()
+        );
+        std::process::exit(EXIT_USAGE);
+    }
+
+    let scope_config = ctx.resolve_scope(manager_name)?;
+    let mut entries = Vec::new();
+    let manager_names = resolve_manager_names(manager_name);
+    let installed = collect_installed(ctx, &scope_config, &manager_names, false, true).await?;
+    for (name, packages) in installed.lists {
+        let scope = entry_scope(&name, &scope_config, &ctx.options);
+        entries.extend(packages.into_iter().map(|package| SbomEntry {
+            package,
+            scope: scope.to_string(),
+        }));
+    }
+    let count = entries.len();
+
+    // 计算 cargo 包的 SHA-256 需要读取 .crate 文件
+    let document = tokio::task::spawn_blocking({
+        let format = format.to_string();
+        move || match format.as_str() {
+            "spdx" => boxy_audit::sbom::to_spdx(&entries),
+            _ => boxy_audit::sbom::to_cyclonedx(&entries),
+        }
+    })
+    .await
+    .map_err(|err| anyhow::anyhow!("任务执行失败: {}", err))?;
+    let content = serde_json::to_string_pretty(&document)?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, content).with_context(|| format!("写入 {} 失败", path))?;
+            eprintln!(
+                "{}",
+                format!("✓ 已导出 {} 个组件到 {}", count, path).bright_green()
+            );
+        }
+        None => println!("{}", content),
+    }
+    // 有管理器未能列出包时 SBOM 缺少这些组件，不能当作成功导出
+    if !installed.failed.is_empty() {
+        eprintln!(
+            "{}",
+            format!(
+                "✗ 以下包管理器的包列表获取失败，SBOM 不完整: {}",
+                installed.failed.join(", ")
+            )
+            .bright_red()
+        );
+        std::process::exit(EXIT_ERROR);
+    }
+    Ok(())
+}
+
+/// 组件所在的范围，与 create_manager 实际操作的环境一致
+///
+/// 本地目录（npm 系项目、pip/uv 虚拟环境、cargo 项目）为 `local`，pip/uv 的 --user 为 `user`，
+/// brew 的 --scope=formula|cask 为对应的包类型，其余为 `global`。
+fn entry_scope(name: &str, scope_config: &ScopeConfig, options: &ManagerOptions) -> &'static str {
+    // npm 系未指定 --global 时操作当前目录的项目
+    if scope_config.workdir.is_some() || (supports_global(name) && !scope_config.global) {
+        return "local";
+    }
+    match name {
+        "pip" | "uv" => match options.python {
+            Some(PythonScope::Venv(_)) => "local",
+            Some(PythonScope::User) => "user",
+            _ => "global",
+        },
+        "brew" => match options.brew {
+            Some(BrewScope::Formula) => "formula",
+            Some(BrewScope::Cask) => "cask",
+            _ => "global",
+        },
+        _ => "global",
+    }
+}
@@ -0,0 +1,325 @@
+use crate::context::{expand_home, run_with_timeout, CliContext};
+use crate::managers::{create_manager, MANAGER_NAMES};
+use crate::project::project_targets;
+use crate::{READ_COMMAND_TIMEOUT, SCAN_CONCURRENCY};
+use anyhow::{Context, Result};
+use boxy_core::project::{aggregate_dependencies, find_projects, ScopeReport};
+use colored::*;
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::Semaphore;
+use tokio::time::{timeout, Duration};
+
+pub async fn cmd_scan(ctx: &CliContext, available_only: bool) -> Result<()> {
+    run_with_timeout("扫描操作超时", async {
+        let scope_config = ctx.resolve_scope(None)?;
+        let global = scope_config.global;
+        let workdir = scope_config.workdir.clone();
+        if ctx.json {
+            let mut results = Vec::new();
+            for name in MANAGER_NAMES.iter() {
+                let cache_clone = ctx.cache.clone();
+                let manager_name = name.to_string();
+                let manager = create_manager(
+                    &manager_name,
+                    cache_clone.clone(),
+                    global,
+                    workdir.as_ref(),
+                    &ctx.options,
+                );
+                if let Some(m) = manager {
+                    let available = m.check_available().await.unwrap_or(false);
+                    if available_only && !available {
+                        continue;
+                    }
+
+                    let packages = if available {
+                        // 只有在 no_cache 为 true 时才清除缓存
+                        if ctx.no_cache {
+                            if let Err(err) = cache_clone.invalidate(m.cache_key()).await {
+                                eprintln!(
+                                    "{}",
+                                    format!("错误: 清除 {} 缓存失败: {}", manager_name, err)
+                                        .bright_red()
+                                );
+                            }
+                        }
+                        match m.list_installed().await {
+                            Ok(list) => list,
+                            Err(err) => {
+                                eprintln!(
+                                    "{}",
+                                    format!("错误: 获取 {} 包列表失败: {}", manager_name, err)
+                                        .bright_red()
+                                );
+                                Vec::new()
+                            }
+                        }
+                    } else {
+                        Vec::new()
+                    };
+
+                    results.push(serde_json::json!({
+                      "manager": manager_name,
+                      "available": available,
+                      "package_count": packages.len(),
+                    }));
+                }
+            }
+            println!("{}", serde_json::to_string_pretty(&results)?);
+        } else {
+            println!("{}", "扫描所有包管理器...".bright_cyan());
+            println!();
+
+            // 并行检查所有管理器
+            let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+            let tasks: Vec<_> = MANAGER_NAMES
+                .iter()
+                .map(|name| {
+                    let cache_clone = ctx.cache.clone();
+                    let manager_name = name.to_string();
+                    let semaphore = semaphore.clone();
+                    let workdir = workdir.clone();
+                    let options = ctx.options.clone();
+                    tokio::spawn(async move {
+                        let _permit = match semaphore.acquire().await {
+                            Ok(permit) => permit,
+                            Err(_) => return (manager_name, false),
+                        };
+                        let manager = create_manager(
+                            &manager_name,
+                            cache_clone,
+                            global,
+                            workdir.as_ref(),
+                            &options,
+                        );
+                        if let Some(m) = manager {
+                            let available = m.check_available().await.unwrap_or(false);
+                            (manager_name, available)
+                        } else {
+                            (manager_name, false)
+                        }
+                    })
+                })
+                .collect();
+
+            let mut statuses = Vec::new();
+            for task in tasks {
+                if let Ok((name, available)) = task.await {
+                    statuses.push((name, available));
+                }
+            }
+
+            for (name, available) in statuses {
+                if available_only && !available {
+                    continue;
+                }
+
+                let status = if available {
+                    "✓".bright_green()
+                } else {
+                    "✗".bright_red()
+                };
+                println!("  {} {}", status, name.bright_white());
+
+                if available {
+                    let cache_clone = ctx.cache.clone();
+                    let manager = create_manager(
+                        &name,
+                        cache_clone.clone(),
+                        global,
+                        workdir.as_ref(),
+                        &ctx.options,
+                    );
+                    if let Some(m) = manager {
+                        // 只有在 no_cache 为 true 时才清除缓存
+                        if ctx.no_cache {
+                            if let Err(err) = cache_clone.invalidate(m.cache_key()).await {
+                                eprintln!(
+                                    "{}",
+                                    format!("错误: 清除 {} 缓存失败: {}", name, err).bright_red()
+                                );
+                            }
+                        }
+                        if let Ok(Ok(packages)) =
+                            timeout(Duration::from_secs(5), m.list_installed()).await
+                        {
+                            println!("    {} 已安装包: {}", "→".bright_blue(), packages.len());
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// 扫描目录下的全部项目：逐个范围列出依赖并检查更新，再按依赖汇总
+///
+/// 各范围使用本地范围的缓存键（按目录哈希），重复扫描时直接读取缓存。
+pub async fn cmd_scan_projects(ctx: &CliContext, root: &str, depth: usize) -> Result<()> {
+    let root = expand_home(root)?;
+    if !root.is_dir() {
+        return Err(anyhow::anyhow!("目录不存在或不可访问"));
+    }
+    let root = root.canonicalize().unwrap_or(root);
+    let walk_root = root.clone();
+    let projects = tokio::task::spawn_blocking(move || find_projects(&walk_root, depth))
+        .await
+        .map_err(|err| anyhow::anyhow!("任务执行失败: {}", err))?;
+    let targets = project_targets(&projects);
+    if !ctx.json {
+        println!(
+            "{}",
+            format!(
+                "在 {} 中发现 {} 个项目（{} 个范围），正在检查依赖...",
+                root.display(),
+                projects.len(),
+                targets.len()
+            )
+            .bright_cyan()
+        );
+        println!();
+    }
+
+    // 每个包管理器只检查一次是否可用
+    let mut available = BTreeMap::new();
+    for target in &targets {
+        if available.contains_key(&target.manager) {
+            continue;
+        }
+        let ok = match create_manager(
+            &target.manager,
+            ctx.cache.clone(),
+            false,
+            None,
+            &ctx.options,
+        ) {
+            Some(manager) => manager.check_available().await.unwrap_or(false),
+            None => false,
+        };
+        available.insert(target.manager.clone(), ok);
+    }
+
+    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+    let tasks: Vec<_> = targets
+        .into_iter()
+        .filter(|target| available.get(&target.manager).copied().unwrap_or(false))
+        .map(|target| {
+            let cache = ctx.cache.clone();
+            let no_cache = ctx.no_cache;
+            let semaphore = semaphore.clone();
+            let options = ctx.options.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await;
+                let path = target.workdir.clone().unwrap_or_default();
+                let manager =
+                    create_manager(&target.manager, cache.clone(), false, Some(&path), &options)
+                        .ok_or_else(|| anyhow::anyhow!("未知的包管理器"))?;
+                if no_cache {
+                    cache
+                        .invalidate(manager.cache_key())
+                        .await
+                        .with_context(|| format!("清除 {} 缓存失败", target.manager))?;
+                }
+                let installed = timeout(READ_COMMAND_TIMEOUT, manager.list_installed())
+                    .await
+                    .map_err(|_| anyhow::anyhow!("列出依赖超时"))??;
+                let outdated = timeout(READ_COMMAND_TIMEOUT, manager.check_outdated())
+                    .await
+                    .map_err(|_| anyhow::anyhow!("检查更新超时"))??;
+                Ok::<_, anyhow::Error>(ScopeReport {
+                    path,
+                    manager: target.manager,
+                    installed,
+                    outdated,
+                })
+            })
+        })
+        .collect();
+
+    let mut reports = Vec::new();
+    let mut errors = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(Ok(report)) => reports.push(report),
+            Ok(Err(err)) => errors.push(err.to_string()),
+            Err(err) => errors.push(format!("任务执行失败: {}", err)),
+        }
+    }
+    reports.sort_by(|a, b| a.path.cmp(&b.path));
+    let usages = aggregate_dependencies(&reports);
+
+    if ctx.json {
+        let scopes: Vec<serde_json::Value> = reports
+            .iter()
+            .map(|report| {
+                serde_json::json!({
+                  "path": report.path,
+                  "manager": report.manager,
+                  "package_count": report.installed.len(),
+                  "outdated": report.outdated,
+                })
+            })
+            .collect();
+        let output = serde_json::json!({
+          "root": root,
+          "projects": projects,
+          "scopes": scopes,
+          "dependencies": usages,
+          "errors": errors,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    for report in &reports {
+        let relative = report.path.strip_prefix(&root).unwrap_or(&report.path);
+        let relative = if relative.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            relative.display().to_string()
+        };
+        println!(
+            "  {} {}  依赖: {}  过时: {}",
+            relative.bright_white(),
+            format!("({})", report.manager).dimmed(),
+            report.installed.len(),
+            if report.outdated.is_empty() {
+                "0".bright_green()
+            } else {
+                report.outdated.len().to_string().bright_yellow()
+            }
+        );
+    }
+    for error in &errors {
+        eprintln!("{}", format!("错误: {}", error).bright_red());
+    }
+
+    // 只有多个项目共用的依赖才值得汇总
+    let shared: Vec<_> = usages
+        .iter()
+        .filter(|usage| usage.projects.len() > 1)
+        .collect();
+    if !shared.is_empty() {
+        println!();
+        println!("{}", "多个项目共用的依赖".bold());
+        for usage in shared {
+            let outdated = if usage.outdated > 0 {
+                format!("，{} 个过时", usage.outdated).bright_yellow()
+            } else {
+                "".normal()
+            };
+            println!(
+                "  {} {} {}  用于 {} 个项目{}",
+                usage.name.bright_white(),
+                usage.versions.join(", "),
+                format!("({})", usage.ecosystem).dimmed(),
+                usage.projects.len(),
+                outdated
+            );
+        }
+    }
+    Ok(())
+}
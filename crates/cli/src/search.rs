@@ -0,0 +1,169 @@
+use crate::context::{resolve_manager_names, run_with_timeout, CliContext};
+use crate::managers::create_manager;
+use crate::{SCAN_CONCURRENCY, SEARCH_TIMEOUT};
+use anyhow::{Context, Result};
+use boxy_core::{search_all, Capability, SearchOutcome};
+use colored::*;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+pub async fn cmd_search(
+    ctx: &CliContext,
+    query: &str,
+    manager_name: Option<&str>,
+    by_manager: bool,
+) -> Result<()> {
+    run_with_timeout("搜索超时", async {
+        let scope_config = ctx.resolve_scope(manager_name)?;
+        let global = scope_config.global;
+        let workdir = scope_config.workdir.clone();
+        let manager_names = resolve_manager_names(manager_name);
+
+        if manager_name.is_none() && !by_manager {
+            let managers = manager_names
+                .iter()
+                .filter_map(|name| {
+                    create_manager(
+                        name,
+                        ctx.cache.clone(),
+                        global,
+                        workdir.as_ref(),
+                        &ctx.options,
+                    )
+                })
+                .filter(|manager| manager.supports(Capability::SearchRemote))
+                .collect();
+            let outcome = search_all(managers, query, SEARCH_TIMEOUT).await;
+            print_merged_search(&outcome, ctx.json)?;
+            return Ok(());
+        }
+
+        // 并行搜索所有管理器
+        let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
+        let tasks: Vec<_> = manager_names
+            .iter()
+            .map(|name| {
+                let manager_name = name.clone();
+                let cache_clone = ctx.cache.clone();
+                let query_str = query.to_string();
+                let semaphore = semaphore.clone();
+                let workdir = workdir.clone();
+                let options = ctx.options.clone();
+                tokio::spawn(async move {
+                    let _permit = match semaphore.acquire().await {
+                        Ok(permit) => permit,
+                        Err(_) => return Ok((manager_name, Vec::new())),
+                    };
+                    let manager = create_manager(
+                        &manager_name,
+                        cache_clone,
+                        global,
+                        workdir.as_ref(),
+                        &options,
+                    );
+                    if let Some(m) = manager {
+                        if !m.check_available().await.unwrap_or(false) {
+                            return Ok((manager_name, Vec::new()));
+                        }
+
+                        let packages = m
+                            .search(&query_str)
+                            .await
+                            .with_context(|| format!("搜索 {} 失败", manager_name))?;
+                        Ok((manager_name, packages))
+                    } else {
+                        Ok((manager_name, Vec::new()))
+                    }
+                })
+            })
+            .collect();
+
+        let mut all_results = Vec::new();
+        for task in tasks {
+            match task.await {
+                Ok(Ok((name, packages))) => all_results.push((name, packages)),
+                Ok(Err(err)) => return Err(err),
+                Err(err) => return Err(anyhow::anyhow!("任务执行失败: {}", err)),
+            }
+        }
+
+        if ctx.json {
+            let output: Vec<serde_json::Value> = all_results
+                .into_iter()
+                .map(|(manager, packages)| {
+                    serde_json::json!({
+                      "manager": manager,
+                      "packages": packages,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            for (manager, packages) in all_results {
+                if packages.is_empty() {
+                    continue;
+                }
+
+                println!(
+                    "{}",
+                    format!("{} ({})", manager.bright_cyan(), packages.len()).bold()
+                );
+                for pkg in packages.iter().take(10) {
+                    println!("  {} {}", "•".bright_green(), pkg.name.bright_white());
+                    if let Some(desc) = &pkg.description {
+                        println!("    {}", desc.dimmed());
+                    }
+                }
+                if packages.len() > 10 {
+                    println!("  ... 还有 {} 个结果", packages.len() - 10);
+                }
+                println!();
+            }
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// 输出跨包管理器合并后的搜索结果
+fn print_merged_search(outcome: &SearchOutcome, json: bool) -> Result<()> {
+    if json {
+        let output = serde_json::json!({
+          "results": outcome.results,
+          "errors": outcome
+              .errors
+              .iter()
+              .map(|(manager, error)| serde_json::json!({ "manager": manager, "error": error }))
+              .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    for (manager, error) in &outcome.errors {
+        eprintln!(
+            "{}",
+            format!("⚠ 搜索 {} 失败: {}", manager, error).bright_yellow()
+        );
+    }
+    if outcome.results.is_empty() {
+        println!("{}", "未找到匹配的包".bright_yellow());
+        return Ok(());
+    }
+    for result in outcome.results.iter().take(20) {
+        println!(
+            "  {} {} {}",
+            "•".bright_green(),
+            result.name.bright_white(),
+            format!("[{}]", result.managers.join(", ")).bright_cyan()
+        );
+        if let Some(desc) = &result.description {
+            println!("    {}", desc.dimmed());
+        }
+    }
+    if outcome.results.len() > 20 {
+        println!("  ... 还有 {} 个结果", outcome.results.len() - 20);
+    }
+    Ok(())
+}
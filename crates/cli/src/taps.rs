@@ -0,0 +1,117 @@
+use crate::context::CliContext;
+use crate::{BrewAction, TapAction, COMMAND_TIMEOUT, READ_COMMAND_TIMEOUT};
+use anyhow::{Context, Result};
+use boxy_brew::{BrewManager, BrewScope};
+use boxy_core::{Package, PackageManager};
+use colored::*;
+use tokio::time::timeout;
+
+pub async fn cmd_brew(ctx: &CliContext, action: BrewAction) -> Result<()> {
+    let manager = BrewManager::new(ctx.cache.clone(), BrewScope::All);
+    if !manager.check_available().await.unwrap_or(false) {
+        return Err(anyhow::anyhow!("包管理器 'brew' 不可用"));
+    }
+
+    let done = |message: String| -> Result<()> {
+        if ctx.json {
+            println!(
+                "{}",
+                serde_json::json!({ "success": true, "message": message })
+            );
+        } else {
+            println!("{}", format!("✓ {}", message).bright_green());
+        }
+        Ok(())
+    };
+
+    let BrewAction::Taps { action } = action;
+    match action.unwrap_or(TapAction::List) {
+        TapAction::List => {
+            let taps = timeout(READ_COMMAND_TIMEOUT, manager.taps())
+                .await
+                .map_err(|_| anyhow::anyhow!("读取 brew tap 超时"))?
+                .context("读取 brew tap 失败")?;
+            // 列出每个 tap 中已安装的包，读取失败时只显示 tap
+            let installed = timeout(READ_COMMAND_TIMEOUT, manager.list_installed())
+                .await
+                .ok()
+                .and_then(|result| result.ok())
+                .unwrap_or_default();
+            let installed_from = |tap: &str| -> Vec<&Package> {
+                installed
+                    .iter()
+                    .filter(|pkg| pkg.tap.as_deref() == Some(tap))
+                    .collect()
+            };
+            if ctx.json {
+                let output: Vec<serde_json::Value> = taps
+                    .iter()
+                    .map(|tap| {
+                        let packages: Vec<&str> = installed_from(&tap.name)
+                            .into_iter()
+                            .map(|pkg| pkg.name.as_str())
+                            .collect();
+                        serde_json::json!({
+                          "tap": tap,
+                          "installed": packages,
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+            if taps.is_empty() {
+                println!("{}", "没有添加任何 tap".bright_yellow());
+                return Ok(());
+            }
+            for tap in &taps {
+                let official = if tap.official {
+                    format!(" {}", "(官方)".dimmed())
+                } else {
+                    String::new()
+                };
+                println!("{}{}", tap.name.bright_cyan().bold(), official);
+                if let Some(remote) = &tap.remote {
+                    println!("  远程: {}", remote);
+                }
+                println!(
+                    "  包含: {} 个 formula，{} 个 cask",
+                    tap.formula_names.len(),
+                    tap.cask_tokens.len()
+                );
+                let packages = installed_from(&tap.name);
+                if !packages.is_empty() {
+                    let names: Vec<&str> = packages.iter().map(|pkg| pkg.name.as_str()).collect();
+                    println!("  已安装: {}", names.join(", "));
+                }
+                if let Some(last_commit) = &tap.last_commit {
+                    println!("  {}", format!("最后更新: {}", last_commit).dimmed());
+                }
+            }
+            Ok(())
+        }
+        TapAction::Add { name, url } => {
+            timeout(COMMAND_TIMEOUT, manager.tap(&name, url.as_deref()))
+                .await
+                .map_err(|_| anyhow::anyhow!("brew tap 超时"))?
+                .with_context(|| format!("添加 tap {} 失败", name))?;
+            done(format!("已添加 tap {}", name))
+        }
+        TapAction::Remove { name, force } => {
+            timeout(COMMAND_TIMEOUT, manager.untap(&name, force))
+                .await
+                .map_err(|_| anyhow::anyhow!("brew untap 超时"))?
+                .with_context(|| {
+                    if force {
+                        format!("移除 tap {} 失败", name)
+                    } else {
+                        format!(
+                            "移除 tap {} 失败（仍有从该 tap 安装的包时可加 --force）",
+                            name
+                        )
+                    }
+                })?;
+            done(format!("已移除 tap {}", name))
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::context::CliContext;
+use crate::diff::print_changes;
+use crate::managers::create_manager;
+use crate::{COMMAND_TIMEOUT, EXIT_ERROR, READ_COMMAND_TIMEOUT};
+use anyhow::{Context, Result};
+use boxy_core::{
+    snapshot_installed, Compensation, HistoryStore, Package, PackageDiff, PackageManager,
+    StepAction, Transaction, TransactionStatus, TransactionStep,
+};
+use boxy_error::BoxyError;
+use colored::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::time::timeout;
+
+/// 事务中计划执行的一步
+pub struct PlannedStep {
+    pub manager: String,
+    pub package: String,
+    pub action: StepAction,
+    pub version: Option<String>,
+}
+
+/// 以事务方式依次执行计划
+///
+/// 执行前记录每个包的原版本；任一步骤失败或收到 Ctrl-C 时，按逆序卸载新装的包、
+/// 重新安装原版本。收到 Ctrl-C 时会等正在执行的命令结束后再回滚，不会与其并发修改环境。
+/// 事务报告写入历史记录，失败时以 EXIT_ERROR 退出。
+pub async fn run_transaction(
+    ctx: &CliContext,
+    operation: &str,
+    plan: Vec<PlannedStep>,
+    force: bool,
+) -> Result<()> {
+    let mut managers: BTreeMap<String, Box<dyn PackageManager>> = BTreeMap::new();
+    let mut installed: BTreeMap<String, Vec<Package>> = BTreeMap::new();
+    for step in &plan {
+        if managers.contains_key(&step.manager) {
+            continue;
+        }
+        let scope_config = ctx.resolve_scope(Some(&step.manager))?;
+        let manager = create_manager(
+            &step.manager,
+            ctx.cache.clone(),
+            scope_config.global,
+            scope_config.workdir.as_ref(),
+            &ctx.options,
+        )
+        .ok_or_else(|| anyhow::anyhow!("未知的包管理器: {}", step.manager))?;
+        if !manager.check_available().await.unwrap_or(false) {
+            return Err(anyhow::anyhow!("{} 不可用", step.manager));
+        }
+        // 基线绕过列表缓存，否则可能记录到过期的原版本
+        let packages = timeout(
+            READ_COMMAND_TIMEOUT,
+            snapshot_installed(manager.as_ref(), &ctx.cache),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("读取 {} 已安装包超时", step.manager))?
+        .with_context(|| format!("读取 {} 已安装包失败", step.manager))?;
+        installed.insert(step.manager.clone(), packages);
+        managers.insert(step.manager.clone(), manager);
+    }
+    let previous_version = |manager: &str, name: &str| {
+        installed.get(manager).and_then(|packages| {
+            packages
+                .iter()
+                .find(|pkg| pkg.name.eq_ignore_ascii_case(name))
+                .map(|pkg| pkg.version.clone())
+        })
+    };
+
+    let mut transaction = Transaction::new(operation);
+    if !ctx.json {
+        println!(
+            "{} {} ({} 步)",
+            "开始事务".bold(),
+            transaction.id.bright_white(),
+            plan.len()
+        );
+    }
+
+    // Ctrl-C 只记录中断请求，当前步骤的子进程结束后再停止，避免补偿操作与其并发执行；
+    // 监听持续到回滚结束，期间再次按下 Ctrl-C 不会打断回滚
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let listener = {
+        let interrupted = interrupted.clone();
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                interrupted.store(true, Ordering::SeqCst);
+            }
+        })
+    };
+    let interrupt_reason = || "用户中断 (Ctrl-C)".to_string();
+
+    let steps = async {
+        for planned in &plan {
+            if interrupted.load(Ordering::SeqCst) {
+                return Err(interrupt_reason());
+            }
+            let manager = &managers[&planned.manager];
+            let previous = previous_version(&planned.manager, &planned.package);
+            let index = transaction.begin_step(TransactionStep::new(
+                &planned.manager,
+                &planned.package,
+                planned.action,
+                planned.version.as_deref(),
+                previous.as_deref(),
+            ));
+            if !ctx.json {
+                println!(
+                    "  {} {} ({})...",
+                    match planned.action {
+                        StepAction::Install => "安装",
+                        StepAction::Upgrade => "更新",
+                        StepAction::Bump => "升级到最新版本",
+                    },
+                    planned.package.bright_white(),
+                    planned.manager.bright_cyan()
+                );
+            }
+            let result = ctx
+                .executor
+                .execute(&planned.manager, || async {
+                    let command = async {
+                        match planned.action {
+                            StepAction::Install => {
+                                manager
+                                    .install(&planned.package, planned.version.as_deref(), force)
+                                    .await
+                            }
+                            StepAction::Upgrade => manager.upgrade(&planned.package).await,
+                            StepAction::Bump => manager.upgrade_latest(&planned.package).await,
+                        }
+                    };
+                    timeout(COMMAND_TIMEOUT, command)
+                        .await
+                        .map_err(|_| BoxyError::CommandTimeout)?
+                })
+                .await;
+            match result {
+                Ok(()) => transaction.complete_step(index),
+                Err(err) => {
+                    transaction.fail_step(index, &err.to_string());
+                    // 子进程同样收到 SIGINT，失败原因以用户中断为准
+                    if interrupted.load(Ordering::SeqCst) {
+                        return Err(interrupt_reason());
+                    }
+                    return Err(format!("{} 失败: {}", planned.package, err));
+                }
+            }
+        }
+        if interrupted.load(Ordering::SeqCst) {
+            return Err(interrupt_reason());
+        }
+        Ok(())
+    };
+    let reason = steps.await.err();
+
+    if let Some(reason) = &reason {
+        if !ctx.json {
+            eprintln!("{}", format!("✗ {}，开始回滚", reason).bright_red());
+        }
+        for (index, compensation) in transaction.rollback_plan() {
+            let step = &transaction.steps[index];
+            let manager = &managers[&step.manager];
+            let package = step.package.clone();
+            let result = ctx
+                .executor
+                .execute(&step.manager, || async {
+                    let command = async {
+                        match &compensation {
+                            Compensation::Uninstall => manager.uninstall(&package, true).await,
+                            Compensation::Reinstall(version) => {
+                                manager.install(&package, Some(version), true).await
+                            }
+                        }
+                    };
+                    timeout(COMMAND_TIMEOUT, command)
+                        .await
+                        .map_err(|_| BoxyError::CommandTimeout)?
+                })
+                .await;
+            if !ctx.json {
+                let action = match &compensation {
+                    Compensation::Uninstall => format!("卸载 {}", package),
+                    Compensation::Reinstall(version) => format!("恢复 {}@{}", package, version),
+                };
+                match &result {
+                    Ok(()) => println!("  {} {}", "↺".bright_yellow(), action),
+                    Err(err) => {
+                        eprintln!("  {}", format!("✗ {} 失败: {}", action, err).bright_red())
+                    }
+                }
+            }
+            transaction.record_rollback(index, result.err().map(|err| err.to_string()));
+        }
+    }
+    listener.abort();
+    transaction.finish(reason);
+
+    for (name, manager) in &managers {
+        let Some(before) = installed.get(name) else {
+            continue;
+        };
+        let after = timeout(
+            READ_COMMAND_TIMEOUT,
+            snapshot_installed(manager.as_ref(), &ctx.cache),
+        )
+        .await;
+        if let Ok(Ok(after)) = after {
+            let diff = PackageDiff::between(name, before, &after);
+            if !diff.is_empty() {
+                transaction.changes.push(diff);
+            }
+        }
+    }
+    let saved = HistoryStore::new().and_then(|store| store.save(&transaction));
+    if let Err(err) = &saved {
+        eprintln!("{}", format!("⚠ 写入事务记录失败: {}", err).bright_yellow());
+    }
+
+    if ctx.json {
+        println!("{}", serde_json::to_string_pretty(&transaction)?);
+    } else {
+        match transaction.status {
+            TransactionStatus::Committed => {
+                println!("{}", "✓ 事务已提交".bright_green());
+            }
+            TransactionStatus::RolledBack => {
+                println!("{}", "↺ 事务已回滚".bright_yellow());
+            }
+            _ => {
+                eprintln!(
+                    "{}",
+                    "✗ 部分步骤回滚失败，请根据事务记录手动处理".bright_red()
+                );
+            }
+        }
+        print_changes(&transaction.changes);
+        if let Ok(path) = &saved {
+            println!("事务记录: {}", path.display());
+        }
+    }
+
+    if transaction.status != TransactionStatus::Committed {
+        std::process::exit(EXIT_ERROR);
+    }
+    Ok(())
+}
@@ -1,12 +1,14 @@
 use boxy_cache::Cache;
-use boxy_cli::managers::{create_manager, MANAGER_NAMES};
+use boxy_cli::managers::{create_manager, ManagerOptions, MANAGER_NAMES};
+use boxy_core::python::PythonScope;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 #[tokio::test]
 async fn test_create_all_managers() {
     let cache = Arc::new(Cache::new().unwrap());
     for name in MANAGER_NAMES {
-        let manager = create_manager(name, cache.clone(), false);
+        let manager = create_manager(name, cache.clone(), false, None, &ManagerOptions::default());
         assert!(manager.is_some(), "应该能创建 {} 管理器", name);
         if let Some(m) = manager {
             assert_eq!(m.name(), name);
@@ -17,7 +19,7 @@ async fn test_create_all_managers() {
 #[tokio::test]
 async fn test_manager_check_available() {
     let cache = Arc::new(Cache::new().unwrap());
-    let manager = create_manager("npm", cache, false);
+    let manager = create_manager("npm", cache, false, None, &ManagerOptions::default());
 
     if let Some(m) = manager {
         // 这个测试可能会失败如果系统没有安装 npm，这是正常的
@@ -28,10 +30,37 @@ async fn test_manager_check_available() {
 #[tokio::test]
 async fn test_manager_capabilities() {
     let cache = Arc::new(Cache::new().unwrap());
-    let manager = create_manager("npm", cache, false);
+    let manager = create_manager("npm", cache, false, None, &ManagerOptions::default());
 
     if let Some(m) = manager {
         let caps = m.capabilities();
         assert!(!caps.is_empty(), "npm 应该至少有一个能力");
     }
 }
+
+#[test]
+fn test_python_scope_only_in_local_scope() {
+    let cache = Arc::new(Cache::new().unwrap());
+    let dir = std::env::temp_dir().join(format!("boxy-cli-venv-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join(".venv")).unwrap();
+    std::fs::write(dir.join(".venv").join("pyvenv.cfg"), "home = /usr/bin\n").unwrap();
+
+    let cache_key = |workdir: Option<&PathBuf>, options: &ManagerOptions| {
+        create_manager("pip", cache.clone(), false, workdir, options)
+            .unwrap()
+            .cache_key()
+            .to_string()
+    };
+    let options = ManagerOptions::default();
+    // 未指定本地目录时不自动发现虚拟环境
+    assert_eq!(cache_key(None, &options), "pip");
+    assert!(cache_key(Some(&dir), &options).starts_with("pip-venv-"));
+    // --python、--user 优先于本地目录中的虚拟环境
+    let user = ManagerOptions {
+        python: Some(PythonScope::User),
+        ..Default::default()
+    };
+    assert_eq!(cache_key(Some(&dir), &user), "pip-user");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,134 @@
+use crate::manager::PackageManager;
+use crate::package::Package;
+use boxy_cache::Cache;
+use boxy_error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffEntry {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// 一次变更操作前后已安装包的差异，包含包管理器顺带安装或移除的依赖
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageDiff {
+    pub manager: String,
+    pub added: Vec<DiffEntry>,
+    pub removed: Vec<DiffEntry>,
+    pub changed: Vec<VersionChange>,
+}
+
+impl PackageDiff {
+    /// 按包名（不区分大小写）对比两次快照，结果按包名排序
+    pub fn between(manager: &str, before: &[Package], after: &[Package]) -> Self {
+        let index = |packages: &[Package]| -> BTreeMap<String, (String, String)> {
+            packages
+                .iter()
+                .map(|pkg| {
+                    (
+                        pkg.name.to_lowercase(),
+                        (pkg.name.clone(), pkg.version.clone()),
+                    )
+                })
+                .collect()
+        };
+        let before = index(before);
+        let after = index(after);
+
+        let mut diff = Self {
+            manager: manager.to_string(),
+            ..Self::default()
+        };
+        for (key, (name, version)) in &after {
+            match before.get(key) {
+                None => diff.added.push(DiffEntry {
+                    name: name.clone(),
+                    version: version.clone(),
+                }),
+                Some((_, previous)) if previous != version => diff.changed.push(VersionChange {
+                    name: name.clone(),
+                    from: previous.clone(),
+                    to: version.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (key, (name, version)) in &before {
+            if !after.contains_key(key) {
+                diff.removed.push(DiffEntry {
+                    name: name.clone(),
+                    version: version.clone(),
+                });
+            }
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// 逐行描述差异，供任务日志使用：`+ 新增`、`- 移除`、`~ 版本变化`
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for entry in &self.added {
+            lines.push(format!("+ {} {}", entry.name, entry.version));
+        }
+        for entry in &self.removed {
+            lines.push(format!("- {} {}", entry.name, entry.version));
+        }
+        for change in &self.changed {
+            lines.push(format!("~ {} {} → {}", change.name, change.from, change.to));
+        }
+        lines
+    }
+}
+
+/// 读取包管理器当前已安装的包
+///
+/// 先清除该管理器的列表缓存，确保快照反映真实状态。
+pub async fn snapshot_installed(
+    manager: &dyn PackageManager,
+    cache: &Cache,
+) -> Result<Vec<Package>> {
+    cache.invalidate(manager.cache_key()).await?;
+    manager.list_installed().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_between() {
+        let before = vec![
+            Package::new("pip", "black", "23.1.0"),
+            Package::new("pip", "click", "8.1.0"),
+            Package::new("pip", "six", "1.16.0"),
+        ];
+        let after = vec![
+            Package::new("pip", "Black", "24.1.0"),
+            Package::new("pip", "click", "8.1.0"),
+            Package::new("pip", "pathspec", "0.12.1"),
+        ];
+        let diff = PackageDiff::between("pip", &before, &after);
+        assert_eq!(
+            diff.lines(),
+            vec![
+                "+ pathspec 0.12.1",
+                "- six 1.16.0",
+                "~ Black 23.1.0 → 24.1.0",
+            ]
+        );
+        assert!(PackageDiff::between("pip", &before, &before).is_empty());
+    }
+}
@@ -0,0 +1,614 @@
+use crate::manager::PackageManager;
+use boxy_cache::Cache;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+const SIZE_CACHE_PREFIX: &str = "disk-usage";
+const CACHE_USAGE_PREFIX: &str = "cache-usage";
+
+/// 包占用空间的统计来源
+#[derive(Debug, Clone)]
+pub enum SizeSource {
+    /// 目录或单个文件（目录本身为符号链接时会解析到真实路径）
+    Path(PathBuf),
+    /// Python 的 dist-info 目录，按 RECORD 中列出的文件统计
+    DistInfo(PathBuf),
+    /// 一组独立文件，例如 cargo 安装的多个二进制
+    Files(Vec<PathBuf>),
+}
+
+impl SizeSource {
+    fn identity(&self) -> String {
+        match self {
+            SizeSource::Path(path) => path.to_string_lossy().to_string(),
+            SizeSource::DistInfo(path) => format!("record:{}", path.to_string_lossy()),
+            SizeSource::Files(paths) => paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join(":"),
+        }
+    }
+
+    /// 决定缓存条目是否仍然有效的路径
+    fn paths(&self) -> Vec<PathBuf> {
+        match self {
+            SizeSource::Path(path) | SizeSource::DistInfo(path) => vec![path.clone()],
+            SizeSource::Files(paths) => paths.clone(),
+        }
+    }
+
+    fn modified(&self) -> Option<i64> {
+        match self {
+            SizeSource::Path(path) | SizeSource::DistInfo(path) => modified_secs(path),
+            SizeSource::Files(paths) => paths.iter().filter_map(|path| modified_secs(path)).max(),
+        }
+    }
+
+    /// 每个包使用独立的 inode 集合，结果不受其他包统计顺序的影响
+    fn compute(&self) -> u64 {
+        let mut seen = InodeSet::new();
+        match self {
+            SizeSource::Path(path) => walk_size(path, &mut seen),
+            SizeSource::DistInfo(path) => {
+                record_size(path).unwrap_or_else(|| walk_size(path, &mut seen))
+            }
+            SizeSource::Files(paths) => paths.iter().map(|path| walk_size(path, &mut seen)).sum(),
+        }
+    }
+}
+
+/// 单个缓存目录的占用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheDir {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// 包管理器下载缓存的可回收空间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheUsage {
+    pub manager: String,
+    pub dirs: Vec<CacheDir>,
+    pub total: u64,
+    /// 执行 `clean_cache` 可释放的空间，无法得知时为 None
+    #[serde(default)]
+    pub reclaimable: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SizeEntry {
+    source: String,
+    mtime: i64,
+    size: u64,
+    /// 旧版本写入的条目没有路径，会在下一次统计时被丢弃
+    #[serde(default)]
+    paths: Vec<PathBuf>,
+}
+
+type InodeSet = HashSet<(u64, u64)>;
+
+/// 原生的并行目录大小统计
+///
+/// 大小统一按实际占用的磁盘块计算，同一个包内的硬链接只计算一次
+/// （pnpm store 大量使用硬链接）。结果按目录修改时间缓存，未变化的目录
+/// 不会重复遍历，对应路径已不存在的条目会从缓存中移除。
+pub struct DiskUsage {
+    cache: Arc<Cache>,
+}
+
+impl DiskUsage {
+    pub fn new(cache: Arc<Cache>) -> Self {
+        Self { cache }
+    }
+
+    pub async fn measure(
+        &self,
+        cache_key: &str,
+        items: Vec<(String, SizeSource)>,
+    ) -> HashMap<String, u64> {
+        let key = format!("{}-{}", SIZE_CACHE_PREFIX, cache_key);
+        let cached: HashMap<String, SizeEntry> = match self.cache.get(&key).await {
+            Ok(value) => value.unwrap_or_default(),
+            Err(err) => {
+                debug!("读取大小缓存失败: {}", err);
+                HashMap::new()
+            }
+        };
+
+        let mut sizes = HashMap::new();
+        // 保留其他条目，单个包的查询不会冲掉整份缓存；已卸载的包随路径一起消失
+        let mut entries = cached.clone();
+        entries.retain(|_, entry| entry.paths.iter().any(|path| path.exists()));
+        let mut pending = Vec::new();
+        for (name, source) in items {
+            // 路径不存在时直接跳过
+            let Some(mtime) = source.modified() else {
+                entries.remove(&name);
+                continue;
+            };
+            let identity = source.identity();
+            if let Some(entry) = cached.get(&name) {
+                if entry.mtime == mtime && entry.source == identity {
+                    sizes.insert(name, entry.size);
+                    continue;
+                }
+            }
+            pending.push((name, source, identity, mtime));
+        }
+
+        if !pending.is_empty() {
+            debug!("统计 {} 个路径的大小 ({})", pending.len(), cache_key);
+            let workers = std::thread::available_parallelism()
+                .map(|value| value.get())
+                .unwrap_or(4);
+            let semaphore = Arc::new(Semaphore::new(workers));
+            let tasks: Vec<_> = pending
+                .into_iter()
+                .map(|(name, source, identity, mtime)| {
+                    let semaphore = semaphore.clone();
+                    tokio::spawn(async move {
+                        let _permit = semaphore.acquire_owned().await.ok()?;
+                        let paths = source.paths();
+                        let size = tokio::task::spawn_blocking(move || source.compute())
+                            .await
+                            .ok()?;
+                        Some((
+                            name,
+                            SizeEntry {
+                                source: identity,
+                                mtime,
+                                size,
+                                paths,
+                            },
+                        ))
+                    })
+                })
+                .collect();
+
+            for task in tasks {
+                if let Ok(Some((name, entry))) = task.await {
+                    sizes.insert(name.clone(), entry.size);
+                    entries.insert(name, entry);
+                }
+            }
+        }
+
+        if let Err(err) = self.cache.set(&key, &entries).await {
+            warn!("写入大小缓存失败: {}", err);
+        }
+
+        sizes
+    }
+}
+
+impl DiskUsage {
+    /// 统计包管理器缓存目录的大小
+    ///
+    /// 缓存目录内部变化不会反映到顶层目录的修改时间，因此每次都完整遍历，
+    /// 结果写入缓存供 TUI 等场景直接读取。
+    pub async fn cache_usage(&self, manager: &dyn PackageManager) -> CacheUsage {
+        let dirs = match manager.cache_dirs().await {
+            Ok(dirs) => dirs,
+            Err(err) => {
+                debug!("获取 {} 缓存目录失败: {}", manager.name(), err);
+                Vec::new()
+            }
+        };
+
+        let tasks: Vec<_> = dirs
+            .into_iter()
+            .map(|path| {
+                tokio::task::spawn_blocking(move || {
+                    let size = dir_size(&path);
+                    CacheDir { path, size }
+                })
+            })
+            .collect();
+        let mut dirs = Vec::new();
+        for task in tasks {
+            if let Ok(dir) = task.await {
+                dirs.push(dir);
+            }
+        }
+
+        let total = dirs.iter().map(|dir| dir.size).sum();
+        let usage = CacheUsage {
+            manager: manager.name().to_string(),
+            total,
+            reclaimable: manager.clean_cache_clears_dirs().then_some(total),
+            dirs,
+        };
+        let key = format!("{}-{}", CACHE_USAGE_PREFIX, usage.manager);
+        if let Err(err) = self.cache.set(&key, &usage).await {
+            warn!("写入缓存占用失败: {}", err);
+        }
+        usage
+    }
+
+    /// 读取上一次统计的缓存占用（不重新遍历）
+    pub async fn cached_cache_usage(&self, manager: &str) -> Option<CacheUsage> {
+        let key = format!("{}-{}", CACHE_USAGE_PREFIX, manager);
+        self.cache.get(&key).await.ok().flatten()
+    }
+}
+
+/// 清理旧缓存条目的结果
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PruneReport {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+/// 删除（或在 dry_run 时仅统计）修改时间早于 cutoff 的缓存条目
+///
+/// 条目是 `parents` 中每个目录的直接子项（单个文件或整个目录），按条目本身的
+/// 修改时间（通常是下载或解压的时间）判断并整体删除，不会只删掉条目中的部分文件。
+pub fn prune_entries_older_than(
+    parents: &[PathBuf],
+    cutoff: SystemTime,
+    dry_run: bool,
+) -> PruneReport {
+    let mut report = PruneReport::default();
+    for parent in parents {
+        let Ok(entries) = fs::read_dir(parent) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let expired = metadata
+                .modified()
+                .map(|modified| modified < cutoff)
+                .unwrap_or(false);
+            if !expired {
+                continue;
+            }
+            let size = dir_size(&path);
+            let removed = dry_run
+                || if metadata.is_dir() {
+                    fs::remove_dir_all(&path).is_ok()
+                } else {
+                    fs::remove_file(&path).is_ok()
+                };
+            if removed {
+                report.entries += 1;
+                report.bytes = report.bytes.saturating_add(size);
+            }
+        }
+    }
+    report
+}
+
+/// 解析 `30d`、`12h`、`2w` 形式的时长，纯数字按天计算
+pub fn parse_age(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    let seconds = match unit.trim() {
+        "" | "d" => 86_400,
+        "w" => 7 * 86_400,
+        "h" => 3_600,
+        "m" => 60,
+        "s" => 1,
+        _ => return None,
+    };
+    Some(Duration::from_secs(number.checked_mul(seconds)?))
+}
+
+/// 过滤出实际存在的绝对路径目录
+pub fn existing_dirs(paths: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+    paths
+        .into_iter()
+        .filter(|path| path.is_absolute() && path.is_dir())
+        .collect()
+}
+
+/// 将字节数格式化为易读的大小
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// 统计单个路径的占用大小（不使用缓存）
+pub fn dir_size(path: &Path) -> u64 {
+    walk_size(path, &mut InodeSet::new())
+}
+
+/// 按 RECORD 文件统计 Python 发行包的大小
+///
+/// RECORD 每行格式为 `path,hash,size`。size 列是文件的字节数，
+/// 为了与目录统计使用同一单位，这里只取路径并读取文件实际占用的磁盘块。
+pub fn record_size(dist_info: &Path) -> Option<u64> {
+    let content = fs::read_to_string(dist_info.join("RECORD")).ok()?;
+    let base = dist_info.parent()?;
+    let mut seen = InodeSet::new();
+    let mut total = 0u64;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        // 路径中可能包含逗号，因此从右侧拆分
+        let mut parts = line.rsplitn(3, ',');
+        let _size = parts.next();
+        let _hash = parts.next();
+        let Some(path) = parts.next() else {
+            continue;
+        };
+        let path = path.trim_matches('"');
+        if let Ok(metadata) = fs::symlink_metadata(base.join(path)) {
+            if metadata.is_file() && claim_inode(&metadata, &mut seen) {
+                total = total.saturating_add(disk_bytes(&metadata));
+            }
+        }
+    }
+    Some(total)
+}
+
+/// 在 site-packages 中查找指定包的 dist-info 目录
+pub fn find_dist_info(site_packages: &Path, name: &str, version: Option<&str>) -> Option<PathBuf> {
+    let wanted = normalize_dist_name(name);
+    let entries = fs::read_dir(site_packages).ok()?;
+    let mut fallback = None;
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(stem) = file_name.strip_suffix(".dist-info") else {
+            continue;
+        };
+        let (dist_name, dist_version) = stem.split_once('-').unwrap_or((stem, ""));
+        if normalize_dist_name(dist_name) != wanted {
+            continue;
+        }
+        match version {
+            Some(version) if version == dist_version => return Some(entry.path()),
+            Some(_) => fallback = Some(entry.path()),
+            None => return Some(entry.path()),
+        }
+    }
+    fallback
+}
+
+/// 按 PEP 503 规范化 Python 包名
+pub fn normalize_dist_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut last_separator = false;
+    for ch in name.chars() {
+        if matches!(ch, '-' | '_' | '.') {
+            if !last_separator {
+                normalized.push('-');
+            }
+            last_separator = true;
+        } else {
+            normalized.push(ch.to_ascii_lowercase());
+            last_separator = false;
+        }
+    }
+    normalized
+}
+
+fn modified_secs(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let secs = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_secs() as i64)
+        .unwrap_or_default();
+    Some(secs)
+}
+
+fn walk_size(path: &Path, seen: &mut InodeSet) -> u64 {
+    // 顶层路径允许是符号链接（例如 pnpm 的 node_modules/<pkg>），内部不跟随符号链接
+    let root = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut total = 0u64;
+    let mut stack = vec![root];
+    while let Some(current) = stack.pop() {
+        let metadata = match fs::symlink_metadata(&current) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        if !claim_inode(&metadata, seen) {
+            continue;
+        }
+        total = total.saturating_add(disk_bytes(&metadata));
+        if metadata.is_dir() {
+            if let Ok(entries) = fs::read_dir(&current) {
+                stack.extend(entries.flatten().map(|entry| entry.path()));
+            }
+        }
+    }
+    total
+}
+
+#[cfg(unix)]
+fn claim_inode(metadata: &fs::Metadata, seen: &mut InodeSet) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    if metadata.is_dir() || metadata.nlink() <= 1 {
+        return true;
+    }
+    seen.insert((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn claim_inode(_metadata: &fs::Metadata, _seen: &mut InodeSet) -> bool {
+    true
+}
+
+#[cfg(unix)]
+fn disk_bytes(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.blocks().saturating_mul(512)
+}
+
+#[cfg(not(unix))]
+fn disk_bytes(metadata: &fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boxy_cache::CacheConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_hardlinks_counted_once() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("pkg");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.js"), vec![0u8; 64 * 1024]).unwrap();
+        let single = dir_size(&dir);
+
+        fs::hard_link(dir.join("a.js"), dir.join("b.js")).unwrap();
+        assert_eq!(dir_size(&dir), single);
+    }
+
+    #[test]
+    fn test_record_size() {
+        let temp_dir = tempdir().unwrap();
+        let dist_info = temp_dir.path().join("black-24.1.0.dist-info");
+        fs::create_dir_all(&dist_info).unwrap();
+        fs::write(temp_dir.path().join("black.py"), "x".repeat(10)).unwrap();
+        let record =
+            "black/__init__.py,sha256=abc,1200\nblack.py,,\nblack-24.1.0.dist-info/RECORD,,\n";
+        fs::write(dist_info.join("RECORD"), record).unwrap();
+
+        // 不存在的文件不计入，已有文件按占用的磁盘块计算，与目录统计一致
+        let expected =
+            dir_size(&temp_dir.path().join("black.py")) + dir_size(&dist_info.join("RECORD"));
+        assert_eq!(record_size(&dist_info), Some(expected));
+        assert_eq!(
+            find_dist_info(temp_dir.path(), "Black", Some("24.1.0")),
+            Some(dist_info)
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30"), Some(Duration::from_secs(30 * 86_400)));
+        assert_eq!(parse_age("2w"), Some(Duration::from_secs(14 * 86_400)));
+        assert_eq!(parse_age("12h"), Some(Duration::from_secs(12 * 3_600)));
+        assert_eq!(parse_age("abc"), None);
+        assert_eq!(parse_age("3y"), None);
+    }
+
+    #[test]
+    fn test_prune_entries_older_than() {
+        let temp_dir = tempdir().unwrap();
+        let crate_dir = temp_dir.path().join("src").join("serde-1.0.0");
+        fs::create_dir_all(crate_dir.join("src")).unwrap();
+        fs::write(crate_dir.join("src").join("lib.rs"), "x".repeat(4096)).unwrap();
+        fs::write(temp_dir.path().join("serde-1.0.0.crate"), "x".repeat(4096)).unwrap();
+        let parents = vec![temp_dir.path().join("src")];
+
+        // 截止时间早于条目修改时间，不会删除任何内容
+        let past = SystemTime::now() - Duration::from_secs(3_600);
+        assert_eq!(prune_entries_older_than(&parents, past, false).entries, 0);
+
+        let future = SystemTime::now() + Duration::from_secs(3_600);
+        let dry_run = prune_entries_older_than(&parents, future, true);
+        assert_eq!(dry_run.entries, 1);
+        assert!(crate_dir.join("src").join("lib.rs").exists());
+
+        // 整个解压目录作为一个条目删除，父目录保留
+        let report = prune_entries_older_than(&parents, future, false);
+        assert_eq!(report.entries, 1);
+        assert_eq!(report.bytes, dry_run.bytes);
+        assert!(!crate_dir.exists());
+        assert!(temp_dir.path().join("src").exists());
+        assert!(temp_dir.path().join("serde-1.0.0.crate").exists());
+    }
+
+    #[tokio::test]
+    async fn test_measure_uses_cache() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Arc::new(
+            Cache::new_with_config(CacheConfig {
+                cache_dir: Some(temp_dir.path().join("cache")),
+                ..CacheConfig::default()
+            })
+            .unwrap(),
+        );
+        let dir = temp_dir.path().join("pkg");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("index.js"), "x".repeat(4096)).unwrap();
+
+        let usage = DiskUsage::new(cache);
+        let items = vec![("pkg".to_string(), SizeSource::Path(dir.clone()))];
+        let first = usage.measure("test", items.clone()).await;
+        let second = usage.measure("test", items).await;
+
+        assert!(first["pkg"] > 0);
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_measure_prunes_removed_paths() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Arc::new(
+            Cache::new_with_config(CacheConfig {
+                cache_dir: Some(temp_dir.path().join("cache")),
+                ..CacheConfig::default()
+            })
+            .unwrap(),
+        );
+        let kept = temp_dir.path().join("kept");
+        let removed = temp_dir.path().join("removed");
+        for dir in [&kept, &removed] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("index.js"), "x".repeat(4096)).unwrap();
+        }
+
+        let usage = DiskUsage::new(cache.clone());
+        usage
+            .measure(
+                "test",
+                vec![
+                    ("kept".to_string(), SizeSource::Path(kept.clone())),
+                    ("removed".to_string(), SizeSource::Path(removed.clone())),
+                ],
+            )
+            .await;
+        fs::remove_dir_all(&removed).unwrap();
+        usage
+            .measure("test", vec![("kept".to_string(), SizeSource::Path(kept))])
+            .await;
+
+        let entries: HashMap<String, SizeEntry> = cache
+            .get(&format!("{}-test", SIZE_CACHE_PREFIX))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(entries.contains_key("kept"));
+        assert!(!entries.contains_key("removed"));
+    }
+}
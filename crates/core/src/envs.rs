@@ -0,0 +1,469 @@
+use crate::disk::dir_size;
+use crate::python::venv_python;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 以路径分隔符（`:`）分隔的扫描根目录
+pub const ENV_ROOTS_ENV: &str = "BOXY_ENV_ROOTS";
+
+/// 默认扫描深度
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+/// 扫描时跳过的目录
+const SKIPPED_DIRS: [&str; 6] = [
+    "node_modules",
+    "target",
+    "Library",
+    "__pycache__",
+    "site-packages",
+    "Applications",
+];
+
+/// Python 环境类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvKind {
+    /// venv / virtualenv 创建的虚拟环境
+    Venv,
+    /// uv 创建的项目环境或工具环境
+    Uv,
+    Conda,
+    Pipx,
+}
+
+impl EnvKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Venv => "venv",
+            Self::Uv => "uv",
+            Self::Conda => "conda",
+            Self::Pipx => "pipx",
+        }
+    }
+}
+
+/// 环境失效的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleReason {
+    /// 记录的项目目录已不存在
+    ProjectMissing,
+    /// 创建环境的基础解释器已不存在
+    InterpreterMissing,
+}
+
+impl StaleReason {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::ProjectMissing => "项目目录已不存在",
+            Self::InterpreterMissing => "基础解释器已不存在",
+        }
+    }
+}
+
+/// 发现的 Python 环境
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PythonEnv {
+    pub path: PathBuf,
+    pub kind: EnvKind,
+    pub interpreter: PathBuf,
+    pub python_version: Option<String>,
+    /// 环境所属的项目目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale: Option<StaleReason>,
+    pub size: u64,
+    /// 已安装的包数量；conda 环境为 conda-meta 中记录的包
+    pub package_count: usize,
+}
+
+impl PythonEnv {
+    /// 检查目录是否为 Python 环境并读取其信息
+    ///
+    /// `kind` 为 None 时按目录内容判断：包含 conda-meta 为 conda，pyvenv.cfg 中记录了 uv 为 uv。
+    pub fn inspect(path: &Path, kind: Option<EnvKind>) -> Option<Self> {
+        let is_conda = path.join("conda-meta").is_dir();
+        let config = if is_conda {
+            None
+        } else {
+            Some(PyvenvConfig::read(path)?)
+        };
+        let kind = kind.unwrap_or(match &config {
+            None => EnvKind::Conda,
+            Some(config) if config.uv => EnvKind::Uv,
+            Some(_) => EnvKind::Venv,
+        });
+
+        let interpreter = venv_python(path);
+        let python_version = config
+            .as_ref()
+            .and_then(|config| config.version.clone())
+            .or_else(|| conda_python_version(path))
+            .or_else(|| site_packages_version(path));
+        let project = project_dir(path);
+        let stale = if project.as_ref().is_some_and(|project| !project.exists()) {
+            Some(StaleReason::ProjectMissing)
+        } else if !interpreter.exists()
+            || config
+                .as_ref()
+                .and_then(|config| config.home.as_ref())
+                .is_some_and(|home| !home.exists())
+        {
+            Some(StaleReason::InterpreterMissing)
+        } else {
+            None
+        };
+        let package_count = if is_conda {
+            count_entries(&path.join("conda-meta"), |name| name.ends_with(".json"))
+        } else {
+            site_packages_dirs(path)
+                .iter()
+                .map(|dir| {
+                    count_entries(dir, |name| {
+                        name.ends_with(".dist-info") || name.ends_with(".egg-info")
+                    })
+                })
+                .sum()
+        };
+
+        Some(Self {
+            path: path.to_path_buf(),
+            kind,
+            interpreter,
+            python_version,
+            project,
+            stale,
+            size: dir_size(path),
+            package_count,
+        })
+    }
+}
+
+/// 在扫描根目录与常见位置（conda、pipx、uv 工具、virtualenvwrapper、pipenv）中发现 Python 环境
+pub struct EnvDiscovery {
+    roots: Vec<PathBuf>,
+    max_depth: usize,
+}
+
+impl EnvDiscovery {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            roots,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// 从 `BOXY_ENV_ROOTS` 读取扫描根目录，未设置时扫描用户目录
+    pub fn from_env() -> Self {
+        let roots = match env::var_os(ENV_ROOTS_ENV) {
+            Some(value) => env::split_paths(&value).collect(),
+            None => dirs::home_dir().into_iter().collect(),
+        };
+        Self::new(roots)
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn discover(&self) -> Vec<PythonEnv> {
+        let mut candidates: Vec<(PathBuf, Option<EnvKind>)> = Vec::new();
+        for root in &self.roots {
+            walk(root, self.max_depth, &mut candidates);
+        }
+        for (dir, kind) in well_known_dirs() {
+            for child in child_dirs(&dir) {
+                candidates.push((child, kind));
+            }
+        }
+        for path in conda_environments() {
+            candidates.push((path, Some(EnvKind::Conda)));
+        }
+        inspect_candidates(candidates)
+    }
+}
+
+/// 去重后逐个读取环境信息，按路径排序
+fn inspect_candidates(mut candidates: Vec<(PathBuf, Option<EnvKind>)>) -> Vec<PythonEnv> {
+    // 同一环境可能从多个位置发现，常见位置给出的类型优先
+    candidates.sort_by_key(|(_, kind)| kind.is_none());
+    let mut seen = HashSet::new();
+    let mut envs = Vec::new();
+    for (path, kind) in candidates {
+        let key = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if !seen.insert(key) {
+            continue;
+        }
+        if let Some(env) = PythonEnv::inspect(&path, kind) {
+            envs.push(env);
+        }
+    }
+    envs.sort_by(|a, b| a.path.cmp(&b.path));
+    envs
+}
+
+/// pyvenv.cfg 中用到的字段
+struct PyvenvConfig {
+    home: Option<PathBuf>,
+    version: Option<String>,
+    uv: bool,
+}
+
+impl PyvenvConfig {
+    fn read(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path.join("pyvenv.cfg")).ok()?;
+        let mut config = Self {
+            home: None,
+            version: None,
+            uv: false,
+        };
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "home" => config.home = Some(PathBuf::from(value)),
+                // venv 写入 version，uv 与 virtualenv 写入 version_info
+                "version" | "version_info" => config.version = Some(value.to_string()),
+                "uv" => config.uv = true,
+                _ => {}
+            }
+        }
+        Some(config)
+    }
+}
+
+/// 递归查找环境，找到后不再进入其内部（conda 安装目录下的 envs 除外）
+fn walk(dir: &Path, depth: usize, found: &mut Vec<(PathBuf, Option<EnvKind>)>) {
+    if dir.join("pyvenv.cfg").is_file() {
+        found.push((dir.to_path_buf(), None));
+        return;
+    }
+    if dir.join("conda-meta").is_dir() {
+        found.push((dir.to_path_buf(), Some(EnvKind::Conda)));
+        for child in child_dirs(&dir.join("envs")) {
+            found.push((child, Some(EnvKind::Conda)));
+        }
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    for child in child_dirs(dir) {
+        let Some(name) = child.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        // 隐藏目录中只有 .venv 可能是项目环境
+        if (name.starts_with('.') && name != ".venv") || SKIPPED_DIRS.contains(&name) {
+            continue;
+        }
+        walk(&child, depth - 1, found);
+    }
+}
+
+/// 目录下的子目录，不跟随符号链接
+fn child_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// 各工具存放虚拟环境的常见目录
+fn well_known_dirs() -> Vec<(PathBuf, Option<EnvKind>)> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".local/share"));
+
+    let mut dirs = Vec::new();
+    match env::var_os("PIPX_HOME") {
+        Some(pipx_home) => dirs.push((PathBuf::from(pipx_home).join("venvs"), Some(EnvKind::Pipx))),
+        None => {
+            dirs.push((home.join(".local/pipx/venvs"), Some(EnvKind::Pipx)));
+            if let Some(data_dir) = dirs::data_dir() {
+                dirs.push((data_dir.join("pipx/venvs"), Some(EnvKind::Pipx)));
+            }
+        }
+    }
+    let uv_tools = env::var_os("UV_TOOL_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| data_home.join("uv/tools"));
+    dirs.push((uv_tools, Some(EnvKind::Uv)));
+    let workon_home = env::var_os("WORKON_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".virtualenvs"));
+    dirs.push((workon_home, None));
+    dirs.push((data_home.join("virtualenvs"), None));
+    dirs.push((home.join(".conda/envs"), Some(EnvKind::Conda)));
+    dirs
+}
+
+/// `~/.conda/environments.txt` 中登记的 conda 环境
+fn conda_environments() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    fs::read_to_string(home.join(".conda/environments.txt"))
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 环境所属的项目目录
+///
+/// pipenv 与 virtualenvwrapper 在环境中的 `.project` 文件记录项目路径；
+/// 位于项目内的 `.venv`、`venv` 以上级目录为项目。
+fn project_dir(path: &Path) -> Option<PathBuf> {
+    if let Ok(content) = fs::read_to_string(path.join(".project")) {
+        let project = content.trim();
+        if !project.is_empty() {
+            return Some(PathBuf::from(project));
+        }
+    }
+    let name = path.file_name()?.to_str()?;
+    if matches!(name, ".venv" | "venv") {
+        return path.parent().map(Path::to_path_buf);
+    }
+    None
+}
+
+/// 从 conda-meta 中的 `python-<版本>-<构建>.json` 读取 Python 版本
+fn conda_python_version(path: &Path) -> Option<String> {
+    fs::read_dir(path.join("conda-meta"))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .find_map(|name| {
+            let rest = name.strip_prefix("python-")?.strip_suffix(".json")?;
+            let version = rest.split('-').next()?;
+            version
+                .starts_with(|c: char| c.is_ascii_digit())
+                .then(|| version.to_string())
+        })
+}
+
+/// 按 `lib/python3.x` 目录名推断 Python 版本
+fn site_packages_version(path: &Path) -> Option<String> {
+    child_dirs(&path.join("lib"))
+        .iter()
+        .filter_map(|dir| dir.file_name()?.to_str()?.strip_prefix("python"))
+        .find(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+/// 环境中的 site-packages 目录
+fn site_packages_dirs(path: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = child_dirs(&path.join("lib"))
+        .into_iter()
+        .map(|dir| dir.join("site-packages"))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    let windows = path.join("Lib").join("site-packages");
+    if windows.is_dir() {
+        dirs.push(windows);
+    }
+    dirs
+}
+
+fn count_entries(dir: &Path, matches: impl Fn(&str) -> bool) -> usize {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_str().is_some_and(&matches))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_venv(path: &Path, config: &str, packages: &[&str]) {
+        let site_packages = path.join("lib/python3.12/site-packages");
+        fs::create_dir_all(&site_packages).unwrap();
+        for package in packages {
+            fs::create_dir_all(site_packages.join(package)).unwrap();
+        }
+        fs::create_dir_all(path.join("bin")).unwrap();
+        fs::write(path.join("bin/python"), "").unwrap();
+        fs::write(path.join("pyvenv.cfg"), config).unwrap();
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().to_string_lossy().to_string();
+        make_venv(
+            &dir.path().join("work/api/.venv"),
+            &format!("home = {}\nversion_info = 3.12.4\nuv = 0.4.18\n", home),
+            &["click-8.1.7.dist-info", "click", "black-24.8.0.dist-info"],
+        );
+        make_venv(
+            &dir.path().join("envs/old"),
+            "home = /nonexistent/python/bin\nversion = 3.9.6\n",
+            &[],
+        );
+        fs::write(dir.path().join("envs/old/.project"), "/nonexistent/project").unwrap();
+        // 跳过 node_modules 中的环境
+        make_venv(
+            &dir.path().join("web/node_modules/x"),
+            "version = 3.12.0\n",
+            &[],
+        );
+        let conda = dir.path().join("miniconda3");
+        fs::create_dir_all(conda.join("conda-meta")).unwrap();
+        fs::write(conda.join("conda-meta/python-3.11.5-h1234.json"), "{}").unwrap();
+        fs::write(conda.join("conda-meta/numpy-1.26.0-py311.json"), "{}").unwrap();
+        fs::create_dir_all(conda.join("envs/ml/conda-meta")).unwrap();
+
+        let mut candidates = Vec::new();
+        walk(dir.path(), 3, &mut candidates);
+        // 重复发现的环境只保留一个
+        candidates.push((dir.path().join("envs/old"), None));
+        let envs = inspect_candidates(candidates);
+        assert_eq!(envs.len(), 4);
+
+        let old = &envs[0];
+        assert_eq!(old.kind, EnvKind::Venv);
+        assert_eq!(old.python_version.as_deref(), Some("3.9.6"));
+        assert_eq!(old.stale, Some(StaleReason::ProjectMissing));
+
+        let conda_base = &envs[1];
+        assert_eq!(conda_base.kind, EnvKind::Conda);
+        assert_eq!(conda_base.python_version.as_deref(), Some("3.11.5"));
+        assert_eq!(conda_base.package_count, 2);
+        assert_eq!(envs[2].path, conda.join("envs/ml"));
+
+        let api = &envs[3];
+        assert_eq!(api.kind, EnvKind::Uv);
+        assert_eq!(api.python_version.as_deref(), Some("3.12.4"));
+        assert_eq!(api.package_count, 2);
+        assert_eq!(
+            api.project.as_deref(),
+            Some(dir.path().join("work/api").as_path())
+        );
+        assert_eq!(api.stale, None);
+        assert!(api.size > 0);
+    }
+}
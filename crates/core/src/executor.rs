@@ -42,6 +42,6 @@ impl ManagerExecutor {
 
         let _guard = lock.lock().await;
         let mut task = f;
-        retry_with_backoff(self.max_attempts, self.base_delay, || task()).await
+        retry_with_backoff(self.max_attempts, self.base_delay, &mut task).await
     }
 }
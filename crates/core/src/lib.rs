@@ -1,9 +1,37 @@
+pub mod diff;
+pub mod disk;
+pub mod envs;
 pub mod executor;
+pub mod license;
 pub mod manager;
+pub mod manifest;
 pub mod package;
+pub mod project;
+pub mod purl;
+pub mod pypi;
+pub mod python;
 pub mod retry;
+pub mod search;
+pub mod spec;
+pub mod transaction;
+pub mod version;
 
+pub use diff::{snapshot_installed, DiffEntry, PackageDiff, VersionChange};
+pub use disk::{format_size, parse_age, CacheUsage, DiskUsage, PruneReport, SizeSource};
 pub use executor::ManagerExecutor;
+pub use license::{LicenseExpr, LicensePolicy, PolicyVerdict};
 pub use manager::PackageManager;
-pub use package::{Capability, Job, JobStatus, ManagerStatus, Operation, Package};
+pub use package::{
+    Capability, DependencyFlag, DependencyType, Job, JobStatus, ManagerStatus, Operation, Package,
+};
+pub use project::{detect_projects, Project};
+pub use purl::Purl;
+pub use pypi::PypiIndex;
 pub use retry::{retry_with_backoff, DEFAULT_MAX_ATTEMPTS, DEFAULT_RETRY_BASE_DELAY};
+pub use search::{merge_results, normalize_name, search_all, SearchOutcome, SearchResult};
+pub use spec::PackageSpec;
+pub use transaction::{
+    Compensation, HistoryStore, StepAction, StepStatus, Transaction, TransactionStatus,
+    TransactionStep,
+};
+pub use version::compare_versions;
@@ -0,0 +1,446 @@
+use boxy_error::{BoxyError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// 读取 npm 包目录中 package.json 的许可证
+///
+/// 兼容 `"license": "MIT"`、旧式的 `{"type": "MIT"}` 以及 `"licenses": [...]`。
+pub fn package_json_license(package_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(package_dir.join("package.json")).ok()?;
+    let data: Value = serde_json::from_str(&content).ok()?;
+    let license_type = |value: &Value| -> Option<String> {
+        value
+            .as_str()
+            .or_else(|| value.get("type").and_then(|value| value.as_str()))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    if let Some(license) = data.get("license").and_then(license_type) {
+        return Some(normalize_license(&license));
+    }
+    let licenses: Vec<String> = data
+        .get("licenses")
+        .and_then(|value| value.as_array())
+        .map(|list| list.iter().filter_map(license_type).collect())
+        .unwrap_or_default();
+    join_alternatives(licenses)
+}
+
+/// 读取 Python dist-info 中 METADATA 的许可证
+///
+/// 优先使用 PEP 639 的 `License-Expression`，其次是 trove 分类，最后是自由文本的 `License`。
+pub fn dist_info_license(dist_info: &Path) -> Option<String> {
+    let content = fs::read_to_string(dist_info.join("METADATA")).ok()?;
+    let mut expression = None;
+    let mut free_text = None;
+    let mut classifiers = Vec::new();
+    // 只解析头部，正文（长描述）从第一个空行开始
+    for line in content.lines() {
+        if line.is_empty() {
+            break;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "License-Expression" => expression = Some(value.to_string()),
+            "License" if free_text.is_none() => free_text = Some(value.to_string()),
+            "Classifier" => {
+                if let Some(name) = value.strip_prefix("License ::") {
+                    let name = name.rsplit("::").next().unwrap_or(name).trim();
+                    if name != "OSI Approved" {
+                        classifiers.push(normalize_license(name));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(expression) = expression.filter(|value| !value.is_empty()) {
+        return Some(expression);
+    }
+    // License 字段有时包含整段许可证全文，只接受简短的名称
+    let free_text = free_text
+        .filter(|value| !value.is_empty() && value.len() <= 64 && value != "UNKNOWN")
+        .map(|value| normalize_license(&value));
+    // 分类只能表达 `BSD License` 这类笼统名称，License 字段已是 SPDX 标识时优先使用
+    if let Some(license) = free_text.as_ref().filter(|value| is_spdx_like(value)) {
+        return Some(license.clone());
+    }
+    join_alternatives(classifiers).or(free_text)
+}
+
+/// 表达式中的每个许可证都是不含空格的标识（如 `MIT`、`BSD-3-Clause`）
+pub fn is_spdx_like(expression: &str) -> bool {
+    LicenseExpr::parse(expression)
+        .licenses()
+        .iter()
+        .all(|id| !id.contains(' '))
+}
+
+/// 读取 Cargo.toml 中 `[package]` 的 license 字段
+pub fn cargo_toml_license(manifest: &Path) -> Option<String> {
+    let content = fs::read_to_string(manifest).ok()?;
+    let mut in_package = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if key.trim() == "license" {
+            let value = value.trim().trim_matches('"').trim();
+            if !value.is_empty() {
+                // 旧版 crate 常用 `MIT/Apache-2.0` 表示二选一
+                return Some(value.replace('/', " OR "));
+            }
+        }
+    }
+    None
+}
+
+/// 将常见的许可证自由文本名称转换为 SPDX 标识
+pub fn normalize_license(raw: &str) -> String {
+    let trimmed = raw.trim();
+    let mapped = match trimmed.to_lowercase().as_str() {
+        "mit license" | "mit" | "the mit license" | "expat" => "MIT",
+        "apache software license"
+        | "apache 2.0"
+        | "apache-2"
+        | "apache license 2.0"
+        | "apache license, version 2.0"
+        | "apache 2"
+        | "apache2" => "Apache-2.0",
+        "bsd 3-clause" | "new bsd license" | "modified bsd license" | "bsd-3" => "BSD-3-Clause",
+        "bsd 2-clause" | "simplified bsd license" | "freebsd" => "BSD-2-Clause",
+        "isc license" | "isc license (iscl)" | "isc" => "ISC",
+        "mozilla public license 2.0 (mpl 2.0)" | "mpl 2.0" | "mpl-2" => "MPL-2.0",
+        "python software foundation license" | "psf" | "psf license" => "PSF-2.0",
+        "the unlicense (unlicense)" | "unlicense" => "Unlicense",
+        "gnu general public license v3 (gplv3)" | "gplv3" => "GPL-3.0-only",
+        "gnu general public license v3 or later (gplv3+)" | "gplv3+" => "GPL-3.0-or-later",
+        "gnu general public license v2 (gplv2)" | "gplv2" => "GPL-2.0-only",
+        "gnu general public license v2 or later (gplv2+)" | "gplv2+" => "GPL-2.0-or-later",
+        "gnu lesser general public license v3 (lgplv3)" | "lgplv3" => "LGPL-3.0-only",
+        "gnu lesser general public license v2 or later (lgplv2+)" => "LGPL-2.0-or-later",
+        "gnu affero general public license v3" | "agplv3" => "AGPL-3.0-only",
+        "zlib/libpng license" | "zlib" => "Zlib",
+        "cc0 1.0 universal (cc0 1.0) public domain dedication" | "cc0" => "CC0-1.0",
+        _ => return trimmed.to_string(),
+    };
+    mapped.to_string()
+}
+
+fn join_alternatives(mut licenses: Vec<String>) -> Option<String> {
+    licenses.dedup();
+    match licenses.len() {
+        0 => None,
+        1 => licenses.pop(),
+        _ => Some(licenses.join(" OR ")),
+    }
+}
+
+/// SPDX 许可证表达式
+#[derive(Debug, Clone, PartialEq)]
+pub enum LicenseExpr {
+    License(String),
+    And(Vec<LicenseExpr>),
+    Or(Vec<LicenseExpr>),
+}
+
+impl LicenseExpr {
+    /// 解析 SPDX 表达式（支持 AND、OR、WITH 与括号），无法解析时整体视为单个许可证
+    pub fn parse(expression: &str) -> Self {
+        let tokens = tokenize(expression);
+        let mut parser = ExprParser { tokens, pos: 0 };
+        match parser.parse_or() {
+            Some(expr) if parser.pos == parser.tokens.len() => expr,
+            _ => LicenseExpr::License(expression.trim().to_string()),
+        }
+    }
+
+    /// 表达式中出现的全部许可证标识
+    pub fn licenses(&self) -> Vec<&str> {
+        match self {
+            LicenseExpr::License(id) => vec![id.as_str()],
+            LicenseExpr::And(items) | LicenseExpr::Or(items) => {
+                items.iter().flat_map(|item| item.licenses()).collect()
+            }
+        }
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<String> {
+    expression
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .replace('/', " OR ")
+        .split_whitespace()
+        .map(|token| token.to_string())
+        .collect()
+}
+
+struct ExprParser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.tokens
+            .get(self.pos)
+            .is_some_and(|token| token.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_or(&mut self) -> Option<LicenseExpr> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek_keyword("OR") {
+            self.pos += 1;
+            items.push(self.parse_and()?);
+        }
+        Some(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            LicenseExpr::Or(items)
+        })
+    }
+
+    fn parse_and(&mut self) -> Option<LicenseExpr> {
+        let mut items = vec![self.parse_atom()?];
+        while self.peek_keyword("AND") {
+            self.pos += 1;
+            items.push(self.parse_atom()?);
+        }
+        Some(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            LicenseExpr::And(items)
+        })
+    }
+
+    fn parse_atom(&mut self) -> Option<LicenseExpr> {
+        let token = self.tokens.get(self.pos)?.clone();
+        self.pos += 1;
+        if token == "(" {
+            let expr = self.parse_or()?;
+            if self.tokens.get(self.pos).map(|token| token.as_str()) != Some(")") {
+                return None;
+            }
+            self.pos += 1;
+            return Some(expr);
+        }
+        if !is_identifier(&token) {
+            return None;
+        }
+        // 非 SPDX 的自由文本名称（如 `BSD License`）由多个单词组成，合并为一个许可证
+        let mut name = token;
+        while let Some(next) = self.tokens.get(self.pos).filter(|next| is_identifier(next)) {
+            name.push(' ');
+            name.push_str(next);
+            self.pos += 1;
+        }
+        // 例外条款（WITH LLVM-exception）不影响策略判断
+        if self.peek_keyword("WITH") {
+            self.pos += 2;
+        }
+        Some(LicenseExpr::License(name))
+    }
+}
+
+fn is_identifier(token: &str) -> bool {
+    token != "("
+        && token != ")"
+        && !["AND", "OR", "WITH"]
+            .iter()
+            .any(|keyword| token.eq_ignore_ascii_case(keyword))
+}
+
+/// 许可证策略
+///
+/// `allow` 为空时允许所有未被 `deny` 的许可证。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LicensePolicy {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// 缺少许可证信息的包是否视为违规
+    #[serde(default)]
+    pub fail_on_unknown: bool,
+}
+
+/// 单个包的策略判断结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyVerdict {
+    Allowed,
+    /// 命中 deny 列表
+    Denied,
+    /// 不在 allow 列表中
+    NotAllowed,
+    /// 没有许可证信息
+    Unknown,
+}
+
+impl LicensePolicy {
+    /// 从 TOML 文件加载策略
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|err| BoxyError::ParseError {
+            input: format!("{}: {}", path.display(), err),
+        })
+    }
+
+    pub fn evaluate(&self, license: Option<&str>) -> PolicyVerdict {
+        match license.map(str::trim).filter(|value| !value.is_empty()) {
+            None => PolicyVerdict::Unknown,
+            Some(license) => self.evaluate_expr(&LicenseExpr::parse(license)),
+        }
+    }
+
+    /// 判断结果是否为违规
+    pub fn is_violation(&self, verdict: &PolicyVerdict) -> bool {
+        match verdict {
+            PolicyVerdict::Allowed => false,
+            PolicyVerdict::Denied | PolicyVerdict::NotAllowed => true,
+            PolicyVerdict::Unknown => self.fail_on_unknown,
+        }
+    }
+
+    fn evaluate_expr(&self, expr: &LicenseExpr) -> PolicyVerdict {
+        match expr {
+            LicenseExpr::License(id) => {
+                let matches =
+                    |list: &[String]| list.iter().any(|item| item.eq_ignore_ascii_case(id));
+                if matches(&self.deny) {
+                    PolicyVerdict::Denied
+                } else if self.allow.is_empty() || matches(&self.allow) {
+                    PolicyVerdict::Allowed
+                } else {
+                    PolicyVerdict::NotAllowed
+                }
+            }
+            // 任选其一：只要有一个分支满足即可
+            LicenseExpr::Or(items) => {
+                let verdicts: Vec<_> = items.iter().map(|item| self.evaluate_expr(item)).collect();
+                if verdicts.contains(&PolicyVerdict::Allowed) {
+                    PolicyVerdict::Allowed
+                } else if verdicts.contains(&PolicyVerdict::NotAllowed) {
+                    PolicyVerdict::NotAllowed
+                } else {
+                    PolicyVerdict::Denied
+                }
+            }
+            // 同时适用：所有分支都必须满足
+            LicenseExpr::And(items) => {
+                let verdicts: Vec<_> = items.iter().map(|item| self.evaluate_expr(item)).collect();
+                if verdicts.contains(&PolicyVerdict::Denied) {
+                    PolicyVerdict::Denied
+                } else if verdicts.contains(&PolicyVerdict::NotAllowed) {
+                    PolicyVerdict::NotAllowed
+                } else {
+                    PolicyVerdict::Allowed
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_expression() {
+        assert_eq!(
+            LicenseExpr::parse("MIT OR Apache-2.0").licenses(),
+            vec!["MIT", "Apache-2.0"]
+        );
+        assert_eq!(
+            LicenseExpr::parse("(MIT OR Apache-2.0) AND Unicode-DFS-2016"),
+            LicenseExpr::And(vec![
+                LicenseExpr::Or(vec![
+                    LicenseExpr::License("MIT".to_string()),
+                    LicenseExpr::License("Apache-2.0".to_string()),
+                ]),
+                LicenseExpr::License("Unicode-DFS-2016".to_string()),
+            ])
+        );
+        assert_eq!(
+            LicenseExpr::parse("Apache-2.0 WITH LLVM-exception").licenses(),
+            vec!["Apache-2.0"]
+        );
+        assert_eq!(
+            LicenseExpr::parse("BSD License OR Apache-2.0").licenses(),
+            vec!["BSD License", "Apache-2.0"]
+        );
+    }
+
+    #[test]
+    fn test_policy_evaluate() {
+        let policy = LicensePolicy {
+            allow: vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            deny: vec!["GPL-3.0-only".to_string()],
+            fail_on_unknown: true,
+        };
+        assert_eq!(
+            policy.evaluate(Some("MIT OR GPL-3.0-only")),
+            PolicyVerdict::Allowed
+        );
+        assert_eq!(
+            policy.evaluate(Some("MIT AND GPL-3.0-only")),
+            PolicyVerdict::Denied
+        );
+        assert_eq!(policy.evaluate(Some("ISC")), PolicyVerdict::NotAllowed);
+        assert_eq!(policy.evaluate(None), PolicyVerdict::Unknown);
+        assert!(policy.is_violation(&PolicyVerdict::Unknown));
+    }
+
+    #[test]
+    fn test_read_license_files() {
+        let temp_dir = tempdir().unwrap();
+        let npm = temp_dir.path().join("lodash");
+        fs::create_dir_all(&npm).unwrap();
+        fs::write(
+            npm.join("package.json"),
+            r#"{"name":"lodash","license":{"type":"MIT"}}"#,
+        )
+        .unwrap();
+        assert_eq!(package_json_license(&npm), Some("MIT".to_string()));
+
+        let dist_info = temp_dir.path().join("requests-2.31.0.dist-info");
+        fs::create_dir_all(&dist_info).unwrap();
+        fs::write(
+            dist_info.join("METADATA"),
+            "Metadata-Version: 2.1\nName: requests\nLicense: Apache 2.0\nClassifier: License :: OSI Approved :: Apache Software License\n\nLicense: body\n",
+        )
+        .unwrap();
+        assert_eq!(
+            dist_info_license(&dist_info),
+            Some("Apache-2.0".to_string())
+        );
+
+        let manifest = temp_dir.path().join("Cargo.toml");
+        fs::write(
+            &manifest,
+            "[package]\nname = \"ripgrep\"\nlicense = \"Unlicense/MIT\"\n\n[dependencies]\nlicense = \"x\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            cargo_toml_license(&manifest),
+            Some("Unlicense OR MIT".to_string())
+        );
+    }
+}
@@ -1,6 +1,8 @@
-use crate::package::{Capability, Package};
+use crate::package::{Capability, DependencyType, Package};
 use async_trait::async_trait;
 use boxy_error::{BoxyError, Result};
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[async_trait]
 pub trait PackageManager: Send + Sync {
@@ -10,6 +12,13 @@ pub trait PackageManager: Send + Sync {
 
     async fn list_installed(&self) -> Result<Vec<crate::package::Package>>;
 
+    /// 为 `list_installed` 中缺少许可证的包补全许可证
+    ///
+    /// 可能需要访问注册表的 Web API，只在许可证清单与 SBOM 中调用，默认不做任何处理。
+    async fn fill_licenses(&self, _packages: &mut [Package]) -> Result<()> {
+        Ok(())
+    }
+
     async fn search(&self, query: &str) -> Result<Vec<crate::package::Package>>;
 
     async fn get_info(&self, name: &str) -> Result<crate::package::Package>;
@@ -18,8 +27,57 @@ pub trait PackageManager: Send + Sync {
 
     async fn upgrade(&self, name: &str) -> Result<()>;
 
+    /// 升级到最新版本，必要时修改项目清单中的版本范围
+    ///
+    /// 默认与 `upgrade` 相同；`upgrade` 只在清单范围内更新的包管理器应该重写此方法。
+    async fn upgrade_latest(&self, name: &str) -> Result<()> {
+        self.upgrade(name).await
+    }
+
     async fn uninstall(&self, name: &str, force: bool) -> Result<()>;
 
+    /// 把依赖写入项目清单（如 `pnpm add -D`、`uv add`、`cargo add`）
+    ///
+    /// 默认按本地范围安装，只支持普通依赖；能区分依赖类型或安装不会修改清单的包管理器应该重写此方法。
+    async fn add_dependency(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        dependency_type: DependencyType,
+    ) -> Result<()> {
+        if dependency_type != DependencyType::Prod {
+            return Err(BoxyError::UnsupportedOperation {
+                manager: self.name().to_string(),
+                operation: format!("add --{}", dependency_type.as_str()),
+            });
+        }
+        self.install(name, version, false).await
+    }
+
+    /// 从项目清单中移除依赖，默认与卸载相同
+    async fn remove_dependency(&self, name: &str) -> Result<()> {
+        self.uninstall(name, false).await
+    }
+
+    /// 一次原生调用安装多个包（如 `npm install a b c`）
+    ///
+    /// 默认实现返回不支持的操作错误，调用方应回退为逐个安装。
+    /// 声明 `Capability::BatchInstall` 的包管理器应该重写此方法。
+    async fn install_batch(&self, _packages: &[(&str, Option<&str>)], _force: bool) -> Result<()> {
+        Err(BoxyError::UnsupportedOperation {
+            manager: self.name().to_string(),
+            operation: "install_batch".to_string(),
+        })
+    }
+
+    /// 一次原生调用卸载多个包，默认实现与 `install_batch` 相同
+    async fn uninstall_batch(&self, _names: &[&str], _force: bool) -> Result<()> {
+        Err(BoxyError::UnsupportedOperation {
+            manager: self.name().to_string(),
+            operation: "uninstall_batch".to_string(),
+        })
+    }
+
     async fn check_outdated(&self) -> Result<Vec<crate::package::Package>>;
 
     async fn list_dependencies(&self, _name: &str) -> Result<Vec<Package>> {
@@ -40,6 +98,41 @@ pub trait PackageManager: Send + Sync {
         })
     }
 
+    /// 清理超过 `older_than` 的缓存，返回释放（dry_run 时为预计释放）的字节数，无法得知时返回 None
+    ///
+    /// 缓存内部存在索引与引用关系，按单个文件的修改时间删除会破坏缓存，
+    /// 因此实现应该整体删除缓存条目，或调用包管理器自带的清理命令。
+    /// 默认实现返回不支持的操作错误。
+    async fn prune_cache(&self, _older_than: Duration, _dry_run: bool) -> Result<Option<u64>> {
+        Err(BoxyError::UnsupportedOperation {
+            manager: self.name().to_string(),
+            operation: "prune_cache".to_string(),
+        })
+    }
+
+    /// 卸载后是否自动调用 `clean_cache`
+    ///
+    /// 缓存由所有项目共享、清空后会影响其它项目离线构建的包管理器（如 cargo、npm）应该返回 false，
+    /// 它们的缓存只在显式清理（`boxy cache clean`）时删除。
+    fn clean_cache_on_uninstall(&self) -> bool {
+        true
+    }
+
+    /// `clean_cache` 是否会清空 `cache_dirs` 中的全部内容
+    ///
+    /// 为 true 时缓存目录的大小就是可回收的空间；只清理部分内容的包管理器
+    /// （如 `pnpm store prune`）应该返回 false，此时可回收的空间未知。
+    fn clean_cache_clears_dirs(&self) -> bool {
+        true
+    }
+
+    /// 包管理器的下载缓存 / 包存储目录
+    ///
+    /// 用于统计可回收空间，默认没有缓存目录。
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+
     fn capabilities(&self) -> &[Capability];
 
     fn cache_key(&self) -> &str {
@@ -0,0 +1,272 @@
+use crate::package::DependencyType;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// package.json 中声明依赖的字段，按优先级从低到高排列
+///
+/// 同时出现在多处时以 npm 的安装行为为准：optionalDependencies 优先，其次是 dependencies，
+/// 仅作为 peerDependencies 声明的依赖由 npm 7+ 自动安装。
+const DEPENDENCY_FIELDS: [(&str, DependencyType); 4] = [
+    ("peerDependencies", DependencyType::Peer),
+    ("devDependencies", DependencyType::Dev),
+    ("dependencies", DependencyType::Prod),
+    ("optionalDependencies", DependencyType::Optional),
+];
+
+/// 读取项目目录中的 package.json
+pub fn read_package_json(project: &Path) -> Option<Value> {
+    let content = fs::read_to_string(project.join("package.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 按 package.json 中的声明区分依赖类型
+pub fn dependency_types(manifest: &Value) -> HashMap<String, DependencyType> {
+    declared(manifest)
+        .map(|(name, dependency_type, _)| (name.to_string(), dependency_type))
+        .collect()
+}
+
+/// package.json 中声明的版本范围，优先级与 `dependency_types` 相同
+pub fn dependency_ranges(manifest: &Value) -> HashMap<String, String> {
+    declared(manifest)
+        .filter_map(|(name, _, range)| Some((name.to_string(), range.as_str()?.to_string())))
+        .collect()
+}
+
+/// 依次产出各字段中的依赖，后出现的优先级更高
+fn declared(manifest: &Value) -> impl Iterator<Item = (&str, DependencyType, &Value)> {
+    DEPENDENCY_FIELDS
+        .into_iter()
+        .filter_map(|(field, dependency_type)| {
+            let deps = manifest.get(field)?.as_object()?;
+            Some(
+                deps.iter()
+                    .map(move |(name, range)| (name.as_str(), dependency_type, range)),
+            )
+        })
+        .flatten()
+}
+
+/// 满足 npm 版本范围的最高正式版本
+///
+/// 支持 `^`、`~`、`x` 通配、比较运算符、连字符范围与 `||`；`workspace:`、git 地址、
+/// dist-tag 等无法按版本比较的范围返回 None。预发布版本不参与匹配。
+pub fn max_satisfying<'a, I>(versions: I, range: &str) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let sets = parse_range(range)?;
+    versions
+        .into_iter()
+        .filter_map(|version| Some((release(version)?, version)))
+        .filter(|(release, _)| {
+            sets.iter()
+                .any(|set| set.iter().all(|(op, bound)| op.matches(*release, *bound)))
+        })
+        .max_by_key(|(release, _)| *release)
+        .map(|(_, version)| version)
+}
+
+type Triple = (u64, u64, u64);
+
+/// 版本号的三个部分，通配（`x`、`*` 或省略）为 None
+type Partial = [Option<u64>; 3];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl Op {
+    fn matches(self, version: Triple, bound: Triple) -> bool {
+        match self {
+            Self::Eq => version == bound,
+            Self::Gt => version > bound,
+            Self::Gte => version >= bound,
+            Self::Lt => version < bound,
+            Self::Lte => version <= bound,
+        }
+    }
+}
+
+/// 正式版本号，预发布版本返回 None
+fn release(version: &str) -> Option<Triple> {
+    let version = version.trim().trim_start_matches(['v', '=']);
+    let version = version.split('+').next()?;
+    if version.contains('-') {
+        return None;
+    }
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    let triple = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(triple)
+}
+
+/// 解析为若干比较器集合，满足任意一个集合即满足范围
+fn parse_range(range: &str) -> Option<Vec<Vec<(Op, Triple)>>> {
+    let range = range.trim();
+    // Berry 的描述符带有 `npm:` 协议
+    let range = range.strip_prefix("npm:").unwrap_or(range);
+    range.split("||").map(|set| parse_set(set.trim())).collect()
+}
+
+fn parse_set(set: &str) -> Option<Vec<(Op, Triple)>> {
+    if let Some((from, to)) = set.split_once(" - ") {
+        let mut comparators = desugar(">=", partial(from.trim())?)?;
+        comparators.extend(desugar("<=", partial(to.trim())?)?);
+        return Some(comparators);
+    }
+    let mut comparators = Vec::new();
+    // 运算符与版本号之间可能有空格，如 `>= 1.2.0`
+    let mut pending = None;
+    for token in set.split_whitespace() {
+        let split = token
+            .find(|ch| !matches!(ch, '<' | '>' | '=' | '^' | '~'))
+            .unwrap_or(token.len());
+        let (op, version) = token.split_at(split);
+        if version.is_empty() {
+            pending = Some(op);
+            continue;
+        }
+        let op = match op {
+            "" => pending.take().unwrap_or(""),
+            op => op,
+        };
+        comparators.extend(desugar(op, partial(version)?)?);
+    }
+    Some(comparators)
+}
+
+fn partial(version: &str) -> Option<Partial> {
+    let version = version.trim_start_matches('v');
+    // 范围中的预发布标记与构建元数据按对应的正式版本处理
+    let version = version.split(['-', '+']).next()?;
+    let mut result = [None; 3];
+    if version.is_empty() {
+        return Some(result);
+    }
+    for (index, part) in version.split('.').enumerate() {
+        if index >= 3 {
+            return None;
+        }
+        match part {
+            "x" | "X" | "*" => break,
+            part => result[index] = Some(part.parse().ok()?),
+        }
+    }
+    Some(result)
+}
+
+/// 把带运算符的部分版本号展开为比较器
+fn desugar(op: &str, version: Partial) -> Option<Vec<(Op, Triple)>> {
+    let [major, minor, patch] = version;
+    let Some(major) = major else {
+        // 通配版本：`>` 与 `<` 不匹配任何版本，其余匹配全部版本
+        return Some(match op {
+            ">" | "<" => vec![(Op::Lt, (0, 0, 0))],
+            _ => Vec::new(),
+        });
+    };
+    let lower = (major, minor.unwrap_or(0), patch.unwrap_or(0));
+    // 通配部分进位后的版本，如 1.2.x 为 1.3.0；完整版本号没有
+    let next = match (minor, patch) {
+        (None, _) => Some((major + 1, 0, 0)),
+        (Some(minor), None) => Some((major, minor + 1, 0)),
+        (Some(_), Some(_)) => None,
+    };
+    let comparators = match op {
+        "" | "=" => match next {
+            Some(next) => vec![(Op::Gte, lower), (Op::Lt, next)],
+            None => vec![(Op::Eq, lower)],
+        },
+        "^" => {
+            let upper = match (major, minor, patch) {
+                (0, Some(0), Some(patch)) => (0, 0, patch + 1),
+                (0, Some(minor), _) => (0, minor + 1, 0),
+                (major, _, _) => (major + 1, 0, 0),
+            };
+            vec![(Op::Gte, lower), (Op::Lt, upper)]
+        }
+        "~" | "~>" => {
+            let upper = match minor {
+                Some(minor) => (major, minor + 1, 0),
+                None => (major + 1, 0, 0),
+            };
+            vec![(Op::Gte, lower), (Op::Lt, upper)]
+        }
+        ">" => match next {
+            Some(next) => vec![(Op::Gte, next)],
+            None => vec![(Op::Gt, lower)],
+        },
+        ">=" => vec![(Op::Gte, lower)],
+        "<" => vec![(Op::Lt, lower)],
+        "<=" => match next {
+            Some(next) => vec![(Op::Lt, next)],
+            None => vec![(Op::Lte, lower)],
+        },
+        _ => return None,
+    };
+    Some(comparators)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_types() {
+        let manifest = serde_json::json!({
+            "dependencies": { "fsevents": "^2.3.3", "react": "^18.2.0" },
+            "optionalDependencies": { "fsevents": "^2.3.3" },
+            "peerDependencies": { "react": "^18.2.0" },
+        });
+        let types = dependency_types(&manifest);
+        assert_eq!(types["fsevents"], DependencyType::Optional);
+        assert_eq!(types["react"], DependencyType::Prod);
+    }
+
+    #[test]
+    fn test_dependency_ranges() {
+        let manifest = serde_json::json!({
+            "dependencies": { "lodash": "^4.17.0" },
+            "devDependencies": { "typescript": "~5.4.0", "lodash": "^3.0.0" },
+        });
+        let ranges = dependency_ranges(&manifest);
+        assert_eq!(ranges["lodash"], "^4.17.0");
+        assert_eq!(ranges["typescript"], "~5.4.0");
+    }
+
+    #[test]
+    fn test_max_satisfying() {
+        let versions = [
+            "0.2.3",
+            "0.2.9",
+            "0.3.0",
+            "1.2.0",
+            "1.2.9",
+            "1.3.0",
+            "2.0.0-beta.1",
+            "1.9.0",
+        ];
+        let max = |range: &str| max_satisfying(versions, range);
+
+        assert_eq!(max("^1.2.0"), Some("1.9.0"));
+        assert_eq!(max("~1.2.0"), Some("1.2.9"));
+        assert_eq!(max("^0.2.3"), Some("0.2.9"));
+        assert_eq!(max("1.2.x"), Some("1.2.9"));
+        assert_eq!(max("1.2.0"), Some("1.2.0"));
+        assert_eq!(max(">= 1.2.0 <1.3.0"), Some("1.2.9"));
+        assert_eq!(max("0.2.0 - 0.3"), Some("0.3.0"));
+        assert_eq!(max("^0.2.0 || ~1.2.0"), Some("1.2.9"));
+        assert_eq!(max("*"), Some("1.9.0"));
+        assert_eq!(max("npm:^1.3.0"), Some("1.9.0"));
+        assert_eq!(max("^3.0.0"), None);
+        assert_eq!(max("latest"), None);
+        assert_eq!(max("workspace:^"), None);
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Package {
     pub name: String,
     pub version: String,
@@ -13,6 +13,139 @@ pub struct Package {
     pub size: Option<u64>,
     pub outdated: bool,
     pub latest_version: Option<String>,
+    /// 非默认注册表的安装来源，如 git 仓库或本地路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// 已安装的版本是否已被注册表撤回（yank）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub yanked: bool,
+    /// 安装该包的工具，如 Python 包 dist-info 中 INSTALLER 记录的 pip、uv
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installer: Option<String>,
+    /// 是否由用户直接安装（而非作为依赖），未知时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested: Option<bool>,
+    /// 项目清单中声明的依赖类型，仅本地范围可用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependency_type: Option<DependencyType>,
+    /// 解析得到的下载地址或本地路径，如 npm 的 `resolved`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<String>,
+    /// 状态标记，如依赖树中的被覆盖、多余、版本不符、缺失，或 Homebrew 的 pin、keg-only
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<DependencyFlag>,
+    /// 满足清单版本范围的最高版本（npm/pnpm/yarn outdated 中的 wanted）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wanted_version: Option<String>,
+    /// 项目清单中声明的版本范围，如 `^4.17.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+    /// Homebrew 包所在的 tap，如 `homebrew/core`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tap: Option<String>,
+    /// 是否为 Homebrew cask
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cask: bool,
+}
+
+impl Package {
+    /// 只有名称、版本与管理器的包，其余字段取默认值
+    pub fn new(
+        manager: impl Into<String>,
+        name: impl Into<String>,
+        version: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            manager: manager.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 不修改清单即可更新：wanted 高于当前版本，未知 wanted 时视为可以更新
+    pub fn has_update_in_range(&self) -> bool {
+        self.wanted_version.as_ref() != Some(&self.version)
+    }
+
+    /// 最新版本超出清单的版本范围，需要修改清单才能升级
+    pub fn needs_manifest_bump(&self) -> bool {
+        match (&self.wanted_version, &self.latest_version) {
+            (Some(wanted), Some(latest)) => wanted != latest,
+            _ => false,
+        }
+    }
+
+    /// Homebrew 第三方 tap 中的包返回 `user/repo/name`，其余返回包名
+    pub fn qualified_name(&self) -> String {
+        match self.tap.as_deref() {
+            Some(tap) if !tap.starts_with("homebrew/") => format!("{}/{}", tap, self.name),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+/// 依赖在项目清单中的类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyType {
+    Prod,
+    Dev,
+    Optional,
+    Peer,
+}
+
+impl DependencyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Prod => "prod",
+            Self::Dev => "dev",
+            Self::Optional => "optional",
+            Self::Peer => "peer",
+        }
+    }
+}
+
+/// 包的状态标记
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyFlag {
+    /// 版本被 overrides 覆盖
+    Overridden,
+    /// 已安装但未在清单中声明
+    Extraneous,
+    /// 已安装的版本不满足声明的范围
+    Invalid,
+    /// 已声明但未安装
+    Missing,
+    /// 已通过 `brew pin` 固定版本，`brew upgrade` 会跳过
+    Pinned,
+    /// keg-only formula，未链接到 Homebrew 前缀
+    KegOnly,
+    /// cask 由应用自行更新，`brew outdated` 默认不检查
+    AutoUpdates,
+}
+
+impl DependencyFlag {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Overridden => "已被 overrides 覆盖",
+            Self::Extraneous => "未在清单中声明",
+            Self::Invalid => "版本不满足声明的范围",
+            Self::Missing => "未安装",
+            Self::Pinned => "已固定版本 (brew pin)",
+            Self::KegOnly => "keg-only，未链接到前缀",
+            Self::AutoUpdates => "由应用自行更新",
+        }
+    }
+
+    /// 是否为依赖树中需要处理的问题，其余只是包的状态
+    pub fn is_problem(&self) -> bool {
+        matches!(
+            self,
+            Self::Overridden | Self::Extraneous | Self::Invalid | Self::Missing
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +155,12 @@ pub struct ManagerStatus {
     pub available: bool,
     pub package_count: usize,
     pub outdated_count: usize,
+    /// 已安装包的总占用（字节）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_size: Option<u64>,
+    /// 下载缓存的可回收空间（字节）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_size: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,8 +220,8 @@ mod tests {
             license: Some("MIT".to_string()),
             installed_path: Some("/usr/local/lib/node_modules".to_string()),
             size: Some(1024),
-            outdated: false,
             latest_version: Some("1.0.0".to_string()),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&package).unwrap();
@@ -92,4 +231,23 @@ mod tests {
         assert_eq!(deserialized.version, package.version);
         assert_eq!(deserialized.manager, package.manager);
     }
+
+    #[test]
+    fn test_update_in_range() {
+        let mut package: Package = serde_json::from_str(
+            r#"{"name": "lodash", "version": "4.17.20", "manager": "npm", "description": null,
+                "homepage": null, "license": null, "installed_path": null, "size": null,
+                "outdated": true, "latest_version": "5.0.0"}"#,
+        )
+        .unwrap();
+        assert!(package.has_update_in_range());
+        assert!(!package.needs_manifest_bump());
+
+        package.wanted_version = Some("4.17.21".to_string());
+        assert!(package.has_update_in_range());
+        assert!(package.needs_manifest_bump());
+
+        package.version = "4.17.21".to_string();
+        assert!(!package.has_update_in_range());
+    }
 }
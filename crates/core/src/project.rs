@@ -0,0 +1,664 @@
+use crate::manifest::read_package_json;
+use crate::package::Package;
+use crate::purl::purl_type_for;
+use crate::search::normalize_name;
+use crate::version::compare_versions;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Node 项目的锁文件与对应的包管理器，按优先级排列
+const NODE_LOCKFILES: [(&str, &str); 5] = [
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lock", "bun"),
+    ("bun.lockb", "bun"),
+    ("package-lock.json", "npm"),
+];
+
+/// 展开 `**` 工作区模式时跳过的目录
+const SKIPPED_DIRS: [&str; 4] = ["node_modules", "target", ".git", ".venv"];
+
+/// `**` 工作区模式的最大展开深度
+const MAX_GLOB_DEPTH: usize = 6;
+
+/// 在根目录下查找项目的默认深度
+pub const DEFAULT_SCAN_DEPTH: usize = 4;
+
+/// 按清单文件识别出的项目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Project {
+    /// 项目目录，工作区项目为工作区根目录
+    pub root: PathBuf,
+    /// 编辑依赖使用的包管理器
+    pub manager: String,
+    /// 识别依据的清单文件
+    pub manifest: PathBuf,
+    /// 工作区成员目录，单个项目为空
+    pub members: Vec<PathBuf>,
+    /// 根清单只声明工作区、本身不是包（如 Cargo 的虚拟清单）
+    pub virtual_root: bool,
+}
+
+impl Project {
+    /// 项目包含的本地范围：根目录（非虚拟清单时）以及每个工作区成员
+    pub fn scopes(&self) -> Vec<PathBuf> {
+        let root = (!self.virtual_root).then(|| self.root.clone());
+        root.into_iter()
+            .chain(self.members.iter().cloned())
+            .collect()
+    }
+}
+
+/// 从 `dir` 向上查找最近的项目目录，返回其中识别出的全部项目
+///
+/// 最近的项目是上层工作区的成员时返回整个工作区。到达 git 仓库根目录或用户目录时停止，
+/// 避免把上层无关的清单当作当前项目。
+pub fn detect_projects(dir: &Path) -> Vec<Project> {
+    let home = dirs::home_dir();
+    let mut projects: Vec<Project> = Vec::new();
+    for ancestor in dir.ancestors() {
+        if home.as_deref() == Some(ancestor) {
+            break;
+        }
+        let found = projects_in(ancestor);
+        if projects.is_empty() {
+            projects = found;
+        } else {
+            for workspace in found {
+                let member = projects.iter_mut().find(|project| {
+                    project.manifest.file_name() == workspace.manifest.file_name()
+                        && workspace.members.contains(&project.root)
+                });
+                if let Some(member) = member {
+                    *member = workspace;
+                }
+            }
+        }
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+    projects
+}
+
+/// 识别目录中的 Node、Python 与 Rust 项目
+pub fn projects_in(dir: &Path) -> Vec<Project> {
+    [node_project(dir), python_project(dir), cargo_project(dir)]
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// 在 `root` 下查找全部项目，最多进入 `max_depth` 层子目录
+///
+/// 工作区成员归入所属的工作区，不单独列出；隐藏目录与依赖、构建目录不会进入。
+pub fn find_projects(root: &Path, max_depth: usize) -> Vec<Project> {
+    let mut projects = Vec::new();
+    // 已被工作区包含的成员：(目录, 清单文件名)
+    let mut covered = HashSet::new();
+    walk(root, max_depth, &mut projects, &mut covered);
+    projects.sort_by(|a, b| a.root.cmp(&b.root));
+    projects
+}
+
+fn walk(
+    dir: &Path,
+    depth: usize,
+    projects: &mut Vec<Project>,
+    covered: &mut HashSet<(PathBuf, String)>,
+) {
+    for project in projects_in(dir) {
+        let manifest_name = manifest_name(&project);
+        if covered.contains(&(project.root.clone(), manifest_name.clone())) {
+            continue;
+        }
+        for member in &project.members {
+            covered.insert((member.clone(), manifest_name.clone()));
+        }
+        projects.push(project);
+    }
+    if depth == 0 {
+        return;
+    }
+    for child in child_dirs(dir) {
+        let hidden = child
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if !hidden {
+            walk(&child, depth - 1, projects, covered);
+        }
+    }
+}
+
+fn manifest_name(project: &Project) -> String {
+    project
+        .manifest
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn node_project(dir: &Path) -> Option<Project> {
+    let manifest = read_package_json(dir)?;
+    let manager = find_upwards(dir, |ancestor| {
+        NODE_LOCKFILES
+            .iter()
+            .find(|(lockfile, _)| ancestor.join(lockfile).is_file())
+            .map(|(_, manager)| manager.to_string())
+    })
+    .or_else(|| package_manager_field(&manifest))
+    .unwrap_or_else(|| "npm".to_string());
+
+    let patterns = if manager == "pnpm" {
+        fs::read_to_string(dir.join("pnpm-workspace.yaml"))
+            .map(|content| pnpm_workspace_packages(&content))
+            .unwrap_or_default()
+    } else {
+        // `workspaces` 可以是数组，也可以是 yarn 的 `{ "packages": [...] }`
+        let workspaces = manifest.get("workspaces");
+        workspaces
+            .and_then(|value| value.get("packages"))
+            .or(workspaces)
+            .and_then(Value::as_array)
+            .map(|list| {
+                list.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    Some(Project {
+        root: dir.to_path_buf(),
+        manager,
+        manifest: dir.join("package.json"),
+        members: expand_workspace(dir, &patterns, &[], "package.json"),
+        virtual_root: false,
+    })
+}
+
+fn python_project(dir: &Path) -> Option<Project> {
+    let pyproject = dir.join("pyproject.toml");
+    let manifest = if pyproject.is_file() {
+        pyproject
+    } else {
+        Some(dir.join("requirements.txt")).filter(|path| path.is_file())?
+    };
+    let config: Option<toml::Value> = fs::read_to_string(&manifest)
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok());
+    let uv = config
+        .as_ref()
+        .and_then(|config| config.get("tool")?.get("uv"));
+    let uses_uv = uv.is_some()
+        || find_upwards(dir, |ancestor| {
+            ancestor.join("uv.lock").is_file().then_some(())
+        })
+        .is_some();
+    let workspace = uv.and_then(|uv| uv.get("workspace"));
+
+    Some(Project {
+        root: dir.to_path_buf(),
+        manager: if uses_uv { "uv" } else { "pip" }.to_string(),
+        manifest,
+        members: expand_workspace(
+            dir,
+            &toml_strings(workspace, "members"),
+            &toml_strings(workspace, "exclude"),
+            "pyproject.toml",
+        ),
+        virtual_root: false,
+    })
+}
+
+fn cargo_project(dir: &Path) -> Option<Project> {
+    let manifest = dir.join("Cargo.toml");
+    let content = fs::read_to_string(&manifest).ok()?;
+    let config: toml::Value = toml::from_str(&content).ok()?;
+    let workspace = config.get("workspace");
+
+    Some(Project {
+        root: dir.to_path_buf(),
+        manager: "cargo".to_string(),
+        members: expand_workspace(
+            dir,
+            &toml_strings(workspace, "members"),
+            &toml_strings(workspace, "exclude"),
+            "Cargo.toml",
+        ),
+        virtual_root: config.get("package").is_none(),
+        manifest,
+    })
+}
+
+/// 从 `dir` 开始逐级向上查找，返回第一个非空结果
+fn find_upwards<T>(dir: &Path, find: impl Fn(&Path) -> Option<T>) -> Option<T> {
+    dir.ancestors().find_map(find)
+}
+
+/// package.json 的 `packageManager` 字段（corepack），如 `pnpm@9.1.0`
+fn package_manager_field(manifest: &Value) -> Option<String> {
+    let value = manifest.get("packageManager")?.as_str()?;
+    let name = value.split('@').next()?;
+    matches!(name, "npm" | "pnpm" | "yarn" | "bun").then(|| name.to_string())
+}
+
+/// TOML 表中的字符串数组
+fn toml_strings(table: Option<&toml::Value>, key: &str) -> Vec<String> {
+    table
+        .and_then(|table| table.get(key))
+        .and_then(toml::Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter_map(toml::Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 解析 pnpm-workspace.yaml 中的 `packages` 列表
+fn pnpm_workspace_packages(content: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) && !trimmed.starts_with('-') {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            let item = item.split(" #").next().unwrap_or_default().trim();
+            packages.push(item.trim_matches(['\'', '"']).to_string());
+        }
+    }
+    packages
+}
+
+/// 展开工作区成员模式，只保留包含清单文件的目录
+///
+/// 支持 `*`、`**` 与 `!` 开头的排除模式。
+fn expand_workspace(
+    root: &Path,
+    patterns: &[String],
+    excludes: &[String],
+    manifest: &str,
+) -> Vec<PathBuf> {
+    let (negated, included): (Vec<&String>, Vec<&String>) = patterns
+        .iter()
+        .partition(|pattern| pattern.starts_with('!'));
+    let excluded: Vec<PathBuf> = negated
+        .iter()
+        .map(|pattern| &pattern[1..])
+        .chain(excludes.iter().map(String::as_str))
+        .flat_map(|pattern| expand_pattern(root, pattern))
+        .collect();
+
+    let mut members: Vec<PathBuf> = included
+        .iter()
+        .flat_map(|pattern| expand_pattern(root, pattern))
+        .filter(|dir| dir.as_path() != root && dir.join(manifest).is_file())
+        .filter(|dir| !excluded.contains(dir))
+        .collect();
+    members.sort();
+    members.dedup();
+    members
+}
+
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = pattern
+        .trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/');
+    let segments: Vec<&str> = pattern
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let mut matches = Vec::new();
+    expand_segments(root, &segments, 0, &mut matches);
+    matches
+}
+
+fn expand_segments(dir: &Path, segments: &[&str], depth: usize, matches: &mut Vec<PathBuf>) {
+    let Some((segment, rest)) = segments.split_first() else {
+        matches.push(dir.to_path_buf());
+        return;
+    };
+    if *segment == "**" {
+        // `**` 匹配零层或多层目录
+        expand_segments(dir, rest, depth, matches);
+        if depth < MAX_GLOB_DEPTH {
+            for child in child_dirs(dir) {
+                expand_segments(&child, segments, depth + 1, matches);
+            }
+        }
+    } else if segment.contains('*') {
+        for child in child_dirs(dir) {
+            let name = child
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            if wildcard_match(segment, name) {
+                expand_segments(&child, rest, depth + 1, matches);
+            }
+        }
+    } else {
+        let child = dir.join(segment);
+        if child.is_dir() {
+            expand_segments(&child, rest, depth + 1, matches);
+        }
+    }
+}
+
+fn child_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            !SKIPPED_DIRS.contains(&name.as_ref())
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// 目录名与只含 `*` 通配符的模式匹配
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (index, part) in parts.iter().enumerate() {
+        if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+/// 一个本地范围的依赖与过时检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopeReport {
+    /// 本地范围目录：项目根目录或工作区成员
+    pub path: PathBuf,
+    pub manager: String,
+    pub installed: Vec<Package>,
+    pub outdated: Vec<Package>,
+}
+
+/// 同一依赖在多个项目中的使用情况
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyUsage {
+    /// purl 类型，如 `npm`、`pypi`、`cargo`
+    pub ecosystem: String,
+    pub name: String,
+    /// 各项目中安装的版本，从低到高排列
+    pub versions: Vec<String>,
+    /// 使用该依赖的项目目录
+    pub projects: Vec<PathBuf>,
+    /// 其中依赖已过时的项目数
+    pub outdated: usize,
+}
+
+#[derive(Default)]
+struct UsageTally {
+    versions: BTreeSet<String>,
+    projects: BTreeSet<PathBuf>,
+    outdated: usize,
+}
+
+/// 按生态与包名汇总各范围的依赖，使用的项目多的排在前面
+///
+/// npm、pnpm、yarn、bun 同属 npm 生态；Python 包名按规范化后的名称合并。
+pub fn aggregate_dependencies(reports: &[ScopeReport]) -> Vec<DependencyUsage> {
+    let mut usages: BTreeMap<(String, String), UsageTally> = BTreeMap::new();
+    for report in reports {
+        let Some(ecosystem) = purl_type_for(&report.manager) else {
+            continue;
+        };
+        let key = |name: &str| {
+            let name = if ecosystem == "pypi" {
+                normalize_name(name)
+            } else {
+                name.to_string()
+            };
+            (ecosystem.to_string(), name)
+        };
+        let outdated: HashSet<String> =
+            report.outdated.iter().map(|pkg| key(&pkg.name).1).collect();
+        for pkg in &report.installed {
+            let key = key(&pkg.name);
+            let usage = usages.entry(key.clone()).or_default();
+            usage.versions.insert(pkg.version.clone());
+            // 同一依赖在一个范围中可能出现多次（如 npm 的多个依赖类型）
+            if usage.projects.insert(report.path.clone()) && outdated.contains(&key.1) {
+                usage.outdated += 1;
+            }
+        }
+    }
+
+    let mut result: Vec<DependencyUsage> = usages
+        .into_iter()
+        .map(|((ecosystem, name), tally)| DependencyUsage {
+            ecosystem,
+            name,
+            versions: sort_versions(tally.versions),
+            projects: tally.projects.into_iter().collect(),
+            outdated: tally.outdated,
+        })
+        .collect();
+    result.sort_by(|a, b| {
+        b.projects
+            .len()
+            .cmp(&a.projects.len())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    result
+}
+
+fn sort_versions(versions: BTreeSet<String>) -> Vec<String> {
+    let mut versions: Vec<String> = versions.into_iter().collect();
+    versions.sort_by(|a, b| compare_versions(a, b));
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_detect_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        write(
+            &root.join("package.json"),
+            r#"{"name": "mono", "private": true}"#,
+        );
+        write(&root.join("pnpm-lock.yaml"), "lockfileVersion: '9.0'\n");
+        write(
+            &root.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'packages/*'\n  - apps/**\n  - '!packages/legacy'\n",
+        );
+        write(&root.join("packages/ui/package.json"), "{}");
+        write(&root.join("packages/legacy/package.json"), "{}");
+        write(&root.join("packages/docs/README.md"), "");
+        write(&root.join("apps/web/site/package.json"), "{}");
+        write(
+            &root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        write(
+            &root.join("crates/core/Cargo.toml"),
+            "[package]\nname = \"core\"\n",
+        );
+
+        let projects = detect_projects(&root.join("packages/docs"));
+        assert_eq!(projects.len(), 2);
+
+        let node = &projects[0];
+        assert_eq!(node.manager, "pnpm");
+        assert_eq!(
+            node.members,
+            vec![root.join("apps/web/site"), root.join("packages/ui")]
+        );
+        assert_eq!(node.scopes().len(), 3);
+
+        let cargo = &projects[1];
+        assert_eq!(cargo.manager, "cargo");
+        assert!(cargo.virtual_root);
+        assert_eq!(cargo.scopes(), vec![root.join("crates/core")]);
+
+        // 在工作区成员中识别为整个工作区
+        let member = detect_projects(&root.join("packages/ui"));
+        assert_eq!(member.len(), 1);
+        assert_eq!(member[0].manager, "pnpm");
+        assert_eq!(member[0].root, root);
+
+        // 不属于工作区的子项目单独识别，锁文件在上层目录
+        let nested = detect_projects(&root.join("packages/legacy"));
+        assert_eq!(nested[0].manager, "pnpm");
+        assert_eq!(nested[0].root, root.join("packages/legacy"));
+    }
+
+    #[test]
+    fn test_python_project() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("requirements.txt"), "requests\n");
+        assert_eq!(projects_in(dir.path())[0].manager, "pip");
+
+        write(
+            &dir.path().join("pyproject.toml"),
+            "[project]\nname = \"api\"\n\n[tool.uv.workspace]\nmembers = [\"libs/*\"]\n",
+        );
+        write(
+            &dir.path().join("libs/common/pyproject.toml"),
+            "[project]\n",
+        );
+        let project = &projects_in(dir.path())[0];
+        assert_eq!(project.manager, "uv");
+        assert_eq!(project.members, vec![dir.path().join("libs/common")]);
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", "ui"));
+        assert!(wildcard_match("plugin-*", "plugin-a"));
+        assert!(wildcard_match("*-cli", "boxy-cli"));
+        assert!(!wildcard_match("plugin-*", "core"));
+        assert_eq!(
+            package_manager_field(&serde_json::json!({"packageManager": "yarn@4.1.0"})).as_deref(),
+            Some("yarn")
+        );
+    }
+
+    #[test]
+    fn test_find_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            &root.join("mono/package.json"),
+            r#"{"private": true, "workspaces": ["packages/*"]}"#,
+        );
+        write(&root.join("mono/yarn.lock"), "");
+        write(&root.join("mono/packages/ui/package.json"), "{}");
+        write(&root.join("mono/node_modules/left-pad/package.json"), "{}");
+        write(
+            &root.join("work/api/pyproject.toml"),
+            "[project]\nname = \"api\"\n",
+        );
+        write(
+            &root.join("work/cli/Cargo.toml"),
+            "[package]\nname = \"cli\"\n",
+        );
+        write(&root.join(".cache/tool/package.json"), "{}");
+        write(&root.join("a/b/c/d/e/package.json"), "{}");
+
+        let projects = find_projects(root, DEFAULT_SCAN_DEPTH);
+        let roots: Vec<PathBuf> = projects.iter().map(|p| p.root.clone()).collect();
+        assert_eq!(
+            roots,
+            vec![
+                root.join("mono"),
+                root.join("work/api"),
+                root.join("work/cli")
+            ]
+        );
+        assert_eq!(projects[0].manager, "yarn");
+        assert_eq!(projects[0].members, vec![root.join("mono/packages/ui")]);
+    }
+
+    #[test]
+    fn test_aggregate_dependencies() {
+        let report = |path: &str, manager: &str, installed: &[(&str, &str)], outdated: &[&str]| {
+            ScopeReport {
+                path: PathBuf::from(path),
+                manager: manager.to_string(),
+                installed: installed
+                    .iter()
+                    .map(|(name, version)| Package::new("npm", *name, *version))
+                    .collect(),
+                outdated: outdated
+                    .iter()
+                    .map(|name| Package::new("npm", *name, ""))
+                    .collect(),
+            }
+        };
+        let reports = [
+            report(
+                "/code/a",
+                "npm",
+                &[("lodash", "4.17.15"), ("react", "18.2.0")],
+                &["lodash"],
+            ),
+            report("/code/b", "pnpm", &[("lodash", "4.17.21")], &[]),
+            report("/code/c", "yarn", &[("lodash", "4.17.15")], &["lodash"]),
+            report(
+                "/code/d",
+                "uv",
+                &[("Django", "5.0"), ("lodash", "1.0")],
+                &[],
+            ),
+            report("/code/e", "pip", &[("django", "4.2")], &["django"]),
+        ];
+
+        let usages = aggregate_dependencies(&reports);
+        assert_eq!(usages[0].name, "lodash");
+        assert_eq!(usages[0].ecosystem, "npm");
+        assert_eq!(usages[0].projects.len(), 3);
+        assert_eq!(usages[0].outdated, 2);
+        assert_eq!(usages[0].versions, vec!["4.17.15", "4.17.21"]);
+
+        let django = usages.iter().find(|usage| usage.name == "django").unwrap();
+        assert_eq!(django.ecosystem, "pypi");
+        assert_eq!(django.versions, vec!["4.2", "5.0"]);
+        assert_eq!(django.outdated, 1);
+        // 不同生态的同名包分别统计
+        assert_eq!(
+            usages.iter().filter(|usage| usage.name == "lodash").count(),
+            2
+        );
+    }
+}
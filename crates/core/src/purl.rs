@@ -0,0 +1,221 @@
+use crate::disk::normalize_dist_name;
+use crate::package::Package;
+use boxy_error::{BoxyError, Result};
+use std::fmt;
+
+/// Package URL（https://github.com/package-url/purl-spec）
+///
+/// 只保留 boxy 用到的部分，qualifiers 与 subpath 在解析时忽略。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Purl {
+    pub purl_type: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// 包管理器对应的 purl 类型
+pub fn purl_type_for(manager: &str) -> Option<&'static str> {
+    match manager {
+        "npm" | "pnpm" | "yarn" | "bun" => Some("npm"),
+        "pip" | "pipx" | "uv" => Some("pypi"),
+        "cargo" => Some("cargo"),
+        "brew" => Some("brew"),
+        "mas" => Some("mas"),
+        _ => None,
+    }
+}
+
+impl Purl {
+    pub fn from_package(pkg: &Package) -> Self {
+        let purl_type = purl_type_for(&pkg.manager).unwrap_or(pkg.manager.as_str());
+        let name = match purl_type {
+            "pypi" => normalize_dist_name(&pkg.name),
+            "brew" => pkg.qualified_name(),
+            _ => pkg.name.clone(),
+        };
+        // npm 的 scope（@types/node）、brew 的 tap（user/tap/formula）作为 namespace
+        let (namespace, name) = match name.rsplit_once('/') {
+            Some((namespace, name)) => (Some(namespace.to_string()), name.to_string()),
+            None => (None, name),
+        };
+        let version = pkg.version.trim();
+        let version = if purl_type == "cargo" {
+            version.trim_start_matches('v')
+        } else {
+            version
+        };
+        Self {
+            purl_type: purl_type.to_string(),
+            namespace,
+            name,
+            version: (!version.is_empty()).then(|| version.to_string()),
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Self> {
+        let parse_error = || BoxyError::ParseError {
+            input: format!("无效的 purl: {}", input),
+        };
+        let rest = input
+            .trim()
+            .strip_prefix("pkg:")
+            .ok_or_else(parse_error)?
+            .trim_start_matches('/');
+        let rest = rest.split('#').next().unwrap_or(rest);
+        let rest = rest.split('?').next().unwrap_or(rest);
+        let (purl_type, path) = rest.split_once('/').ok_or_else(parse_error)?;
+        let (path, version) = match path.rsplit_once('@') {
+            // npm 的 namespace 未编码时以 @ 开头，不能当作版本分隔符
+            Some((path, version)) if !path.is_empty() => (path, Some(percent_decode(version))),
+            _ => (path, None),
+        };
+        let mut segments: Vec<String> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect();
+        let name = segments.pop().ok_or_else(parse_error)?;
+        if purl_type.is_empty() || name.is_empty() {
+            return Err(parse_error());
+        }
+        Ok(Self {
+            purl_type: purl_type.to_lowercase(),
+            namespace: (!segments.is_empty()).then(|| segments.join("/")),
+            name,
+            version: version.filter(|value| !value.is_empty()),
+        })
+    }
+
+    /// 传给包管理器的包名（含 namespace，如 `@types/node`）
+    pub fn package_name(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}/{}", namespace, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// 该类型默认使用的包管理器
+    pub fn default_manager(&self) -> Option<&'static str> {
+        match self.purl_type.as_str() {
+            "npm" => Some("npm"),
+            "pypi" => Some("pip"),
+            "cargo" => Some("cargo"),
+            "brew" => Some("brew"),
+            "mas" => Some("mas"),
+            _ => None,
+        }
+    }
+
+    /// 指定的包管理器能否处理该类型（如 pkg:npm 也可交给 pnpm）
+    pub fn supports_manager(&self, manager: &str) -> bool {
+        purl_type_for(manager) == Some(self.purl_type.as_str())
+    }
+}
+
+impl fmt::Display for Purl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pkg:{}/", self.purl_type)?;
+        if let Some(namespace) = &self.namespace {
+            for segment in namespace.split('/') {
+                write!(f, "{}/", percent_encode(segment))?;
+            }
+        }
+        write!(f, "{}", percent_encode(&self.name))?;
+        if let Some(version) = &self.version {
+            write!(f, "@{}", percent_encode(version))?;
+        }
+        Ok(())
+    }
+}
+
+impl Package {
+    /// 规范的 purl，如 `pkg:npm/typescript@5.4.0`、`pkg:pypi/black@24.1.0`
+    pub fn purl(&self) -> String {
+        Purl::from_package(self).to_string()
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'-' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let hex = |byte: u8| (byte as char).to_digit(16);
+            if let (Some(high), Some(low)) = (hex(bytes[index + 1]), hex(bytes[index + 2])) {
+                decoded.push((high * 16 + low) as u8);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_purl() {
+        assert_eq!(
+            Package::new("npm", "typescript", "5.4.0").purl(),
+            "pkg:npm/typescript@5.4.0"
+        );
+        assert_eq!(
+            Package::new("pnpm", "@types/node", "20.1.0").purl(),
+            "pkg:npm/%40types/node@20.1.0"
+        );
+        assert_eq!(
+            Package::new("pip", "Typing_Extensions", "4.9.0").purl(),
+            "pkg:pypi/typing-extensions@4.9.0"
+        );
+        assert_eq!(
+            Package::new("cargo", "ripgrep", "v14.1.0").purl(),
+            "pkg:cargo/ripgrep@14.1.0"
+        );
+        assert_eq!(Package::new("brew", "wget", "").purl(), "pkg:brew/wget");
+
+        let mut terraform = Package::new("brew", "terraform", "1.10.2");
+        terraform.tap = Some("hashicorp/tap".to_string());
+        assert_eq!(terraform.purl(), "pkg:brew/hashicorp/tap/terraform@1.10.2");
+        terraform.tap = Some("homebrew/core".to_string());
+        assert_eq!(terraform.purl(), "pkg:brew/terraform@1.10.2");
+    }
+
+    #[test]
+    fn test_parse() {
+        let purl = Purl::parse("pkg:cargo/ripgrep@14").unwrap();
+        assert_eq!(purl.package_name(), "ripgrep");
+        assert_eq!(purl.version.as_deref(), Some("14"));
+        assert_eq!(purl.default_manager(), Some("cargo"));
+
+        let purl = Purl::parse("pkg:npm/%40types/node@20.1.0?arch=x64").unwrap();
+        assert_eq!(purl.package_name(), "@types/node");
+        assert!(purl.supports_manager("pnpm"));
+        assert!(!purl.supports_manager("pip"));
+        assert_eq!(purl.to_string(), "pkg:npm/%40types/node@20.1.0");
+
+        let purl = Purl::parse("pkg:npm/@types/node").unwrap();
+        assert_eq!(purl.package_name(), "@types/node");
+        assert_eq!(purl.version, None);
+
+        assert!(Purl::parse("npm/typescript").is_err());
+        assert!(Purl::parse("pkg:npm").is_err());
+    }
+}
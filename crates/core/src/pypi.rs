@@ -0,0 +1,505 @@
+use crate::disk::{format_size, normalize_dist_name};
+use crate::package::Package;
+use crate::version::compare_versions;
+use boxy_cache::Cache;
+use boxy_error::{BoxyError, Result};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::{debug, info};
+
+/// 默认的 PyPI 地址
+pub const DEFAULT_INDEX_URL: &str = "https://pypi.org/simple";
+/// 依次读取的索引地址环境变量，boxy 自己的配置优先于 pip、uv 的配置
+const INDEX_URL_ENVS: [&str; 4] = [
+    "BOXY_PYPI_INDEX_URL",
+    "PIP_INDEX_URL",
+    "UV_DEFAULT_INDEX",
+    "UV_INDEX_URL",
+];
+/// PEP 691 定义的 JSON 格式简单索引
+const SIMPLE_JSON: &str = "application/vnd.pypi.simple.v1+json";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+/// 完整的项目列表较大（PyPI 超过 30 MB），单独使用更长的超时
+const PROJECT_LIST_TIMEOUT: Duration = Duration::from_secs(300);
+/// 下载项目列表时每收到这么多数据输出一次进度
+const PROGRESS_STEP: usize = 8 * 1024 * 1024;
+const SEARCH_LIMIT: usize = 20;
+
+/// PyPI 兼容的包索引（官方 PyPI、私有镜像或本地测试服务）
+///
+/// 通过简单索引（PEP 503/691）列出项目名进行匹配，再从 JSON API
+/// （`<base>/pypi/<name>/json`）读取简介和最新版本；镜像不提供 JSON API 时
+/// 退回项目页的版本列表（PEP 700）。
+#[derive(Clone)]
+pub struct PypiIndex {
+    simple_url: String,
+    client: reqwest::Client,
+}
+
+impl PypiIndex {
+    /// `index_url` 为简单索引地址，如 `https://pypi.org/simple`
+    pub fn new(index_url: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("boxy/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self {
+            simple_url: index_url.trim().trim_end_matches('/').to_string(),
+            client,
+        }
+    }
+
+    /// 按 `BOXY_PYPI_INDEX_URL`、`PIP_INDEX_URL`、`UV_DEFAULT_INDEX`、`UV_INDEX_URL`
+    /// 的顺序读取索引地址，均未设置时使用官方 PyPI
+    pub fn from_env() -> Self {
+        let url = INDEX_URL_ENVS
+            .iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .find(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_INDEX_URL.to_string());
+        Self::new(&url)
+    }
+
+    pub fn index_url(&self) -> &str {
+        &self.simple_url
+    }
+
+    /// JSON API 地址：简单索引以 `/simple` 结尾时，同级的 `/pypi` 即为 JSON API
+    fn json_api_url(&self, name: &str) -> Option<String> {
+        self.simple_url
+            .strip_suffix("/simple")
+            .map(|base| format!("{}/pypi/{}/json", base, name))
+    }
+
+    /// 搜索项目名，返回的包带有简介与最新版本
+    ///
+    /// 项目名列表较大，会按索引地址缓存在 `cache` 中。
+    pub async fn search(&self, cache: &Cache, query: &str, manager: &str) -> Result<Vec<Package>> {
+        let names = self.project_names(cache).await?;
+        let matches = match_projects(&names, query, SEARCH_LIMIT);
+        let details = self.projects_details(&matches).await;
+        Ok(matches
+            .into_iter()
+            .zip(details)
+            .map(|(name, details)| details.unwrap_or_default().into_package(name, manager))
+            .collect())
+    }
+
+    /// 读取单个项目的最新版本、简介与主页，`version` 为索引上的最新版本
+    pub async fn project(&self, name: &str, manager: &str) -> Result<Package> {
+        self.project_details(name)
+            .await
+            .map(|details| details.into_package(name.to_string(), manager))
+            .ok_or_else(|| BoxyError::PackageNotFound {
+                manager: manager.to_string(),
+                package: name.to_string(),
+            })
+    }
+
+    /// 并发查询多个项目的最新版本，键为传入的名称，查询失败的项目不会出现在结果中
+    pub async fn latest_versions(&self, names: &[String]) -> HashMap<String, String> {
+        let details = self.projects_details(names).await;
+        names
+            .iter()
+            .zip(details)
+            .filter_map(|(name, details)| Some((name.clone(), details?.version?)))
+            .collect()
+    }
+
+    /// 并发读取各项目的详情，结果与 `names` 一一对应
+    async fn projects_details(&self, names: &[String]) -> Vec<Option<ProjectDetails>> {
+        let mut tasks = JoinSet::new();
+        for (position, name) in names.iter().enumerate() {
+            let index = self.clone();
+            let name = name.clone();
+            tasks.spawn(async move { (position, index.project_details(&name).await) });
+        }
+        let mut details: Vec<Option<ProjectDetails>> = names.iter().map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((position, project)) = joined {
+                details[position] = project;
+            }
+        }
+        details
+    }
+
+    async fn project_names(&self, cache: &Cache) -> Result<Vec<String>> {
+        let cache_key = self.cache_key();
+        if let Ok(Some(names)) = cache.get::<Vec<String>>(&cache_key).await {
+            return Ok(names);
+        }
+        let body = self.fetch_project_list().await?;
+        let names = parse_simple_index(&body);
+        if names.is_empty() {
+            return Err(BoxyError::ParseError {
+                input: format!("无法解析索引 {}", self.simple_url),
+            });
+        }
+        let _ = cache.set(&cache_key, &names).await;
+        Ok(names)
+    }
+
+    async fn project_details(&self, name: &str) -> Option<ProjectDetails> {
+        if let Some(url) = self.json_api_url(name) {
+            match self.get(&url).await {
+                Ok(body) => {
+                    if let Some(details) = parse_json_api(&body) {
+                        return Some(details);
+                    }
+                }
+                Err(err) => debug!("读取 {} 失败: {}", url, err),
+            }
+        }
+        let body = self
+            .get(&format!(
+                "{}/{}/",
+                self.simple_url,
+                normalize_dist_name(name)
+            ))
+            .await
+            .ok()?;
+        Some(ProjectDetails {
+            version: latest_version(&body),
+            ..ProjectDetails::default()
+        })
+    }
+
+    /// 流式下载简单索引首页，并按 `PROGRESS_STEP` 输出下载进度
+    async fn fetch_project_list(&self) -> Result<String> {
+        let url = format!("{}/", self.simple_url);
+        let network_error = |err: reqwest::Error| BoxyError::NetworkError {
+            message: format!("{}: {}", url, err),
+        };
+        info!("正在下载项目列表 {}，首次搜索需要一些时间", url);
+        let mut response = self
+            .request(&url)
+            .timeout(PROJECT_LIST_TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(network_error)?;
+        let total = response.content_length();
+        let mut body = Vec::new();
+        let mut reported = 0;
+        while let Some(chunk) = response.chunk().await.map_err(network_error)? {
+            body.extend_from_slice(&chunk);
+            if body.len() - reported >= PROGRESS_STEP {
+                reported = body.len();
+                let received = format_size(body.len() as u64);
+                match total {
+                    Some(total) => info!("已下载 {} / {}", received, format_size(total)),
+                    None => info!("已下载 {}", received),
+                }
+            }
+        }
+        String::from_utf8(body).map_err(|_| BoxyError::ParseError {
+            input: format!("索引 {} 不是有效的 UTF-8", self.simple_url),
+        })
+    }
+
+    async fn get(&self, url: &str) -> Result<String> {
+        let network_error = |err: reqwest::Error| BoxyError::NetworkError {
+            message: format!("{}: {}", url, err),
+        };
+        self.request(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(network_error)?
+            .text()
+            .await
+            .map_err(network_error)
+    }
+
+    fn request(&self, url: &str) -> reqwest::RequestBuilder {
+        debug!("GET {}", url);
+        self.client.get(url).header(
+            reqwest::header::ACCEPT,
+            format!("{}, text/html;q=0.1, application/json;q=0.5", SIMPLE_JSON),
+        )
+    }
+
+    fn cache_key(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.simple_url.hash(&mut hasher);
+        format!("pypi-index-{}", hasher.finish())
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ProjectDetails {
+    version: Option<String>,
+    summary: Option<String>,
+    homepage: Option<String>,
+}
+
+impl ProjectDetails {
+    fn into_package(self, name: String, manager: &str) -> Package {
+        Package {
+            name,
+            version: self.version.unwrap_or_default(),
+            manager: manager.to_string(),
+            description: self.summary,
+            homepage: self.homepage,
+            ..Default::default()
+        }
+    }
+}
+
+/// 解析简单索引首页，兼容 JSON（PEP 691）与 HTML（PEP 503）两种格式
+fn parse_simple_index(body: &str) -> Vec<String> {
+    if let Ok(value) = serde_json::from_str::<Value>(body) {
+        return value
+            .get("projects")
+            .and_then(|projects| projects.as_array())
+            .map(|projects| {
+                projects
+                    .iter()
+                    .filter_map(|project| project.get("name")?.as_str())
+                    .map(|name| name.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+    anchor_texts(body)
+}
+
+/// 提取 HTML 中 `<a ...>text</a>` 的文本
+fn anchor_texts(html: &str) -> Vec<String> {
+    html.split("<a ")
+        .skip(1)
+        .filter_map(|segment| {
+            let start = segment.find('>')? + 1;
+            let end = segment[start..].find("</a>")? + start;
+            let text = segment[start..end].trim();
+            (!text.is_empty()).then(|| text.to_string())
+        })
+        .collect()
+}
+
+/// 从 JSON API 的 `info` 中读取最新版本、简介和主页
+fn parse_json_api(body: &str) -> Option<ProjectDetails> {
+    let value: Value = serde_json::from_str(body).ok()?;
+    let info = value.get("info")?;
+    let text = |value: Option<&Value>| {
+        value
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    };
+    let homepage = text(info.get("home_page")).or_else(|| {
+        let urls = info.get("project_urls")?.as_object()?;
+        urls.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("homepage"))
+            .and_then(|(_, value)| text(Some(value)))
+    });
+    Some(ProjectDetails {
+        version: text(info.get("version")),
+        summary: text(info.get("summary")),
+        homepage,
+    })
+}
+
+/// 从项目页读取最新的正式版本
+///
+/// JSON 格式优先使用 `versions` 字段（PEP 700），HTML 格式从文件名中解析版本。
+fn latest_version(body: &str) -> Option<String> {
+    let versions: Vec<String> = match serde_json::from_str::<Value>(body) {
+        Ok(value) => value
+            .get("versions")
+            .and_then(|versions| versions.as_array())
+            .map(|versions| {
+                versions
+                    .iter()
+                    .filter_map(|version| version.as_str())
+                    .map(|version| version.to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Err(_) => anchor_texts(body)
+            .iter()
+            .filter_map(|file| file_version(file))
+            .collect(),
+    };
+    let stable = |version: &&String| version.chars().all(|ch| ch.is_ascii_digit() || ch == '.');
+    let candidates: Vec<&String> = if versions.iter().any(|version| stable(&version)) {
+        versions.iter().filter(stable).collect()
+    } else {
+        versions.iter().collect()
+    };
+    candidates
+        .into_iter()
+        .max_by(|a, b| compare_versions(a, b))
+        .cloned()
+}
+
+/// 从 wheel 或源码包文件名中取出版本，如 `black-24.1.0-py3-none-any.whl`、`black-24.1.0.tar.gz`
+fn file_version(file: &str) -> Option<String> {
+    if let Some(stem) = file.strip_suffix(".whl") {
+        return stem.split('-').nth(1).map(|version| version.to_string());
+    }
+    let stem = [".tar.gz", ".zip", ".tar.bz2"]
+        .iter()
+        .find_map(|suffix| file.strip_suffix(suffix))?;
+    stem.rsplit_once('-')
+        .map(|(_, version)| version.to_string())
+}
+
+/// 按名称匹配项目：完全匹配优先，其次前缀匹配、包含匹配，同级中名称短的优先
+fn match_projects(names: &[String], query: &str, limit: usize) -> Vec<String> {
+    let query = normalize_dist_name(query.trim());
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<(u8, &String)> = names
+        .iter()
+        .filter_map(|name| {
+            let normalized = normalize_dist_name(name);
+            let tier = if normalized == query {
+                0
+            } else if normalized.starts_with(&query) {
+                1
+            } else if normalized.contains(&query) {
+                2
+            } else {
+                return None;
+            };
+            Some((tier, name))
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.len().cmp(&b.1.len()))
+            .then_with(|| a.1.cmp(b.1))
+    });
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, name)| name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boxy_cache::CacheConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_simple_index() {
+        let json =
+            r#"{"meta":{"api-version":"1.1"},"projects":[{"name":"black"},{"name":"Flask"}]}"#;
+        assert_eq!(parse_simple_index(json), vec!["black", "Flask"]);
+        let html = r#"<html><body><a href="/simple/black/">black</a>
+            <a href="/simple/flask/">Flask</a></body></html>"#;
+        assert_eq!(parse_simple_index(html), vec!["black", "Flask"]);
+    }
+
+    #[test]
+    fn test_match_projects() {
+        let names: Vec<String> = ["blackd", "black", "django-black", "flake8", "Black_Box"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        assert_eq!(
+            match_projects(&names, "black", 10),
+            vec!["black", "blackd", "Black_Box", "django-black"]
+        );
+        assert_eq!(match_projects(&names, "black", 1), vec!["black"]);
+    }
+
+    #[test]
+    fn test_latest_version() {
+        let json = r#"{"name":"black","versions":["23.1.0","24.1.0b1","24.1.0","9.0"]}"#;
+        assert_eq!(latest_version(json).as_deref(), Some("24.1.0"));
+        let html = r#"<a href="x">black-23.1.0.tar.gz</a>
+            <a href="y">black-24.1.0-py3-none-any.whl</a>"#;
+        assert_eq!(latest_version(html).as_deref(), Some("24.1.0"));
+    }
+
+    #[test]
+    fn test_parse_json_api() {
+        let body = r#"{"info":{"version":"24.1.0","summary":"The uncompromising code formatter.",
+            "home_page":"","project_urls":{"Homepage":"https://github.com/psf/black"}}}"#;
+        assert_eq!(
+            parse_json_api(body),
+            Some(ProjectDetails {
+                version: Some("24.1.0".into()),
+                summary: Some("The uncompromising code formatter.".into()),
+                homepage: Some("https://github.com/psf/black".into()),
+            })
+        );
+    }
+
+    /// 启动一个只返回固定内容的本地索引服务
+    async fn serve(routes: Vec<(&'static str, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = vec![0; 4096];
+                let size = stream.read(&mut buffer).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..size]).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let response = match routes.iter().find(|(route, _)| *route == path) {
+                    Some((_, body)) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    ),
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    }
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    #[tokio::test]
+    async fn test_search_local_index() {
+        let base = serve(vec![
+            (
+                "/simple/",
+                r#"{"projects":[{"name":"black"},{"name":"blacken-docs"},{"name":"ruff"}]}"#,
+            ),
+            (
+                "/pypi/black/json",
+                r#"{"info":{"version":"24.1.0","summary":"The uncompromising code formatter."}}"#,
+            ),
+            (
+                "/simple/blacken-docs/",
+                r#"{"versions":["1.16.0","1.18.0"]}"#,
+            ),
+        ])
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new_with_config(CacheConfig {
+            cache_dir: Some(dir.path().to_path_buf()),
+            ..CacheConfig::default()
+        })
+        .unwrap();
+
+        let index = PypiIndex::new(&format!("{}/simple/", base));
+        let packages = index.search(&cache, "black", "pip").await.unwrap();
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "black");
+        assert_eq!(packages[0].version, "24.1.0");
+        assert_eq!(
+            packages[0].description.as_deref(),
+            Some("The uncompromising code formatter.")
+        );
+        assert_eq!(packages[1].name, "blacken-docs");
+        assert_eq!(packages[1].version, "1.18.0");
+        assert_eq!(packages[1].description, None);
+    }
+}
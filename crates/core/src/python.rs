@@ -0,0 +1,269 @@
+use crate::disk::find_dist_info;
+use crate::package::Package;
+use boxy_error::{BoxyError, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// pip、uv 操作的 Python 环境
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PythonScope {
+    /// PATH 上的 pip；uv 按自身规则查找环境
+    #[default]
+    Default,
+    /// 指定的 Python 解释器
+    Interpreter(PathBuf),
+    /// 虚拟环境目录（包含 pyvenv.cfg）
+    Venv(PathBuf),
+    /// 用户 site-packages（`pip --user`）
+    User,
+}
+
+impl PythonScope {
+    /// 在本地范围目录中自动发现虚拟环境，找不到时使用默认环境
+    ///
+    /// 只用于显式指定的本地目录，不会查找当前目录，以免在全局范围下误操作项目环境。
+    pub fn detect(workdir: &Path) -> Self {
+        find_venv(workdir).map(Self::Venv).unwrap_or_default()
+    }
+
+    /// 该环境使用的解释器，默认环境与用户范围返回 None
+    pub fn interpreter(&self) -> Option<PathBuf> {
+        match self {
+            Self::Interpreter(path) => Some(path.clone()),
+            Self::Venv(dir) => Some(venv_python(dir)),
+            Self::Default | Self::User => None,
+        }
+    }
+
+    /// 按环境区分缓存键，如 `pip`、`pip-user`、`pip-venv-<hash>`
+    pub fn cache_key(&self, base: &str) -> String {
+        let (kind, path) = match self {
+            Self::Default => return base.to_string(),
+            Self::User => return format!("{}-user", base),
+            Self::Interpreter(path) => ("python", path),
+            Self::Venv(dir) => ("venv", dir),
+        };
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        // 使用路径哈希，避免缓存键过长
+        format!("{}-{}-{}", base, kind, hasher.finish())
+    }
+}
+
+/// 查找目录本身或其中 `.venv` 对应的虚拟环境
+pub fn find_venv(dir: &Path) -> Option<PathBuf> {
+    [dir.to_path_buf(), dir.join(".venv")]
+        .into_iter()
+        .find(|candidate| candidate.join("pyvenv.cfg").is_file())
+}
+
+/// 虚拟环境中的 Python 解释器路径
+pub fn venv_python(dir: &Path) -> PathBuf {
+    if cfg!(windows) {
+        dir.join("Scripts").join("python.exe")
+    } else {
+        dir.join("bin").join("python")
+    }
+}
+
+/// `pip list --format=json` 与 `uv pip list --format json` 输出中的一项
+///
+/// 加上 `--outdated` 时带有 `latest_version`；pip 加上 `--verbose` 时带有 `location` 与 `installer`。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ListEntry {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub latest_version: Option<String>,
+    /// 可编辑安装（`pip install -e`）的项目目录
+    #[serde(default)]
+    pub editable_project_location: Option<String>,
+    /// 所在的 site-packages 目录
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub installer: Option<String>,
+}
+
+impl ListEntry {
+    pub fn into_package(self, manager: &str) -> Package {
+        let outdated = self.latest_version.is_some();
+        Package {
+            name: self.name,
+            version: self.version,
+            manager: manager.to_string(),
+            installed_path: self.location,
+            outdated,
+            latest_version: self.latest_version,
+            source: self
+                .editable_project_location
+                .map(|location| format!("editable: {}", location)),
+            installer: self.installer.filter(|installer| !installer.is_empty()),
+            ..Default::default()
+        }
+    }
+}
+
+/// 解析 JSON 格式的包列表
+///
+/// 旧版本的 pip 可能在 JSON 之前输出警告，从第一个 `[` 开始解析。
+pub fn parse_list_json(output: &str) -> Result<Vec<ListEntry>> {
+    let start = output.find('[').ok_or_else(|| BoxyError::ParseError {
+        input: output.chars().take(200).collect(),
+    })?;
+    serde_json::from_str(output[start..].trim_end()).map_err(|err| BoxyError::JsonError {
+        message: format!("解析包列表失败: {}", err),
+    })
+}
+
+/// dist-info 中记录的安装信息
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InstallInfo {
+    /// `INSTALLER` 文件内容，如 pip、uv
+    pub installer: Option<String>,
+    /// 存在 `REQUESTED` 文件表示由用户直接安装（PEP 376）
+    pub requested: bool,
+    /// `direct_url.json`（PEP 610）记录的非索引来源
+    pub source: Option<String>,
+}
+
+/// 读取 dist-info 目录中的 INSTALLER、REQUESTED 与 direct_url.json
+pub fn read_install_info(dist_info: &Path) -> InstallInfo {
+    let installer = fs::read_to_string(dist_info.join("INSTALLER"))
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty());
+    let source = fs::read_to_string(dist_info.join("direct_url.json"))
+        .ok()
+        .and_then(|content| direct_url_source(&content));
+    InstallInfo {
+        installer,
+        requested: dist_info.join("REQUESTED").exists(),
+        source,
+    }
+}
+
+/// 把 PEP 610 的 direct_url.json 转为来源描述
+///
+/// 可编辑安装为 `editable: <目录>`，VCS 为 `git+<url>@<commit>`，其余为原始 URL。
+fn direct_url_source(content: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(content).ok()?;
+    let url = value.get("url")?.as_str()?;
+    if let Some(vcs) = value.get("vcs_info") {
+        let kind = vcs.get("vcs").and_then(Value::as_str).unwrap_or("git");
+        let commit = vcs.get("commit_id").and_then(Value::as_str);
+        return Some(match commit {
+            Some(commit) => format!("{}+{}@{}", kind, url, commit),
+            None => format!("{}+{}", kind, url),
+        });
+    }
+    let editable = value
+        .get("dir_info")
+        .and_then(|info| info.get("editable"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if editable {
+        let path = url.strip_prefix("file://").unwrap_or(url);
+        return Some(format!("editable: {}", path));
+    }
+    Some(url.to_string())
+}
+
+/// 按 dist-info 补全包的安装工具、是否直接安装与来源，返回找到的 dist-info 目录
+pub fn apply_install_info(package: &mut Package, site_packages: &Path) -> Option<PathBuf> {
+    let dist_info = find_dist_info(site_packages, &package.name, Some(&package.version))?;
+    let info = read_install_info(&dist_info);
+    package.installer = package.installer.take().or(info.installer);
+    package.requested = Some(info.requested);
+    if package.source.is_none() {
+        package.source = info.source;
+    }
+    Some(dist_info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_json() {
+        let output = "WARNING: Ignoring invalid distribution ~ip\n[{\"name\": \"demo-tool\", \"version\": \"0.1.0\", \"editable_project_location\": \"/home/dev/src/demo-tool\"}, {\"name\": \"pip\", \"version\": \"23.2.1\"}]\n";
+        let entries = parse_list_json(output).unwrap();
+        assert_eq!(entries.len(), 2);
+        let package = entries[0].clone().into_package("pip");
+        assert_eq!(
+            package.source.as_deref(),
+            Some("editable: /home/dev/src/demo-tool")
+        );
+        assert!(!package.outdated);
+        assert!(parse_list_json("").is_err());
+    }
+
+    #[test]
+    fn test_python_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let venv = dir.path().join(".venv");
+        fs::create_dir_all(&venv).unwrap();
+        fs::write(venv.join("pyvenv.cfg"), "home = /usr/bin\n").unwrap();
+
+        assert_eq!(find_venv(dir.path()), Some(venv.clone()));
+        assert_eq!(find_venv(&venv), Some(venv.clone()));
+        assert_eq!(find_venv(&venv.join("bin")), None);
+
+        let scope = PythonScope::detect(dir.path());
+        assert_eq!(scope, PythonScope::Venv(venv.clone()));
+        assert_eq!(PythonScope::detect(&venv.join("bin")), PythonScope::Default);
+        assert_eq!(scope.interpreter(), Some(venv_python(&venv)));
+        assert!(scope.cache_key("pip").starts_with("pip-venv-"));
+        assert_ne!(
+            scope.cache_key("pip"),
+            PythonScope::Interpreter(venv_python(&venv)).cache_key("pip")
+        );
+        assert_eq!(PythonScope::Default.cache_key("uv"), "uv");
+        assert_eq!(PythonScope::User.cache_key("pip"), "pip-user");
+        assert_eq!(PythonScope::User.interpreter(), None);
+    }
+
+    #[test]
+    fn test_read_install_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let dist_info = dir.path().join("demo_tool-0.1.0.dist-info");
+        fs::create_dir_all(&dist_info).unwrap();
+        fs::write(dist_info.join("INSTALLER"), "uv\n").unwrap();
+        fs::write(dist_info.join("REQUESTED"), "").unwrap();
+        fs::write(
+            dist_info.join("direct_url.json"),
+            r#"{"url": "https://github.com/example/demo.git", "vcs_info": {"vcs": "git", "commit_id": "0123abcd"}}"#,
+        )
+        .unwrap();
+
+        let mut package = ListEntry {
+            name: "demo-tool".into(),
+            version: "0.1.0".into(),
+            latest_version: None,
+            editable_project_location: None,
+            location: None,
+            installer: None,
+        }
+        .into_package("pip");
+        assert!(apply_install_info(&mut package, dir.path()).is_some());
+        assert_eq!(package.installer.as_deref(), Some("uv"));
+        assert_eq!(package.requested, Some(true));
+        assert_eq!(
+            package.source.as_deref(),
+            Some("git+https://github.com/example/demo.git@0123abcd")
+        );
+
+        assert_eq!(
+            direct_url_source(
+                r#"{"url": "file:///home/dev/src/demo", "dir_info": {"editable": true}}"#
+            )
+            .as_deref(),
+            Some("editable: /home/dev/src/demo")
+        );
+    }
+}
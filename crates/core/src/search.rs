@@ -0,0 +1,260 @@
+use crate::manager::PackageManager;
+use crate::package::Package;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::task::JoinSet;
+use tokio::time::{timeout, Duration};
+
+/// 名称完全匹配
+const SCORE_EXACT: u32 = 1000;
+/// 名称以关键词开头
+const SCORE_PREFIX: u32 = 400;
+/// 名称包含关键词
+const SCORE_CONTAINS: u32 = 200;
+/// 描述中每命中一个关键词
+const SCORE_DESCRIPTION_TERM: u32 = 30;
+/// 各包管理器返回的结果大多按热度排序，靠前的结果获得加分
+const SCORE_RANK_MAX: u32 = 50;
+const SCORE_RANK_STEP: u32 = 5;
+/// 每多一个生态提供同一工具的加分
+const SCORE_PER_MANAGER: u32 = 20;
+
+/// 合并后的搜索结果：同一工具在多个生态中的包归为一组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// 规范化后的名称，用于跨生态分组
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// 提供该工具的包管理器，按各自结果中的排名排序
+    pub managers: Vec<String>,
+    pub packages: Vec<Package>,
+    pub score: u32,
+}
+
+/// 搜索所有包管理器的结果
+#[derive(Debug, Default)]
+pub struct SearchOutcome {
+    pub results: Vec<SearchResult>,
+    /// 搜索失败或超时的包管理器及原因
+    pub errors: Vec<(String, String)>,
+}
+
+/// 规范化包名以便跨生态比较：忽略大小写，`_`、`.` 视为 `-`，去掉 brew tap 前缀
+///
+/// npm 的 scope（`@types/node`）是包名的一部分，保留不动。
+pub fn normalize_name(name: &str) -> String {
+    let name = name.trim();
+    let name = if name.starts_with('@') {
+        name
+    } else {
+        name.rsplit('/').next().unwrap_or(name)
+    };
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// 合并各包管理器的搜索结果，按相关度从高到低排序
+pub fn merge_results(query: &str, results: Vec<(String, Vec<Package>)>) -> Vec<SearchResult> {
+    let normalized_query = normalize_name(query);
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| term.to_lowercase())
+        .collect();
+
+    let mut groups: Vec<SearchResult> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut rank_bonus: Vec<u32> = Vec::new();
+    for (manager, packages) in results {
+        for (rank, package) in packages.into_iter().enumerate() {
+            let key = normalize_name(&package.name);
+            let bonus = SCORE_RANK_MAX.saturating_sub(rank as u32 * SCORE_RANK_STEP);
+            let index = *positions.entry(key.clone()).or_insert_with(|| {
+                groups.push(SearchResult {
+                    key: key.clone(),
+                    name: package.name.clone(),
+                    description: None,
+                    managers: Vec::new(),
+                    packages: Vec::new(),
+                    score: 0,
+                });
+                rank_bonus.push(0);
+                groups.len() - 1
+            });
+            let group = &mut groups[index];
+            if group.managers.contains(&manager) {
+                continue;
+            }
+            if group.description.is_none() {
+                group.description = package
+                    .description
+                    .clone()
+                    .filter(|value| !value.trim().is_empty());
+            }
+            group.managers.push(manager.clone());
+            group.packages.push(package);
+            rank_bonus[index] = rank_bonus[index].max(bonus);
+        }
+    }
+
+    for (group, bonus) in groups.iter_mut().zip(rank_bonus) {
+        group.score = name_score(&group.key, &normalized_query)
+            + description_score(group.description.as_deref(), &terms)
+            + bonus
+            + SCORE_PER_MANAGER * (group.managers.len() as u32 - 1);
+    }
+    groups.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.key.cmp(&b.key)));
+    groups
+}
+
+fn name_score(key: &str, query: &str) -> u32 {
+    if query.is_empty() {
+        0
+    } else if key == query {
+        SCORE_EXACT
+    } else if key.starts_with(query) {
+        SCORE_PREFIX
+    } else if key.contains(query) {
+        SCORE_CONTAINS
+    } else {
+        0
+    }
+}
+
+fn description_score(description: Option<&str>, terms: &[String]) -> u32 {
+    let Some(description) = description else {
+        return 0;
+    };
+    let description = description.to_lowercase();
+    terms
+        .iter()
+        .filter(|term| description.contains(term.as_str()))
+        .count() as u32
+        * SCORE_DESCRIPTION_TERM
+}
+
+/// 并发搜索多个包管理器并合并结果
+///
+/// 不可用的包管理器会被跳过，`limit` 限制每个包管理器的检查与搜索总时长，
+/// 单个包管理器失败或超时不影响其它结果。
+pub async fn search_all(
+    managers: Vec<Box<dyn PackageManager>>,
+    query: &str,
+    limit: Duration,
+) -> SearchOutcome {
+    let mut tasks = JoinSet::new();
+    for (order, manager) in managers.into_iter().enumerate() {
+        let query = query.to_string();
+        tasks.spawn(async move {
+            let name = manager.name().to_string();
+            let search = async {
+                if !manager.check_available().await.unwrap_or(false) {
+                    return Ok(Vec::new());
+                }
+                manager.search(&query).await
+            };
+            let result = match timeout(limit, search).await {
+                Ok(Ok(packages)) => Ok(packages),
+                Ok(Err(err)) => Err(err.to_string()),
+                Err(_) => Err("搜索超时".to_string()),
+            };
+            (order, name, result)
+        });
+    }
+
+    let mut collected = Vec::new();
+    let mut errors = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((order, name, Ok(packages))) => collected.push((order, name, packages)),
+            Ok((_, name, Err(err))) => errors.push((name, err)),
+            Err(err) => errors.push(("unknown".to_string(), err.to_string())),
+        }
+    }
+    // 按传入顺序合并，保证结果稳定
+    collected.sort_by_key(|(order, _, _)| *order);
+    errors.sort();
+    SearchOutcome {
+        results: merge_results(
+            query,
+            collected
+                .into_iter()
+                .map(|(_, name, packages)| (name, packages))
+                .collect(),
+        ),
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Typing_Extensions"), "typing-extensions");
+        assert_eq!(normalize_name("zope.interface"), "zope-interface");
+        assert_eq!(normalize_name("homebrew/core/ripgrep"), "ripgrep");
+        assert_eq!(normalize_name("@types/node"), "@types/node");
+    }
+
+    #[test]
+    fn test_merge_results() {
+        let results = vec![
+            (
+                "brew".to_string(),
+                vec![
+                    Package {
+                        description: Some("rga: ripgrep, but also search in PDFs".to_string()),
+                        ..Package::new("brew", "ripgrep-all", "")
+                    },
+                    Package {
+                        description: Some(
+                            "Search tool like grep and The Silver Searcher".to_string(),
+                        ),
+                        ..Package::new("brew", "ripgrep", "")
+                    },
+                ],
+            ),
+            (
+                "cargo".to_string(),
+                vec![
+                    Package::new("cargo", "ripgrep", ""),
+                    Package {
+                        description: Some(
+                            "Fast line oriented regex searching as a library".to_string(),
+                        ),
+                        ..Package::new("cargo", "grep", "")
+                    },
+                ],
+            ),
+        ];
+        let merged = merge_results("ripgrep", results);
+        assert_eq!(merged[0].key, "ripgrep");
+        assert_eq!(merged[0].managers, vec!["brew", "cargo"]);
+        assert_eq!(
+            merged[0].description.as_deref(),
+            Some("Search tool like grep and The Silver Searcher")
+        );
+        assert_eq!(merged[1].key, "ripgrep-all");
+        assert_eq!(merged[2].key, "grep");
+    }
+
+    #[test]
+    fn test_merge_description_relevance() {
+        let results = vec![(
+            "npm".to_string(),
+            vec![
+                Package {
+                    description: Some("unrelated".to_string()),
+                    ..Package::new("npm", "foo", "")
+                },
+                Package {
+                    description: Some("A JSON schema validator".to_string()),
+                    ..Package::new("npm", "bar", "")
+                },
+            ],
+        )];
+        let merged = merge_results("json validator", results);
+        assert_eq!(merged[0].name, "bar");
+    }
+}
@@ -0,0 +1,188 @@
+use crate::purl::{purl_type_for, Purl};
+use boxy_error::{BoxyError, Result};
+
+/// PEP 440 版本约束的比较运算符起始字符
+const PYTHON_OPERATORS: [char; 5] = ['=', '<', '>', '!', '~'];
+
+/// 命令行中的包描述
+///
+/// 支持 `manager:name@version`（如 `npm:typescript@^5`、`pip:black==24.*`、`brew:wget`）
+/// 以及 purl（`pkg:cargo/ripgrep@14`）。版本保持用户输入的原样，由各管理器按原生语法传递。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageSpec {
+    pub manager: Option<String>,
+    pub name: String,
+    pub version: Option<String>,
+    /// 来自 purl 时的类型，`-m` 可以指定同类型的其它管理器
+    purl_type: Option<String>,
+}
+
+impl PackageSpec {
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.starts_with("pkg:") {
+            let purl = Purl::parse(input)?;
+            let manager = purl
+                .default_manager()
+                .ok_or_else(|| BoxyError::ParseError {
+                    input: format!("不支持的 purl 类型: {}", purl.purl_type),
+                })?;
+            return Ok(Self {
+                manager: Some(manager.to_string()),
+                name: purl.package_name(),
+                version: purl.version.clone(),
+                purl_type: Some(purl.purl_type),
+            });
+        }
+
+        // 只有已知的管理器名才视为前缀，避免误拆包名中的冒号
+        let (manager, target) = match input.split_once(':') {
+            Some((prefix, rest)) if purl_type_for(prefix).is_some() => (Some(prefix), rest),
+            _ => (None, input),
+        };
+        let (name, version) = split_version(target);
+        if name.is_empty() {
+            return Err(BoxyError::ParseError {
+                input: format!("无效的包描述: {}", input),
+            });
+        }
+        Ok(Self {
+            manager: manager.map(|value| value.to_string()),
+            name: name.to_string(),
+            version: version
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string()),
+            purl_type: None,
+        })
+    }
+
+    /// 合并 `-m` 指定的管理器，与包描述中的管理器冲突时返回错误
+    pub fn with_manager(mut self, manager: Option<&str>) -> Result<Self> {
+        let Some(manager) = manager else {
+            return Ok(self);
+        };
+        let compatible = match (&self.purl_type, &self.manager) {
+            (Some(purl_type), _) => purl_type_for(manager) == Some(purl_type.as_str()),
+            (None, Some(current)) => current == manager,
+            (None, None) => true,
+        };
+        if !compatible {
+            return Err(BoxyError::ParseError {
+                input: format!("{} 与 --manager {} 冲突", self, manager),
+            });
+        }
+        self.manager = Some(manager.to_string());
+        Ok(self)
+    }
+}
+
+impl std::fmt::Display for PackageSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(manager) = &self.manager {
+            write!(f, "{}:", manager)?;
+        }
+        write!(f, "{}", self.name)?;
+        match &self.version {
+            Some(version) if version.starts_with(PYTHON_OPERATORS) => write!(f, "{}", version),
+            Some(version) => write!(f, "@{}", version),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 拆分包名与版本
+///
+/// 优先按 `@` 拆分（跳过 npm scope 开头的 `@`），否则按 PEP 440 运算符拆分并保留运算符。
+fn split_version(target: &str) -> (&str, Option<&str>) {
+    if let Some(index) = target.rfind('@').filter(|index| *index > 0) {
+        return (&target[..index], Some(&target[index + 1..]));
+    }
+    match target.find(PYTHON_OPERATORS) {
+        Some(index) if index > 0 => (&target[..index], Some(&target[index..])),
+        _ => (target, None),
+    }
+}
+
+/// 组装 Python 包的依赖描述：带运算符的约束原样拼接，纯版本号使用 `==`
+pub fn python_requirement(name: &str, version: Option<&str>) -> String {
+    match version.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(version) if version.starts_with(PYTHON_OPERATORS) => format!("{}{}", name, version),
+        Some(version) => format!("{}=={}", name, version),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(input: &str) -> (Option<String>, String, Option<String>) {
+        let spec = PackageSpec::parse(input).unwrap();
+        (spec.manager, spec.name, spec.version)
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(
+            spec("npm:typescript@^5"),
+            (Some("npm".into()), "typescript".into(), Some("^5".into()))
+        );
+        assert_eq!(
+            spec("pip:black==24.*"),
+            (Some("pip".into()), "black".into(), Some("==24.*".into()))
+        );
+        assert_eq!(
+            spec("brew:wget"),
+            (Some("brew".into()), "wget".into(), None)
+        );
+        assert_eq!(
+            spec("cargo:ripgrep@14.1"),
+            (Some("cargo".into()), "ripgrep".into(), Some("14.1".into()))
+        );
+        assert_eq!(
+            spec("pnpm:@types/node"),
+            (Some("pnpm".into()), "@types/node".into(), None)
+        );
+        assert_eq!(spec("typescript"), (None, "typescript".into(), None));
+        assert_eq!(
+            spec("pkg:cargo/ripgrep@14"),
+            (Some("cargo".into()), "ripgrep".into(), Some("14".into()))
+        );
+        assert!(PackageSpec::parse("npm:").is_err());
+    }
+
+    #[test]
+    fn test_with_manager() {
+        let parsed = PackageSpec::parse("pkg:npm/typescript").unwrap();
+        assert_eq!(
+            parsed
+                .with_manager(Some("pnpm"))
+                .unwrap()
+                .manager
+                .as_deref(),
+            Some("pnpm")
+        );
+        let parsed = PackageSpec::parse("npm:typescript").unwrap();
+        assert!(parsed.with_manager(Some("pnpm")).is_err());
+        let parsed = PackageSpec::parse("black").unwrap();
+        assert_eq!(
+            parsed
+                .with_manager(Some("pipx"))
+                .unwrap()
+                .manager
+                .as_deref(),
+            Some("pipx")
+        );
+    }
+
+    #[test]
+    fn test_python_requirement() {
+        assert_eq!(python_requirement("black", Some("24.1.0")), "black==24.1.0");
+        assert_eq!(python_requirement("black", Some("==24.*")), "black==24.*");
+        assert_eq!(
+            python_requirement("ruff", Some(">=0.4,<0.5")),
+            "ruff>=0.4,<0.5"
+        );
+        assert_eq!(python_requirement("ruff", None), "ruff");
+    }
+}
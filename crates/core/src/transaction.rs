@@ -0,0 +1,330 @@
+use crate::diff::PackageDiff;
+use boxy_error::{BoxyError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 事务中单个步骤执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepAction {
+    Install,
+    Upgrade,
+    /// 升级到最新版本并修改清单中的版本范围
+    Bump,
+}
+
+/// 步骤状态
+///
+/// `Pending` 表示命令已发出但未返回（如被 Ctrl-C 中断），回滚时同样需要补偿。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Pending,
+    Done,
+    Failed,
+}
+
+/// 回滚时的补偿操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compensation {
+    /// 事务前未安装，卸载即可
+    Uninstall,
+    /// 事务前已安装，重新安装原版本
+    Reinstall(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollbackOutcome {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionStep {
+    pub manager: String,
+    pub package: String,
+    pub action: StepAction,
+    /// 用户指定的版本
+    pub version: Option<String>,
+    /// 事务开始前已安装的版本
+    pub previous_version: Option<String>,
+    pub status: StepStatus,
+    pub error: Option<String>,
+    pub rollback: Option<RollbackOutcome>,
+}
+
+impl TransactionStep {
+    pub fn new(
+        manager: &str,
+        package: &str,
+        action: StepAction,
+        version: Option<&str>,
+        previous_version: Option<&str>,
+    ) -> Self {
+        Self {
+            manager: manager.to_string(),
+            package: package.to_string(),
+            action,
+            version: version.map(|value| value.to_string()),
+            previous_version: previous_version.map(|value| value.to_string()),
+            status: StepStatus::Pending,
+            error: None,
+            rollback: None,
+        }
+    }
+
+    /// 撤销该步骤所需的补偿操作，失败的步骤没有改变系统状态，不需要补偿
+    pub fn compensation(&self) -> Option<Compensation> {
+        if self.status == StepStatus::Failed {
+            return None;
+        }
+        match &self.previous_version {
+            Some(version) => Some(Compensation::Reinstall(version.clone())),
+            None => Some(Compensation::Uninstall),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionStatus {
+    Running,
+    Committed,
+    RolledBack,
+    /// 部分补偿操作失败，需要手动处理
+    RollbackFailed,
+}
+
+/// 多包操作的事务记录，完成后写入历史记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub id: String,
+    /// 触发事务的命令，如 install、update
+    pub operation: String,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub status: TransactionStatus,
+    /// 失败或中断的原因
+    pub reason: Option<String>,
+    pub steps: Vec<TransactionStep>,
+    /// 事务结束后各包管理器已安装包的变化
+    #[serde(default)]
+    pub changes: Vec<PackageDiff>,
+}
+
+impl Transaction {
+    pub fn new(operation: &str) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: format!(
+                "{}-{}",
+                now.format("%Y%m%d%H%M%S"),
+                &uuid::Uuid::new_v4().simple().to_string()[..8]
+            ),
+            operation: operation.to_string(),
+            started_at: now.timestamp(),
+            finished_at: None,
+            status: TransactionStatus::Running,
+            reason: None,
+            steps: Vec::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// 记录即将执行的步骤，返回其下标
+    pub fn begin_step(&mut self, step: TransactionStep) -> usize {
+        self.steps.push(step);
+        self.steps.len() - 1
+    }
+
+    pub fn complete_step(&mut self, index: usize) {
+        self.steps[index].status = StepStatus::Done;
+    }
+
+    pub fn fail_step(&mut self, index: usize, error: &str) {
+        self.steps[index].status = StepStatus::Failed;
+        self.steps[index].error = Some(error.to_string());
+    }
+
+    /// 按执行的逆序列出需要补偿的步骤
+    pub fn rollback_plan(&self) -> Vec<(usize, Compensation)> {
+        self.steps
+            .iter()
+            .enumerate()
+            .rev()
+            .filter_map(|(index, step)| step.compensation().map(|action| (index, action)))
+            .collect()
+    }
+
+    pub fn record_rollback(&mut self, index: usize, error: Option<String>) {
+        self.steps[index].rollback = Some(RollbackOutcome {
+            success: error.is_none(),
+            error,
+        });
+    }
+
+    /// 结束事务，根据补偿结果确定最终状态
+    pub fn finish(&mut self, reason: Option<String>) {
+        self.finished_at = Some(chrono::Utc::now().timestamp());
+        self.status = match &reason {
+            None => TransactionStatus::Committed,
+            Some(_) => {
+                let failed = self
+                    .steps
+                    .iter()
+                    .any(|step| matches!(&step.rollback, Some(outcome) if !outcome.success));
+                if failed {
+                    TransactionStatus::RollbackFailed
+                } else {
+                    TransactionStatus::RolledBack
+                }
+            }
+        };
+        self.reason = reason;
+    }
+}
+
+/// 事务历史记录，每个事务保存为一个 JSON 文件
+pub struct HistoryStore {
+    dir: PathBuf,
+}
+
+impl HistoryStore {
+    /// 默认位于数据目录下的 boxy/history
+    pub fn new() -> Result<Self> {
+        let dir = dirs::data_local_dir().ok_or_else(|| BoxyError::CacheError {
+            message: "无法获取数据目录".to_string(),
+        })?;
+        Ok(Self::with_dir(dir.join("boxy").join("history")))
+    }
+
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn save(&self, transaction: &Transaction) -> Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.json", transaction.id));
+        let content =
+            serde_json::to_string_pretty(transaction).map_err(|err| BoxyError::JsonError {
+                message: format!("序列化事务记录失败: {}", err),
+            })?;
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    /// 按开始时间倒序列出事务，无法解析的文件会被跳过
+    pub fn list(&self) -> Result<Vec<Transaction>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut transactions: Vec<Transaction> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect();
+        transactions.sort_by(|a, b| {
+            b.started_at
+                .cmp(&a.started_at)
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        Ok(transactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_plan() {
+        let mut transaction = Transaction::new("install");
+        let first = transaction.begin_step(TransactionStep::new(
+            "npm",
+            "typescript",
+            StepAction::Install,
+            Some("5.4.0"),
+            Some("5.3.0"),
+        ));
+        transaction.complete_step(first);
+        let second = transaction.begin_step(TransactionStep::new(
+            "pip",
+            "black",
+            StepAction::Install,
+            None,
+            None,
+        ));
+        transaction.complete_step(second);
+        let third = transaction.begin_step(TransactionStep::new(
+            "cargo",
+            "ripgrep",
+            StepAction::Install,
+            None,
+            None,
+        ));
+        transaction.fail_step(third, "network error");
+        // 被中断的步骤也需要补偿
+        transaction.begin_step(TransactionStep::new(
+            "brew",
+            "wget",
+            StepAction::Upgrade,
+            None,
+            Some("1.21"),
+        ));
+
+        assert_eq!(
+            transaction.rollback_plan(),
+            vec![
+                (3, Compensation::Reinstall("1.21".into())),
+                (1, Compensation::Uninstall),
+                (0, Compensation::Reinstall("5.3.0".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_finish_status() {
+        let mut transaction = Transaction::new("update");
+        let index = transaction.begin_step(TransactionStep::new(
+            "npm",
+            "tsx",
+            StepAction::Upgrade,
+            None,
+            Some("4.0.0"),
+        ));
+        transaction.complete_step(index);
+        transaction.finish(None);
+        assert_eq!(transaction.status, TransactionStatus::Committed);
+
+        transaction.record_rollback(index, Some("failed".into()));
+        transaction.finish(Some("中断".into()));
+        assert_eq!(transaction.status, TransactionStatus::RollbackFailed);
+    }
+
+    #[test]
+    fn test_history_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::with_dir(dir.path().join("history"));
+        assert!(store.list().unwrap().is_empty());
+
+        let mut older = Transaction::new("install");
+        older.started_at -= 10;
+        older.id = "older".into();
+        older.finish(None);
+        store.save(&older).unwrap();
+        let newer = Transaction::new("update");
+        store.save(&newer).unwrap();
+
+        let listed = store.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].operation, "update");
+        assert_eq!(listed[1].status, TransactionStatus::Committed);
+    }
+}
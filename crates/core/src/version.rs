@@ -0,0 +1,141 @@
+use std::cmp::Ordering;
+
+/// 版本号中的一个片段
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Number(u64),
+    Text(String),
+}
+
+/// 比较两个版本号
+///
+/// 兼容 semver（`1.2.3-beta.1`）、PEP 440（`1.2rc1`、`1.2.post1`）以及带 `v` 前缀的写法。
+/// 构建元数据（`+` 之后的部分）不参与比较，末尾的 `.0` 视为相等。
+pub fn compare_versions(left: &str, right: &str) -> Ordering {
+    let left = segments(left);
+    let right = segments(right);
+    let mut index = 0;
+    loop {
+        match (left.get(index), right.get(index)) {
+            (None, None) => return Ordering::Equal,
+            (Some(_), None) => return compare_tail(&left[index..]),
+            (None, Some(_)) => return compare_tail(&right[index..]).reverse(),
+            (Some(a), Some(b)) => {
+                let ordering = compare_segment(a, b);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+        index += 1;
+    }
+}
+
+/// 去掉 `v` 前缀与首尾空白后的版本号
+pub fn normalize_version(version: &str) -> &str {
+    let version = version.trim();
+    version
+        .strip_prefix('v')
+        .or_else(|| version.strip_prefix('V'))
+        .filter(|rest| rest.starts_with(|ch: char| ch.is_ascii_digit()))
+        .unwrap_or(version)
+}
+
+fn segments(version: &str) -> Vec<Segment> {
+    let version = normalize_version(version);
+    let version = version.split('+').next().unwrap_or_default();
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut numeric = false;
+    for ch in version.chars() {
+        if ch.is_ascii_alphanumeric() {
+            let is_digit = ch.is_ascii_digit();
+            if !current.is_empty() && is_digit != numeric {
+                result.push(make_segment(&current, numeric));
+                current.clear();
+            }
+            numeric = is_digit;
+            current.push(ch.to_ascii_lowercase());
+        } else if !current.is_empty() {
+            result.push(make_segment(&current, numeric));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        result.push(make_segment(&current, numeric));
+    }
+    result
+}
+
+fn make_segment(value: &str, numeric: bool) -> Segment {
+    if numeric {
+        Segment::Number(value.parse().unwrap_or(u64::MAX))
+    } else {
+        Segment::Text(value.to_string())
+    }
+}
+
+/// 较长版本号剩余部分与“空”比较：数字片段为更高版本，预发布标记为更低版本
+fn compare_tail(rest: &[Segment]) -> Ordering {
+    for segment in rest {
+        match segment {
+            Segment::Number(0) => continue,
+            Segment::Number(_) => return Ordering::Greater,
+            Segment::Text(text) => {
+                return if text_rank(text) > 0 {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                };
+            }
+        }
+    }
+    Ordering::Equal
+}
+
+fn compare_segment(left: &Segment, right: &Segment) -> Ordering {
+    match (left, right) {
+        (Segment::Number(a), Segment::Number(b)) => a.cmp(b),
+        // 数字片段总是高于文本标记（1.0.0 > 1.0rc1，1.0.1 > 1.0.post1）
+        (Segment::Number(_), Segment::Text(_)) => Ordering::Greater,
+        (Segment::Text(_), Segment::Number(_)) => compare_segment(right, left).reverse(),
+        (Segment::Text(a), Segment::Text(b)) => text_rank(a).cmp(&text_rank(b)).then_with(|| a.cmp(b)),
+    }
+}
+
+/// 预发布标记的先后顺序，正式版本为 0
+fn text_rank(text: &str) -> i8 {
+    match text {
+        "dev" | "snapshot" => -5,
+        "alpha" | "a" => -4,
+        "beta" | "b" => -3,
+        "pre" | "preview" => -2,
+        "rc" | "c" => -1,
+        "post" | "p" | "pl" | "rev" | "r" => 1,
+        _ => -3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_semver() {
+        assert_eq!(compare_versions("1.2.3", "1.2.10"), Ordering::Less);
+        assert_eq!(compare_versions("v14.1.0", "14.1.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0-beta.1", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-alpha", "1.0.0-beta"), Ordering::Less);
+        assert_eq!(compare_versions("2.0", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0+build.5", "1.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_pep440() {
+        assert_eq!(compare_versions("24.1rc1", "24.1"), Ordering::Less);
+        assert_eq!(compare_versions("24.1.post1", "24.1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.dev0", "1.0a1"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.1", "1.0.post1"), Ordering::Greater);
+        assert_eq!(compare_versions("2.31.0", "2.4.0"), Ordering::Greater);
+    }
+}
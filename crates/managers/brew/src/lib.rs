@@ -1,26 +1,107 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{existing_dirs, DiskUsage, SizeSource},
     manager::PackageManager,
-    package::{Capability, Package},
+    package::{Capability, DependencyFlag, Package},
 };
 use boxy_error::{BoxyError, Result};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
 
+/// 管理的包类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrewScope {
+    /// formula 与 cask
+    All,
+    Formula,
+    Cask,
+}
+
+impl BrewScope {
+    fn cache_key(self) -> &'static str {
+        match self {
+            Self::All => "brew",
+            Self::Formula => "brew-formula",
+            Self::Cask => "brew-cask",
+        }
+    }
+
+    fn includes(self, cask: bool) -> bool {
+        match self {
+            Self::All => true,
+            Self::Formula => !cask,
+            Self::Cask => cask,
+        }
+    }
+}
+
+/// 限定 formula 或 cask 的命令参数
+fn kind_flag(cask: bool) -> &'static str {
+    if cask {
+        "--cask"
+    } else {
+        "--formula"
+    }
+}
+
+/// 带 tap 的包名（`user/repo/name`）所在的 tap
+fn tap_of(name: &str) -> Option<&str> {
+    let (tap, _) = name.rsplit_once('/')?;
+    (tap.matches('/').count() == 1).then_some(tap)
+}
+
+/// `name` 是否指向该包，可以是包名或带 tap 的包名
+fn matches_name(pkg: &Package, name: &str) -> bool {
+    pkg.name == name || pkg.qualified_name() == name
+}
+
+/// `brew tap-info --json --installed` 中的一个 tap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrewTap {
+    /// 如 `hashicorp/tap`
+    pub name: String,
+    /// git 远程地址
+    pub remote: Option<String>,
+    /// 远程地址不是 GitHub 上默认的 `user/homebrew-repo`
+    #[serde(default)]
+    pub custom_remote: bool,
+    /// Homebrew 官方维护的 tap
+    #[serde(default)]
+    pub official: bool,
+    pub path: Option<PathBuf>,
+    /// 带 tap 的 formula 名，如 `hashicorp/tap/terraform`
+    #[serde(default)]
+    pub formula_names: Vec<String>,
+    #[serde(default)]
+    pub cask_tokens: Vec<String>,
+    /// 如 `3 days ago`
+    pub last_commit: Option<String>,
+}
+
+/// 解析 `brew tap-info --json --installed`
+fn parse_tap_info(output: &str) -> Result<Vec<BrewTap>> {
+    serde_json::from_str(output).map_err(|e| BoxyError::JsonError {
+        message: format!("解析 brew tap-info 输出失败: {}", e),
+    })
+}
+
 pub struct BrewManager {
     cache: Arc<Cache>,
+    scope: BrewScope,
 }
 
 impl BrewManager {
-    pub fn new(cache: Arc<Cache>) -> Self {
-        Self { cache }
+    pub fn new(cache: Arc<Cache>, scope: BrewScope) -> Self {
+        Self { cache, scope }
     }
 
     async fn exec(&self, args: &[&str]) -> Result<String> {
@@ -46,33 +127,56 @@ impl BrewManager {
         }
     }
 
-    fn parse_list_output_with_versions(&self, output: &str) -> Vec<Package> {
-        output
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
+    /// 限定为 formula 或 cask 时对应的命令参数
+    fn scope_flag(&self) -> Option<&'static str> {
+        match self.scope {
+            BrewScope::All => None,
+            BrewScope::Formula => Some(kind_flag(false)),
+            BrewScope::Cask => Some(kind_flag(true)),
+        }
+    }
 
-                let mut parts = line.split_whitespace();
-                let name = parts.next()?.to_string();
-                let version = parts.next().unwrap_or_default().to_string();
+    /// 已添加的 tap
+    pub async fn taps(&self) -> Result<Vec<BrewTap>> {
+        let output = self.exec(&["tap-info", "--json", "--installed"]).await?;
+        parse_tap_info(&output)
+    }
 
-                Some(Package {
-                    name,
-                    version,
-                    manager: "brew".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
-                })
-            })
-            .collect()
+    /// 添加 tap，`url` 为不在 GitHub 上的仓库地址
+    pub async fn tap(&self, name: &str, url: Option<&str>) -> Result<()> {
+        let mut args = vec!["tap", name];
+        args.extend(url);
+
+        info!("brew {}", args.join(" "));
+
+        self.exec(&args).await?;
+        Ok(())
+    }
+
+    /// 移除 tap；仍有从该 tap 安装的包时 brew 会拒绝，`force` 时强制移除
+    pub async fn untap(&self, name: &str, force: bool) -> Result<()> {
+        let mut args = vec!["untap"];
+        if force {
+            args.push("--force");
+        }
+        args.push(name);
+
+        info!("brew {}", args.join(" "));
+
+        self.exec(&args).await?;
+        self.invalidate_cache().await?;
+        Ok(())
+    }
+
+    /// 安装带 tap 的包前添加尚未添加的 tap，之后才能查询包的类型
+    async fn ensure_tap(&self, name: &str) -> Result<()> {
+        let Some(tap) = tap_of(name) else {
+            return Ok(());
+        };
+        if self.taps().await?.iter().any(|item| item.name == tap) {
+            return Ok(());
+        }
+        self.tap(tap, None).await
     }
 
     fn parse_search_output(&self, output: &str) -> Vec<Package> {
@@ -89,224 +193,341 @@ impl BrewManager {
                     name: line.to_string(),
                     version: String::new(),
                     manager: "brew".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
+                    ..Default::default()
                 })
             })
             .collect()
     }
 
-    fn parse_outdated_output(&self, output: &str) -> Vec<Package> {
-        output
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                // brew outdated 输出格式: package_name (current_version) < latest_version
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.is_empty() {
-                    return None;
-                }
-
-                let name = parts[0].to_string();
-                let current_version = parts
-                    .get(1)
-                    .map(|s| s.trim_matches(&['(', ')'] as &[char]).to_string())
-                    .unwrap_or_default();
-                let latest_version = parts.get(3).map(|s| s.to_string()).unwrap_or_default();
+    async fn resolve_prefix_dir(&self, flag: &str) -> Option<PathBuf> {
+        let output = self.exec(&[flag]).await.ok()?;
+        let dir = output.trim();
+        if dir.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(dir))
+        }
+    }
 
-                Some(Package {
-                    name,
-                    version: current_version,
-                    manager: "brew".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: true,
-                    latest_version: Some(latest_version),
-                })
+    /// 统计 Cellar / Caskroom 中每个包的目录大小，找不到目录的包不显示大小
+    async fn collect_sizes(&self, packages: &mut [Package]) {
+        let cellar = self.resolve_prefix_dir("--cellar").await;
+        let caskroom = self.resolve_prefix_dir("--caskroom").await;
+        let items = packages
+            .iter()
+            .filter_map(|pkg| {
+                let dir = if pkg.cask {
+                    caskroom.as_ref()
+                } else {
+                    cellar.as_ref()
+                };
+                let path = dir?.join(&pkg.name);
+                path.exists()
+                    .then(|| (pkg.name.clone(), SizeSource::Path(path)))
             })
-            .collect()
+            .collect::<Vec<_>>();
+        let paths: HashMap<String, String> = items
+            .iter()
+            .filter_map(|(name, source)| match source {
+                SizeSource::Path(path) => Some((name.clone(), path.to_string_lossy().to_string())),
+                _ => None,
+            })
+            .collect();
+        let size_map = DiskUsage::new(self.cache.clone())
+            .measure("brew", items)
+            .await;
+
+        for pkg in packages.iter_mut() {
+            if let Some(size) = size_map.get(&pkg.name) {
+                pkg.size = Some(*size);
+            }
+            if let Some(path) = paths.get(&pkg.name) {
+                pkg.installed_path = Some(path.clone());
+            }
+        }
     }
 
-    fn parse_license(value: &Value) -> Option<String> {
-        if let Some(license) = value.as_str() {
-            return Some(license.to_string());
+    /// 查询包是否为 cask
+    ///
+    /// 已安装的包按已安装列表判断；未安装时按 `brew info --json=v2` 的结果判断，
+    /// 同名的 formula 与 cask 同时存在时与 brew 一致优先使用 formula。
+    async fn is_cask(&self, name: &str) -> Result<bool> {
+        if let Some(flag) = self.scope_flag() {
+            return Ok(flag == kind_flag(true));
         }
-
-        if let Some(list) = value.as_array() {
-            let licenses = list
-                .iter()
-                .filter_map(|item| item.as_str())
-                .collect::<Vec<_>>();
-            if !licenses.is_empty() {
-                return Some(licenses.join(", "));
-            }
+        if let Some(pkg) = self
+            .list_installed()
+            .await?
+            .into_iter()
+            .find(|pkg| matches_name(pkg, name))
+        {
+            return Ok(pkg.cask);
+        }
+        let output = self.exec(&["info", "--json=v2", name]).await?;
+        let packages = parse_info_output(&output)?;
+        // 带 tap 的名称只对应该 tap 中的包
+        let packages: Vec<&Package> = packages
+            .iter()
+            .filter(|pkg| tap_of(name).is_none() || matches_name(pkg, name))
+            .collect();
+        match packages.iter().find(|pkg| !pkg.cask).or(packages.first()) {
+            Some(pkg) => Ok(pkg.cask),
+            None => Err(BoxyError::PackageNotFound {
+                manager: "brew".to_string(),
+                package: name.to_string(),
+            }),
         }
-
-        None
     }
 
-    fn parse_size_from_installed(value: &Value) -> Option<u64> {
-        value
-            .get("installed")
-            .and_then(|installed| installed.as_array())
-            .and_then(|installed| installed.first())
-            .and_then(|item| item.get("installed_size"))
-            .and_then(|size| size.as_u64())
-            .map(|size| size.saturating_mul(1024))
+    async fn invalidate_cache(&self) -> Result<()> {
+        for scope in [BrewScope::All, BrewScope::Formula, BrewScope::Cask] {
+            self.cache.invalidate(scope.cache_key()).await?;
+        }
+        Ok(())
     }
+}
 
-    async fn fetch_installed_sizes(&self) -> Result<HashMap<String, u64>> {
-        let output = self.exec(&["info", "--json=v2", "--installed"]).await?;
-        let data: Value =
-            serde_json::from_str(&output).map_err(|e| BoxyError::JsonError {
-                message: format!("解析 brew info 输出失败: {}", e),
-            })?;
+/// `brew info --json=v2` 与 `brew outdated --json=v2` 的输出
+#[derive(Debug, Deserialize)]
+struct BrewJson<F, C> {
+    #[serde(default = "Vec::new")]
+    formulae: Vec<F>,
+    #[serde(default = "Vec::new")]
+    casks: Vec<C>,
+}
 
-        let mut sizes = HashMap::new();
-        if let Some(formulae) = data.get("formulae").and_then(|list| list.as_array()) {
-            for formula in formulae {
-                let name = match formula.get("name").and_then(|value| value.as_str()) {
-                    Some(value) => value,
-                    None => continue,
-                };
-                if let Some(size) = Self::parse_size_from_installed(formula) {
-                    sizes.insert(name.to_string(), size);
-                }
-            }
-        }
+#[derive(Debug, Deserialize)]
+struct FormulaInfo {
+    name: String,
+    tap: Option<String>,
+    desc: Option<String>,
+    homepage: Option<String>,
+    /// 字符串，或多个许可证的数组
+    #[serde(default)]
+    license: Value,
+    versions: FormulaVersions,
+    #[serde(default)]
+    installed: Vec<FormulaInstall>,
+    /// 链接到前缀的版本，keg-only 或未链接时为 null
+    linked_keg: Option<String>,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(default)]
+    keg_only: bool,
+    #[serde(default)]
+    outdated: bool,
+}
 
-        if let Some(casks) = data.get("casks").and_then(|list| list.as_array()) {
-            for cask in casks {
-                let name = cask
-                    .get("token")
-                    .and_then(|value| value.as_str())
-                    .or_else(|| cask.get("name").and_then(|value| value.as_str()));
-                let name = match name {
-                    Some(value) => value,
-                    None => continue,
-                };
-                if let Some(size) = cask.get("installed_size").and_then(|value| value.as_u64()) {
-                    sizes.insert(name.to_string(), size.saturating_mul(1024));
-                }
-            }
-        }
+#[derive(Debug, Deserialize)]
+struct FormulaVersions {
+    stable: Option<String>,
+}
 
-        Ok(sizes)
-    }
+#[derive(Debug, Deserialize)]
+struct FormulaInstall {
+    version: String,
+    #[serde(default)]
+    installed_on_request: bool,
+}
 
-    fn parse_json_info(&self, output: &str, name: &str) -> Result<Package> {
-        let data: Value =
-            serde_json::from_str(output).map_err(|e| BoxyError::JsonError {
-                message: format!("解析 brew info 输出失败: {}", e),
-            })?;
+#[derive(Debug, Deserialize)]
+struct CaskInfo {
+    token: String,
+    tap: Option<String>,
+    desc: Option<String>,
+    homepage: Option<String>,
+    version: String,
+    /// 已安装的版本，未安装时为 null
+    installed: Option<String>,
+    /// 多数 cask 为 null
+    auto_updates: Option<bool>,
+    #[serde(default)]
+    outdated: bool,
+}
 
-        if let Some(formula) = data
-            .get("formulae")
-            .and_then(|list| list.as_array())
-            .and_then(|list| list.first())
-        {
-            let version = formula
-                .get("versions")
-                .and_then(|value| value.get("stable"))
-                .and_then(|value| value.as_str())
-                .unwrap_or_default()
-                .to_string();
-            let description = formula
-                .get("desc")
-                .and_then(|value| value.as_str())
-                .map(|value| value.to_string());
-            let homepage = formula
-                .get("homepage")
-                .and_then(|value| value.as_str())
-                .map(|value| value.to_string());
-            let license = formula
-                .get("license")
-                .and_then(Self::parse_license);
-            let size = Self::parse_size_from_installed(formula);
-
-            return Ok(Package {
-                name: formula
-                    .get("name")
-                    .and_then(|value| value.as_str())
-                    .unwrap_or(name)
-                    .to_string(),
-                version,
-                manager: "brew".to_string(),
-                description,
-                homepage,
-                license,
-                installed_path: None,
-                size,
-                outdated: false,
-                latest_version: None,
-            });
+#[derive(Debug, Deserialize)]
+struct OutdatedFormula {
+    name: String,
+    installed_versions: Vec<String>,
+    current_version: String,
+    #[serde(default)]
+    pinned: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutdatedCask {
+    name: String,
+    /// 旧版 Homebrew 输出为字符串
+    installed_versions: InstalledVersions,
+    current_version: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum InstalledVersions {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl InstalledVersions {
+    fn last(self) -> Option<String> {
+        match self {
+            Self::One(version) => Some(version),
+            Self::Many(versions) => versions.into_iter().last(),
         }
+    }
+}
 
-        if let Some(cask) = data
-            .get("casks")
-            .and_then(|list| list.as_array())
-            .and_then(|list| list.first())
-        {
-            let version = cask
-                .get("version")
-                .and_then(|value| value.as_str())
-                .map(|value| value.to_string())
-                .or_else(|| {
-                    cask.get("version").and_then(|value| {
-                        value.as_array().map(|items| {
-                            items
-                                .iter()
-                                .filter_map(|item| item.as_str())
-                                .collect::<Vec<_>>()
-                                .join(", ")
-                        })
-                    })
-                })
-                .unwrap_or_default();
-            let description = cask
-                .get("desc")
-                .and_then(|value| value.as_str())
-                .map(|value| value.to_string());
-            let homepage = cask
-                .get("homepage")
-                .and_then(|value| value.as_str())
-                .map(|value| value.to_string());
-            let size = cask.get("installed_size").and_then(|value| value.as_u64());
-
-            return Ok(Package {
-                name: cask
-                    .get("token")
-                    .and_then(|value| value.as_str())
-                    .unwrap_or(name)
-                    .to_string(),
-                version,
-                manager: "brew".to_string(),
-                description,
-                homepage,
-                license: None,
-                installed_path: None,
-                size: size.map(|value| value.saturating_mul(1024)),
-                outdated: false,
-                latest_version: None,
-            });
+/// 解析 `brew cleanup` 末尾的 `This operation has freed approximately 1.2GB of disk space.`
+///
+/// Homebrew 按 1024 进位输出 B、KB、MB、GB；没有可清理内容时不输出该行。
+fn parse_cleanup_freed(output: &str) -> u64 {
+    output
+        .lines()
+        .find_map(|line| {
+            let rest = line.split_once("approximately ")?.1;
+            let size = rest.split_whitespace().next()?;
+            let split = size
+                .find(|ch: char| ch.is_ascii_alphabetic())
+                .unwrap_or(size.len());
+            let (number, unit) = size.split_at(split);
+            let multiplier = match unit {
+                "GB" => 1u64 << 30,
+                "MB" => 1 << 20,
+                "KB" => 1 << 10,
+                _ => 1,
+            };
+            let value: f64 = number.parse().ok()?;
+            Some((value * multiplier as f64) as u64)
+        })
+        .unwrap_or(0)
+}
+
+fn parse_license(value: &Value) -> Option<String> {
+    if let Some(license) = value.as_str() {
+        return Some(license.to_string());
+    }
+
+    if let Some(list) = value.as_array() {
+        let licenses = list
+            .iter()
+            .filter_map(|item| item.as_str())
+            .collect::<Vec<_>>();
+        if !licenses.is_empty() {
+            return Some(licenses.join(", "));
         }
+    }
 
-        Err(BoxyError::JsonError {
-            message: "解析 brew info 输出失败".to_string(),
+    None
+}
+
+fn brew_package(name: String, version: String, cask: bool) -> Package {
+    Package {
+        name,
+        version,
+        manager: "brew".to_string(),
+        cask,
+        ..Default::default()
+    }
+}
+
+fn formula_package(formula: FormulaInfo) -> Package {
+    // 正在使用的是链接的版本；keg-only 或未链接时取最后安装的版本
+    let install = formula
+        .linked_keg
+        .as_ref()
+        .and_then(|linked| {
+            formula
+                .installed
+                .iter()
+                .find(|item| &item.version == linked)
         })
+        .or(formula.installed.last());
+    let version = install
+        .map(|item| item.version.clone())
+        .or_else(|| formula.versions.stable.clone())
+        .unwrap_or_default();
+
+    let mut pkg = brew_package(formula.name, version, false);
+    pkg.description = formula.desc;
+    pkg.homepage = formula.homepage;
+    pkg.license = parse_license(&formula.license);
+    pkg.outdated = install.is_some() && formula.outdated;
+    pkg.latest_version = formula.versions.stable;
+    pkg.requested = install.map(|item| item.installed_on_request);
+    pkg.tap = formula.tap;
+    if formula.pinned {
+        pkg.flags.push(DependencyFlag::Pinned);
     }
+    if formula.keg_only {
+        pkg.flags.push(DependencyFlag::KegOnly);
+    }
+    pkg
+}
+
+fn cask_package(cask: CaskInfo) -> Package {
+    let installed = cask.installed.is_some();
+    let mut pkg = brew_package(
+        cask.token,
+        cask.installed.unwrap_or_else(|| cask.version.clone()),
+        true,
+    );
+    pkg.description = cask.desc;
+    pkg.homepage = cask.homepage;
+    pkg.outdated = installed && cask.outdated;
+    pkg.latest_version = Some(cask.version);
+    // cask 不会作为依赖被安装
+    pkg.requested = installed.then_some(true);
+    pkg.tap = cask.tap;
+    if cask.auto_updates == Some(true) {
+        pkg.flags.push(DependencyFlag::AutoUpdates);
+    }
+    pkg
+}
+
+/// 解析 `brew info --json=v2`，formula 在前、cask 在后
+fn parse_info_output(output: &str) -> Result<Vec<Package>> {
+    let data: BrewJson<FormulaInfo, CaskInfo> =
+        serde_json::from_str(output).map_err(|e| BoxyError::JsonError {
+            message: format!("解析 brew info 输出失败: {}", e),
+        })?;
+    Ok(data
+        .formulae
+        .into_iter()
+        .map(formula_package)
+        .chain(data.casks.into_iter().map(cask_package))
+        .collect())
+}
+
+/// 解析 `brew outdated --json=v2`
+fn parse_outdated_output(output: &str) -> Result<Vec<Package>> {
+    let data: BrewJson<OutdatedFormula, OutdatedCask> =
+        serde_json::from_str(output).map_err(|e| BoxyError::JsonError {
+            message: format!("解析 brew outdated 输出失败: {}", e),
+        })?;
+
+    let formulae = data.formulae.into_iter().map(|formula| {
+        let version = formula
+            .installed_versions
+            .into_iter()
+            .last()
+            .unwrap_or_default();
+        let mut pkg = brew_package(formula.name, version, false);
+        pkg.outdated = true;
+        pkg.latest_version = Some(formula.current_version);
+        if formula.pinned {
+            pkg.flags.push(DependencyFlag::Pinned);
+        }
+        pkg
+    });
+    let casks = data.casks.into_iter().map(|cask| {
+        let version = cask.installed_versions.last().unwrap_or_default();
+        let mut pkg = brew_package(cask.name, version, true);
+        pkg.outdated = true;
+        pkg.latest_version = Some(cask.current_version);
+        pkg
+    });
+    Ok(formulae.chain(casks).collect())
 }
 
 #[async_trait]
@@ -315,6 +536,10 @@ impl PackageManager for BrewManager {
         "brew"
     }
 
+    fn cache_key(&self) -> &str {
+        self.scope.cache_key()
+    }
+
     async fn check_available(&self) -> Result<bool> {
         match Command::new("brew").arg("--version").output().await {
             Ok(output) => Ok(output.status.success()),
@@ -323,152 +548,171 @@ impl PackageManager for BrewManager {
     }
 
     async fn list_installed(&self) -> Result<Vec<Package>> {
-        if let Some(cached) = self.cache.get("brew").await? {
+        let cache_key = self.scope.cache_key();
+        if let Some(cached) = self.cache.get(cache_key).await? {
             debug!("使用缓存的 brew 包列表");
             return Ok(cached);
         }
 
-        let output = self.exec(&["list", "--versions"]).await?;
-        let mut packages = self.parse_list_output_with_versions(&output);
-
-        match self.exec(&["list", "--cask", "--versions"]).await {
-            Ok(cask_output) => {
-                let mut cask_packages = self.parse_list_output_with_versions(&cask_output);
-                packages.append(&mut cask_packages);
-            }
-            Err(err) => {
-                warn!("brew 获取 cask 列表失败: {}", err);
-            }
-        }
-
-        // 去重：按包名去重，保留第一个出现的
-        let mut seen = std::collections::HashSet::new();
-        packages.retain(|pkg| seen.insert(pkg.name.clone()));
+        let output = self.exec(&["info", "--json=v2", "--installed"]).await?;
+        let mut packages = parse_info_output(&output)?;
+        packages.retain(|pkg| self.scope.includes(pkg.cask));
 
-        match self.fetch_installed_sizes().await {
-            Ok(size_map) => {
-                for pkg in packages.iter_mut() {
-                    if let Some(size) = size_map.get(&pkg.name) {
-                        pkg.size = Some(*size);
-                    }
-                }
-            }
-            Err(err) => {
-                warn!("brew 获取包大小失败: {}", err);
-            }
-        }
+        self.collect_sizes(&mut packages).await;
 
-        self.cache.set("brew", &packages).await?;
+        self.cache.set(cache_key, &packages).await?;
         debug!("brew 已安装包: {} 个", packages.len());
 
         Ok(packages)
     }
 
     async fn search(&self, query: &str) -> Result<Vec<Package>> {
-        let output = self.exec(&["search", query]).await?;
+        let mut args = vec!["search"];
+        args.extend(self.scope_flag());
+        args.push(query);
+        let output = self.exec(&args).await?;
         Ok(self.parse_search_output(&output))
     }
 
+    /// 同名的 formula 与 cask 同时存在时返回 formula
     async fn get_info(&self, name: &str) -> Result<Package> {
-        let output = self.exec(&["info", "--json=v2", name]).await?;
-        if let Ok(pkg) = self.parse_json_info(&output, name) {
-            return Ok(pkg);
-        }
-        let output = self.exec(&["info", "--json=v2", "--cask", name]).await?;
-        self.parse_json_info(&output, name)
+        let mut args = vec!["info", "--json=v2"];
+        args.extend(self.scope_flag());
+        args.push(name);
+        let output = self.exec(&args).await?;
+        parse_info_output(&output)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| BoxyError::PackageNotFound {
+                manager: "brew".to_string(),
+                package: name.to_string(),
+            })
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
-        let mut args = vec!["install".to_string()];
+        let target = match version {
+            Some(v) => format!("{}@{}", name, v),
+            None => name.to_string(),
+        };
+        self.ensure_tap(name).await?;
+        let cask = self.is_cask(&target).await?;
+
+        let mut args = vec!["install", kind_flag(cask)];
         if force {
-            args.push("--force".to_string());
-        }
-        if let Some(v) = version {
-            args.push(format!("{}@{}", name, v));
-        } else {
-            args.push(name.to_string());
+            args.push("--force");
         }
+        args.push(&target);
+
+        info!("brew {}", args.join(" "));
 
-        info!("brew install {}", args.join(" "));
+        self.exec(&args).await?;
+        self.invalidate_cache().await?;
+
+        Ok(())
+    }
 
-        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-        match self.exec(&args_refs).await {
-            Ok(_) => {}
-            Err(formula_err) => {
-                // formula 安装失败，尝试 cask
-                let mut cask_args = vec!["install", "--cask"];
-                if force {
-                    cask_args.push("--force");
+    /// 先查询各包的类型，formula 与 cask 分别一次安装；失败时由调用方逐个安装回退
+    async fn install_batch(&self, packages: &[(&str, Option<&str>)], force: bool) -> Result<()> {
+        let targets: Vec<String> = packages
+            .iter()
+            .map(|(name, version)| match version {
+                Some(v) => format!("{}@{}", name, v),
+                None => name.to_string(),
+            })
+            .collect();
+
+        let (casks, formulae): (Vec<&str>, Vec<&str>) = match self.scope {
+            BrewScope::Formula => (Vec::new(), targets.iter().map(String::as_str).collect()),
+            BrewScope::Cask => (targets.iter().map(String::as_str).collect(), Vec::new()),
+            BrewScope::All => {
+                for (name, _) in packages {
+                    self.ensure_tap(name).await?;
                 }
-                cask_args.push(name);
-                self.exec(&cask_args).await.map_err(|cask_err| {
-                    BoxyError::CommandFailed {
-                        manager: "brew".to_string(),
-                        command: format!("install {} (formula: {}, cask: {})", name, formula_err, cask_err),
-                        exit_code: -1,
-                    }
-                })?;
+                let mut args = vec!["info", "--json=v2"];
+                args.extend(targets.iter().map(String::as_str));
+                let found = parse_info_output(&self.exec(&args).await?)?;
+                // formula 可能通过别名指定，只有按 token 匹配到的才是 cask
+                targets.iter().map(String::as_str).partition(|target| {
+                    found
+                        .iter()
+                        .any(|pkg| pkg.cask && matches_name(pkg, target))
+                        && !found
+                            .iter()
+                            .any(|pkg| !pkg.cask && matches_name(pkg, target))
+                })
+            }
+        };
+
+        for (cask, group) in [(false, formulae), (true, casks)] {
+            if group.is_empty() {
+                continue;
+            }
+            let mut args = vec!["install", kind_flag(cask)];
+            if force {
+                args.push("--force");
             }
+            args.extend(group);
+
+            info!("brew {}", args.join(" "));
+
+            self.exec(&args).await?;
         }
-        self.cache.invalidate("brew").await?;
+        self.invalidate_cache().await?;
 
         Ok(())
     }
 
     async fn upgrade(&self, name: &str) -> Result<()> {
-        info!("brew upgrade {}", name);
-        match self.exec(&["upgrade", name]).await {
-            Ok(_) => {}
-            Err(formula_err) => {
-                self.exec(&["upgrade", "--cask", name]).await.map_err(|cask_err| {
-                    BoxyError::CommandFailed {
-                        manager: "brew".to_string(),
-                        command: format!("upgrade {} (formula: {}, cask: {})", name, formula_err, cask_err),
-                        exit_code: -1,
-                    }
-                })?;
-            }
-        }
-        self.cache.invalidate("brew").await?;
+        let cask = self.is_cask(name).await?;
+        let args = ["upgrade", kind_flag(cask), name];
+
+        info!("brew {}", args.join(" "));
+
+        self.exec(&args).await?;
+        self.invalidate_cache().await?;
 
         Ok(())
     }
 
     async fn uninstall(&self, name: &str, force: bool) -> Result<()> {
-        let mut args = vec!["uninstall".to_string()];
+        let cask = self.is_cask(name).await?;
+        let mut args = vec!["uninstall", kind_flag(cask)];
         if force {
-            args.push("--force".to_string());
+            args.push("--force");
         }
-        args.push(name.to_string());
+        args.push(name);
 
-        info!("brew uninstall {} (force: {})", name, force);
+        info!("brew {}", args.join(" "));
 
-        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-        match self.exec(&args_refs).await {
-            Ok(_) => {}
-            Err(formula_err) => {
-                let mut cask_args = vec!["uninstall", "--cask"];
-                if force {
-                    cask_args.push("--force");
-                }
-                cask_args.push(name);
-                self.exec(&cask_args).await.map_err(|cask_err| {
-                    BoxyError::CommandFailed {
-                        manager: "brew".to_string(),
-                        command: format!("uninstall {} (formula: {}, cask: {})", name, formula_err, cask_err),
-                        exit_code: -1,
-                    }
-                })?;
-            }
+        self.exec(&args).await?;
+        self.invalidate_cache().await?;
+
+        Ok(())
+    }
+
+    /// `brew uninstall` 同时处理 formula 与 cask
+    async fn uninstall_batch(&self, names: &[&str], force: bool) -> Result<()> {
+        let mut args = vec!["uninstall"];
+        args.extend(self.scope_flag());
+        if force {
+            args.push("--force");
         }
-        self.cache.invalidate("brew").await?;
+        args.extend_from_slice(names);
+
+        info!("brew {}", args.join(" "));
+
+        self.exec(&args).await?;
+        self.invalidate_cache().await?;
 
         Ok(())
     }
 
+    /// 已固定版本的 formula 同样列出并带有 pinned 标记，`brew upgrade` 会跳过它们
     async fn check_outdated(&self) -> Result<Vec<Package>> {
-        let output = self.exec(&["outdated"]).await?;
-        Ok(self.parse_outdated_output(&output))
+        let mut args = vec!["outdated", "--json=v2"];
+        args.extend(self.scope_flag());
+        let output = self.exec(&args).await?;
+        parse_outdated_output(&output)
     }
 
     /// 清理 Homebrew 下载缓存
@@ -480,10 +724,31 @@ impl PackageManager for BrewManager {
         Ok(())
     }
 
+    /// 执行 `brew cleanup --prune=<天数>`，删除超过该天数的下载缓存
+    ///
+    /// 天数向上取整；dry_run 时加上 `--dry-run`，按 Homebrew 输出的估算值返回。
+    async fn prune_cache(&self, older_than: Duration, dry_run: bool) -> Result<Option<u64>> {
+        let days = older_than.as_secs().div_ceil(86_400);
+        let prune = format!("--prune={}", days);
+        let mut args = vec!["cleanup", prune.as_str()];
+        if dry_run {
+            args.push("--dry-run");
+        }
+        info!("brew {}", args.join(" "));
+        let output = self.exec(&args).await?;
+        Ok(Some(parse_cleanup_freed(&output)))
+    }
+
+    /// Homebrew 的下载缓存目录（`brew --cache`）
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        let output = self.exec(&["--cache"]).await?;
+        Ok(existing_dirs([PathBuf::from(output.trim())]))
+    }
+
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
-        &[ListInstalled, SearchRemote, VersionSelection]
+        &[ListInstalled, SearchRemote, VersionSelection, BatchInstall]
     }
 }
 
@@ -494,18 +759,136 @@ mod tests {
     #[test]
     fn test_brew_manager_creation() {
         let cache = Arc::new(Cache::new().unwrap());
-        let manager = BrewManager::new(cache);
+        let manager = BrewManager::new(cache.clone(), BrewScope::All);
         assert_eq!(manager.name(), "brew");
+        assert_eq!(manager.cache_key(), "brew");
+
+        let manager = BrewManager::new(cache, BrewScope::Cask);
+        assert_eq!(manager.cache_key(), "brew-cask");
+        assert_eq!(manager.scope_flag(), Some("--cask"));
     }
 
     #[test]
     fn test_capabilities() {
         let cache = Arc::new(Cache::new().unwrap());
-        let manager = BrewManager::new(cache);
+        let manager = BrewManager::new(cache, BrewScope::All);
         let caps = manager.capabilities();
 
         assert!(caps.contains(&Capability::ListInstalled));
         assert!(caps.contains(&Capability::SearchRemote));
         assert!(caps.contains(&Capability::VersionSelection));
     }
+
+    #[test]
+    fn test_parse_info_output() {
+        let packages = parse_info_output(include_str!(
+            "../tests/fixtures/brew-4.4-info-installed.json"
+        ))
+        .unwrap();
+        let find = |name: &str| packages.iter().find(|pkg| pkg.name == name).unwrap();
+
+        assert_eq!(packages.len(), 6);
+
+        let node = find("node@20");
+        assert_eq!(node.version, "20.17.0");
+        assert!(node.outdated);
+        assert_eq!(node.latest_version.as_deref(), Some("20.18.1"));
+        assert_eq!(
+            node.flags,
+            vec![DependencyFlag::Pinned, DependencyFlag::KegOnly]
+        );
+        assert_eq!(node.requested, Some(true));
+
+        // 多个版本并存时取链接的版本
+        let openssl = find("openssl@3");
+        assert_eq!(openssl.version, "3.4.0");
+        assert_eq!(openssl.requested, Some(false));
+        assert!(openssl.flags.is_empty());
+
+        let terraform = find("terraform");
+        assert_eq!(terraform.tap.as_deref(), Some("hashicorp/tap"));
+        assert_eq!(terraform.license, None);
+        assert!(!terraform.cask);
+
+        let firefox = find("firefox");
+        assert!(firefox.cask);
+        assert_eq!(firefox.version, "132.0.2");
+        assert_eq!(firefox.tap.as_deref(), Some("homebrew/cask"));
+        assert_eq!(firefox.flags, vec![DependencyFlag::AutoUpdates]);
+        assert!(!firefox.outdated);
+
+        let font = find("font-jetbrains-mono");
+        assert!(font.outdated);
+        assert!(font.flags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_outdated_output() {
+        let packages =
+            parse_outdated_output(include_str!("../tests/fixtures/brew-4.4-outdated.json"))
+                .unwrap();
+
+        assert_eq!(packages.len(), 3);
+        assert_eq!(packages[0].name, "node@20");
+        assert_eq!(packages[0].version, "20.17.0");
+        assert_eq!(packages[0].flags, vec![DependencyFlag::Pinned]);
+        assert_eq!(packages[1].latest_version.as_deref(), Some("1.25.0"));
+        assert!(packages[1].flags.is_empty());
+        assert!(packages[2].cask);
+        assert_eq!(packages[2].version, "2.242");
+        assert!(packages.iter().all(|pkg| pkg.outdated));
+
+        // 旧版 Homebrew 中 cask 的已安装版本为字符串
+        let legacy = r#"{"formulae":[],"casks":[{"name":"iterm2","installed_versions":"3.5.0","current_version":"3.5.10"}]}"#;
+        let packages = parse_outdated_output(legacy).unwrap();
+        assert_eq!(packages[0].version, "3.5.0");
+    }
+
+    #[test]
+    fn test_parse_tap_info() {
+        let taps =
+            parse_tap_info(include_str!("../tests/fixtures/brew-4.4-tap-info.json")).unwrap();
+
+        assert_eq!(taps.len(), 2);
+        assert_eq!(taps[0].name, "hashicorp/tap");
+        assert!(!taps[0].official);
+        assert_eq!(taps[0].formula_names.len(), 5);
+        assert_eq!(taps[0].cask_tokens.len(), 2);
+        assert_eq!(taps[1].name, "acme/internal");
+        assert!(taps[1].custom_remote);
+        assert_eq!(
+            taps[1].remote.as_deref(),
+            Some("git@git.acme.example:platform/homebrew-internal.git")
+        );
+        assert_eq!(taps[1].last_commit.as_deref(), Some("2 weeks ago"));
+    }
+
+    #[test]
+    fn test_tap_qualified_name() {
+        assert_eq!(tap_of("hashicorp/tap/terraform"), Some("hashicorp/tap"));
+        assert_eq!(tap_of("wget"), None);
+        assert_eq!(tap_of("openssl@3"), None);
+
+        let packages = parse_info_output(include_str!(
+            "../tests/fixtures/brew-4.4-info-installed.json"
+        ))
+        .unwrap();
+        let terraform = packages.iter().find(|pkg| pkg.name == "terraform").unwrap();
+        assert!(matches_name(terraform, "terraform"));
+        assert!(matches_name(terraform, "hashicorp/tap/terraform"));
+        assert!(!matches_name(terraform, "acme/internal/terraform"));
+        let wget = packages.iter().find(|pkg| pkg.name == "wget").unwrap();
+        assert_eq!(wget.qualified_name(), "wget");
+    }
+
+    #[test]
+    fn test_parse_cleanup_freed() {
+        assert_eq!(
+            parse_cleanup_freed(include_str!(
+                "../tests/fixtures/brew-4.4-cleanup-dry-run.txt"
+            )),
+            (27.5 * 1048576.0) as u64
+        );
+        assert_eq!(parse_cleanup_freed(""), 0);
+    }
 }
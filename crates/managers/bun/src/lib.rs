@@ -1,11 +1,14 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{existing_dirs, DiskUsage, SizeSource},
+    license::package_json_license,
     manager::PackageManager,
-    package::{Capability, Package},
+    package::{Capability, DependencyType, Package},
 };
 use boxy_error::{BoxyError, Result};
 use std::{
+    collections::HashMap,
     collections::hash_map::DefaultHasher,
     env,
     hash::{Hash, Hasher},
@@ -17,7 +20,6 @@ use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
 
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
-const MAX_SIZE_PACKAGES: usize = 1000;
 
 pub struct BunManager {
     cache: Arc<Cache>,
@@ -106,75 +108,14 @@ impl BunManager {
         }
     }
 
-    async fn collect_sizes(
-        &self,
-        root: &Path,
-        names: &[String],
-    ) -> Result<std::collections::HashMap<String, u64>> {
-        let mut items: Vec<(String, PathBuf)> = Vec::new();
-        for name in names {
-            let path = root.join(name);
-            if path.exists() {
-                items.push((name.clone(), path));
-            }
-        }
-
-        if items.is_empty() {
-            return Ok(std::collections::HashMap::new());
-        }
-
-        let mut sizes = std::collections::HashMap::new();
-        for chunk in items.chunks(100) {
-            let mut cmd = Command::new("du");
-            cmd.arg("-sk");
-            let mut path_map = std::collections::HashMap::new();
-            for (name, path) in chunk {
-                let path_str = path.to_string_lossy().to_string();
-                path_map.insert(path_str.clone(), name.clone());
-                cmd.arg(path_str);
-            }
-
-            let output = cmd.output().await.map_err(|_| BoxyError::CommandFailed {
-                manager: "bun".to_string(),
-                command: "du -sk".to_string(),
-                exit_code: -1,
-            })?;
-
-            if !output.status.success() {
-                return Err(BoxyError::CommandFailed {
-                    manager: "bun".to_string(),
-                    command: "du -sk".to_string(),
-                    exit_code: output.status.code().unwrap_or(-1),
-                });
-            }
-
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-                let mut parts = line.split_whitespace();
-                let size_kb = parts.next().and_then(|value| value.parse::<u64>().ok());
-                let size_value = match size_kb {
-                    Some(value) => value,
-                    None => continue,
-                };
-                let path_value = match parts.next() {
-                    Some(value) => {
-                        let prefix_len = line.find(value).unwrap_or(0);
-                        line[prefix_len..].trim()
-                    }
-                    None => continue,
-                };
-
-                if let Some(name) = path_map.get(path_value) {
-                    sizes.insert(name.clone(), size_value.saturating_mul(1024));
-                }
-            }
-        }
-
-        Ok(sizes)
+    async fn collect_sizes(&self, root: &Path, names: &[String]) -> HashMap<String, u64> {
+        let items = names
+            .iter()
+            .map(|name| (name.clone(), SizeSource::Path(root.join(name))))
+            .collect();
+        DiskUsage::new(self.cache.clone())
+            .measure(self.cache_key(), items)
+            .await
     }
 }
 
@@ -224,42 +165,28 @@ impl PackageManager for BunManager {
                     name,
                     version,
                     manager: "bun".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
                     installed_path: if self.global {
                         Some("~/.bun/install/global".to_string())
                     } else {
                         None
                     },
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
+                    ..Default::default()
                 })
             })
             .collect();
 
-        if packages.len() <= MAX_SIZE_PACKAGES {
-            if let Some(root) = self.resolve_root().await {
-                let names: Vec<String> =
-                    packages.iter().map(|pkg| pkg.name.clone()).collect();
-                match self.collect_sizes(&root, &names).await {
-                    Ok(size_map) => {
-                        for pkg in packages.iter_mut() {
-                            if let Some(size) = size_map.get(&pkg.name) {
-                                pkg.size = Some(*size);
-                                pkg.installed_path =
-                                    Some(root.join(&pkg.name).to_string_lossy().to_string());
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        warn!("bun 获取包大小失败: {}", err);
-                    }
+        if let Some(root) = self.resolve_root().await {
+            let names: Vec<String> = packages.iter().map(|pkg| pkg.name.clone()).collect();
+            let size_map = self.collect_sizes(&root, &names).await;
+            for pkg in packages.iter_mut() {
+                if let Some(size) = size_map.get(&pkg.name) {
+                    pkg.size = Some(*size);
+                    pkg.installed_path = Some(root.join(&pkg.name).to_string_lossy().to_string());
+                }
+                if pkg.license.is_none() {
+                    pkg.license = package_json_license(&root.join(&pkg.name));
                 }
             }
-        } else {
-            warn!("bun 包数量过多，跳过大小统计");
         }
 
         self.cache.set(self.cache_key(), &packages).await?;
@@ -292,13 +219,7 @@ impl PackageManager for BunManager {
                     name,
                     version,
                     manager: "bun".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
+                    ..Default::default()
                 })
             })
             .collect();
@@ -327,31 +248,21 @@ impl PackageManager for BunManager {
             version,
             manager: "bun".to_string(),
             description,
-            homepage: None,
-            license: None,
             installed_path: if self.global {
                 Some("~/.bun/install/global".to_string())
             } else {
                 None
             },
-            size: None,
-            outdated: false,
-            latest_version: None,
+            ..Default::default()
         };
 
         if let Some(root) = self.resolve_root().await {
             let names = vec![package.name.clone()];
-            match self.collect_sizes(&root, &names).await {
-                Ok(size_map) => {
-                    if let Some(size) = size_map.get(&package.name) {
-                        package.size = Some(*size);
-                        package.installed_path =
-                            Some(root.join(&package.name).to_string_lossy().to_string());
-                    }
-                }
-                Err(err) => {
-                    warn!("bun 获取包大小失败: {}", err);
-                }
+            let size_map = self.collect_sizes(&root, &names).await;
+            if let Some(size) = size_map.get(&package.name) {
+                package.size = Some(*size);
+                package.installed_path =
+                    Some(root.join(&package.name).to_string_lossy().to_string());
             }
         }
 
@@ -359,15 +270,18 @@ impl PackageManager for BunManager {
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
+        self.install_batch(&[(name, version)], force).await
+    }
+
+    async fn install_batch(&self, packages: &[(&str, Option<&str>)], force: bool) -> Result<()> {
         let mut args: Vec<String> = vec!["install".to_string()];
         if force {
             args.push("--force".to_string());
         }
-        let target = match version {
+        args.extend(packages.iter().map(|(name, version)| match version {
             Some(v) => format!("{}@{}", name, v),
             None => name.to_string(),
-        };
-        args.push(target);
+        }));
 
         info!("bun install {}", args.join(" "));
 
@@ -378,6 +292,31 @@ impl PackageManager for BunManager {
         Ok(())
     }
 
+    /// `bun add` 按依赖类型写入 package.json 对应的字段
+    async fn add_dependency(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        dependency_type: DependencyType,
+    ) -> Result<()> {
+        let target = match version {
+            Some(v) => format!("{}@{}", name, v),
+            None => name.to_string(),
+        };
+        let mut args = vec!["add", target.as_str()];
+        match dependency_type {
+            DependencyType::Prod => {}
+            DependencyType::Dev => args.push("--dev"),
+            DependencyType::Optional => args.push("--optional"),
+            DependencyType::Peer => args.push("--peer"),
+        }
+        info!("bun {}", args.join(" "));
+        self.exec(&args).await?;
+        self.cache.invalidate(self.cache_key()).await?;
+
+        Ok(())
+    }
+
     async fn upgrade(&self, name: &str) -> Result<()> {
         info!("bun update {}", name);
         self.exec(&["update", name]).await?;
@@ -387,12 +326,17 @@ impl PackageManager for BunManager {
     }
 
     async fn uninstall(&self, name: &str, force: bool) -> Result<()> {
-        let mut args: Vec<String> = vec!["remove".to_string(), name.to_string()];
+        self.uninstall_batch(&[name], force).await
+    }
+
+    async fn uninstall_batch(&self, names: &[&str], force: bool) -> Result<()> {
+        let mut args: Vec<String> = vec!["remove".to_string()];
+        args.extend(names.iter().map(|name| name.to_string()));
         if force {
             args.push("--force".to_string());
         }
 
-        warn!("bun remove {} (force: {})", name, force);
+        warn!("bun remove {} (force: {})", names.join(" "), force);
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
@@ -416,13 +360,10 @@ impl PackageManager for BunManager {
                             name: pkg.name,
                             version: pkg.version,
                             manager: "bun".to_string(),
-                            description: None,
-                            homepage: None,
-                            license: None,
                             installed_path: pkg.installed_path,
-                            size: None,
                             outdated: true,
                             latest_version: info.latest_version,
+                            ..Default::default()
                         });
                     }
                 }
@@ -463,10 +404,21 @@ impl PackageManager for BunManager {
         Ok(())
     }
 
+    /// bun 的安装缓存目录（`bun pm cache`）
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        let output = self.exec(&["pm", "cache"]).await?;
+        Ok(existing_dirs([PathBuf::from(output.trim())]))
+    }
+
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
-        &[ListInstalled, SearchRemote, VersionSelection]
+        &[
+            ListInstalled,
+            SearchRemote,
+            VersionSelection,
+            BatchInstall,
+        ]
     }
 }
 
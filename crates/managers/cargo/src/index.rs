@@ -0,0 +1,610 @@
+use boxy_cache::Cache;
+use boxy_core::version::compare_versions;
+use boxy_error::{BoxyError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::debug;
+
+/// crates.io 的 sparse 索引
+pub const DEFAULT_INDEX_URL: &str = "https://index.crates.io/";
+/// 显式指定索引地址的环境变量，优先于 cargo 配置中的源替换
+const INDEX_URL_ENV: &str = "BOXY_CARGO_INDEX_URL";
+/// crates.io 在 `.crates2.json` 中的两种源标识
+const CRATES_IO_SOURCES: [&str; 2] = [
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+/// 同时发出的索引请求上限
+const CONCURRENCY: usize = 8;
+/// 两次 Web API 请求的最小间隔，遵守 crates.io 每秒一次的爬虫策略
+const API_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+/// 缓存的索引条目有效期
+const METADATA_TTL: i64 = 60 * 60;
+
+/// 索引所在位置：HTTP sparse 索引或本地镜像目录
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Location {
+    Remote(String),
+    Local(PathBuf),
+}
+
+/// crate 的单个版本
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexVersion {
+    pub version: String,
+    pub yanked: bool,
+}
+
+/// 从索引读取的 crate 元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateMetadata {
+    /// 索引中记录的规范名称
+    pub name: String,
+    pub versions: Vec<IndexVersion>,
+}
+
+impl CrateMetadata {
+    /// 解析索引文件：每行一个版本的 JSON，只保留名称与 `name` 完全一致（不区分大小写）的条目
+    pub fn parse(name: &str, body: &str) -> Option<Self> {
+        let mut canonical = None;
+        let versions: Vec<IndexVersion> = body
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+            .filter_map(|entry| {
+                let entry_name = entry.get("name")?.as_str()?;
+                if !entry_name.eq_ignore_ascii_case(name) {
+                    return None;
+                }
+                canonical.get_or_insert_with(|| entry_name.to_string());
+                Some(IndexVersion {
+                    version: entry.get("vers")?.as_str()?.to_string(),
+                    yanked: entry
+                        .get("yanked")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                })
+            })
+            .collect();
+        Some(Self {
+            name: canonical?,
+            versions,
+        })
+    }
+
+    /// 最新的未撤回版本，存在正式版时忽略预发布版本
+    pub fn latest(&self) -> Option<&str> {
+        let available = || self.versions.iter().filter(|version| !version.yanked);
+        let has_stable = available().any(|version| !version.version.contains('-'));
+        available()
+            .filter(|version| !has_stable || !version.version.contains('-'))
+            .max_by(|a, b| compare_versions(&a.version, &b.version))
+            .map(|version| version.version.as_str())
+    }
+
+    pub fn is_yanked(&self, version: &str) -> bool {
+        let version = version.trim_start_matches('v');
+        self.versions
+            .iter()
+            .any(|entry| entry.yanked && entry.version == version)
+    }
+
+    /// 已安装版本落后于最新版本
+    pub fn is_outdated(&self, version: &str) -> bool {
+        self.latest().is_some_and(|latest| {
+            compare_versions(latest, version.trim_start_matches('v')) == Ordering::Greater
+        })
+    }
+}
+
+/// 缓存中的索引条目，单独记录读取时间，避免新增条目刷新整个缓存的有效期
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedMetadata {
+    fetched_at: i64,
+    metadata: CrateMetadata,
+}
+
+/// registry 的附加信息，来自 crates.io 兼容的 Web API
+#[derive(Debug, Default, Clone)]
+pub struct CrateDetails {
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub license: Option<String>,
+}
+
+/// cargo 的 sparse 注册表索引（crates.io、镜像或本地目录）
+///
+/// 按 crate 名直接读取索引文件，不会像 `cargo search` 那样返回名称相近的其它 crate。
+#[derive(Clone)]
+pub struct SparseIndex {
+    location: Location,
+    client: reqwest::Client,
+}
+
+impl SparseIndex {
+    /// `url` 可以是 `https://…`、`sparse+https://…`、`file://…` 或本地目录
+    pub fn new(url: &str) -> Self {
+        let url = url.trim();
+        let url = url.strip_prefix("sparse+").unwrap_or(url);
+        let location = if let Some(path) = url.strip_prefix("file://") {
+            Location::Local(PathBuf::from(path))
+        } else if url.starts_with("http://") || url.starts_with("https://") {
+            Location::Remote(format!("{}/", url.trim_end_matches('/')))
+        } else {
+            Location::Local(PathBuf::from(url))
+        };
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("boxy/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { location, client }
+    }
+
+    /// 按 `BOXY_CARGO_INDEX_URL`、cargo 配置中 crates-io 的源替换、官方索引的顺序确定索引地址
+    pub fn from_env(cargo_home: Option<&Path>) -> Self {
+        let url = env::var(INDEX_URL_ENV)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .or_else(|| cargo_home.and_then(replaced_source))
+            .unwrap_or_else(|| DEFAULT_INDEX_URL.to_string());
+        Self::new(&url)
+    }
+
+    pub fn index_url(&self) -> String {
+        match &self.location {
+            Location::Remote(url) => url.clone(),
+            Location::Local(path) => path.display().to_string(),
+        }
+    }
+
+    fn cache_key(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.index_url().hash(&mut hasher);
+        format!("cargo-index-{:x}", hasher.finish())
+    }
+
+    /// 读取单个 crate 的元数据，结果会缓存
+    pub async fn crate_metadata(&self, cache: &Cache, name: &str) -> Result<CrateMetadata> {
+        let mut found = self.crates_metadata(cache, &[name.to_string()]).await;
+        found
+            .remove(&name.to_lowercase())
+            .ok_or_else(|| not_found(name))
+    }
+
+    /// 并发读取多个 crate 的元数据，键为小写的 crate 名，读取失败的 crate 不会出现在结果中
+    ///
+    /// 所有 crate 的元数据按索引地址缓存在同一个缓存项中，每个条目超过一小时后重新读取。
+    pub async fn crates_metadata(
+        &self,
+        cache: &Cache,
+        names: &[String],
+    ) -> HashMap<String, CrateMetadata> {
+        let cache_key = self.cache_key();
+        let now = chrono::Utc::now().timestamp();
+        let mut cached: HashMap<String, CachedMetadata> = cache
+            .get(&cache_key)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        cached.retain(|_, entry| now - entry.fetched_at <= METADATA_TTL);
+
+        let mut missing: Vec<String> = names
+            .iter()
+            .map(|name| name.to_lowercase())
+            .filter(|name| !cached.contains_key(name))
+            .collect();
+        missing.sort();
+        missing.dedup();
+
+        if !missing.is_empty() {
+            let mut tasks = JoinSet::new();
+            let mut pending = missing.into_iter();
+            let mut spawn_next = |tasks: &mut JoinSet<_>| {
+                if let Some(name) = pending.next() {
+                    let index = self.clone();
+                    tasks.spawn(async move {
+                        let result = index.fetch(&name).await;
+                        (name, result)
+                    });
+                }
+            };
+            for _ in 0..CONCURRENCY {
+                spawn_next(&mut tasks);
+            }
+            while let Some(joined) = tasks.join_next().await {
+                match joined {
+                    Ok((name, Ok(metadata))) => {
+                        cached.insert(
+                            name,
+                            CachedMetadata {
+                                fetched_at: now,
+                                metadata,
+                            },
+                        );
+                    }
+                    Ok((name, Err(err))) => debug!("读取 crate {} 的索引失败: {}", name, err),
+                    Err(err) => debug!("索引任务异常: {}", err),
+                }
+                spawn_next(&mut tasks);
+            }
+            let _ = cache.set(&cache_key, &cached).await;
+        }
+
+        names
+            .iter()
+            .filter_map(|name| {
+                let key = name.to_lowercase();
+                cached.get(&key).map(|entry| (key, entry.metadata.clone()))
+            })
+            .collect()
+    }
+
+    async fn fetch(&self, name: &str) -> Result<CrateMetadata> {
+        let path = index_path(name).ok_or_else(|| not_found(name))?;
+        let body = self.read(&path).await?;
+        CrateMetadata::parse(name, &body).ok_or_else(|| not_found(name))
+    }
+
+    /// 通过索引 `config.json` 中声明的 Web API 读取简介、主页与许可证
+    ///
+    /// 本地镜像或未声明 API 的注册表返回空结果。
+    pub async fn crate_details(&self, name: &str) -> CrateDetails {
+        let Some(api) = self.api_url().await else {
+            return CrateDetails::default();
+        };
+        let url = format!("{}/api/v1/crates/{}", api, name);
+        match self.get(&url).await {
+            Ok(body) => parse_crate_details(&body),
+            Err(err) => {
+                debug!("读取 {} 失败: {}", url, err);
+                CrateDetails::default()
+            }
+        }
+    }
+
+    /// 通过 Web API 读取指定版本的许可证，键为 crate 名，查不到的 crate 不会出现在结果中
+    ///
+    /// 用于 registry 源码已被清理（如 `boxy cache clean -m cargo`）时补全许可证。
+    /// 按 crates.io 的爬虫策略逐个请求、每秒最多一次，结果（包括未声明许可证）按索引地址缓存。
+    pub async fn crate_licenses(
+        &self,
+        cache: &Cache,
+        crates: &[(String, String)],
+    ) -> HashMap<String, String> {
+        let cache_key = format!("{}-licenses", self.cache_key());
+        let mut cached: HashMap<String, Option<String>> = cache
+            .get(&cache_key)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+
+        let key = |name: &str, version: &str| {
+            format!(
+                "{}@{}",
+                name.to_lowercase(),
+                version.trim_start_matches('v')
+            )
+        };
+        let missing: Vec<&(String, String)> = crates
+            .iter()
+            .filter(|(name, version)| !cached.contains_key(&key(name, version)))
+            .collect();
+        if !missing.is_empty() {
+            if let Some(api) = self.api_url().await {
+                let mut fetched = false;
+                for (name, version) in missing {
+                    if fetched {
+                        tokio::time::sleep(API_REQUEST_INTERVAL).await;
+                    }
+                    fetched = true;
+                    let url = format!(
+                        "{}/api/v1/crates/{}/{}",
+                        api,
+                        name,
+                        version.trim_start_matches('v')
+                    );
+                    match self.get(&url).await {
+                        Ok(body) => {
+                            cached.insert(key(name, version), parse_version_license(&body));
+                        }
+                        Err(err) => debug!("读取 {} 失败: {}", url, err),
+                    }
+                }
+                let _ = cache.set(&cache_key, &cached).await;
+            }
+        }
+
+        crates
+            .iter()
+            .filter_map(|(name, version)| {
+                let license = cached.get(&key(name, version))?.clone()?;
+                Some((name.clone(), license))
+            })
+            .collect()
+    }
+
+    /// 索引 `config.json` 中声明的 Web API 地址
+    async fn api_url(&self) -> Option<String> {
+        let body = self.read("config.json").await.ok()?;
+        let config = serde_json::from_str::<Value>(&body).ok()?;
+        let api = config.get("api")?.as_str()?;
+        Some(api.trim_end_matches('/').to_string())
+    }
+
+    async fn read(&self, path: &str) -> Result<String> {
+        match &self.location {
+            Location::Remote(base) => self.get(&format!("{}{}", base, path)).await,
+            Location::Local(root) => {
+                let file = root.join(path);
+                tokio::fs::read_to_string(&file).await.map_err(|err| {
+                    if err.kind() == std::io::ErrorKind::NotFound {
+                        not_found(path.rsplit('/').next().unwrap_or(path))
+                    } else {
+                        err.into()
+                    }
+                })
+            }
+        }
+    }
+
+    async fn get(&self, url: &str) -> Result<String> {
+        let response =
+            self.client
+                .get(url)
+                .send()
+                .await
+                .map_err(|err| BoxyError::NetworkError {
+                    message: format!("请求 {} 失败: {}", url, err),
+                })?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(not_found(url.rsplit('/').next().unwrap_or(url)));
+        }
+        if !status.is_success() {
+            return Err(BoxyError::NetworkError {
+                message: format!("请求 {} 失败: HTTP {}", url, status),
+            });
+        }
+        response
+            .text()
+            .await
+            .map_err(|err| BoxyError::NetworkError {
+                message: format!("读取 {} 失败: {}", url, err),
+            })
+    }
+}
+
+fn not_found(name: &str) -> BoxyError {
+    BoxyError::PackageNotFound {
+        manager: "cargo".to_string(),
+        package: name.to_string(),
+    }
+}
+
+/// crate 在索引中的相对路径
+///
+/// 名称统一小写：1、2 个字符分别位于 `1/`、`2/`，3 个字符位于 `3/<首字母>/`，
+/// 更长的名称位于 `<前两位>/<三四位>/`。
+pub fn index_path(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    let valid = name
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    if !valid {
+        return None;
+    }
+    match name.len() {
+        0 => None,
+        1 => Some(format!("1/{}", name)),
+        2 => Some(format!("2/{}", name)),
+        3 => Some(format!("3/{}/{}", &name[..1], name)),
+        _ => Some(format!("{}/{}/{}", &name[..2], &name[2..4], name)),
+    }
+}
+
+/// 读取 `$CARGO_HOME/config.toml` 中 crates-io 的源替换
+///
+/// 支持 `registry = "sparse+…"` 与 `local-registry = "<目录>"`；替换为 git 索引或
+/// vendored 目录时无法按文件读取，返回 `None` 使用官方索引。
+fn replaced_source(cargo_home: &Path) -> Option<String> {
+    let content = ["config.toml", "config"]
+        .iter()
+        .find_map(|file| std::fs::read_to_string(cargo_home.join(file)).ok())?;
+    let config: toml::Value = content.parse().ok()?;
+    let sources = config.get("source")?;
+    let replacement = sources.get("crates-io")?.get("replace-with")?.as_str()?;
+    let source = sources.get(replacement)?;
+    if let Some(registry) = source.get("registry").and_then(toml::Value::as_str) {
+        return registry
+            .starts_with("sparse+")
+            .then(|| registry.to_string());
+    }
+    source
+        .get("local-registry")
+        .and_then(toml::Value::as_str)
+        .map(|path| Path::new(path).join("index").display().to_string())
+}
+
+fn parse_crate_details(body: &str) -> CrateDetails {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return CrateDetails::default();
+    };
+    let text = |value: Option<&Value>| {
+        value
+            .and_then(Value::as_str)
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    let krate = value.get("crate");
+    CrateDetails {
+        description: text(krate.and_then(|krate| krate.get("description"))),
+        homepage: text(krate.and_then(|krate| krate.get("homepage")))
+            .or_else(|| text(krate.and_then(|krate| krate.get("repository")))),
+        license: text(
+            value
+                .get("versions")
+                .and_then(Value::as_array)
+                .and_then(|versions| versions.first())
+                .and_then(|version| version.get("license")),
+        ),
+    }
+}
+
+/// 单个版本 API 响应中的许可证，旧版 crate 的 `MIT/Apache-2.0` 转换为 SPDX 表达式
+fn parse_version_license(body: &str) -> Option<String> {
+    let value = serde_json::from_str::<Value>(body).ok()?;
+    let license = value.get("version")?.get("license")?.as_str()?.trim();
+    (!license.is_empty()).then(|| license.replace('/', " OR "))
+}
+
+/// `cargo install` 记录的安装信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallRecord {
+    pub version: String,
+    /// 非 crates.io 的来源，如 `git+https://…#<commit>`、`path+file:///…` 或其它注册表
+    pub source: Option<String>,
+}
+
+/// 解析 `~/.cargo/.crates2.json`，键为 crate 名
+///
+/// `installs` 的键形如 `ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)`。
+pub fn parse_crates2(content: &str) -> HashMap<String, InstallRecord> {
+    let Ok(value) = serde_json::from_str::<Value>(content) else {
+        return HashMap::new();
+    };
+    let Some(installs) = value.get("installs").and_then(Value::as_object) else {
+        return HashMap::new();
+    };
+    installs
+        .keys()
+        .filter_map(|key| {
+            let (package, source) = key.split_once(" (")?;
+            let (name, version) = package.split_once(' ')?;
+            let source = source.trim_end_matches(')');
+            let source = (!CRATES_IO_SOURCES.contains(&source)).then(|| source.to_string());
+            Some((
+                name.to_string(),
+                InstallRecord {
+                    version: version.to_string(),
+                    source,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERDE_INDEX: &str = r#"{"name":"serde","vers":"1.0.200","deps":[],"cksum":"","features":{},"yanked":false}
+{"name":"serde","vers":"1.0.201","deps":[],"cksum":"","features":{},"yanked":true}
+{"name":"serde","vers":"2.0.0-alpha.1","deps":[],"cksum":"","features":{},"yanked":false}
+"#;
+
+    #[test]
+    fn test_index_path() {
+        assert_eq!(index_path("a").as_deref(), Some("1/a"));
+        assert_eq!(index_path("cc").as_deref(), Some("2/cc"));
+        assert_eq!(index_path("syn").as_deref(), Some("3/s/syn"));
+        assert_eq!(
+            index_path("Serde_JSON").as_deref(),
+            Some("se/rd/serde_json")
+        );
+        assert_eq!(index_path("../x"), None);
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let metadata = CrateMetadata::parse("Serde", SERDE_INDEX).unwrap();
+        assert_eq!(metadata.name, "serde");
+        assert_eq!(metadata.latest(), Some("1.0.200"));
+        assert!(metadata.is_yanked("1.0.201"));
+        assert!(!metadata.is_yanked("1.0.200"));
+        assert!(metadata.is_outdated("v1.0.100"));
+        assert!(!metadata.is_outdated("1.0.201"));
+        assert!(CrateMetadata::parse("serde_json", SERDE_INDEX).is_none());
+    }
+
+    #[test]
+    fn test_parse_crates2() {
+        let content = r#"{"installs":{
+            "ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)":{"bins":["rg"]},
+            "bat 0.24.0 (sparse+https://index.crates.io/)":{"bins":["bat"]},
+            "tool 0.1.0 (git+https://github.com/example/tool#0123abcd)":{"bins":["tool"]},
+            "local 0.2.0 (path+file:///home/user/local)":{"bins":["local"]}
+        }}"#;
+        let records = parse_crates2(content);
+        assert_eq!(records.len(), 4);
+        assert_eq!(records["ripgrep"].source, None);
+        assert_eq!(records["bat"].source, None);
+        assert_eq!(
+            records["tool"].source.as_deref(),
+            Some("git+https://github.com/example/tool#0123abcd")
+        );
+        assert_eq!(records["local"].version, "0.2.0");
+    }
+
+    #[test]
+    fn test_parse_version_license() {
+        let body = r#"{"version":{"crate":"ripgrep","num":"13.0.0","license":"Unlicense/MIT"}}"#;
+        assert_eq!(
+            parse_version_license(body).as_deref(),
+            Some("Unlicense OR MIT")
+        );
+        assert_eq!(
+            parse_version_license(r#"{"version":{"license":null}}"#),
+            None
+        );
+        assert_eq!(parse_version_license(r#"{"errors":[]}"#), None);
+    }
+
+    #[tokio::test]
+    async fn test_local_mirror() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("se").join("rd").join("serde");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, SERDE_INDEX).unwrap();
+        let cache = Cache::new_with_config(boxy_cache::CacheConfig {
+            cache_dir: Some(dir.path().join("cache")),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let index = SparseIndex::new(&format!("file://{}", dir.path().display()));
+        let metadata = index.crate_metadata(&cache, "serde").await.unwrap();
+        assert_eq!(metadata.latest(), Some("1.0.200"));
+        assert!(index.crate_metadata(&cache, "missing").await.is_err());
+
+        // 第二次读取来自缓存
+        std::fs::remove_file(&file).unwrap();
+        assert!(index.crate_metadata(&cache, "serde").await.is_ok());
+    }
+
+    #[test]
+    fn test_replaced_source() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            "[source.crates-io]\nreplace-with = \"mirror\"\n\n[source.mirror]\nregistry = \"sparse+https://mirror.example.com/index/\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            replaced_source(dir.path()).as_deref(),
+            Some("sparse+https://mirror.example.com/index/")
+        );
+        let index = SparseIndex::new("sparse+https://mirror.example.com/index");
+        assert_eq!(index.index_url(), "https://mirror.example.com/index/");
+    }
+}
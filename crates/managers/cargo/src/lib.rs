@@ -1,34 +1,62 @@
+mod index;
+mod metadata;
+
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{existing_dirs, prune_entries_older_than, DiskUsage, SizeSource},
+    license::cargo_toml_license,
     manager::PackageManager,
-    package::{Capability, Package},
+    manifest::max_satisfying,
+    package::{Capability, DependencyType, Package},
 };
 use boxy_error::{BoxyError, Result};
-use std::sync::Arc;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::{collections::HashMap, env, fs, path::PathBuf, sync::Arc};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
 
+pub use index::{CrateMetadata, SparseIndex};
+use index::{parse_crates2, InstallRecord};
+pub use metadata::{cargo_req_to_range, parse_metadata, ProjectDependency};
+
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
 
 pub struct CargoManager {
     cache: Arc<Cache>,
-    _global: bool,
+    /// 项目目录，设置后管理 Cargo.toml 中的依赖，否则管理 `cargo install` 安装的二进制
+    project: Option<PathBuf>,
+    cache_key: String,
 }
 
 impl CargoManager {
-    pub fn new(cache: Arc<Cache>, global: bool) -> Self {
+    pub fn new(cache: Arc<Cache>, project: Option<PathBuf>) -> Self {
+        let cache_key = match &project {
+            Some(dir) => {
+                let mut hasher = DefaultHasher::new();
+                dir.hash(&mut hasher);
+                format!("cargo-project-{}", hasher.finish())
+            }
+            None => "cargo".to_string(),
+        };
         Self {
             cache,
-            _global: global,
+            project,
+            cache_key,
         }
     }
 
     async fn exec(&self, args: &[&str]) -> Result<String> {
         debug!("执行 cargo 命令: {}", args.join(" "));
 
-        let output = timeout(COMMAND_TIMEOUT, Command::new("cargo").args(args).output())
+        let mut cmd = Command::new("cargo");
+        cmd.args(args);
+        if let Some(project) = &self.project {
+            cmd.current_dir(project);
+        }
+        let output = timeout(COMMAND_TIMEOUT, cmd.output())
             .await
             .map_err(|_| BoxyError::CommandTimeout)?
             .map_err(|_| BoxyError::CommandFailed {
@@ -47,6 +75,181 @@ impl CargoManager {
             })
         }
     }
+
+    fn cargo_home() -> Option<PathBuf> {
+        if let Ok(home) = env::var("CARGO_HOME") {
+            return Some(PathBuf::from(home));
+        }
+        let home = env::var("HOME").ok()?;
+        Some(PathBuf::from(home).join(".cargo"))
+    }
+
+    fn bin_dir() -> Option<PathBuf> {
+        Some(Self::cargo_home()?.join("bin"))
+    }
+
+    fn index() -> SparseIndex {
+        SparseIndex::from_env(Self::cargo_home().as_deref())
+    }
+
+    /// 读取 `cargo install` 在 `.crates2.json` 中记录的安装来源
+    fn install_records() -> HashMap<String, InstallRecord> {
+        Self::cargo_home()
+            .and_then(|home| fs::read_to_string(home.join(".crates2.json")).ok())
+            .map(|content| parse_crates2(&content))
+            .unwrap_or_default()
+    }
+
+    /// 解析 `cargo install --list` 输出
+    ///
+    /// 顶格行为 `name v1.0.0:`，其下缩进行是该 crate 安装的二进制。
+    fn parse_list_output(output: &str) -> Vec<(Package, Vec<String>)> {
+        let mut packages: Vec<(Package, Vec<String>)> = Vec::new();
+        for line in output.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if line.starts_with(char::is_whitespace) {
+                if let Some((_, binaries)) = packages.last_mut() {
+                    binaries.push(line.trim().to_string());
+                }
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let name = parts[0].to_string();
+            let version = parts
+                .get(1)
+                .map(|s| s.trim_matches(':').to_string())
+                .unwrap_or_default();
+
+            packages.push((
+                Package {
+                    name,
+                    version,
+                    manager: "cargo".to_string(),
+                    installed_path: Some("~/.cargo/bin".to_string()),
+                    ..Default::default()
+                },
+                Vec::new(),
+            ));
+        }
+        packages
+    }
+
+    /// 从 registry 解压的源码中读取 crate 的许可证
+    ///
+    /// `cargo install` 只会解压到 `registry/src/<索引>/<名称>-<版本>`，通过 git 或本地路径安装的 crate 查不到，
+    /// 源码被清理后由 `fill_licenses` 改从注册表 API 读取。
+    fn crate_license(name: &str, version: &str) -> Option<String> {
+        let src = Self::cargo_home()?.join("registry").join("src");
+        let dir_name = format!("{}-{}", name, version.trim_start_matches('v'));
+        fs::read_dir(src)
+            .ok()?
+            .flatten()
+            .map(|index| index.path().join(&dir_name).join("Cargo.toml"))
+            .find_map(|manifest| cargo_toml_license(&manifest))
+    }
+
+    /// 读取项目 Cargo.toml 中直接声明的依赖
+    async fn project_dependencies(&self) -> Result<Vec<ProjectDependency>> {
+        let project = self
+            .project
+            .as_ref()
+            .ok_or_else(|| BoxyError::UnsupportedOperation {
+                manager: "cargo".to_string(),
+                operation: "metadata".to_string(),
+            })?;
+        let output = self.exec(&["metadata", "--format-version", "1"]).await?;
+        let manifest = project.join("Cargo.toml");
+        let manifest = manifest.canonicalize().unwrap_or(manifest);
+        parse_metadata(&output, &manifest).ok_or_else(|| BoxyError::JsonError {
+            message: "解析 cargo metadata 输出失败".to_string(),
+        })
+    }
+
+    /// 依赖所在的依赖表参数，如 `--dev`、`--build`
+    async fn dependency_section(&self, name: &str) -> Result<Option<&'static str>> {
+        Ok(self
+            .project_dependencies()
+            .await?
+            .into_iter()
+            .find(|dep| dep.package.name == name)
+            .and_then(|dep| dep.section))
+    }
+
+    /// `cargo add` 写入依赖，`extra` 为依赖表参数
+    async fn cargo_add(&self, targets: &[String], extra: &[&str]) -> Result<()> {
+        let mut args = vec!["add"];
+        args.extend(targets.iter().map(String::as_str));
+        args.extend_from_slice(extra);
+        info!("cargo {}", args.join(" "));
+        self.exec(&args).await?;
+        self.cache.invalidate(&self.cache_key).await?;
+
+        Ok(())
+    }
+
+    /// 项目依赖的过时检查：latest 取自索引，wanted 为满足 Cargo.toml 版本要求的最高版本
+    ///
+    /// 路径与 git 依赖、未启用的可选依赖跳过检查。
+    async fn project_outdated(&self) -> Result<Vec<Package>> {
+        let installed: Vec<Package> = self
+            .list_installed()
+            .await?
+            .into_iter()
+            .filter(|pkg| pkg.source.is_none() && Some(&pkg.version) != pkg.range.as_ref())
+            .collect();
+        let names: Vec<String> = installed.iter().map(|pkg| pkg.name.clone()).collect();
+        let metadata = Self::index().crates_metadata(&self.cache, &names).await;
+
+        let outdated = installed
+            .into_iter()
+            .filter_map(|pkg| {
+                let crate_metadata = metadata.get(&pkg.name.to_lowercase())?;
+                let yanked = crate_metadata.is_yanked(&pkg.version);
+                if !yanked && !crate_metadata.is_outdated(&pkg.version) {
+                    return None;
+                }
+                let wanted = pkg.range.as_deref().and_then(|req| {
+                    let versions = crate_metadata
+                        .versions
+                        .iter()
+                        .filter(|version| !version.yanked)
+                        .map(|version| version.version.as_str());
+                    max_satisfying(versions, &cargo_req_to_range(req)).map(str::to_string)
+                });
+                Some(Package {
+                    outdated: true,
+                    latest_version: crate_metadata.latest().map(str::to_string),
+                    wanted_version: wanted,
+                    yanked,
+                    ..pkg
+                })
+            })
+            .collect();
+
+        Ok(outdated)
+    }
+
+    /// 按已安装二进制统计每个 crate 的大小
+    async fn collect_sizes(&self, entries: &[(Package, Vec<String>)]) -> HashMap<String, u64> {
+        let Some(bin_dir) = Self::bin_dir() else {
+            return HashMap::new();
+        };
+        let items = entries
+            .iter()
+            .filter(|(_, binaries)| !binaries.is_empty())
+            .map(|(pkg, binaries)| {
+                let files = binaries.iter().map(|bin| bin_dir.join(bin)).collect();
+                (pkg.name.clone(), SizeSource::Files(files))
+            })
+            .collect();
+        DiskUsage::new(self.cache.clone())
+            .measure("cargo", items)
+            .await
+    }
 }
 
 #[async_trait]
@@ -63,54 +266,63 @@ impl PackageManager for CargoManager {
     }
 
     async fn list_installed(&self) -> Result<Vec<Package>> {
-        if let Some(cached) = self.cache.get("cargo").await? {
+        if let Some(cached) = self.cache.get(&self.cache_key).await? {
             debug!("使用缓存的 cargo 包列表");
             return Ok(cached);
         }
 
-        let output = self.exec(&["install", "--list"]).await?;
-
-        let packages: Vec<Package> = output
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                // cargo install --list 输出格式: package_name version:
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.is_empty() {
-                    return None;
-                }
-
-                let name = parts[0].to_string();
-                let version = parts
-                    .get(1)
-                    .map(|s| s.trim_matches(':').to_string())
-                    .unwrap_or_default();
+        if self.project.is_some() {
+            let packages: Vec<Package> = self
+                .project_dependencies()
+                .await?
+                .into_iter()
+                .map(|dep| dep.package)
+                .collect();
+            self.cache.set(&self.cache_key, &packages).await?;
+            debug!("cargo 项目依赖: {} 个", packages.len());
+            return Ok(packages);
+        }
 
-                Some(Package {
-                    name,
-                    version,
-                    manager: "cargo".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: Some("~/.cargo/bin".to_string()),
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
-                })
+        let output = self.exec(&["install", "--list"]).await?;
+        let entries = Self::parse_list_output(&output);
+        let size_map = self.collect_sizes(&entries).await;
+        let records = Self::install_records();
+        let packages: Vec<Package> = entries
+            .into_iter()
+            .map(|(mut pkg, _)| {
+                pkg.size = size_map.get(&pkg.name).copied();
+                pkg.license = Self::crate_license(&pkg.name, &pkg.version);
+                pkg.source = records
+                    .get(&pkg.name)
+                    .and_then(|record| record.source.clone());
+                pkg
             })
             .collect();
 
-        self.cache.set("cargo", &packages).await?;
+        self.cache.set(&self.cache_key, &packages).await?;
         debug!("cargo 已安装包: {} 个", packages.len());
 
         Ok(packages)
     }
 
+    async fn fill_licenses(&self, packages: &mut [Package]) -> Result<()> {
+        // 通过 git 或本地路径安装的 crate 与注册表中的同名 crate 无关，不能用注册表的许可证
+        let lookup = |pkg: &Package| pkg.license.is_none() && pkg.source.is_none();
+        let missing: Vec<(String, String)> = packages
+            .iter()
+            .filter(|pkg| lookup(pkg))
+            .map(|pkg| (pkg.name.clone(), pkg.version.clone()))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let mut licenses = Self::index().crate_licenses(&self.cache, &missing).await;
+        for pkg in packages.iter_mut().filter(|pkg| lookup(pkg)) {
+            pkg.license = licenses.remove(&pkg.name);
+        }
+        Ok(())
+    }
+
     async fn search(&self, query: &str) -> Result<Vec<Package>> {
         let output = self.exec(&["search", query]).await?;
 
@@ -145,12 +357,7 @@ impl PackageManager for CargoManager {
                     version,
                     manager: "cargo".to_string(),
                     description,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
+                    ..Default::default()
                 })
             })
             .collect();
@@ -158,47 +365,53 @@ impl PackageManager for CargoManager {
         Ok(packages)
     }
 
+    /// 从 sparse 索引按名称精确读取 crate 信息
+    ///
+    /// 已安装时 `version` 为已安装版本，并标记是否过时或已被撤回；
+    /// 通过 git 或本地路径安装的 crate 不查询索引，直接返回安装记录。
     async fn get_info(&self, name: &str) -> Result<Package> {
-        let output = self.exec(&["search", name, "--limit", "1"]).await?;
-
-        let mut version = String::new();
-        let mut description = None;
-
-        for line in output.lines() {
-            let line = line.trim();
-            if line.starts_with(name) && line.contains('=') {
-                let parts: Vec<&str> = line.split('=').collect();
-                if parts.len() >= 2 {
-                    version = parts[1]
-                        .split('#')
-                        .next()
-                        .unwrap_or("")
-                        .trim()
-                        .trim_matches(&['"', ' '] as &[char])
-                        .to_string();
-                    if parts.len() > 1 {
-                        description = parts[1].split('#').nth(1).map(|s| s.trim().to_string());
-                    }
-                }
-                break;
-            }
+        let installed = self.list_installed().await.ok().and_then(|packages| {
+            packages
+                .into_iter()
+                .find(|pkg| pkg.name.eq_ignore_ascii_case(name))
+        });
+        if let Some(pkg) = installed.as_ref().filter(|pkg| pkg.source.is_some()) {
+            return Ok(pkg.clone());
         }
 
-        Ok(Package {
-            name: name.to_string(),
-            version,
-            manager: "cargo".to_string(),
-            description,
-            homepage: None,
-            license: None,
-            installed_path: Some("~/.cargo/bin".to_string()),
-            size: None,
-            outdated: false,
-            latest_version: None,
-        })
+        let index = Self::index();
+        let metadata = index.crate_metadata(&self.cache, name).await?;
+        let details = index.crate_details(&metadata.name).await;
+        let latest = metadata.latest().map(str::to_string);
+
+        let package = match installed {
+            Some(pkg) => Package {
+                outdated: metadata.is_outdated(&pkg.version),
+                yanked: metadata.is_yanked(&pkg.version),
+                latest_version: latest,
+                description: details.description,
+                homepage: details.homepage,
+                license: pkg.license.or(details.license),
+                ..pkg
+            },
+            None => Package {
+                name: metadata.name.clone(),
+                version: latest.clone().unwrap_or_default(),
+                manager: "cargo".to_string(),
+                description: details.description,
+                homepage: details.homepage,
+                license: details.license,
+                latest_version: latest,
+                ..Default::default()
+            },
+        };
+        Ok(package)
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
+        if self.project.is_some() {
+            return self.install_batch(&[(name, version)], force).await;
+        }
         let mut args: Vec<String> = vec!["install".to_string()];
         if force {
             args.push("--force".to_string());
@@ -213,76 +426,256 @@ impl PackageManager for CargoManager {
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
-        self.cache.invalidate("cargo").await?;
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
 
     async fn upgrade(&self, name: &str) -> Result<()> {
+        if self.project.is_some() {
+            // 在 Cargo.toml 的版本要求内更新 Cargo.lock
+            info!("cargo update -p {}", name);
+            self.exec(&["update", "-p", name]).await?;
+            self.cache.invalidate(&self.cache_key).await?;
+            return Ok(());
+        }
         info!("cargo install --force {}", name);
         self.exec(&["install", "--force", name]).await?;
-        self.cache.invalidate("cargo").await?;
+        self.cache.invalidate(&self.cache_key).await?;
+
+        Ok(())
+    }
+
+    /// 项目依赖升级到最新版本，同时修改 Cargo.toml 中的版本要求
+    async fn upgrade_latest(&self, name: &str) -> Result<()> {
+        if self.project.is_none() {
+            return self.upgrade(name).await;
+        }
+        let section = self.dependency_section(name).await?;
+        let latest = Self::index()
+            .crate_metadata(&self.cache, name)
+            .await?
+            .latest()
+            .map(str::to_string);
+        let target = match latest {
+            Some(latest) => format!("{}@{}", name, latest),
+            None => name.to_string(),
+        };
+        self.cargo_add(&[target], section.as_slice()).await
+    }
+
+    /// `cargo install a b@1.2` 一次安装多个 crate，多个 crate 时不能使用 --version，版本写成 `name@version`
+    ///
+    /// 项目中改为 `cargo add a b@1.2` 写入 Cargo.toml。
+    async fn install_batch(&self, packages: &[(&str, Option<&str>)], force: bool) -> Result<()> {
+        if self.project.is_some() {
+            let targets: Vec<String> = packages
+                .iter()
+                .map(|(name, version)| match version {
+                    Some(v) => format!("{}@{}", name, v),
+                    None => name.to_string(),
+                })
+                .collect();
+            return self.cargo_add(&targets, &[]).await;
+        }
+        let mut args: Vec<String> = vec!["install".to_string()];
+        if force {
+            args.push("--force".to_string());
+        }
+        args.extend(packages.iter().map(|(name, version)| match version {
+            Some(v) => format!("{}@{}", name, v),
+            None => name.to_string(),
+        }));
+
+        info!("cargo install {}", args.join(" "));
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        self.exec(&args_refs).await?;
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
 
     async fn uninstall(&self, name: &str, force: bool) -> Result<()> {
-        warn!("cargo uninstall {} (force: {})", name, force);
-        self.exec(&["uninstall", name]).await?;
-        self.cache.invalidate("cargo").await?;
+        self.uninstall_batch(&[name], force).await
+    }
+
+    async fn uninstall_batch(&self, names: &[&str], force: bool) -> Result<()> {
+        if self.project.is_some() {
+            for name in names {
+                self.remove_dependency(name).await?;
+            }
+            return Ok(());
+        }
+        warn!("cargo uninstall {} (force: {})", names.join(" "), force);
+        let mut args = vec!["uninstall"];
+        args.extend_from_slice(names);
+        self.exec(&args).await?;
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
 
-    async fn check_outdated(&self) -> Result<Vec<Package>> {
-        // cargo 没有直接的 outdated 命令，需要手动检查
-        let installed = self.list_installed().await?;
-        let mut outdated = Vec::new();
-
-        for pkg in installed {
-            match self.get_info(&pkg.name).await {
-                Ok(info) => {
-                    if info.latest_version.is_some()
-                        && info.latest_version.as_ref() != Some(&pkg.version)
-                    {
-                        outdated.push(Package {
-                            name: pkg.name,
-                            version: pkg.version,
-                            manager: "cargo".to_string(),
-                            description: None,
-                            homepage: None,
-                            license: None,
-                            installed_path: pkg.installed_path,
-                            size: None,
-                            outdated: true,
-                            latest_version: info.latest_version,
-                        });
-                    }
-                }
-                Err(_) => continue,
+    /// `cargo add` 按依赖类型写入 `[dependencies]`、`[dev-dependencies]`，可选依赖加上 `--optional`
+    async fn add_dependency(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        dependency_type: DependencyType,
+    ) -> Result<()> {
+        let extra: &[&str] = match dependency_type {
+            _ if self.project.is_none() => {
+                return Err(BoxyError::UnsupportedOperation {
+                    manager: "cargo".to_string(),
+                    operation: "add（需要在 Cargo 项目中使用）".to_string(),
+                });
             }
+            DependencyType::Prod => &[],
+            DependencyType::Dev => &["--dev"],
+            DependencyType::Optional => &["--optional"],
+            DependencyType::Peer => {
+                return Err(BoxyError::UnsupportedOperation {
+                    manager: "cargo".to_string(),
+                    operation: "add --peer".to_string(),
+                });
+            }
+        };
+        let target = match version {
+            Some(v) => format!("{}@{}", name, v),
+            None => name.to_string(),
+        };
+        self.cargo_add(&[target], extra).await
+    }
+
+    /// `cargo remove` 需要指定依赖所在的依赖表
+    async fn remove_dependency(&self, name: &str) -> Result<()> {
+        if self.project.is_none() {
+            return self.uninstall(name, false).await;
+        }
+        let mut args = vec!["remove", name];
+        args.extend(self.dependency_section(name).await?);
+        info!("cargo {}", args.join(" "));
+        self.exec(&args).await?;
+        self.cache.invalidate(&self.cache_key).await?;
+
+        Ok(())
+    }
+
+    /// 并发读取已安装 crate 的索引元数据，列出有新版本或已安装版本被撤回的 crate
+    ///
+    /// 通过 git 或本地路径安装的 crate 不在注册表中，跳过检查。
+    async fn check_outdated(&self) -> Result<Vec<Package>> {
+        if self.project.is_some() {
+            return self.project_outdated().await;
         }
+        let installed: Vec<Package> = self
+            .list_installed()
+            .await?
+            .into_iter()
+            .filter(|pkg| pkg.source.is_none())
+            .collect();
+        let names: Vec<String> = installed.iter().map(|pkg| pkg.name.clone()).collect();
+        let metadata = Self::index().crates_metadata(&self.cache, &names).await;
+
+        let outdated = installed
+            .into_iter()
+            .filter_map(|pkg| {
+                let crate_metadata = metadata.get(&pkg.name.to_lowercase())?;
+                let yanked = crate_metadata.is_yanked(&pkg.version);
+                if !yanked && !crate_metadata.is_outdated(&pkg.version) {
+                    return None;
+                }
+                Some(Package {
+                    outdated: true,
+                    latest_version: crate_metadata.latest().map(str::to_string),
+                    yanked,
+                    ..pkg
+                })
+            })
+            .collect();
 
         Ok(outdated)
     }
 
+    /// registry 与 git 缓存由所有项目共享，卸载单个 crate 后清空会导致其它项目无法离线构建
+    fn clean_cache_on_uninstall(&self) -> bool {
+        false
+    }
+
     /// 清理 cargo 缓存
     ///
-    /// 由于 cargo 的全局缓存清理是实验性功能，
-    /// 此方法暂不实现，保持默认行为（返回不支持错误）
-    /// 用户可以手动使用 `cargo clean` 清理项目构建产物
-    // async fn clean_cache(&self) -> Result<()> {
-    //     // 未来可以实现: cargo clean gc (需要 nightly 或配置启用)
-    //     Err(BoxyError::UnsupportedOperation {
-    //         manager: self.name().to_string(),
-    //         operation: "clean_cache".to_string(),
-    //     })
-    // }
+    /// 删除 registry 下载的 .crate 包、解压后的源码以及 git 依赖的检出，
+    /// registry 索引会保留以避免下次构建重新拉取全部索引。
+    async fn clean_cache(&self) -> Result<()> {
+        for dir in self.cache_dirs().await? {
+            info!("删除 cargo 缓存目录: {:?}", dir);
+            tokio::fs::remove_dir_all(&dir)
+                .await
+                .map_err(|e| BoxyError::CommandFailed {
+                    manager: "cargo".to_string(),
+                    command: format!("删除缓存目录失败: {}", e),
+                    exit_code: -1,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// 按条目清理 cargo 缓存：单个 .crate 包、解压后的源码目录、git 仓库与检出目录
+    ///
+    /// 条目按下载或解压的时间判断并整体删除，缺失的条目会在下次构建时重新下载。
+    async fn prune_cache(&self, older_than: Duration, dry_run: bool) -> Result<Option<u64>> {
+        let Some(home) = Self::cargo_home() else {
+            return Ok(Some(0));
+        };
+        // registry/cache 与 registry/src 下按源（如 index.crates.io-xxxx）再分一层
+        let mut parents = Vec::new();
+        for dir in ["cache", "src"] {
+            if let Ok(entries) = fs::read_dir(home.join("registry").join(dir)) {
+                parents.extend(entries.flatten().map(|entry| entry.path()));
+            }
+        }
+        parents.push(home.join("git").join("db"));
+        parents.push(home.join("git").join("checkouts"));
+
+        let cutoff = std::time::SystemTime::now()
+            .checked_sub(older_than)
+            .unwrap_or(std::time::UNIX_EPOCH);
+        let report = tokio::task::spawn_blocking(move || {
+            prune_entries_older_than(&parents, cutoff, dry_run)
+        })
+        .await
+        .map_err(|err| BoxyError::CacheError {
+            message: err.to_string(),
+        })?;
+        Ok(Some(report.bytes))
+    }
+
+    /// cargo 的 registry 与 git 缓存（位于 CARGO_HOME 下，不含 registry 索引）
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        let Some(home) = Self::cargo_home() else {
+            return Ok(Vec::new());
+        };
+        Ok(existing_dirs([
+            home.join("registry").join("cache"),
+            home.join("registry").join("src"),
+            home.join("git").join("db"),
+            home.join("git").join("checkouts"),
+        ]))
+    }
+
+    fn cache_key(&self) -> &str {
+        &self.cache_key
+    }
 
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
-        &[ListInstalled, SearchRemote, VersionSelection]
+        &[
+            ListInstalled,
+            SearchRemote,
+            VersionSelection,
+            BatchInstall,
+        ]
     }
 }
 
@@ -293,14 +686,23 @@ mod tests {
     #[test]
     fn test_cargo_manager_creation() {
         let cache = Arc::new(Cache::new().unwrap());
-        let manager = CargoManager::new(cache.clone(), true);
+        let manager = CargoManager::new(cache.clone(), None);
         assert_eq!(manager.name(), "cargo");
     }
 
+    #[test]
+    fn test_parse_list_output() {
+        let output = "bat v0.24.0:\n    bat\nripgrep v14.1.0:\n    rg\n";
+        let entries = CargoManager::parse_list_output(output);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].0.name, "ripgrep");
+        assert_eq!(entries[1].1, vec!["rg".to_string()]);
+    }
+
     #[test]
     fn test_capabilities() {
         let cache = Arc::new(Cache::new().unwrap());
-        let manager = CargoManager::new(cache, true);
+        let manager = CargoManager::new(cache, None);
         let caps = manager.capabilities();
 
         assert!(caps.contains(&Capability::ListInstalled));
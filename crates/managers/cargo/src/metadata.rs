@@ -0,0 +1,209 @@
+use boxy_core::package::{DependencyType, Package};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// `cargo metadata --format-version 1` 中需要的部分
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    packages: Vec<MetadataPackage>,
+    resolve: Option<Resolve>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataPackage {
+    id: String,
+    name: String,
+    version: String,
+    description: Option<String>,
+    license: Option<String>,
+    manifest_path: String,
+    #[serde(default)]
+    dependencies: Vec<ManifestDependency>,
+}
+
+/// Cargo.toml 中声明的一个依赖
+#[derive(Debug, Deserialize)]
+struct ManifestDependency {
+    /// crate 名称（重命名前）
+    name: String,
+    source: Option<String>,
+    req: String,
+    /// null 为普通依赖，其余为 `dev`、`build`
+    kind: Option<String>,
+    #[serde(default)]
+    optional: bool,
+    /// 本地路径依赖的目录
+    path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Resolve {
+    nodes: Vec<ResolveNode>,
+    root: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolveNode {
+    id: String,
+    #[serde(default)]
+    deps: Vec<NodeDep>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodeDep {
+    pkg: String,
+}
+
+/// 项目直接声明的依赖
+#[derive(Debug, Clone)]
+pub struct ProjectDependency {
+    pub package: Package,
+    /// `cargo add`、`cargo remove` 选择依赖表的参数，如 `--dev`、`--build`
+    pub section: Option<&'static str>,
+}
+
+/// 解析 `cargo metadata` 输出中 `manifest` 对应包的直接依赖
+///
+/// 版本取自依赖解析结果；未启用的可选依赖不在解析结果中，版本为声明的范围。
+pub fn parse_metadata(output: &str, manifest: &Path) -> Option<Vec<ProjectDependency>> {
+    let metadata: Metadata = serde_json::from_str(output).ok()?;
+    let resolve = metadata.resolve?;
+    let root = metadata
+        .packages
+        .iter()
+        .find(|pkg| Path::new(&pkg.manifest_path) == manifest)
+        .or_else(|| {
+            let root = resolve.root.as_ref()?;
+            metadata.packages.iter().find(|pkg| &pkg.id == root)
+        })?;
+    let by_id: HashMap<&str, &MetadataPackage> = metadata
+        .packages
+        .iter()
+        .map(|pkg| (pkg.id.as_str(), pkg))
+        .collect();
+    // 已解析的直接依赖：crate 名称 -> 包
+    let resolved: HashMap<&str, &MetadataPackage> = resolve
+        .nodes
+        .iter()
+        .find(|node| node.id == root.id)
+        .map(|node| {
+            node.deps
+                .iter()
+                .filter_map(|dep| by_id.get(dep.pkg.as_str()).copied())
+                .map(|pkg| (pkg.name.as_str(), pkg))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut dependencies: Vec<ProjectDependency> = Vec::new();
+    for dep in &root.dependencies {
+        // 同一个 crate 可能同时出现在多个依赖表或多个目标平台中
+        if dependencies
+            .iter()
+            .any(|item| item.package.name == dep.name)
+        {
+            continue;
+        }
+        let package = resolved.get(dep.name.as_str());
+        let (section, dependency_type) = match dep.kind.as_deref() {
+            Some("dev") => (Some("--dev"), DependencyType::Dev),
+            Some("build") => (Some("--build"), DependencyType::Prod),
+            _ if dep.optional => (None, DependencyType::Optional),
+            _ => (None, DependencyType::Prod),
+        };
+        let source = match (&dep.path, &dep.source) {
+            (Some(path), _) => Some(path.clone()),
+            (None, Some(source)) if !source.starts_with("registry+") => Some(source.clone()),
+            _ => None,
+        };
+        dependencies.push(ProjectDependency {
+            package: Package {
+                name: dep.name.clone(),
+                version: package
+                    .map(|pkg| pkg.version.clone())
+                    .unwrap_or_else(|| dep.req.clone()),
+                manager: "cargo".to_string(),
+                description: package.and_then(|pkg| pkg.description.clone()),
+                license: package.and_then(|pkg| pkg.license.clone()),
+                installed_path: package.and_then(|pkg| {
+                    Path::new(&pkg.manifest_path)
+                        .parent()
+                        .map(|dir| dir.to_string_lossy().to_string())
+                }),
+                source,
+                requested: Some(true),
+                dependency_type: Some(dependency_type),
+                range: Some(dep.req.clone()),
+                ..Default::default()
+            },
+            section,
+        });
+    }
+    dependencies.sort_by(|a, b| a.package.name.cmp(&b.package.name));
+    Some(dependencies)
+}
+
+/// 把 Cargo 的版本要求转为 npm 范围写法
+///
+/// Cargo 中不带运算符的版本等同于 `^`，多个条件以逗号分隔。
+pub fn cargo_req_to_range(req: &str) -> String {
+    req.split(',')
+        .map(str::trim)
+        .map(|part| {
+            if part.starts_with(|ch: char| ch.is_ascii_digit()) {
+                format!("^{}", part)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata() {
+        let dependencies = parse_metadata(
+            include_str!("../tests/fixtures/cargo-1.95-metadata.json"),
+            Path::new("/home/dev/demo/app/Cargo.toml"),
+        )
+        .unwrap();
+        let find = |name: &str| {
+            dependencies
+                .iter()
+                .find(|dep| dep.package.name == name)
+                .unwrap()
+        };
+
+        assert_eq!(dependencies.len(), 6);
+        let anyhow = &find("anyhow").package;
+        assert_eq!(anyhow.version, "1.0.104");
+        assert_eq!(anyhow.range.as_deref(), Some("^1.0.80"));
+        assert_eq!(anyhow.source, None);
+
+        assert_eq!(find("tempfile").section, Some("--dev"));
+        assert_eq!(find("cc").section, Some("--build"));
+        assert_eq!(find("itoa").package.version, "1.0.18");
+        assert_eq!(
+            find("log").package.dependency_type,
+            Some(DependencyType::Optional)
+        );
+        assert_eq!(find("log").package.version, "^0.4");
+        assert_eq!(
+            find("shared").package.source.as_deref(),
+            Some("/home/dev/demo/shared")
+        );
+    }
+
+    #[test]
+    fn test_cargo_req_to_range() {
+        assert_eq!(cargo_req_to_range("^1.0.80"), "^1.0.80");
+        assert_eq!(cargo_req_to_range("1.2"), "^1.2");
+        assert_eq!(cargo_req_to_range(">=1.2, <1.5"), ">=1.2 <1.5");
+        assert_eq!(cargo_req_to_range("=0.4.3"), "=0.4.3");
+    }
+}
@@ -1,16 +1,18 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{DiskUsage, SizeSource},
     manager::PackageManager,
     package::{Capability, Package},
 };
 use boxy_error::{BoxyError, Result};
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
 
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+const APPLICATIONS_DIR: &str = "/Applications";
 
 pub struct MasManager {
     cache: Arc<Cache>,
@@ -43,6 +45,31 @@ impl MasManager {
             })
         }
     }
+
+    /// 统计 /Applications 下对应 .app 包的大小
+    async fn collect_sizes(&self, packages: &mut [Package]) {
+        let root = Path::new(APPLICATIONS_DIR);
+        let items = packages
+            .iter()
+            .map(|pkg| {
+                let app = root.join(format!("{}.app", pkg.name));
+                (pkg.name.clone(), SizeSource::Path(app))
+            })
+            .collect();
+        let size_map = DiskUsage::new(self.cache.clone())
+            .measure("mas", items)
+            .await;
+        for pkg in packages.iter_mut() {
+            if let Some(size) = size_map.get(&pkg.name) {
+                pkg.size = Some(*size);
+                pkg.installed_path = Some(
+                    root.join(format!("{}.app", pkg.name))
+                        .to_string_lossy()
+                        .to_string(),
+                );
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -66,7 +93,7 @@ impl PackageManager for MasManager {
 
         let output = self.exec(&["list"]).await?;
 
-        let packages: Vec<Package> = output
+        let mut packages: Vec<Package> = output
             .lines()
             .filter_map(|line| {
                 let line = line.trim();
@@ -97,17 +124,14 @@ impl PackageManager for MasManager {
                     name: if name.is_empty() { id.clone() } else { name },
                     version,
                     manager: "mas".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: Some("/Applications".to_string()),
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
+                    installed_path: Some(APPLICATIONS_DIR.to_string()),
+                    ..Default::default()
                 })
             })
             .collect();
 
+        self.collect_sizes(&mut packages).await;
+
         self.cache.set("mas", &packages).await?;
         debug!("mas 已安装包: {} 个", packages.len());
 
@@ -139,13 +163,7 @@ impl PackageManager for MasManager {
                     name: if name.is_empty() { id.clone() } else { name },
                     version: String::new(),
                     manager: "mas".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
+                    ..Default::default()
                 })
             })
             .collect();
@@ -174,12 +192,8 @@ impl PackageManager for MasManager {
             version,
             manager: "mas".to_string(),
             description,
-            homepage: None,
-            license: None,
-            installed_path: Some("/Applications".to_string()),
-            size: None,
-            outdated: false,
-            latest_version: None,
+            installed_path: Some(APPLICATIONS_DIR.to_string()),
+            ..Default::default()
         })
     }
 
@@ -257,17 +271,14 @@ impl PackageManager for MasManager {
                     },
                     version: current_version,
                     manager: "mas".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: Some("/Applications".to_string()),
-                    size: None,
+                    installed_path: Some(APPLICATIONS_DIR.to_string()),
                     outdated: true,
                     latest_version: if latest_version.is_empty() {
                         None
                     } else {
                         Some(latest_version)
                     },
+                    ..Default::default()
                 })
             })
             .collect();
@@ -1,8 +1,11 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{existing_dirs, DiskUsage, SizeSource},
+    license::package_json_license,
     manager::PackageManager,
-    package::{Capability, Package},
+    manifest::{dependency_ranges, dependency_types, read_package_json},
+    package::{Capability, DependencyFlag, DependencyType, Package},
 };
 use boxy_error::{BoxyError, Result};
 use serde::Deserialize;
@@ -19,7 +22,6 @@ use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
 
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
-const MAX_SIZE_PACKAGES: usize = 1000;
 
 #[derive(Debug, Deserialize)]
 struct NpmListOutput {
@@ -30,7 +32,40 @@ type NpmDependencies = HashMap<String, NpmDependency>;
 
 #[derive(Debug, Deserialize)]
 struct NpmDependency {
-    version: String,
+    /// 缺失的依赖没有版本
+    version: Option<String>,
+    resolved: Option<String>,
+    #[serde(default)]
+    overridden: bool,
+    #[serde(default)]
+    extraneous: bool,
+    /// npm 7+ 为说明文字，npm 6 为布尔值
+    #[serde(default)]
+    invalid: Option<Value>,
+    #[serde(default)]
+    missing: bool,
+    /// 缺失依赖声明的版本范围
+    #[serde(default)]
+    required: Option<Value>,
+}
+
+impl NpmDependency {
+    fn flags(&self) -> Vec<DependencyFlag> {
+        let invalid = match &self.invalid {
+            Some(Value::Bool(value)) => *value,
+            Some(Value::Null) | None => false,
+            Some(_) => true,
+        };
+        [
+            (self.overridden, DependencyFlag::Overridden),
+            (self.extraneous, DependencyFlag::Extraneous),
+            (invalid, DependencyFlag::Invalid),
+            (self.missing, DependencyFlag::Missing),
+        ]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -80,6 +115,44 @@ impl NpmManager {
         &self.cache_key
     }
 
+    /// 解析 `npm ls --json`，`manifest` 为本地项目的 package.json，用于区分依赖类型
+    fn parse_list_output(output: &str, manifest: Option<&Value>) -> Result<Vec<Package>> {
+        let data: NpmListOutput =
+            serde_json::from_str(output).map_err(|e| BoxyError::JsonError {
+                message: format!("解析 npm list 输出失败: {}", e),
+            })?;
+        let types = manifest.map(dependency_types).unwrap_or_default();
+        let ranges = manifest.map(dependency_ranges).unwrap_or_default();
+
+        let mut packages: Vec<Package> = data
+            .dependencies
+            .unwrap_or_default()
+            .into_iter()
+            // 未安装的可选依赖（如其他平台的 fsevents）只有空对象
+            .filter(|(_, dep)| dep.version.is_some() || dep.missing)
+            .map(|(name, dep)| {
+                let flags = dep.flags();
+                let version = match (dep.version, &dep.required) {
+                    (Some(version), _) => version.trim_start_matches(['^', '~']).to_string(),
+                    (None, Some(Value::String(required))) => required.clone(),
+                    (None, _) => "unknown".to_string(),
+                };
+                Package {
+                    dependency_type: types.get(&name).copied(),
+                    range: ranges.get(&name).cloned(),
+                    name,
+                    version,
+                    manager: "npm".to_string(),
+                    resolved: dep.resolved,
+                    flags,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(packages)
+    }
+
     fn parse_search_item(value: &Value) -> Option<Package> {
         let package_value = value.get("package").unwrap_or(value);
         let name = package_value.get("name")?.as_str()?.to_string();
@@ -94,12 +167,7 @@ impl NpmManager {
             version,
             manager: "npm".to_string(),
             description,
-            homepage: None,
-            license: None,
-            installed_path: None,
-            size: None,
-            outdated: false,
-            latest_version: None,
+            ..Default::default()
         })
     }
 
@@ -129,6 +197,11 @@ impl NpmManager {
     }
 
     async fn exec(&self, args: &[&str]) -> Result<String> {
+        self.exec_with(args, false).await
+    }
+
+    /// `allow_failure` 为 true 时，命令失败但有输出也返回输出（如存在问题时的 `npm ls`）
+    async fn exec_with(&self, args: &[&str], allow_failure: bool) -> Result<String> {
         let mut cmd_args = Vec::new();
         if self.scope == NpmScope::Global {
             cmd_args.push("-g");
@@ -151,7 +224,7 @@ impl NpmManager {
                 exit_code: -1,
             })?;
 
-        if output.status.success() {
+        if output.status.success() || (allow_failure && !output.stdout.is_empty()) {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
             Err(BoxyError::CommandFailed {
@@ -176,71 +249,68 @@ impl NpmManager {
         }
     }
 
-    async fn collect_sizes(&self, root: &Path, names: &[String]) -> Result<HashMap<String, u64>> {
-        let mut items: Vec<(String, PathBuf)> = Vec::new();
-        for name in names {
-            let path = root.join(name);
-            if path.exists() {
-                items.push((name.clone(), path));
-            }
+    /// 本地范围的 node_modules 位于项目目录下，读取其中的 package.json
+    fn project_manifest(root: Option<&Path>, scope: NpmScope) -> Option<Value> {
+        match (root, scope) {
+            (Some(root), NpmScope::Local) => root.parent().and_then(read_package_json),
+            _ => None,
         }
+    }
 
-        if items.is_empty() {
-            return Ok(HashMap::new());
+    /// 解析 `npm outdated --json`，`manifest` 用于补充声明的版本范围与依赖类型
+    fn parse_outdated_output(output: &str, manifest: Option<&Value>) -> Result<Vec<Package>> {
+        if output.trim().is_empty() {
+            return Ok(Vec::new());
         }
 
-        let mut sizes = HashMap::new();
-        for chunk in items.chunks(100) {
-            let mut cmd = Command::new("du");
-            cmd.arg("-sk");
-            let mut path_map = HashMap::new();
-            for (name, path) in chunk {
-                let path_str = path.to_string_lossy().to_string();
-                path_map.insert(path_str.clone(), name.clone());
-                cmd.arg(path_str);
-            }
-
-            let output = cmd.output().await.map_err(|_| BoxyError::CommandFailed {
-                manager: "npm".to_string(),
-                command: "du -sk".to_string(),
-                exit_code: -1,
+        let outdated: HashMap<String, NpmOutdatedEntry> =
+            serde_json::from_str(output).map_err(|e| BoxyError::JsonError {
+                message: format!("解析 npm outdated 输出失败: {}", e),
             })?;
+        let types = manifest.map(dependency_types).unwrap_or_default();
+        let ranges = manifest.map(dependency_ranges).unwrap_or_default();
 
-            if !output.status.success() {
-                return Err(BoxyError::CommandFailed {
-                    manager: "npm".to_string(),
-                    command: "du -sk".to_string(),
-                    exit_code: output.status.code().unwrap_or(-1),
-                });
-            }
-
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-                let mut parts = line.split_whitespace();
-                let size_kb = parts.next().and_then(|value| value.parse::<u64>().ok());
-                let size_value = match size_kb {
-                    Some(value) => value,
-                    None => continue,
-                };
-                let path_value = match parts.next() {
-                    Some(value) => {
-                        let prefix_len = line.find(value).unwrap_or(0);
-                        line[prefix_len..].trim()
-                    }
-                    None => continue,
+        let mut packages: Vec<Package> = outdated
+            .into_iter()
+            .flat_map(|(name, entry)| {
+                let entries = match entry {
+                    NpmOutdatedEntry::One(pkg) => vec![pkg],
+                    NpmOutdatedEntry::Many(pkgs) => pkgs,
                 };
+                entries.into_iter().map(move |pkg| (name.clone(), pkg))
+            })
+            .map(|(name, pkg)| Package {
+                dependency_type: types.get(&name).copied(),
+                range: ranges.get(&name).cloned(),
+                name,
+                // 声明但未安装的依赖没有 current
+                flags: if pkg.current.is_none() {
+                    vec![DependencyFlag::Missing]
+                } else {
+                    Vec::new()
+                },
+                version: pkg.current.unwrap_or_else(|| "unknown".to_string()),
+                manager: "npm".to_string(),
+                installed_path: pkg.location,
+                outdated: true,
+                latest_version: Some(pkg.latest),
+                wanted_version: pkg.wanted,
+                ..Default::default()
+            })
+            .collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
 
-                if let Some(name) = path_map.get(path_value) {
-                    sizes.insert(name.clone(), size_value.saturating_mul(1024));
-                }
-            }
-        }
+        Ok(packages)
+    }
 
-        Ok(sizes)
+    async fn collect_sizes(&self, root: &Path, names: &[String]) -> HashMap<String, u64> {
+        let items = names
+            .iter()
+            .map(|name| (name.clone(), SizeSource::Path(root.join(name))))
+            .collect();
+        DiskUsage::new(self.cache.clone())
+            .measure(self.cache_key_value(), items)
+            .await
     }
 }
 
@@ -263,56 +333,31 @@ impl PackageManager for NpmManager {
             return Ok(cached);
         }
 
-        let output = self.exec(&["list", "--json"]).await?;
-
-        let data: NpmListOutput =
-            serde_json::from_str(&output).map_err(|e| BoxyError::JsonError {
-                message: format!("解析 npm list 输出失败: {}", e),
-            })?;
-
-        let mut packages: Vec<Package> = data
-            .dependencies
-            .unwrap_or_default()
-            .into_iter()
-            .map(|(name, dep)| Package {
-                name,
-                version: dep.version.trim_start_matches(['^', '~']).to_string(),
-                manager: "npm".to_string(),
-                description: None,
-                homepage: None,
-                license: None,
-                installed_path: if self.scope == NpmScope::Global {
-                    Some("/usr/local/lib/node_modules".to_string())
-                } else {
-                    None
-                },
-                size: None,
-                outdated: false,
-                latest_version: None,
-            })
-            .collect();
+        // 存在缺失、多余或版本不符的依赖时 npm ls 以非零状态退出，但仍输出完整的 JSON
+        let output = self.exec_with(&["list", "--json"], true).await?;
+        let root = self.resolve_root().await;
+        // 本地范围的 node_modules 位于项目目录下，按其中的 package.json 区分依赖类型
+        let manifest = Self::project_manifest(root.as_deref(), self.scope);
+        let mut packages = Self::parse_list_output(&output, manifest.as_ref())?;
 
-        if packages.len() <= MAX_SIZE_PACKAGES {
-            if let Some(root) = self.resolve_root().await {
-                let names: Vec<String> =
-                    packages.iter().map(|pkg| pkg.name.clone()).collect();
-                match self.collect_sizes(&root, &names).await {
-                    Ok(size_map) => {
-                        for pkg in packages.iter_mut() {
-                            if let Some(size) = size_map.get(&pkg.name) {
-                                pkg.size = Some(*size);
-                                pkg.installed_path =
-                                    Some(root.join(&pkg.name).to_string_lossy().to_string());
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        warn!("npm 获取包大小失败: {}", err);
-                    }
+        // 全局根目录随 nvm、volta、Homebrew 等安装方式变化，使用 `npm root` 的结果
+        if let Some(root) = root {
+            let names: Vec<String> = packages
+                .iter()
+                .filter(|pkg| !pkg.flags.contains(&DependencyFlag::Missing))
+                .map(|pkg| pkg.name.clone())
+                .collect();
+            let size_map = self.collect_sizes(&root, &names).await;
+            for pkg in packages.iter_mut() {
+                if pkg.flags.contains(&DependencyFlag::Missing) {
+                    continue;
+                }
+                pkg.installed_path = Some(root.join(&pkg.name).to_string_lossy().to_string());
+                pkg.size = size_map.get(&pkg.name).copied();
+                if pkg.license.is_none() {
+                    pkg.license = package_json_license(&root.join(&pkg.name));
                 }
             }
-        } else {
-            warn!("npm 包数量过多，跳过大小统计");
         }
 
         self.cache.set(self.cache_key_value(), &packages).await?;
@@ -346,29 +391,17 @@ impl PackageManager for NpmManager {
             description: Some(info.description),
             homepage: info.homepage,
             license: info.license,
-            installed_path: if self.scope == NpmScope::Global {
-                Some("/usr/local/lib/node_modules".to_string())
-            } else {
-                None
-            },
-            size: None,
-            outdated: false,
             latest_version: Some(info.version),
+            ..Default::default()
         };
 
         if let Some(root) = self.resolve_root().await {
             let names = vec![package.name.clone()];
-            match self.collect_sizes(&root, &names).await {
-                Ok(size_map) => {
-                    if let Some(size) = size_map.get(&package.name) {
-                        package.size = Some(*size);
-                        package.installed_path =
-                            Some(root.join(&package.name).to_string_lossy().to_string());
-                    }
-                }
-                Err(err) => {
-                    warn!("npm 获取包大小失败: {}", err);
-                }
+            let size_map = self.collect_sizes(&root, &names).await;
+            if let Some(size) = size_map.get(&package.name) {
+                package.size = Some(*size);
+                package.installed_path =
+                    Some(root.join(&package.name).to_string_lossy().to_string());
             }
         }
 
@@ -376,15 +409,18 @@ impl PackageManager for NpmManager {
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
+        self.install_batch(&[(name, version)], force).await
+    }
+
+    async fn install_batch(&self, packages: &[(&str, Option<&str>)], force: bool) -> Result<()> {
         let mut args: Vec<String> = vec!["install".to_string()];
         if force {
             args.push("--force".to_string());
         }
-        let target = match version {
+        args.extend(packages.iter().map(|(name, version)| match version {
             Some(v) => format!("{}@{}", name, v),
             None => name.to_string(),
-        };
-        args.push(target);
+        }));
 
         info!("npm install {}", args.join(" "));
 
@@ -395,6 +431,30 @@ impl PackageManager for NpmManager {
         Ok(())
     }
 
+    /// 按依赖类型写入 package.json 对应的字段
+    async fn add_dependency(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        dependency_type: DependencyType,
+    ) -> Result<()> {
+        let target = match version {
+            Some(v) => format!("{}@{}", name, v),
+            None => name.to_string(),
+        };
+        let flag = match dependency_type {
+            DependencyType::Prod => "--save-prod",
+            DependencyType::Dev => "--save-dev",
+            DependencyType::Optional => "--save-optional",
+            DependencyType::Peer => "--save-peer",
+        };
+        info!("npm install {} {}", flag, target);
+        self.exec(&["install", flag, &target]).await?;
+        self.cache.invalidate(self.cache_key_value()).await?;
+
+        Ok(())
+    }
+
     async fn upgrade(&self, name: &str) -> Result<()> {
         let args: Vec<String> = vec!["update".to_string(), name.to_string()];
 
@@ -407,13 +467,28 @@ impl PackageManager for NpmManager {
         Ok(())
     }
 
+    /// `npm update` 只在 package.json 的版本范围内更新，升级到最新版本需要重新安装并改写范围
+    async fn upgrade_latest(&self, name: &str) -> Result<()> {
+        let target = format!("{}@latest", name);
+        info!("npm install {}", target);
+        self.exec(&["install", &target]).await?;
+        self.cache.invalidate(self.cache_key_value()).await?;
+
+        Ok(())
+    }
+
     async fn uninstall(&self, name: &str, force: bool) -> Result<()> {
-        let mut args: Vec<String> = vec!["uninstall".to_string(), name.to_string()];
+        self.uninstall_batch(&[name], force).await
+    }
+
+    async fn uninstall_batch(&self, names: &[&str], force: bool) -> Result<()> {
+        let mut args: Vec<String> = vec!["uninstall".to_string()];
+        args.extend(names.iter().map(|name| name.to_string()));
         if force {
             args.push("-f".to_string());
         }
 
-        warn!("npm uninstall {} (force: {})", name, force);
+        warn!("npm uninstall {} (force: {})", names.join(" "), force);
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
@@ -423,38 +498,11 @@ impl PackageManager for NpmManager {
     }
 
     async fn check_outdated(&self) -> Result<Vec<Package>> {
-        let output = self.exec(&["outdated", "--json"]).await?;
-
-        if output.trim().is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let outdated: HashMap<String, NpmOutdatedPackage> =
-            serde_json::from_str(&output).map_err(|e| BoxyError::JsonError {
-                message: format!("解析 npm outdated 输出失败: {}", e),
-            })?;
-
-        let packages = outdated
-            .into_iter()
-            .map(|(name, pkg)| Package {
-                name,
-                version: pkg.current,
-                manager: "npm".to_string(),
-                description: None,
-                homepage: None,
-                license: None,
-                installed_path: if self.scope == NpmScope::Global {
-                    Some("/usr/local/lib/node_modules".to_string())
-                } else {
-                    None
-                },
-                size: None,
-                outdated: true,
-                latest_version: Some(pkg.latest),
-            })
-            .collect();
-
-        Ok(packages)
+        // 存在可更新的包时 npm outdated 以状态码 1 退出
+        let output = self.exec_with(&["outdated", "--json"], true).await?;
+        let root = self.resolve_root().await;
+        let manifest = Self::project_manifest(root.as_deref(), self.scope);
+        Self::parse_outdated_output(&output, manifest.as_ref())
     }
 
     async fn list_dependencies(&self, name: &str) -> Result<Vec<Package>> {
@@ -477,19 +525,18 @@ impl PackageManager for NpmManager {
                 name: dep_name,
                 version: dep_version.trim_start_matches(['^', '~']).to_string(),
                 manager: "npm".to_string(),
-                description: None,
-                homepage: None,
-                license: None,
-                installed_path: None,
-                size: None,
-                outdated: false,
-                latest_version: None,
+                ..Default::default()
             })
             .collect();
 
         Ok(packages)
     }
 
+    /// npm 缓存由所有项目共享，卸载单个包后不清空整个缓存
+    fn clean_cache_on_uninstall(&self) -> bool {
+        false
+    }
+
     /// 清理 npm 缓存
     ///
     /// 执行 `npm cache clean --force` 清理所有下载缓存
@@ -499,6 +546,24 @@ impl PackageManager for NpmManager {
         Ok(())
     }
 
+    /// npm 没有按时间清理的命令，执行 `npm cache verify` 回收不再被索引引用的内容
+    ///
+    /// `npm cache verify` 不支持预演，dry_run 时无法估算可释放的空间。
+    async fn prune_cache(&self, _older_than: Duration, dry_run: bool) -> Result<Option<u64>> {
+        if dry_run {
+            return Ok(None);
+        }
+        info!("npm cache verify");
+        let output = self.exec(&["cache", "verify"]).await?;
+        Ok(Some(parse_cache_verify(&output)))
+    }
+
+    /// npm 的下载缓存位于 `npm config get cache` 下的 _cacache 目录
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        let output = self.exec(&["config", "get", "cache"]).await?;
+        Ok(existing_dirs([PathBuf::from(output.trim()).join("_cacache")]))
+    }
+
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
@@ -507,6 +572,7 @@ impl PackageManager for NpmManager {
             SearchRemote,
             QueryDependencies,
             VersionSelection,
+            BatchInstall,
         ]
     }
 
@@ -515,10 +581,22 @@ impl PackageManager for NpmManager {
     }
 }
 
+/// 同一个包安装在多个位置（如 workspaces）时 npm 输出数组
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NpmOutdatedEntry {
+    One(NpmOutdatedPackage),
+    Many(Vec<NpmOutdatedPackage>),
+}
+
 #[derive(Debug, Deserialize)]
 struct NpmOutdatedPackage {
-    current: String,
+    current: Option<String>,
+    /// 满足 package.json 版本范围的最高版本
+    wanted: Option<String>,
     latest: String,
+    /// 安装位置，npm 给出的是实际路径
+    location: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -527,6 +605,22 @@ pub enum NpmScope {
     Local,
 }
 
+/// 解析 `npm cache verify` 输出中回收的字节数，没有回收内容时 npm 不输出该行
+fn parse_cache_verify(output: &str) -> u64 {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Content garbage-collected:"))
+        .and_then(|rest| {
+            let (_, size) = rest.split_once('(')?;
+            size.trim_end_matches(')')
+                .trim_end_matches("bytes")
+                .trim()
+                .parse()
+                .ok()
+        })
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -538,6 +632,83 @@ mod tests {
         assert_eq!(manager.name(), "npm");
     }
 
+    #[test]
+    fn test_parse_cache_verify() {
+        assert_eq!(
+            parse_cache_verify(include_str!("../tests/fixtures/npm-10.8-cache-verify.txt")),
+            10_485_760
+        );
+        assert_eq!(
+            parse_cache_verify("Index entries: 0\nFinished in 0.01s\n"),
+            0
+        );
+    }
+
+    #[test]
+    fn test_parse_list_output() {
+        let manifest: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/npm-10.8-package.json")).unwrap();
+        let packages = NpmManager::parse_list_output(
+            include_str!("../tests/fixtures/npm-10.8-ls-problems.json"),
+            Some(&manifest),
+        )
+        .unwrap();
+        let find = |name: &str| packages.iter().find(|pkg| pkg.name == name).unwrap();
+
+        assert_eq!(packages.len(), 7);
+        assert!(packages.iter().all(|pkg| pkg.name != "fsevents"));
+        assert_eq!(find("left-pad").dependency_type, Some(DependencyType::Prod));
+        assert_eq!(
+            find("left-pad").resolved.as_deref(),
+            Some("https://registry.npmjs.org/left-pad/-/left-pad-1.3.0.tgz")
+        );
+        assert_eq!(find("typescript").dependency_type, Some(DependencyType::Dev));
+        assert_eq!(find("react").dependency_type, Some(DependencyType::Peer));
+        assert_eq!(find("ms").flags, vec![DependencyFlag::Overridden]);
+        assert_eq!(find("chalk").flags, vec![DependencyFlag::Extraneous]);
+        assert_eq!(find("chalk").dependency_type, None);
+        assert_eq!(find("is-odd").flags, vec![DependencyFlag::Invalid]);
+        assert_eq!(find("debug").flags, vec![DependencyFlag::Missing]);
+        assert_eq!(find("debug").version, "^4.3.4");
+
+        let global = NpmManager::parse_list_output(
+            include_str!("../tests/fixtures/npm-10.8-ls-problems.json"),
+            None,
+        )
+        .unwrap();
+        assert!(global.iter().all(|pkg| pkg.dependency_type.is_none()));
+    }
+
+    #[test]
+    fn test_parse_outdated_output() {
+        let manifest: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/npm-10.8-package.json")).unwrap();
+        let packages = NpmManager::parse_outdated_output(
+            include_str!("../tests/fixtures/npm-10.8-outdated.json"),
+            Some(&manifest),
+        )
+        .unwrap();
+        let find = |name: &str| packages.iter().find(|pkg| pkg.name == name).unwrap();
+
+        assert_eq!(packages.len(), 5);
+        let react = find("react");
+        assert_eq!(react.wanted_version.as_deref(), Some("18.3.1"));
+        assert_eq!(react.latest_version.as_deref(), Some("19.2.0"));
+        assert_eq!(react.range.as_deref(), Some("^18.2.0"));
+        assert!(react.has_update_in_range());
+        assert!(react.needs_manifest_bump());
+
+        let ms = find("ms");
+        assert!(!ms.has_update_in_range());
+        assert!(ms.needs_manifest_bump());
+
+        assert!(!find("is-odd").needs_manifest_bump());
+        assert_eq!(find("typescript").dependency_type, Some(DependencyType::Dev));
+        assert_eq!(find("debug").flags, vec![DependencyFlag::Missing]);
+
+        assert!(NpmManager::parse_outdated_output("", None).unwrap().is_empty());
+    }
+
     #[test]
     fn test_capabilities() {
         let cache = Arc::new(Cache::new().unwrap());
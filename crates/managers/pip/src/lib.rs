@@ -1,11 +1,16 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{existing_dirs, normalize_dist_name, DiskUsage, SizeSource},
+    license::dist_info_license,
     manager::PackageManager,
     package::{Capability, Package},
+    pypi::PypiIndex,
+    python::{apply_install_info, parse_list_json, PythonScope},
+    spec::python_requirement,
 };
 use boxy_error::{BoxyError, Result};
-use std::sync::Arc;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
@@ -14,22 +19,44 @@ const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
 
 pub struct PipManager {
     cache: Arc<Cache>,
-    global: bool,
+    scope: PythonScope,
+    cache_key: String,
 }
 
 impl PipManager {
-    pub fn new(cache: Arc<Cache>, global: bool) -> Self {
-        Self { cache, global }
+    pub fn new(cache: Arc<Cache>, scope: PythonScope) -> Self {
+        let cache_key = scope.cache_key("pip");
+        Self {
+            cache,
+            scope,
+            cache_key,
+        }
+    }
+
+    /// 指定解释器或虚拟环境时通过 `python -m pip` 执行，否则使用 PATH 上的 pip
+    fn command(&self) -> Command {
+        match self.scope.interpreter() {
+            Some(python) => {
+                let mut cmd = Command::new(python);
+                cmd.args(["-m", "pip"]);
+                cmd
+            }
+            None => Command::new("pip"),
+        }
     }
 
     async fn exec(&self, args: &[&str]) -> Result<String> {
-        let cmd = if self.global { "pip3" } else { "pip" };
         let mut cmd_args = Vec::new();
         cmd_args.extend_from_slice(args);
+        // 用户范围只影响列出与安装，卸载与缓存命令不接受 --user
+        if self.scope == PythonScope::User && matches!(args.first(), Some(&"list" | &"install"))
+        {
+            cmd_args.push("--user");
+        }
 
-        debug!("执行 {} 命令: {}", cmd, cmd_args.join(" "));
+        debug!("执行 pip 命令 ({:?}): {}", self.scope, cmd_args.join(" "));
 
-        let output = timeout(COMMAND_TIMEOUT, Command::new(cmd).args(&cmd_args).output())
+        let output = timeout(COMMAND_TIMEOUT, self.command().args(&cmd_args).output())
             .await
             .map_err(|_| BoxyError::CommandTimeout)?
             .map_err(|_| BoxyError::CommandFailed {
@@ -49,36 +76,76 @@ impl PipManager {
         }
     }
 
-    fn parse_list_output(&self, output: &str) -> Vec<Package> {
-        output
-            .lines()
-            .skip(2) // 跳过标题行
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
+    /// 解析 `pip list --format=json` 的输出
+    fn parse_list_output(output: &str) -> Result<Vec<Package>> {
+        Ok(parse_list_json(output)?
+            .into_iter()
+            .map(|entry| entry.into_package("pip"))
+            .collect())
+    }
+
+    /// 解析 `pip show` 输出中的包名与安装位置
+    fn parse_show_locations(output: &str) -> HashMap<String, PathBuf> {
+        let mut locations = HashMap::new();
+        let mut name: Option<String> = None;
+        for line in output.lines() {
+            let line = line.trim();
+            if let Some(value) = line.strip_prefix("Name:") {
+                name = Some(normalize_dist_name(value.trim()));
+            } else if let Some(value) = line.strip_prefix("Location:") {
+                if let Some(name) = name.take() {
+                    locations.insert(name, PathBuf::from(value.trim()));
                 }
+            }
+        }
+        locations
+    }
 
-                // pip list 输出格式: package_name version
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() < 2 {
-                    return None;
+    /// 按 dist-info 补全安装信息与许可证，并按 RECORD 统计每个包在 site-packages 中的大小
+    ///
+    /// `pip list --verbose` 已给出安装位置，缺失时（旧版本 pip）再通过 `pip show` 查询。
+    async fn collect_sizes(&self, packages: &mut [Package]) {
+        let missing: Vec<&str> = packages
+            .iter()
+            .filter(|pkg| pkg.installed_path.is_none())
+            .map(|pkg| pkg.name.as_str())
+            .collect();
+        let mut locations = HashMap::new();
+        for chunk in missing.chunks(100) {
+            let mut args = vec!["show"];
+            args.extend_from_slice(chunk);
+            match self.exec(&args).await {
+                Ok(output) => locations.extend(Self::parse_show_locations(&output)),
+                Err(err) => warn!("pip 获取安装位置失败: {}", err),
+            }
+        }
+
+        let mut items = Vec::new();
+        for pkg in packages.iter_mut() {
+            let location = match &pkg.installed_path {
+                Some(path) => PathBuf::from(path),
+                None => match locations.get(&normalize_dist_name(&pkg.name)) {
+                    Some(location) => location.clone(),
+                    None => continue,
+                },
+            };
+            pkg.installed_path = Some(location.to_string_lossy().to_string());
+            if let Some(dist_info) = apply_install_info(pkg, &location) {
+                if pkg.license.is_none() {
+                    pkg.license = dist_info_license(&dist_info);
                 }
+                items.push((pkg.name.clone(), SizeSource::DistInfo(dist_info)));
+            }
+        }
+        let size_map = DiskUsage::new(self.cache.clone())
+            .measure(&self.cache_key, items)
+            .await;
 
-                Some(Package {
-                    name: parts[0].to_string(),
-                    version: parts[1].to_string(),
-                    manager: "pip".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
-                })
-            })
-            .collect()
+        for pkg in packages.iter_mut() {
+            if let Some(size) = size_map.get(&pkg.name) {
+                pkg.size = Some(*size);
+            }
+        }
     }
 }
 
@@ -89,73 +156,33 @@ impl PackageManager for PipManager {
     }
 
     async fn check_available(&self) -> Result<bool> {
-        let cmd = if self.global { "pip3" } else { "pip" };
-        match Command::new(cmd).arg("--version").output().await {
+        match self.command().arg("--version").output().await {
             Ok(output) => Ok(output.status.success()),
             Err(_) => Ok(false),
         }
     }
 
     async fn list_installed(&self) -> Result<Vec<Package>> {
-        if let Some(cached) = self.cache.get("pip").await? {
+        if let Some(cached) = self.cache.get(&self.cache_key).await? {
             debug!("使用缓存的 pip 包列表");
             return Ok(cached);
         }
 
-        let output = self.exec(&["list"]).await?;
-        let packages = self.parse_list_output(&output);
+        let output = self.exec(&["list", "--format=json", "--verbose"]).await?;
+        let mut packages = Self::parse_list_output(&output)?;
+        self.collect_sizes(&mut packages).await;
 
-        self.cache.set("pip", &packages).await?;
+        self.cache.set(&self.cache_key, &packages).await?;
         debug!("pip 已安装包: {} 个", packages.len());
 
         Ok(packages)
     }
 
     async fn search(&self, query: &str) -> Result<Vec<Package>> {
-        let output = self.exec(&["search", query]).await?;
-
-        let packages: Vec<Package> = output
-            .lines()
-            .skip(2) // 跳过标题行
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                // pip search 输出格式: package_name (version) - description
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.is_empty() {
-                    return None;
-                }
-
-                let name = parts[0].to_string();
-                let version = parts
-                    .get(1)
-                    .map(|s| s.trim_matches(&['(', ')'] as &[char]).to_string())
-                    .unwrap_or_default();
-                let description = if parts.len() > 3 {
-                    Some(parts[3..].join(" "))
-                } else {
-                    None
-                };
-
-                Some(Package {
-                    name,
-                    version,
-                    manager: "pip".to_string(),
-                    description,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
-                })
-            })
-            .collect();
-
-        Ok(packages)
+        // PyPI 已关闭 XML-RPC 搜索接口，改为基于简单索引与 JSON API 搜索
+        PypiIndex::from_env()
+            .search(&self.cache, query, "pip")
+            .await
     }
 
     async fn get_info(&self, name: &str) -> Result<Package> {
@@ -186,29 +213,31 @@ impl PackageManager for PipManager {
             description,
             homepage,
             license,
-            installed_path: None,
-            size: None,
-            outdated: false,
-            latest_version: None,
+            ..Default::default()
         })
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
+        self.install_batch(&[(name, version)], force).await
+    }
+
+    async fn install_batch(&self, packages: &[(&str, Option<&str>)], force: bool) -> Result<()> {
         let mut args: Vec<String> = vec!["install".to_string()];
         if force {
             args.push("--force-reinstall".to_string());
         }
-        let target = match version {
-            Some(v) => format!("{}=={}", name, v),
-            None => name.to_string(),
-        };
-        args.push(target);
+        // 支持 `==24.*`、`>=1.0` 等原生约束，纯版本号按 `==` 处理
+        args.extend(
+            packages
+                .iter()
+                .map(|(name, version)| python_requirement(name, *version)),
+        );
 
         info!("pip install {}", args.join(" "));
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
-        self.cache.invalidate("pip").await?;
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
@@ -216,65 +245,36 @@ impl PackageManager for PipManager {
     async fn upgrade(&self, name: &str) -> Result<()> {
         info!("pip install --upgrade {}", name);
         self.exec(&["install", "--upgrade", name]).await?;
-        self.cache.invalidate("pip").await?;
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
 
     async fn uninstall(&self, name: &str, force: bool) -> Result<()> {
+        self.uninstall_batch(&[name], force).await
+    }
+
+    async fn uninstall_batch(&self, names: &[&str], force: bool) -> Result<()> {
         let mut args: Vec<String> = vec!["uninstall".to_string()];
         if force {
             args.push("--yes".to_string());
         }
-        args.push(name.to_string());
+        args.extend(names.iter().map(|name| name.to_string()));
 
-        warn!("pip uninstall {} (force: {})", name, force);
+        warn!("pip uninstall {} (force: {})", names.join(" "), force);
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
-        self.cache.invalidate("pip").await?;
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
 
     async fn check_outdated(&self) -> Result<Vec<Package>> {
-        let output = self.exec(&["list", "--outdated"]).await?;
-
-        let outdated: Vec<Package> = output
-            .lines()
-            .skip(2) // 跳过标题行
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                // pip list --outdated 输出格式: package_name current_version (latest_version)
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() < 3 {
-                    return None;
-                }
-
-                let name = parts[0].to_string();
-                let current_version = parts[1].to_string();
-                let latest_version = parts[2].trim_matches(&['(', ')'] as &[char]).to_string();
-
-                Some(Package {
-                    name,
-                    version: current_version,
-                    manager: "pip".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: true,
-                    latest_version: Some(latest_version),
-                })
-            })
-            .collect();
-
-        Ok(outdated)
+        let output = self
+            .exec(&["list", "--outdated", "--format=json"])
+            .await?;
+        Self::parse_list_output(&output)
     }
 
     /// 清理 pip 缓存
@@ -286,10 +286,25 @@ impl PackageManager for PipManager {
         Ok(())
     }
 
+    /// pip 的 HTTP / wheel 缓存目录（`pip cache dir`）
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        let output = self.exec(&["cache", "dir"]).await?;
+        Ok(existing_dirs([PathBuf::from(output.trim())]))
+    }
+
+    fn cache_key(&self) -> &str {
+        &self.cache_key
+    }
+
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
-        &[ListInstalled, SearchRemote, VersionSelection]
+        &[
+            ListInstalled,
+            SearchRemote,
+            VersionSelection,
+            BatchInstall,
+        ]
     }
 }
 
@@ -300,28 +315,67 @@ mod tests {
     #[test]
     fn test_pip_manager_creation() {
         let cache = Arc::new(Cache::new().unwrap());
-        let manager = PipManager::new(cache.clone(), false);
+        let manager = PipManager::new(cache.clone(), PythonScope::Default);
         assert_eq!(manager.name(), "pip");
     }
 
     #[test]
     fn test_parse_list_output() {
-        let cache = Arc::new(Cache::new().unwrap());
-        let manager = PipManager::new(cache, false);
-        let output = "Package    Version\n------------\nrequests  2.31.0\nurllib3   2.0.7\n";
-        let packages = manager.parse_list_output(output);
-        assert_eq!(packages.len(), 2);
-        assert_eq!(packages[0].name, "requests");
+        let packages =
+            PipManager::parse_list_output(include_str!("../tests/fixtures/pip-23.2-list.json"))
+                .unwrap();
+        assert_eq!(packages.len(), 4);
+        assert_eq!(packages[0].name, "demo-tool");
+        assert_eq!(
+            packages[0].source.as_deref(),
+            Some("editable: /home/dev/src/demo-tool")
+        );
+        assert_eq!(packages[1].source, None);
+        assert_eq!(packages[1].installed_path, None);
+    }
+
+    #[test]
+    fn test_parse_list_verbose() {
+        let packages = PipManager::parse_list_output(include_str!(
+            "../tests/fixtures/pip-23.2-list-verbose.json"
+        ))
+        .unwrap();
+        assert_eq!(packages[0].installer.as_deref(), Some("uv"));
+        assert_eq!(
+            packages[2].installed_path.as_deref(),
+            Some("/home/dev/.venvs/demo/lib/python3.11/site-packages")
+        );
+
+        // 发行版自带的包 INSTALLER 为空
+        let packages = PipManager::parse_list_output(include_str!(
+            "../tests/fixtures/pip-21.0-list-verbose.json"
+        ))
+        .unwrap();
+        assert_eq!(packages[0].installer, None);
+        assert_eq!(packages[1].installer.as_deref(), Some("pip"));
+    }
+
+    #[test]
+    fn test_parse_outdated_output() {
+        let packages = PipManager::parse_list_output(include_str!(
+            "../tests/fixtures/pip-24.0-list-outdated.json"
+        ))
+        .unwrap();
+        assert_eq!(packages.len(), 3);
+        assert!(packages.iter().all(|pkg| pkg.outdated));
+        assert_eq!(packages[0].version, "23.1.0");
+        assert_eq!(packages[0].latest_version.as_deref(), Some("24.2.0"));
     }
 
     #[test]
     fn test_capabilities() {
         let cache = Arc::new(Cache::new().unwrap());
-        let manager = PipManager::new(cache, false);
+        let manager = PipManager::new(cache, PythonScope::User);
         let caps = manager.capabilities();
 
         assert!(caps.contains(&Capability::ListInstalled));
         assert!(caps.contains(&Capability::SearchRemote));
         assert!(caps.contains(&Capability::VersionSelection));
+        assert_eq!(manager.cache_key(), "pip-user");
     }
 }
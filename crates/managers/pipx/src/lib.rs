@@ -1,11 +1,23 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{find_dist_info, normalize_dist_name, DiskUsage, SizeSource},
+    license::dist_info_license,
     manager::PackageManager,
     package::{Capability, Package},
+    pypi::PypiIndex,
+    spec::python_requirement,
+    version::compare_versions,
 };
 use boxy_error::{BoxyError, Result};
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
@@ -44,40 +56,237 @@ impl PipxManager {
         }
     }
 
-    fn parse_list_output(&self, output: &str) -> Vec<Package> {
-        output
-            .lines()
-            .skip(1) // 跳过标题行
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                // pipx list 输出格式: package_name version
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.is_empty() {
-                    return None;
-                }
-
-                let name = parts[0].to_string();
-                let version = parts.get(1).unwrap_or(&"").to_string();
-
-                Some(Package {
-                    name,
-                    version,
-                    manager: "pipx".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
-                })
+    async fn resolve_venvs_dir(&self) -> Option<PathBuf> {
+        let output = self
+            .exec(&["environment", "--value", "PIPX_LOCAL_VENVS"])
+            .await
+            .ok()?;
+        let dir = output.trim();
+        if dir.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(dir))
+        }
+    }
+
+    /// 统计每个应用独立虚拟环境的大小，`packages` 与 `venvs` 一一对应
+    async fn collect_sizes(&self, venvs: &[PipxVenv], packages: &mut [Package]) {
+        let venvs_dir = self.resolve_venvs_dir().await;
+        let dirs: Vec<Option<PathBuf>> = venvs
+            .iter()
+            .map(|venv| {
+                venv.path
+                    .clone()
+                    .or_else(|| venvs_dir.as_ref().map(|dir| dir.join(&venv.name)))
             })
-            .collect()
+            .collect();
+        if dirs.iter().all(Option::is_none) {
+            warn!("pipx 无法解析虚拟环境目录，跳过大小统计");
+            return;
+        }
+        let items = venvs
+            .iter()
+            .zip(&dirs)
+            .filter_map(|(venv, dir)| Some((venv.name.clone(), SizeSource::Path(dir.clone()?))))
+            .collect();
+        let size_map = DiskUsage::new(self.cache.clone())
+            .measure("pipx", items)
+            .await;
+        for ((venv, dir), pkg) in venvs.iter().zip(dirs).zip(packages.iter_mut()) {
+            let Some(dir) = dir else {
+                continue;
+            };
+            pkg.size = size_map.get(&venv.name).copied();
+            pkg.license = Self::venv_site_packages(&dir)
+                .and_then(|site_packages| find_dist_info(&site_packages, &pkg.name, None))
+                .and_then(|dist_info| dist_info_license(&dist_info));
+            pkg.installed_path = Some(dir.to_string_lossy().to_string());
+        }
+    }
+
+    /// 虚拟环境中的 site-packages 目录（`lib/python3.x/site-packages` 或 Windows 的 `Lib/site-packages`）
+    fn venv_site_packages(venv: &Path) -> Option<PathBuf> {
+        let windows = venv.join("Lib").join("site-packages");
+        if windows.is_dir() {
+            return Some(windows);
+        }
+        fs::read_dir(venv.join("lib"))
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path().join("site-packages"))
+            .find(|path| path.is_dir())
+    }
+
+    /// 读取所有应用虚拟环境的元数据
+    pub async fn venvs(&self) -> Result<Vec<PipxVenv>> {
+        let output = self.exec(&["list", "--json"]).await?;
+        parse_list_json(&output)
+    }
+
+    /// 向应用的虚拟环境注入额外的包（`pipx inject`）
+    pub async fn inject(&self, app: &str, packages: &[&str]) -> Result<()> {
+        info!("pipx inject {} {}", app, packages.join(" "));
+        let mut args = vec!["inject", app];
+        args.extend_from_slice(packages);
+        self.exec(&args).await?;
+        self.cache.invalidate("pipx").await?;
+        Ok(())
+    }
+
+    /// 重建应用的虚拟环境，保留注入的包（`pipx reinstall`）
+    pub async fn reinstall(&self, app: &str) -> Result<()> {
+        info!("pipx reinstall {}", app);
+        self.exec(&["reinstall", app]).await?;
+        self.cache.invalidate("pipx").await?;
+        Ok(())
+    }
+
+    /// 重建所有应用的虚拟环境，常用于系统 Python 升级之后（`pipx reinstall-all`）
+    pub async fn reinstall_all(&self) -> Result<()> {
+        info!("pipx reinstall-all");
+        self.exec(&["reinstall-all"]).await?;
+        self.cache.invalidate("pipx").await?;
+        Ok(())
+    }
+}
+
+/// 注入到应用虚拟环境中的包
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InjectedPackage {
+    pub name: String,
+    pub version: String,
+}
+
+/// `pipx list --json` 中的一个应用虚拟环境
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipxVenv {
+    /// 虚拟环境名，使用 `--suffix` 安装时与包名不同
+    pub name: String,
+    /// 主包名
+    pub package: String,
+    pub version: String,
+    /// 安装时使用的包名、URL 或本地路径
+    pub package_or_url: String,
+    /// 暴露到 PATH 的命令
+    pub apps: Vec<String>,
+    pub injected: Vec<InjectedPackage>,
+    /// 如 `Python 3.12.1`
+    pub python_version: Option<String>,
+    /// 虚拟环境目录，由命令路径推断
+    pub path: Option<PathBuf>,
+}
+
+impl PipxVenv {
+    /// 不是从包索引安装时（URL、git 或本地路径）返回安装来源
+    pub fn source(&self) -> Option<&str> {
+        let spec = self.package_or_url.trim();
+        let from_index = spec.is_empty()
+            || python_requirement_name(spec).is_some_and(|name| {
+                normalize_dist_name(name) == normalize_dist_name(&self.package)
+            });
+        (!from_index).then_some(spec)
     }
+
+    fn to_package(&self) -> Package {
+        Package {
+            name: self.package.clone(),
+            version: self.version.clone(),
+            manager: "pipx".to_string(),
+            installed_path: self
+                .path
+                .as_ref()
+                .map(|path| path.to_string_lossy().to_string()),
+            source: self.source().map(|source| source.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// 带约束的需求（`black==24.1.0`、`black[d]>=24`）中的包名部分
+fn python_requirement_name(spec: &str) -> Option<&str> {
+    let end = spec
+        .find(|ch: char| !(ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.')))
+        .unwrap_or(spec.len());
+    let rest = spec[end..].trim_start();
+    let is_requirement = rest.is_empty() || rest.starts_with(['[', '=', '>', '<', '~', '!', ';']);
+    (end > 0 && is_requirement).then(|| &spec[..end])
+}
+
+/// 解析 `pipx list --json`
+///
+/// 路径在 JSON 中序列化为 `{"__type__": "Path", "__Path__": "..."}`。
+fn parse_list_json(output: &str) -> Result<Vec<PipxVenv>> {
+    let value: Value = serde_json::from_str(output).map_err(|err| BoxyError::JsonError {
+        message: format!("解析 pipx list --json 失败: {}", err),
+    })?;
+    let venvs = value
+        .get("venvs")
+        .and_then(Value::as_object)
+        .ok_or_else(|| BoxyError::ParseError {
+            input: "pipx list --json 缺少 venvs".to_string(),
+        })?;
+
+    let text = |value: Option<&Value>| {
+        value
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let strings = |value: Option<&Value>| -> Vec<String> {
+        value
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut result: Vec<PipxVenv> = venvs
+        .iter()
+        .filter_map(|(name, venv)| {
+            let metadata = venv.get("metadata")?;
+            let main = metadata.get("main_package")?;
+            let package = text(main.get("package")).unwrap_or_else(|| name.clone());
+            let mut injected: Vec<InjectedPackage> = metadata
+                .get("injected_packages")
+                .and_then(Value::as_object)
+                .map(|packages| {
+                    packages
+                        .iter()
+                        .map(|(key, info)| InjectedPackage {
+                            name: text(info.get("package")).unwrap_or_else(|| key.clone()),
+                            version: text(info.get("package_version")).unwrap_or_default(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            injected.sort_by(|a, b| a.name.cmp(&b.name));
+            // 命令位于 `<venv>/bin/<app>`（Windows 为 `Scripts`）
+            let path = main
+                .get("app_paths")
+                .and_then(Value::as_array)
+                .and_then(|paths| paths.first())
+                .and_then(|path| text(path.get("__Path__")))
+                .and_then(|app| Some(Path::new(&app).parent()?.parent()?.to_path_buf()));
+            Some(PipxVenv {
+                name: name.clone(),
+                version: text(main.get("package_version")).unwrap_or_default(),
+                package_or_url: text(main.get("package_or_url")).unwrap_or_else(|| package.clone()),
+                package,
+                apps: strings(main.get("apps")),
+                injected,
+                python_version: text(metadata.get("python_version")),
+                path,
+            })
+        })
+        .collect();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(result)
 }
 
 #[async_trait]
@@ -99,8 +308,9 @@ impl PackageManager for PipxManager {
             return Ok(cached);
         }
 
-        let output = self.exec(&["list"]).await?;
-        let packages = self.parse_list_output(&output);
+        let venvs = self.venvs().await?;
+        let mut packages: Vec<Package> = venvs.iter().map(PipxVenv::to_package).collect();
+        self.collect_sizes(&venvs, &mut packages).await;
 
         self.cache.set("pipx", &packages).await?;
         debug!("pipx 已安装包: {} 个", packages.len());
@@ -109,109 +319,39 @@ impl PackageManager for PipxManager {
     }
 
     async fn search(&self, query: &str) -> Result<Vec<Package>> {
-        // pipx 不支持搜索，使用 pip search
-        let output = timeout(
-            COMMAND_TIMEOUT,
-            Command::new("pip").args(["search", query]).output(),
-        )
-        .await
-        .map_err(|_| BoxyError::CommandTimeout)?
-        .map_err(|_| BoxyError::CommandFailed {
-            manager: "pipx".to_string(),
-            command: format!("pip search {}", query),
-            exit_code: -1,
-        })?;
-
-        if !output.status.success() {
-            return Ok(Vec::new());
-        }
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        let packages: Vec<Package> = output_str
-            .lines()
-            .skip(2)
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.is_empty() {
-                    return None;
-                }
-
-                let name = parts[0].to_string();
-                let version = parts
-                    .get(1)
-                    .map(|s| s.trim_matches(&['(', ')'] as &[char]).to_string())
-                    .unwrap_or_default();
-
-                Some(Package {
-                    name,
-                    version,
-                    manager: "pipx".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
-                })
-            })
-            .collect();
-
-        Ok(packages)
+        // PyPI 已关闭 XML-RPC 搜索接口，改为基于简单索引与 JSON API 搜索
+        PypiIndex::from_env()
+            .search(&self.cache, query, "pipx")
+            .await
     }
 
+    /// 已安装的应用读取其虚拟环境中的版本，并从包索引补充简介与最新版本
     async fn get_info(&self, name: &str) -> Result<Package> {
-        // pipx 没有直接的 info 命令，使用 pip show
-        let output = timeout(
-            COMMAND_TIMEOUT,
-            Command::new("pip").args(["show", name]).output(),
-        )
-        .await
-        .map_err(|_| BoxyError::CommandTimeout)?
-        .map_err(|_| BoxyError::CommandFailed {
-            manager: "pipx".to_string(),
-            command: format!("pip show {}", name),
-            exit_code: -1,
-        })?;
-
-        if !output.status.success() {
-            return Err(BoxyError::CommandFailed {
-                manager: "pipx".to_string(),
-                command: format!("pip show {}", name),
-                exit_code: output.status.code().unwrap_or(-1),
-            });
+        let installed = self
+            .list_installed()
+            .await?
+            .into_iter()
+            .find(|pkg| normalize_dist_name(&pkg.name) == normalize_dist_name(name));
+        let index = PypiIndex::from_env();
+        let Some(pkg) = installed else {
+            return index.project(name, "pipx").await;
+        };
+        if pkg.source.is_some() {
+            return Ok(pkg);
         }
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        let mut version = String::new();
-        let mut description = None;
-
-        for line in output_str.lines() {
-            let line = line.trim();
-            if line.starts_with("Version:") {
-                version = line.replace("Version:", "").trim().to_string();
-            } else if line.starts_with("Summary:") {
-                description = Some(line.replace("Summary:", "").trim().to_string());
+        match index.project(&pkg.name, "pipx").await {
+            Ok(project) => Ok(Package {
+                outdated: is_newer(&project.version, &pkg.version),
+                latest_version: Some(project.version).filter(|version| !version.is_empty()),
+                description: project.description,
+                homepage: project.homepage,
+                ..pkg
+            }),
+            Err(err) => {
+                debug!("读取 {} 的索引信息失败: {}", pkg.name, err);
+                Ok(pkg)
             }
         }
-
-        Ok(Package {
-            name: name.to_string(),
-            version,
-            manager: "pipx".to_string(),
-            description,
-            homepage: None,
-            license: None,
-            installed_path: None,
-            size: None,
-            outdated: false,
-            latest_version: None,
-        })
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
@@ -219,11 +359,8 @@ impl PackageManager for PipxManager {
         if force {
             args.push("--force".to_string());
         }
-        let target = match version {
-            Some(v) => format!("{}=={}", name, v),
-            None => name.to_string(),
-        };
-        args.push(target);
+        // 支持 `==24.*`、`>=1.0` 等原生约束，纯版本号按 `==` 处理
+        args.push(python_requirement(name, version));
 
         info!("pipx install {}", args.join(" "));
 
@@ -258,34 +395,30 @@ impl PackageManager for PipxManager {
         Ok(())
     }
 
+    /// 并发查询各虚拟环境主包在索引上的最新版本
+    ///
+    /// 通过 URL 或本地路径安装的应用不在索引中，跳过检查。
     async fn check_outdated(&self) -> Result<Vec<Package>> {
-        let output = self.exec(&["list"]).await?;
-        let installed = self.parse_list_output(&output);
-        let mut outdated = Vec::new();
-
-        for pkg in installed {
-            match self.get_info(&pkg.name).await {
-                Ok(info) => {
-                    if info.latest_version.is_some()
-                        && info.latest_version.as_ref() != Some(&pkg.version)
-                    {
-                        outdated.push(Package {
-                            name: pkg.name,
-                            version: pkg.version,
-                            manager: "pipx".to_string(),
-                            description: None,
-                            homepage: None,
-                            license: None,
-                            installed_path: None,
-                            size: None,
-                            outdated: true,
-                            latest_version: info.latest_version,
-                        });
-                    }
-                }
-                Err(_) => continue,
-            }
-        }
+        let installed: Vec<Package> = self
+            .list_installed()
+            .await?
+            .into_iter()
+            .filter(|pkg| pkg.source.is_none())
+            .collect();
+        let names: Vec<String> = installed.iter().map(|pkg| pkg.name.clone()).collect();
+        let latest = PypiIndex::from_env().latest_versions(&names).await;
+
+        let outdated = installed
+            .into_iter()
+            .filter_map(|pkg| {
+                let version = latest.get(&pkg.name)?;
+                is_newer(version, &pkg.version).then(|| Package {
+                    outdated: true,
+                    latest_version: Some(version.clone()),
+                    ..pkg
+                })
+            })
+            .collect();
 
         Ok(outdated)
     }
@@ -293,10 +426,14 @@ impl PackageManager for PipxManager {
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
-        &[ListInstalled, VersionSelection]
+        &[ListInstalled, SearchRemote, VersionSelection]
     }
 }
 
+fn is_newer(latest: &str, current: &str) -> bool {
+    !latest.is_empty() && compare_versions(latest, current) == Ordering::Greater
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -308,6 +445,76 @@ mod tests {
         assert_eq!(manager.name(), "pipx");
     }
 
+    const LIST_JSON: &str = r#"{
+  "pipx_spec_version": "0.1",
+  "venvs": {
+    "black": {
+      "metadata": {
+        "injected_packages": {
+          "tomli": {"package": "tomli", "package_or_url": "tomli", "package_version": "2.0.1", "apps": []}
+        },
+        "main_package": {
+          "app_paths": [
+            {"__type__": "Path", "__Path__": "/home/dev/.local/share/pipx/venvs/black/bin/black"},
+            {"__type__": "Path", "__Path__": "/home/dev/.local/share/pipx/venvs/black/bin/blackd"}
+          ],
+          "apps": ["black", "blackd"],
+          "package": "black",
+          "package_or_url": "black[d]==24.1.0",
+          "package_version": "24.1.0",
+          "suffix": ""
+        },
+        "pipx_metadata_version": "0.5",
+        "python_version": "Python 3.12.1"
+      }
+    },
+    "mytool-dev": {
+      "metadata": {
+        "injected_packages": {},
+        "main_package": {
+          "app_paths": [],
+          "apps": ["mytool"],
+          "package": "mytool",
+          "package_or_url": "git+https://github.com/example/mytool.git",
+          "package_version": "0.3.0",
+          "suffix": "-dev"
+        },
+        "python_version": "Python 3.11.8"
+      }
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_parse_list_json() {
+        let venvs = parse_list_json(LIST_JSON).unwrap();
+        assert_eq!(venvs.len(), 2);
+
+        let black = &venvs[0];
+        assert_eq!(black.package, "black");
+        assert_eq!(black.version, "24.1.0");
+        assert_eq!(black.apps, vec!["black", "blackd"]);
+        assert_eq!(black.injected[0].name, "tomli");
+        assert_eq!(black.python_version.as_deref(), Some("Python 3.12.1"));
+        assert_eq!(
+            black.path.as_deref(),
+            Some(Path::new("/home/dev/.local/share/pipx/venvs/black"))
+        );
+        assert_eq!(black.source(), None);
+
+        let tool = &venvs[1];
+        assert_eq!(tool.name, "mytool-dev");
+        assert_eq!(tool.path, None);
+        let package = tool.to_package();
+        assert_eq!(package.name, "mytool");
+        assert_eq!(
+            package.source.as_deref(),
+            Some("git+https://github.com/example/mytool.git")
+        );
+
+        assert!(parse_list_json("nothing installed").is_err());
+    }
+
     #[test]
     fn test_capabilities() {
         let cache = Arc::new(Cache::new().unwrap());
@@ -315,6 +522,7 @@ mod tests {
         let caps = manager.capabilities();
 
         assert!(caps.contains(&Capability::ListInstalled));
+        assert!(caps.contains(&Capability::SearchRemote));
         assert!(caps.contains(&Capability::VersionSelection));
     }
 }
@@ -1,8 +1,11 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{existing_dirs, DiskUsage, SizeSource},
+    license::package_json_license,
     manager::PackageManager,
-    package::{Capability, Package},
+    manifest::{dependency_ranges, read_package_json},
+    package::{Capability, DependencyFlag, DependencyType, Package},
 };
 use boxy_error::{BoxyError, Result};
 use serde::Deserialize;
@@ -19,7 +22,6 @@ use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
 
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
-const MAX_SIZE_PACKAGES: usize = 1000;
 
 #[derive(Debug, Deserialize)]
 struct PnpmInfoOutput {
@@ -92,6 +94,11 @@ impl PnpmManager {
     }
 
     async fn exec(&self, args: &[&str]) -> Result<String> {
+        self.exec_with(args, false).await
+    }
+
+    /// `allow_failure` 为 true 时，命令失败但有输出也返回输出（如存在可更新包时的 `pnpm outdated`）
+    async fn exec_with(&self, args: &[&str], allow_failure: bool) -> Result<String> {
         let mut cmd_args = Vec::new();
         if self.global {
             cmd_args.push("-g");
@@ -114,7 +121,7 @@ impl PnpmManager {
                 exit_code: -1,
             })?;
 
-        if output.status.success() {
+        if output.status.success() || (allow_failure && !output.stdout.is_empty()) {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
             Err(BoxyError::CommandFailed {
@@ -125,6 +132,47 @@ impl PnpmManager {
         }
     }
 
+    /// 解析 `pnpm outdated --format json`，`manifest` 用于补充声明的版本范围
+    fn parse_outdated_output(output: &str, manifest: Option<&Value>) -> Result<Vec<Package>> {
+        if output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let outdated: HashMap<String, PnpmOutdatedPackage> = serde_json::from_str(output)
+            .map_err(|e| BoxyError::JsonError {
+                message: format!("解析 pnpm outdated 输出失败: {}", e),
+            })?;
+        let ranges = manifest.map(dependency_ranges).unwrap_or_default();
+
+        let mut packages: Vec<Package> = outdated
+            .into_iter()
+            .map(|(name, pkg)| Package {
+                range: ranges.get(&name).cloned(),
+                name,
+                flags: if pkg.current.is_none() {
+                    vec![DependencyFlag::Missing]
+                } else {
+                    Vec::new()
+                },
+                version: pkg.current.unwrap_or_else(|| "unknown".to_string()),
+                manager: "pnpm".to_string(),
+                outdated: true,
+                latest_version: Some(pkg.latest),
+                dependency_type: match pkg.dependency_type.as_deref() {
+                    Some("dependencies") => Some(DependencyType::Prod),
+                    Some("devDependencies") => Some(DependencyType::Dev),
+                    Some("optionalDependencies") => Some(DependencyType::Optional),
+                    _ => None,
+                },
+                wanted_version: pkg.wanted,
+                ..Default::default()
+            })
+            .collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(packages)
+    }
+
     async fn resolve_root(&self) -> Option<PathBuf> {
         let output = if self.global {
             self.exec(&["root", "-g"]).await.ok()?
@@ -139,71 +187,14 @@ impl PnpmManager {
         }
     }
 
-    async fn collect_sizes(&self, root: &Path, names: &[String]) -> Result<HashMap<String, u64>> {
-        let mut items: Vec<(String, PathBuf)> = Vec::new();
-        for name in names {
-            let path = root.join(name);
-            if path.exists() {
-                items.push((name.clone(), path));
-            }
-        }
-
-        if items.is_empty() {
-            return Ok(HashMap::new());
-        }
-
-        let mut sizes = HashMap::new();
-        for chunk in items.chunks(100) {
-            let mut cmd = Command::new("du");
-            cmd.arg("-sk");
-            let mut path_map = HashMap::new();
-            for (name, path) in chunk {
-                let path_str = path.to_string_lossy().to_string();
-                path_map.insert(path_str.clone(), name.clone());
-                cmd.arg(path_str);
-            }
-
-            let output = cmd.output().await.map_err(|_| BoxyError::CommandFailed {
-                manager: "pnpm".to_string(),
-                command: "du -sk".to_string(),
-                exit_code: -1,
-            })?;
-
-            if !output.status.success() {
-                return Err(BoxyError::CommandFailed {
-                    manager: "pnpm".to_string(),
-                    command: "du -sk".to_string(),
-                    exit_code: output.status.code().unwrap_or(-1),
-                });
-            }
-
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-                let mut parts = line.split_whitespace();
-                let size_kb = parts.next().and_then(|value| value.parse::<u64>().ok());
-                let size_value = match size_kb {
-                    Some(value) => value,
-                    None => continue,
-                };
-                let path_value = match parts.next() {
-                    Some(value) => {
-                        let prefix_len = line.find(value).unwrap_or(0);
-                        line[prefix_len..].trim()
-                    }
-                    None => continue,
-                };
-
-                if let Some(name) = path_map.get(path_value) {
-                    sizes.insert(name.clone(), size_value.saturating_mul(1024));
-                }
-            }
-        }
-
-        Ok(sizes)
+    async fn collect_sizes(&self, root: &Path, names: &[String]) -> HashMap<String, u64> {
+        let items = names
+            .iter()
+            .map(|name| (name.clone(), SizeSource::Path(root.join(name))))
+            .collect();
+        DiskUsage::new(self.cache.clone())
+            .measure(self.cache_key_value(), items)
+            .await
     }
 }
 
@@ -251,41 +242,27 @@ impl PackageManager for PnpmManager {
                 name,
                 version: version.trim_start_matches(['^', '~']).to_string(),
                 manager: "pnpm".to_string(),
-                description: None,
-                homepage: None,
-                license: None,
                 installed_path: if self.global {
                     Some("~/.pnpm-global".to_string())
                 } else {
                     None
                 },
-                size: None,
-                outdated: false,
-                latest_version: None,
+                ..Default::default()
             })
             .collect();
 
-        if packages.len() <= MAX_SIZE_PACKAGES {
-            if let Some(root) = self.resolve_root().await {
-                let names: Vec<String> =
-                    packages.iter().map(|pkg| pkg.name.clone()).collect();
-                match self.collect_sizes(&root, &names).await {
-                    Ok(size_map) => {
-                        for pkg in packages.iter_mut() {
-                            if let Some(size) = size_map.get(&pkg.name) {
-                                pkg.size = Some(*size);
-                                pkg.installed_path =
-                                    Some(root.join(&pkg.name).to_string_lossy().to_string());
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        warn!("pnpm 获取包大小失败: {}", err);
-                    }
+        if let Some(root) = self.resolve_root().await {
+            let names: Vec<String> = packages.iter().map(|pkg| pkg.name.clone()).collect();
+            let size_map = self.collect_sizes(&root, &names).await;
+            for pkg in packages.iter_mut() {
+                if let Some(size) = size_map.get(&pkg.name) {
+                    pkg.size = Some(*size);
+                    pkg.installed_path = Some(root.join(&pkg.name).to_string_lossy().to_string());
+                }
+                if pkg.license.is_none() {
+                    pkg.license = package_json_license(&root.join(&pkg.name));
                 }
             }
-        } else {
-            warn!("pnpm 包数量过多，跳过大小统计");
         }
 
         self.cache.set(cache_key, &packages).await?;
@@ -316,12 +293,7 @@ impl PackageManager for PnpmManager {
                         version,
                         manager: "pnpm".to_string(),
                         description,
-                        homepage: None,
-                        license: None,
-                        installed_path: None,
-                        size: None,
-                        outdated: false,
-                        latest_version: None,
+                        ..Default::default()
                     })
                 })
                 .collect()
@@ -352,24 +324,17 @@ impl PackageManager for PnpmManager {
             } else {
                 None
             },
-            size: None,
-            outdated: false,
             latest_version: Some(info.version),
+            ..Default::default()
         };
 
         if let Some(root) = self.resolve_root().await {
             let names = vec![package.name.clone()];
-            match self.collect_sizes(&root, &names).await {
-                Ok(size_map) => {
-                    if let Some(size) = size_map.get(&package.name) {
-                        package.size = Some(*size);
-                        package.installed_path =
-                            Some(root.join(&package.name).to_string_lossy().to_string());
-                    }
-                }
-                Err(err) => {
-                    warn!("pnpm 获取包大小失败: {}", err);
-                }
+            let size_map = self.collect_sizes(&root, &names).await;
+            if let Some(size) = size_map.get(&package.name) {
+                package.size = Some(*size);
+                package.installed_path =
+                    Some(root.join(&package.name).to_string_lossy().to_string());
             }
         }
 
@@ -377,15 +342,18 @@ impl PackageManager for PnpmManager {
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
+        self.install_batch(&[(name, version)], force).await
+    }
+
+    async fn install_batch(&self, packages: &[(&str, Option<&str>)], force: bool) -> Result<()> {
         let mut args: Vec<String> = vec!["install".to_string()];
         if force {
             args.push("--force".to_string());
         }
-        let target = match version {
+        args.extend(packages.iter().map(|(name, version)| match version {
             Some(v) => format!("{}@{}", name, v),
             None => name.to_string(),
-        };
-        args.push(target);
+        }));
 
         info!("pnpm install {}", args.join(" "));
 
@@ -396,6 +364,30 @@ impl PackageManager for PnpmManager {
         Ok(())
     }
 
+    /// `pnpm add` 按依赖类型写入 package.json 对应的字段
+    async fn add_dependency(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        dependency_type: DependencyType,
+    ) -> Result<()> {
+        let target = match version {
+            Some(v) => format!("{}@{}", name, v),
+            None => name.to_string(),
+        };
+        let flag = match dependency_type {
+            DependencyType::Prod => "--save-prod",
+            DependencyType::Dev => "--save-dev",
+            DependencyType::Optional => "--save-optional",
+            DependencyType::Peer => "--save-peer",
+        };
+        info!("pnpm add {} {}", flag, target);
+        self.exec(&["add", flag, &target]).await?;
+        self.cache.invalidate(self.cache_key_value()).await?;
+
+        Ok(())
+    }
+
     async fn upgrade(&self, name: &str) -> Result<()> {
         info!("pnpm update {}", name);
         self.exec(&["update", name]).await?;
@@ -404,13 +396,27 @@ impl PackageManager for PnpmManager {
         Ok(())
     }
 
+    /// `pnpm update` 只在 package.json 的版本范围内更新，`--latest` 会改写范围
+    async fn upgrade_latest(&self, name: &str) -> Result<()> {
+        info!("pnpm update --latest {}", name);
+        self.exec(&["update", "--latest", name]).await?;
+        self.cache.invalidate(self.cache_key_value()).await?;
+
+        Ok(())
+    }
+
     async fn uninstall(&self, name: &str, force: bool) -> Result<()> {
-        let mut args: Vec<String> = vec!["uninstall".to_string(), name.to_string()];
+        self.uninstall_batch(&[name], force).await
+    }
+
+    async fn uninstall_batch(&self, names: &[&str], force: bool) -> Result<()> {
+        let mut args: Vec<String> = vec!["uninstall".to_string()];
+        args.extend(names.iter().map(|name| name.to_string()));
         if force {
             args.push("--force".to_string());
         }
 
-        warn!("pnpm uninstall {} (force: {})", name, force);
+        warn!("pnpm uninstall {} (force: {})", names.join(" "), force);
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
@@ -420,37 +426,25 @@ impl PackageManager for PnpmManager {
     }
 
     async fn check_outdated(&self) -> Result<Vec<Package>> {
-        let output = self.exec(&["outdated", "--json"]).await?;
-
-        if output.trim().is_empty() {
-            return Ok(Vec::new());
+        // 存在可更新的包时 pnpm outdated 以状态码 1 退出
+        let output = self
+            .exec_with(&["outdated", "--format", "json"], true)
+            .await?;
+        // 本地范围的 node_modules 位于项目目录下，按其中的 package.json 补充声明的版本范围
+        let manifest = if self.global {
+            None
+        } else {
+            self.resolve_root()
+                .await
+                .and_then(|root| root.parent().and_then(read_package_json))
+        };
+        let mut packages = Self::parse_outdated_output(&output, manifest.as_ref())?;
+        if self.global {
+            for pkg in packages.iter_mut() {
+                pkg.installed_path = Some("~/.pnpm-global".to_string());
+            }
         }
 
-        let outdated: HashMap<String, PnpmOutdatedPackage> = serde_json::from_str(&output)
-            .map_err(|e| BoxyError::JsonError {
-                message: format!("解析 pnpm outdated 输出失败: {}", e),
-            })?;
-
-        let packages = outdated
-            .into_iter()
-            .map(|(name, pkg)| Package {
-                name,
-                version: pkg.current,
-                manager: "pnpm".to_string(),
-                description: None,
-                homepage: None,
-                license: None,
-                installed_path: if self.global {
-                    Some("~/.pnpm-global".to_string())
-                } else {
-                    None
-                },
-                size: None,
-                outdated: true,
-                latest_version: Some(pkg.latest),
-            })
-            .collect();
-
         Ok(packages)
     }
 
@@ -463,6 +457,17 @@ impl PackageManager for PnpmManager {
         Ok(())
     }
 
+    /// `pnpm store prune` 只清理未被项目引用的包，store 中仍在使用的部分无法回收
+    fn clean_cache_clears_dirs(&self) -> bool {
+        false
+    }
+
+    /// pnpm 的内容寻址存储（`pnpm store path`）
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        let output = self.exec(&["store", "path"]).await?;
+        Ok(existing_dirs([PathBuf::from(output.trim())]))
+    }
+
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
@@ -471,14 +476,20 @@ impl PackageManager for PnpmManager {
             SearchRemote,
             QueryDependencies,
             VersionSelection,
+            BatchInstall,
         ]
     }
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct PnpmOutdatedPackage {
-    current: String,
+    /// 声明但未安装的依赖没有 current
+    current: Option<String>,
+    /// 满足 package.json 版本范围的最高版本
+    wanted: Option<String>,
     latest: String,
+    dependency_type: Option<String>,
 }
 
 #[cfg(test)]
@@ -492,6 +503,31 @@ mod tests {
         assert_eq!(manager.name(), "pnpm");
     }
 
+    #[test]
+    fn test_parse_outdated_output() {
+        let manifest = serde_json::json!({
+            "dependencies": { "is-odd": "^3.0.0", "react": "^18.2.0" },
+            "devDependencies": { "typescript": "~5.4.0" },
+        });
+        let packages = PnpmManager::parse_outdated_output(
+            include_str!("../tests/fixtures/pnpm-9-outdated.json"),
+            Some(&manifest),
+        )
+        .unwrap();
+        assert_eq!(packages.len(), 3);
+
+        let react = &packages[1];
+        assert_eq!(react.name, "react");
+        assert_eq!(react.wanted_version.as_deref(), Some("18.3.1"));
+        assert_eq!(react.range.as_deref(), Some("^18.2.0"));
+        assert!(react.needs_manifest_bump());
+
+        let typescript = &packages[2];
+        assert_eq!(typescript.dependency_type, Some(DependencyType::Dev));
+        assert!(!typescript.has_update_in_range());
+        assert!(!packages[0].needs_manifest_bump());
+    }
+
     #[test]
     fn test_capabilities() {
         let cache = Arc::new(Cache::new().unwrap());
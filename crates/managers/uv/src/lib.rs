@@ -1,11 +1,16 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{existing_dirs, normalize_dist_name, DiskUsage, SizeSource},
+    license::dist_info_license,
     manager::PackageManager,
-    package::{Capability, Package},
+    package::{Capability, DependencyType, Package},
+    pypi::PypiIndex,
+    python::{apply_install_info, parse_list_json, PythonScope},
+    spec::python_requirement,
 };
 use boxy_error::{BoxyError, Result};
-use std::sync::Arc;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
@@ -14,26 +19,52 @@ const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
 
 pub struct UvManager {
     cache: Arc<Cache>,
-    _global: bool,
+    scope: PythonScope,
+    /// 项目目录，`uv add`、`uv remove` 在其中查找 pyproject.toml
+    project: Option<PathBuf>,
+    cache_key: String,
 }
 
 impl UvManager {
-    pub fn new(cache: Arc<Cache>, global: bool) -> Self {
+    pub fn new(cache: Arc<Cache>, scope: PythonScope, project: Option<PathBuf>) -> Self {
+        let cache_key = scope.cache_key("uv");
         Self {
             cache,
-            _global: global,
+            scope,
+            project,
+            cache_key,
         }
     }
 
     async fn exec(&self, args: &[&str]) -> Result<String> {
-        debug!("执行 uv 命令: {}", args.join(" "));
+        let mut cmd_args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        // `uv pip` 子命令通过 --python 指定目标环境，缓存等命令与环境无关
+        if args.first() == Some(&"pip") {
+            if self.scope == PythonScope::User {
+                return Err(BoxyError::ManagerUnavailable {
+                    name: "uv".to_string(),
+                    reason: "uv 不支持用户 site-packages（--user）".to_string(),
+                });
+            }
+            if let Some(python) = self.scope.interpreter() {
+                cmd_args.push("--python".to_string());
+                cmd_args.push(python.to_string_lossy().to_string());
+            }
+        }
 
-        let output = timeout(COMMAND_TIMEOUT, Command::new("uv").args(args).output())
+        debug!("执行 uv 命令: {}", cmd_args.join(" "));
+
+        let mut cmd = Command::new("uv");
+        cmd.args(&cmd_args);
+        if let Some(project) = &self.project {
+            cmd.current_dir(project);
+        }
+        let output = timeout(COMMAND_TIMEOUT, cmd.output())
             .await
             .map_err(|_| BoxyError::CommandTimeout)?
             .map_err(|_| BoxyError::CommandFailed {
                 manager: "uv".to_string(),
-                command: args.join(" "),
+                command: cmd_args.join(" "),
                 exit_code: -1,
             })?;
 
@@ -42,11 +73,72 @@ impl UvManager {
         } else {
             Err(BoxyError::CommandFailed {
                 manager: "uv".to_string(),
-                command: args.join(" "),
+                command: cmd_args.join(" "),
                 exit_code: output.status.code().unwrap_or(-1),
             })
         }
     }
+
+    /// 解析 `uv pip show` 输出中的包名与安装位置
+    fn parse_show_locations(output: &str) -> HashMap<String, PathBuf> {
+        let mut locations = HashMap::new();
+        let mut name: Option<String> = None;
+        for line in output.lines() {
+            let line = line.trim();
+            if let Some(value) = line.strip_prefix("Name:") {
+                name = Some(normalize_dist_name(value.trim()));
+            } else if let Some(value) = line.strip_prefix("Location:") {
+                if let Some(name) = name.take() {
+                    locations.insert(name, PathBuf::from(value.trim()));
+                }
+            }
+        }
+        locations
+    }
+
+    /// 解析 `uv pip list --format json` 的输出
+    fn parse_list_output(output: &str) -> Result<Vec<Package>> {
+        Ok(parse_list_json(output)?
+            .into_iter()
+            .map(|entry| entry.into_package("uv"))
+            .collect())
+    }
+
+    /// 按 dist-info 补全安装信息与许可证，并按 RECORD 统计每个包在 site-packages 中的大小
+    async fn collect_sizes(&self, packages: &mut [Package]) {
+        let mut locations = HashMap::new();
+        for chunk in packages.chunks(100) {
+            let mut args = vec!["pip", "show"];
+            args.extend(chunk.iter().map(|pkg| pkg.name.as_str()));
+            match self.exec(&args).await {
+                Ok(output) => locations.extend(Self::parse_show_locations(&output)),
+                Err(err) => warn!("uv 获取安装位置失败: {}", err),
+            }
+        }
+
+        let mut items = Vec::new();
+        for pkg in packages.iter_mut() {
+            let Some(location) = locations.get(&normalize_dist_name(&pkg.name)) else {
+                continue;
+            };
+            pkg.installed_path = Some(location.to_string_lossy().to_string());
+            if let Some(dist_info) = apply_install_info(pkg, location) {
+                if pkg.license.is_none() {
+                    pkg.license = dist_info_license(&dist_info);
+                }
+                items.push((pkg.name.clone(), SizeSource::DistInfo(dist_info)));
+            }
+        }
+        let size_map = DiskUsage::new(self.cache.clone())
+            .measure(&self.cache_key, items)
+            .await;
+
+        for pkg in packages.iter_mut() {
+            if let Some(size) = size_map.get(&pkg.name) {
+                pkg.size = Some(*size);
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -56,6 +148,9 @@ impl PackageManager for UvManager {
     }
 
     async fn check_available(&self) -> Result<bool> {
+        if self.scope == PythonScope::User {
+            return Ok(false);
+        }
         match Command::new("uv").arg("--version").output().await {
             Ok(output) => Ok(output.status.success()),
             Err(_) => Ok(false),
@@ -63,87 +158,26 @@ impl PackageManager for UvManager {
     }
 
     async fn list_installed(&self) -> Result<Vec<Package>> {
-        if let Some(cached) = self.cache.get("uv").await? {
+        if let Some(cached) = self.cache.get(&self.cache_key).await? {
             debug!("使用缓存的 uv 包列表");
             return Ok(cached);
         }
 
-        let output = self.exec(&["pip", "list"]).await?;
-
-        let packages: Vec<Package> = output
-            .lines()
-            .skip(2)
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() < 2 {
-                    return None;
-                }
-
-                Some(Package {
-                    name: parts[0].to_string(),
-                    version: parts[1].to_string(),
-                    manager: "uv".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
-                })
-            })
-            .collect();
+        let output = self.exec(&["pip", "list", "--format", "json"]).await?;
+        let mut packages = Self::parse_list_output(&output)?;
+        self.collect_sizes(&mut packages).await;
 
-        self.cache.set("uv", &packages).await?;
+        self.cache.set(&self.cache_key, &packages).await?;
         debug!("uv 已安装包: {} 个", packages.len());
 
         Ok(packages)
     }
 
     async fn search(&self, query: &str) -> Result<Vec<Package>> {
-        let output = self.exec(&["pip", "search", query]).await?;
-
-        let packages: Vec<Package> = output
-            .lines()
-            .skip(2)
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.is_empty() {
-                    return None;
-                }
-
-                let name = parts[0].to_string();
-                let version = parts
-                    .get(1)
-                    .map(|s| s.trim_matches(&['(', ')'] as &[char]).to_string())
-                    .unwrap_or_default();
-
-                Some(Package {
-                    name,
-                    version,
-                    manager: "uv".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: false,
-                    latest_version: None,
-                })
-            })
-            .collect();
-
-        Ok(packages)
+        // PyPI 已关闭 XML-RPC 搜索接口，改为基于简单索引与 JSON API 搜索
+        PypiIndex::from_env()
+            .search(&self.cache, query, "uv")
+            .await
     }
 
     async fn get_info(&self, name: &str) -> Result<Package> {
@@ -174,29 +208,31 @@ impl PackageManager for UvManager {
             description,
             homepage,
             license,
-            installed_path: None,
-            size: None,
-            outdated: false,
-            latest_version: None,
+            ..Default::default()
         })
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
+        self.install_batch(&[(name, version)], force).await
+    }
+
+    async fn install_batch(&self, packages: &[(&str, Option<&str>)], force: bool) -> Result<()> {
         let mut args: Vec<String> = vec!["pip".to_string(), "install".to_string()];
         if force {
             args.push("--force-reinstall".to_string());
         }
-        let target = match version {
-            Some(v) => format!("{}=={}", name, v),
-            None => name.to_string(),
-        };
-        args.push(target);
+        // 支持 `==24.*`、`>=1.0` 等原生约束，纯版本号按 `==` 处理
+        args.extend(
+            packages
+                .iter()
+                .map(|(name, version)| python_requirement(name, *version)),
+        );
 
         info!("uv pip install {}", args.join(" "));
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
-        self.cache.invalidate("uv").await?;
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
@@ -204,64 +240,74 @@ impl PackageManager for UvManager {
     async fn upgrade(&self, name: &str) -> Result<()> {
         info!("uv pip install --upgrade {}", name);
         self.exec(&["pip", "install", "--upgrade", name]).await?;
-        self.cache.invalidate("uv").await?;
+        self.cache.invalidate(&self.cache_key).await?;
+
+        Ok(())
+    }
+
+    /// `uv add` 写入 pyproject.toml 并同步项目环境，开发依赖写入 `dev` 依赖组
+    async fn add_dependency(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        dependency_type: DependencyType,
+    ) -> Result<()> {
+        let requirement = python_requirement(name, version);
+        let mut args = vec!["add", requirement.as_str()];
+        match dependency_type {
+            DependencyType::Prod => {}
+            DependencyType::Dev => args.push("--dev"),
+            // 可选依赖需要指定 extra 名称，Python 没有 peer 依赖
+            DependencyType::Optional | DependencyType::Peer => {
+                return Err(BoxyError::UnsupportedOperation {
+                    manager: "uv".to_string(),
+                    operation: format!("add --{}", dependency_type.as_str()),
+                });
+            }
+        }
+        info!("uv {}", args.join(" "));
+        self.exec(&args).await?;
+        self.cache.invalidate(&self.cache_key).await?;
+
+        Ok(())
+    }
+
+    async fn remove_dependency(&self, name: &str) -> Result<()> {
+        info!("uv remove {}", name);
+        // 只在 dev 依赖组中声明的依赖需要加上 --dev 才能移除
+        if self.exec(&["remove", name]).await.is_err() {
+            self.exec(&["remove", "--dev", name]).await?;
+        }
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
 
     async fn uninstall(&self, name: &str, force: bool) -> Result<()> {
+        self.uninstall_batch(&[name], force).await
+    }
+
+    async fn uninstall_batch(&self, names: &[&str], force: bool) -> Result<()> {
         let mut args: Vec<String> = vec!["pip".to_string(), "uninstall".to_string()];
         if force {
             args.push("--yes".to_string());
         }
-        args.push(name.to_string());
+        args.extend(names.iter().map(|name| name.to_string()));
 
-        warn!("uv pip uninstall {} (force: {})", name, force);
+        warn!("uv pip uninstall {} (force: {})", names.join(" "), force);
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
-        self.cache.invalidate("uv").await?;
+        self.cache.invalidate(&self.cache_key).await?;
 
         Ok(())
     }
 
     async fn check_outdated(&self) -> Result<Vec<Package>> {
-        let output = self.exec(&["pip", "list", "--outdated"]).await?;
-
-        let outdated: Vec<Package> = output
-            .lines()
-            .skip(2)
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.is_empty() {
-                    return None;
-                }
-
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() < 3 {
-                    return None;
-                }
-
-                let name = parts[0].to_string();
-                let current_version = parts[1].to_string();
-                let latest_version = parts[2].trim_matches(&['(', ')'] as &[char]).to_string();
-
-                Some(Package {
-                    name,
-                    version: current_version,
-                    manager: "uv".to_string(),
-                    description: None,
-                    homepage: None,
-                    license: None,
-                    installed_path: None,
-                    size: None,
-                    outdated: true,
-                    latest_version: Some(latest_version),
-                })
-            })
-            .collect();
-
-        Ok(outdated)
+        let output = self
+            .exec(&["pip", "list", "--outdated", "--format", "json"])
+            .await?;
+        Self::parse_list_output(&output)
     }
 
     /// 清理 uv 缓存
@@ -273,10 +319,36 @@ impl PackageManager for UvManager {
         Ok(())
     }
 
+    /// uv 没有按时间清理的命令，执行 `uv cache prune` 删除不再被引用的缓存条目
+    ///
+    /// `uv cache prune` 不支持预演，dry_run 时无法估算可释放的空间。
+    async fn prune_cache(&self, _older_than: Duration, dry_run: bool) -> Result<Option<u64>> {
+        if !dry_run {
+            info!("uv cache prune");
+            self.exec(&["cache", "prune"]).await?;
+        }
+        Ok(None)
+    }
+
+    /// uv 的全局缓存目录（`uv cache dir`）
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        let output = self.exec(&["cache", "dir"]).await?;
+        Ok(existing_dirs([PathBuf::from(output.trim())]))
+    }
+
+    fn cache_key(&self) -> &str {
+        &self.cache_key
+    }
+
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
-        &[ListInstalled, SearchRemote, VersionSelection]
+        &[
+            ListInstalled,
+            SearchRemote,
+            VersionSelection,
+            BatchInstall,
+        ]
     }
 }
 
@@ -287,18 +359,54 @@ mod tests {
     #[test]
     fn test_uv_manager_creation() {
         let cache = Arc::new(Cache::new().unwrap());
-        let manager = UvManager::new(cache.clone(), false);
+        let manager = UvManager::new(cache.clone(), PythonScope::Default, None);
         assert_eq!(manager.name(), "uv");
     }
 
+    #[test]
+    fn test_parse_list_output() {
+        let packages =
+            UvManager::parse_list_output(include_str!("../tests/fixtures/uv-0.4-list.json"))
+                .unwrap();
+        assert_eq!(packages.len(), 4);
+        assert_eq!(packages[0].name, "black");
+        assert_eq!(
+            packages[2].source.as_deref(),
+            Some("editable: /home/dev/src/demo-tool")
+        );
+        assert!(packages.iter().all(|pkg| !pkg.outdated));
+
+        let empty =
+            UvManager::parse_list_output(include_str!("../tests/fixtures/uv-0.5-list-empty.json"))
+                .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_parse_outdated_output() {
+        let packages = UvManager::parse_list_output(include_str!(
+            "../tests/fixtures/uv-0.5-list-outdated.json"
+        ))
+        .unwrap();
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[1].name, "click");
+        assert_eq!(packages[1].latest_version.as_deref(), Some("8.1.8"));
+        assert!(packages[1].outdated);
+    }
+
     #[test]
     fn test_capabilities() {
         let cache = Arc::new(Cache::new().unwrap());
-        let manager = UvManager::new(cache, false);
+        let manager = UvManager::new(
+            cache,
+            PythonScope::Venv(PathBuf::from("/tmp/demo/.venv")),
+            None,
+        );
         let caps = manager.capabilities();
 
         assert!(caps.contains(&Capability::ListInstalled));
         assert!(caps.contains(&Capability::SearchRemote));
         assert!(caps.contains(&Capability::VersionSelection));
+        assert!(manager.cache_key().starts_with("uv-venv-"));
     }
 }
@@ -0,0 +1,155 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// `yarn info --all --json --cache` 输出中的一个依赖
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BerryPackage {
+    pub name: String,
+    pub version: String,
+    /// 非 npm 来源的描述符，如 `portal:../shared`、`https://github.com/...`
+    pub source: Option<String>,
+    /// 缓存中的 zip 文件，PnP 模式下包直接从这里加载
+    pub cache_path: Option<PathBuf>,
+    pub cache_size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InfoLine {
+    value: String,
+    #[serde(default)]
+    children: InfoChildren,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct InfoChildren {
+    version: Option<String>,
+    cache: Option<CacheEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CacheEntry {
+    path: Option<String>,
+    size: Option<u64>,
+}
+
+/// 解析 `yarn info --json`（每行一个 JSON 对象），跳过工作区自身
+pub(crate) fn parse_info_output(output: &str) -> Vec<BerryPackage> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<InfoLine>(line.trim()).ok())
+        .filter_map(|line| {
+            let (name, reference) = split_locator(&line.value)?;
+            if reference.starts_with("workspace:") {
+                return None;
+            }
+            let npm_version = reference.strip_prefix("npm:");
+            let version = line
+                .children
+                .version
+                .or_else(|| npm_version.map(str::to_string))
+                .unwrap_or_else(|| "unknown".to_string());
+            // 内置的兼容补丁（如 fsevents、typescript）仍来自 npm
+            let from_npm = npm_version.is_some()
+                || (reference.starts_with("patch:") && reference.contains("npm%3A"));
+            let cache = line.children.cache;
+            Some(BerryPackage {
+                name: name.to_string(),
+                version,
+                source: (!from_npm).then(|| reference.to_string()),
+                cache_path: cache
+                    .as_ref()
+                    .and_then(|cache| cache.path.as_ref())
+                    .map(PathBuf::from),
+                cache_size: cache.and_then(|cache| cache.size),
+            })
+        })
+        .collect()
+}
+
+/// 把 `@scope/name@npm:1.0.0` 拆为包名与引用
+fn split_locator(value: &str) -> Option<(&str, &str)> {
+    // 作用域包名以 @ 开头，从第二个字符开始查找分隔符
+    let index = value.get(1..)?.find('@')? + 1;
+    Some((&value[..index], &value[index + 1..]))
+}
+
+/// 解析 `yarn npm info --json`（每个包一行）
+pub(crate) fn parse_npm_info(output: &str) -> Vec<Value> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+        .filter(Value::is_object)
+        .collect()
+}
+
+/// manifest 中的 license 可能是字符串或 `{ "type": ... }`
+pub(crate) fn manifest_license(manifest: &Value) -> Option<String> {
+    let license = manifest.get("license")?;
+    license
+        .as_str()
+        .or_else(|| license.get("type").and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+/// 项目是否使用 Plug'n'Play（没有 node_modules，依赖从缓存 zip 中加载）
+pub(crate) fn is_pnp(project: &Path) -> bool {
+    project.join(".pnp.cjs").is_file() || project.join(".pnp.js").is_file()
+}
+
+/// `yarn --version` 输出的主版本号
+pub(crate) fn major_version(output: &str) -> Option<u64> {
+    output.trim().split('.').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_info_output() {
+        let packages = parse_info_output(include_str!("../tests/fixtures/berry-4.1-info-all.json"));
+        assert_eq!(packages.len(), 4);
+
+        let babel = &packages[0];
+        assert_eq!(babel.name, "@babel/core");
+        assert_eq!(babel.version, "7.23.2");
+        assert_eq!(babel.source, None);
+        assert_eq!(babel.cache_size, Some(193_716));
+        assert!(babel
+            .cache_path
+            .as_ref()
+            .unwrap()
+            .ends_with("@babel-core-npm-7.23.2-b93f586907-003897718d.zip"));
+
+        assert_eq!(packages[1].name, "lodash");
+        assert_eq!(packages[2].name, "typescript");
+        assert_eq!(packages[2].source, None);
+        assert_eq!(packages[3].name, "shared");
+        assert_eq!(
+            packages[3].source.as_deref(),
+            Some("portal:../shared::locator=app%40workspace%3A.")
+        );
+    }
+
+    #[test]
+    fn test_parse_npm_info() {
+        let manifests = parse_npm_info(include_str!("../tests/fixtures/berry-4.1-npm-info.json"));
+        assert_eq!(manifests.len(), 2);
+        assert_eq!(manifests[0]["version"], "4.17.21");
+        assert_eq!(manifest_license(&manifests[0]).as_deref(), Some("MIT"));
+        assert_eq!(
+            manifest_license(&manifests[1]).as_deref(),
+            Some("Apache-2.0")
+        );
+    }
+
+    #[test]
+    fn test_major_version() {
+        assert_eq!(major_version("1.22.19\n"), Some(1));
+        assert_eq!(major_version("4.1.0"), Some(4));
+        assert_eq!(major_version(""), None);
+    }
+}
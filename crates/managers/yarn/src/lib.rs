@@ -1,11 +1,15 @@
 use async_trait::async_trait;
 use boxy_cache::Cache;
 use boxy_core::{
+    disk::{existing_dirs, DiskUsage, SizeSource},
+    license::package_json_license,
     manager::PackageManager,
-    package::{Capability, Package},
+    manifest::{dependency_ranges, max_satisfying, read_package_json},
+    package::{Capability, DependencyType, Package},
+    version::compare_versions,
 };
 use boxy_error::{BoxyError, Result};
-use serde::Deserialize;
+use serde_json::Value;
 use std::{
     collections::HashMap,
     collections::hash_map::DefaultHasher,
@@ -15,17 +19,30 @@ use std::{
     sync::Arc,
 };
 use tokio::process::Command;
+use tokio::sync::OnceCell;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
 
+mod berry;
+
+use berry::{is_pnp, major_version, manifest_license, parse_info_output, parse_npm_info};
+
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
-const MAX_SIZE_PACKAGES: usize = 1000;
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Yarn 的版本线：1.x 为 Classic，2 及以上为 Berry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum YarnFlavor {
+    Classic,
+    Berry,
+}
 
 pub struct YarnManager {
     cache: Arc<Cache>,
     global: bool,
     workdir: Option<PathBuf>,
     cache_key: String,
+    flavor: OnceCell<YarnFlavor>,
 }
 
 impl YarnManager {
@@ -36,6 +53,40 @@ impl YarnManager {
             global,
             workdir,
             cache_key,
+            flavor: OnceCell::new(),
+        }
+    }
+
+    /// 在项目目录中执行 `yarn --version` 判断版本线
+    ///
+    /// corepack 的 `packageManager` 字段与 `.yarnrc.yml` 的 `yarnPath` 会让不同项目使用不同版本。
+    async fn flavor(&self) -> YarnFlavor {
+        *self
+            .flavor
+            .get_or_init(|| async {
+                let mut cmd = Command::new("yarn");
+                cmd.arg("--version");
+                if let Some(workdir) = &self.workdir {
+                    cmd.current_dir(workdir);
+                }
+                let version = match timeout(VERSION_TIMEOUT, cmd.output()).await {
+                    Ok(Ok(output)) if output.status.success() => {
+                        major_version(&String::from_utf8_lossy(&output.stdout))
+                    }
+                    _ => None,
+                };
+                match version {
+                    Some(major) if major >= 2 => YarnFlavor::Berry,
+                    _ => YarnFlavor::Classic,
+                }
+            })
+            .await
+    }
+
+    fn project_dir(&self) -> Option<PathBuf> {
+        match &self.workdir {
+            Some(dir) => Some(dir.clone()),
+            None => env::current_dir().ok(),
         }
     }
 
@@ -51,8 +102,21 @@ impl YarnManager {
     }
 
     async fn exec(&self, args: &[&str]) -> Result<String> {
+        self.exec_with(args, false).await
+    }
+
+    /// `allow_failure` 为 true 时，命令失败但有输出也返回输出（如存在可更新包时的 `yarn outdated`）
+    async fn exec_with(&self, args: &[&str], allow_failure: bool) -> Result<String> {
         let mut cmd_args = Vec::new();
         if self.global {
+            // Berry 移除了 `yarn global`，全局工具应改用 `yarn dlx` 或其他包管理器
+            if self.flavor().await == YarnFlavor::Berry {
+                return Err(BoxyError::UnsupportedOperation {
+                    manager: "yarn".to_string(),
+                    operation: "全局范围（Yarn 2+ 不再支持 yarn global，请在项目中使用或改用 npm、pnpm）"
+                        .to_string(),
+                });
+            }
             cmd_args.push("global");
         }
         cmd_args.extend_from_slice(args);
@@ -73,7 +137,7 @@ impl YarnManager {
                 exit_code: -1,
             })?;
 
-        if output.status.success() {
+        if output.status.success() || (allow_failure && !output.stdout.is_empty()) {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
             Err(BoxyError::CommandFailed {
@@ -85,6 +149,9 @@ impl YarnManager {
     }
 
     async fn resolve_root(&self) -> Option<PathBuf> {
+        if self.global && self.flavor().await == YarnFlavor::Berry {
+            return None;
+        }
         if self.global {
             let output = self.exec(&["dir"]).await.ok()?;
             let root = output.lines().next()?.trim();
@@ -106,71 +173,170 @@ impl YarnManager {
         }
     }
 
-    async fn collect_sizes(&self, root: &Path, names: &[String]) -> Result<HashMap<String, u64>> {
-        let mut items: Vec<(String, PathBuf)> = Vec::new();
-        for name in names {
-            let path = root.join(name);
-            if path.exists() {
-                items.push((name.clone(), path));
-            }
-        }
-
-        if items.is_empty() {
-            return Ok(HashMap::new());
-        }
+    async fn collect_sizes(&self, root: &Path, names: &[String]) -> HashMap<String, u64> {
+        let items = names
+            .iter()
+            .map(|name| (name.clone(), SizeSource::Path(root.join(name))))
+            .collect();
+        DiskUsage::new(self.cache.clone())
+            .measure(self.cache_key(), items)
+            .await
+    }
 
-        let mut sizes = HashMap::new();
-        for chunk in items.chunks(100) {
-            let mut cmd = Command::new("du");
-            cmd.arg("-sk");
-            let mut path_map = HashMap::new();
-            for (name, path) in chunk {
-                let path_str = path.to_string_lossy().to_string();
-                path_map.insert(path_str.clone(), name.clone());
-                cmd.arg(path_str);
-            }
+    /// 解析 Classic 的 `yarn outdated --json`
+    ///
+    /// 输出为每行一个 JSON 对象，其中 `table` 的表头给出列名（Package、Current、Wanted、Latest、
+    /// Package Type 等），工作区项目会多出 Workspace 列。
+    fn parse_outdated_output(output: &str) -> Vec<Package> {
+        let Some(table) = output
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+            .find(|line| line["type"].as_str() == Some("table"))
+        else {
+            return Vec::new();
+        };
+        let head: Vec<&str> = table["data"]["head"]
+            .as_array()
+            .map(|head| head.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let column = |name: &str| head.iter().position(|column| *column == name);
+        let (Some(name_index), Some(current_index), Some(latest_index)) =
+            (column("Package"), column("Current"), column("Latest"))
+        else {
+            return Vec::new();
+        };
+        let wanted_index = column("Wanted");
+        let type_index = column("Package Type");
+
+        let mut packages: Vec<Package> = table["data"]["body"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|row| {
+                let cell = |index: Option<usize>| {
+                    row.get(index?)?.as_str().map(str::to_string)
+                };
+                Some(Package {
+                    name: cell(Some(name_index))?,
+                    version: cell(Some(current_index))?,
+                    manager: "yarn".to_string(),
+                    outdated: true,
+                    latest_version: cell(Some(latest_index)),
+                    dependency_type: match cell(type_index).as_deref() {
+                        Some("dependencies") => Some(DependencyType::Prod),
+                        Some("devDependencies") => Some(DependencyType::Dev),
+                        Some("optionalDependencies") => Some(DependencyType::Optional),
+                        _ => None,
+                    },
+                    wanted_version: cell(wanted_index),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        packages
+    }
 
-            let output = cmd.output().await.map_err(|_| BoxyError::CommandFailed {
-                manager: "yarn".to_string(),
-                command: "du -sk".to_string(),
-                exit_code: -1,
-            })?;
+    /// 列出 Berry 项目的依赖
+    ///
+    /// node-modules 链接模式下统计 node_modules 中的目录；PnP 模式下包以 zip 形式存放在缓存中，
+    /// 使用 `--cache` 给出的 zip 路径与大小。
+    async fn list_berry(&self) -> Result<Vec<Package>> {
+        let output = self.exec(&["info", "--all", "--json", "--cache"]).await?;
+        let entries = parse_info_output(&output);
+        let pnp = self.project_dir().is_some_and(|dir| is_pnp(&dir));
+        let root = if pnp {
+            None
+        } else {
+            self.resolve_root().await
+        };
 
-            if !output.status.success() {
-                return Err(BoxyError::CommandFailed {
+        let mut packages: Vec<Package> = entries
+            .into_iter()
+            .map(|entry| {
+                let (installed_path, size) = match &root {
+                    Some(_) => (None, None),
+                    None => (
+                        entry
+                            .cache_path
+                            .map(|path| path.to_string_lossy().to_string()),
+                        entry.cache_size,
+                    ),
+                };
+                Package {
+                    name: entry.name,
+                    version: entry.version,
                     manager: "yarn".to_string(),
-                    command: "du -sk".to_string(),
-                    exit_code: output.status.code().unwrap_or(-1),
-                });
-            }
-
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
+                    installed_path,
+                    size,
+                    source: entry.source,
+                    ..Default::default()
                 }
-                let mut parts = line.split_whitespace();
-                let size_kb = parts.next().and_then(|value| value.parse::<u64>().ok());
-                let size_value = match size_kb {
-                    Some(value) => value,
-                    None => continue,
-                };
-                let path_value = match parts.next() {
-                    Some(value) => {
-                        let prefix_len = line.find(value).unwrap_or(0);
-                        line[prefix_len..].trim()
-                    }
-                    None => continue,
-                };
+            })
+            .collect();
 
-                if let Some(name) = path_map.get(path_value) {
-                    sizes.insert(name.clone(), size_value.saturating_mul(1024));
+        if let Some(root) = root {
+            let names: Vec<String> = packages.iter().map(|pkg| pkg.name.clone()).collect();
+            let size_map = self.collect_sizes(&root, &names).await;
+            for pkg in packages.iter_mut() {
+                if let Some(size) = size_map.get(&pkg.name) {
+                    pkg.size = Some(*size);
+                    pkg.installed_path = Some(root.join(&pkg.name).to_string_lossy().to_string());
                 }
+                pkg.license = package_json_license(&root.join(&pkg.name));
             }
         }
+        Ok(packages)
+    }
 
-        Ok(sizes)
+    /// Berry 没有 `yarn outdated`，通过 `yarn npm info` 查询 npm 依赖的最新版本
+    ///
+    /// wanted 为发布版本中满足 package.json 版本范围的最高版本。
+    async fn outdated_berry(&self) -> Result<Vec<Package>> {
+        let installed: Vec<Package> = self
+            .list_installed()
+            .await?
+            .into_iter()
+            .filter(|pkg| pkg.source.is_none())
+            .collect();
+        if installed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut args = vec!["npm", "info"];
+        args.extend(installed.iter().map(|pkg| pkg.name.as_str()));
+        args.extend(["--fields", "name,version,versions", "--json"]);
+        let output = self.exec(&args).await?;
+        let registry: HashMap<String, Value> = parse_npm_info(&output)
+            .into_iter()
+            .filter_map(|manifest| Some((manifest["name"].as_str()?.to_string(), manifest)))
+            .collect();
+        let ranges = self
+            .project_dir()
+            .and_then(|dir| read_package_json(&dir))
+            .map(|manifest| dependency_ranges(&manifest))
+            .unwrap_or_default();
+
+        Ok(installed
+            .into_iter()
+            .filter_map(|mut pkg| {
+                let manifest = registry.get(&pkg.name)?;
+                let latest = manifest["version"].as_str()?;
+                if compare_versions(latest, &pkg.version) != std::cmp::Ordering::Greater {
+                    return None;
+                }
+                pkg.range = ranges.get(&pkg.name).cloned();
+                pkg.wanted_version = pkg.range.as_deref().and_then(|range| {
+                    let versions = manifest["versions"].as_array()?;
+                    max_satisfying(versions.iter().filter_map(Value::as_str), range)
+                        .map(str::to_string)
+                });
+                pkg.outdated = true;
+                pkg.latest_version = Some(latest.to_string());
+                Some(pkg)
+            })
+            .collect())
     }
 }
 
@@ -197,6 +363,13 @@ impl PackageManager for YarnManager {
             return Ok(cached);
         }
 
+        if self.flavor().await == YarnFlavor::Berry {
+            let packages = self.list_berry().await?;
+            self.cache.set(self.cache_key(), &packages).await?;
+            debug!("yarn (Berry) 已安装包: {} 个", packages.len());
+            return Ok(packages);
+        }
+
         let output = self.exec(&["list", "--json"]).await?;
 
         // yarn list --json 输出格式是每行一个 JSON 对象
@@ -215,17 +388,12 @@ impl PackageManager for YarnManager {
                         name,
                         version,
                         manager: "yarn".to_string(),
-                        description: None,
-                        homepage: None,
-                        license: None,
                         installed_path: if self.global {
                             Some("~/.yarn/global".to_string())
                         } else {
                             None
                         },
-                        size: None,
-                        outdated: false,
-                        latest_version: None,
+                        ..Default::default()
                     })
                 } else {
                     None
@@ -233,27 +401,18 @@ impl PackageManager for YarnManager {
             })
             .collect();
 
-        if packages.len() <= MAX_SIZE_PACKAGES {
-            if let Some(root) = self.resolve_root().await {
-                let names: Vec<String> =
-                    packages.iter().map(|pkg| pkg.name.clone()).collect();
-                match self.collect_sizes(&root, &names).await {
-                    Ok(size_map) => {
-                        for pkg in packages.iter_mut() {
-                            if let Some(size) = size_map.get(&pkg.name) {
-                                pkg.size = Some(*size);
-                                pkg.installed_path =
-                                    Some(root.join(&pkg.name).to_string_lossy().to_string());
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        warn!("yarn 获取包大小失败: {}", err);
-                    }
+        if let Some(root) = self.resolve_root().await {
+            let names: Vec<String> = packages.iter().map(|pkg| pkg.name.clone()).collect();
+            let size_map = self.collect_sizes(&root, &names).await;
+            for pkg in packages.iter_mut() {
+                if let Some(size) = size_map.get(&pkg.name) {
+                    pkg.size = Some(*size);
+                    pkg.installed_path = Some(root.join(&pkg.name).to_string_lossy().to_string());
+                }
+                if pkg.license.is_none() {
+                    pkg.license = package_json_license(&root.join(&pkg.name));
                 }
             }
-        } else {
-            warn!("yarn 包数量过多，跳过大小统计");
         }
 
         self.cache.set(self.cache_key(), &packages).await?;
@@ -263,6 +422,12 @@ impl PackageManager for YarnManager {
     }
 
     async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if self.flavor().await == YarnFlavor::Berry {
+            return Err(BoxyError::UnsupportedOperation {
+                manager: "yarn".to_string(),
+                operation: "search（Yarn 2+ 没有搜索命令）".to_string(),
+            });
+        }
         let output = self.exec(&["search", "--json", query]).await?;
 
         let data: serde_json::Value =
@@ -283,12 +448,7 @@ impl PackageManager for YarnManager {
                         version,
                         manager: "yarn".to_string(),
                         description,
-                        homepage: None,
-                        license: None,
-                        installed_path: None,
-                        size: None,
-                        outdated: false,
-                        latest_version: None,
+                        ..Default::default()
                     })
                 })
                 .collect()
@@ -300,12 +460,28 @@ impl PackageManager for YarnManager {
     }
 
     async fn get_info(&self, name: &str) -> Result<Package> {
-        let output = self.exec(&["info", "--json", name]).await?;
-
-        let info: serde_json::Value =
-            serde_json::from_str(&output).map_err(|e| BoxyError::JsonError {
-                message: format!("解析 yarn info 输出失败: {}", e),
-            })?;
+        let info = if self.flavor().await == YarnFlavor::Berry {
+            let output = self.exec(&["npm", "info", name, "--json"]).await?;
+            let mut manifests = parse_npm_info(&output);
+            if manifests.is_empty() {
+                return Err(BoxyError::PackageNotFound {
+                    manager: "yarn".to_string(),
+                    package: name.to_string(),
+                });
+            }
+            manifests.remove(0)
+        } else {
+            let output = self.exec(&["info", "--json", name]).await?;
+            let info: serde_json::Value =
+                serde_json::from_str(&output).map_err(|e| BoxyError::JsonError {
+                    message: format!("解析 yarn info 输出失败: {}", e),
+                })?;
+            // Classic 的输出为 {"type":"inspect","data":{...}}
+            match info.get("data") {
+                Some(data) if data.is_object() => data.clone(),
+                _ => info,
+            }
+        };
 
         let mut package = Package {
             name: info["name"].as_str().unwrap_or(name).to_string(),
@@ -313,30 +489,23 @@ impl PackageManager for YarnManager {
             manager: "yarn".to_string(),
             description: info["description"].as_str().map(|s| s.to_string()),
             homepage: info["homepage"].as_str().map(|s| s.to_string()),
-            license: info["license"].as_str().map(|s| s.to_string()),
+            license: manifest_license(&info),
             installed_path: if self.global {
                 Some("~/.yarn/global".to_string())
             } else {
                 None
             },
-            size: None,
-            outdated: false,
             latest_version: info["version"].as_str().map(|s| s.to_string()),
+            ..Default::default()
         };
 
         if let Some(root) = self.resolve_root().await {
             let names = vec![package.name.clone()];
-            match self.collect_sizes(&root, &names).await {
-                Ok(size_map) => {
-                    if let Some(size) = size_map.get(&package.name) {
-                        package.size = Some(*size);
-                        package.installed_path =
-                            Some(root.join(&package.name).to_string_lossy().to_string());
-                    }
-                }
-                Err(err) => {
-                    warn!("yarn 获取包大小失败: {}", err);
-                }
+            let size_map = self.collect_sizes(&root, &names).await;
+            if let Some(size) = size_map.get(&package.name) {
+                package.size = Some(*size);
+                package.installed_path =
+                    Some(root.join(&package.name).to_string_lossy().to_string());
             }
         }
 
@@ -344,15 +513,20 @@ impl PackageManager for YarnManager {
     }
 
     async fn install(&self, name: &str, version: Option<&str>, force: bool) -> Result<()> {
+        self.install_batch(&[(name, version)], force).await
+    }
+
+    async fn install_batch(&self, packages: &[(&str, Option<&str>)], force: bool) -> Result<()> {
         let mut args: Vec<String> = vec!["add".to_string()];
-        if force {
+        // Berry 的 add、remove 没有 --force
+        let berry = self.flavor().await == YarnFlavor::Berry;
+        if force && !berry {
             args.push("--force".to_string());
         }
-        let target = match version {
+        args.extend(packages.iter().map(|(name, version)| match version {
             Some(v) => format!("{}@{}", name, v),
             None => name.to_string(),
-        };
-        args.push(target);
+        }));
 
         info!("yarn add {}", args.join(" "));
 
@@ -363,21 +537,68 @@ impl PackageManager for YarnManager {
         Ok(())
     }
 
+    /// `yarn add` 按依赖类型写入 package.json 对应的字段，Classic 与 Berry 的参数相同
+    async fn add_dependency(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        dependency_type: DependencyType,
+    ) -> Result<()> {
+        let target = match version {
+            Some(v) => format!("{}@{}", name, v),
+            None => name.to_string(),
+        };
+        let mut args = vec!["add", target.as_str()];
+        match dependency_type {
+            DependencyType::Prod => {}
+            DependencyType::Dev => args.push("--dev"),
+            DependencyType::Optional => args.push("--optional"),
+            DependencyType::Peer => args.push("--peer"),
+        }
+        info!("yarn {}", args.join(" "));
+        self.exec(&args).await?;
+        self.cache.invalidate(self.cache_key()).await?;
+
+        Ok(())
+    }
+
     async fn upgrade(&self, name: &str) -> Result<()> {
-        info!("yarn upgrade {}", name);
-        self.exec(&["upgrade", name]).await?;
+        // Berry 的 `yarn up` 会改写 package.json 中的范围，`-R` 只在现有范围内重新解析
+        let args = match self.flavor().await {
+            YarnFlavor::Classic => vec!["upgrade", name],
+            YarnFlavor::Berry => vec!["up", "-R", name],
+        };
+        info!("yarn {}", args.join(" "));
+        self.exec(&args).await?;
+        self.cache.invalidate(self.cache_key()).await?;
+
+        Ok(())
+    }
+
+    async fn upgrade_latest(&self, name: &str) -> Result<()> {
+        let args = match self.flavor().await {
+            YarnFlavor::Classic => vec!["upgrade", name, "--latest"],
+            YarnFlavor::Berry => vec!["up", name],
+        };
+        info!("yarn {}", args.join(" "));
+        self.exec(&args).await?;
         self.cache.invalidate(self.cache_key()).await?;
 
         Ok(())
     }
 
     async fn uninstall(&self, name: &str, force: bool) -> Result<()> {
-        let mut args: Vec<String> = vec!["remove".to_string(), name.to_string()];
-        if force {
+        self.uninstall_batch(&[name], force).await
+    }
+
+    async fn uninstall_batch(&self, names: &[&str], force: bool) -> Result<()> {
+        let mut args: Vec<String> = vec!["remove".to_string()];
+        args.extend(names.iter().map(|name| name.to_string()));
+        if force && self.flavor().await == YarnFlavor::Classic {
             args.push("--force".to_string());
         }
 
-        warn!("yarn remove {} (force: {})", name, force);
+        warn!("yarn remove {} (force: {})", names.join(" "), force);
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         self.exec(&args_refs).await?;
@@ -387,49 +608,49 @@ impl PackageManager for YarnManager {
     }
 
     async fn check_outdated(&self) -> Result<Vec<Package>> {
-        let output = self.exec(&["outdated", "--json"]).await?;
-
-        if output.trim().is_empty() {
-            return Ok(Vec::new());
+        if self.flavor().await == YarnFlavor::Berry {
+            return self.outdated_berry().await;
+        }
+        // 存在可更新的包时 yarn outdated 以状态码 1 退出
+        let output = self.exec_with(&["outdated", "--json"], true).await?;
+        let mut packages = Self::parse_outdated_output(&output);
+        if self.global {
+            for pkg in packages.iter_mut() {
+                pkg.installed_path = Some("~/.yarn/global".to_string());
+            }
+        } else if let Some(manifest) = self.project_dir().and_then(|dir| read_package_json(&dir)) {
+            let ranges = dependency_ranges(&manifest);
+            for pkg in packages.iter_mut() {
+                pkg.range = ranges.get(&pkg.name).cloned();
+            }
         }
-
-        let outdated: HashMap<String, YarnOutdatedPackage> = serde_json::from_str(&output)
-            .map_err(|e| BoxyError::JsonError {
-                message: format!("解析 yarn outdated 输出失败: {}", e),
-            })?;
-
-        let packages = outdated
-            .into_iter()
-            .map(|(name, pkg)| Package {
-                name,
-                version: pkg.current,
-                manager: "yarn".to_string(),
-                description: None,
-                homepage: None,
-                license: None,
-                installed_path: if self.global {
-                    Some("~/.yarn/global".to_string())
-                } else {
-                    None
-                },
-                size: None,
-                outdated: true,
-                latest_version: Some(pkg.latest),
-            })
-            .collect();
 
         Ok(packages)
     }
 
     /// 清理 yarn 缓存
     ///
-    /// 执行 `yarn cache clean` 清理所有下载缓存
+    /// 执行 `yarn cache clean` 清理所有下载缓存，Berry 加上 `--all` 同时清理全局镜像
     async fn clean_cache(&self) -> Result<()> {
-        info!("yarn cache clean");
-        self.exec(&["cache", "clean"]).await?;
+        if self.flavor().await == YarnFlavor::Berry {
+            info!("yarn cache clean --all");
+            self.exec(&["cache", "clean", "--all"]).await?;
+        } else {
+            info!("yarn cache clean");
+            self.exec(&["cache", "clean"]).await?;
+        }
         Ok(())
     }
 
+    /// yarn 的全局缓存目录（Classic 为 `yarn cache dir`，Berry 为 `cacheFolder` 配置）
+    async fn cache_dirs(&self) -> Result<Vec<PathBuf>> {
+        let output = match self.flavor().await {
+            YarnFlavor::Classic => self.exec(&["cache", "dir"]).await?,
+            YarnFlavor::Berry => self.exec(&["config", "get", "cacheFolder"]).await?,
+        };
+        Ok(existing_dirs([PathBuf::from(output.trim())]))
+    }
+
     fn capabilities(&self) -> &[Capability] {
         use Capability::*;
 
@@ -438,15 +659,11 @@ impl PackageManager for YarnManager {
             SearchRemote,
             QueryDependencies,
             VersionSelection,
+            BatchInstall,
         ]
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct YarnOutdatedPackage {
-    current: String,
-    latest: String,
-}
 
 #[cfg(test)]
 mod tests {
@@ -459,6 +676,25 @@ mod tests {
         assert_eq!(manager.name(), "yarn");
     }
 
+    #[test]
+    fn test_parse_outdated_output() {
+        let packages = YarnManager::parse_outdated_output(include_str!(
+            "../tests/fixtures/yarn-1.22-outdated.json"
+        ));
+        assert_eq!(packages.len(), 3);
+
+        let react = &packages[1];
+        assert_eq!(react.name, "react");
+        assert_eq!(react.version, "18.2.0");
+        assert_eq!(react.wanted_version.as_deref(), Some("18.3.1"));
+        assert_eq!(react.latest_version.as_deref(), Some("19.2.0"));
+        assert!(react.needs_manifest_bump());
+
+        assert_eq!(packages[2].dependency_type, Some(DependencyType::Dev));
+        assert!(!packages[2].has_update_in_range());
+        assert!(YarnManager::parse_outdated_output("").is_empty());
+    }
+
     #[test]
     fn test_capabilities() {
         let cache = Arc::new(Cache::new().unwrap());
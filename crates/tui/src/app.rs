@@ -1,7 +1,11 @@
 use crate::managers::{create_manager, supports_global, MANAGER_NAMES};
 use anyhow::{Context, Result};
+use boxy_brew::{BrewManager, BrewScope, BrewTap};
 use boxy_cache::Cache;
-use boxy_core::{Job, JobStatus, ManagerExecutor, ManagerStatus, Operation, Package};
+use boxy_core::{
+  search_all, snapshot_installed, Capability, DiskUsage, Job, JobStatus, ManagerExecutor,
+  ManagerStatus, Operation, Package, PackageDiff,
+};
 use chrono::{DateTime, Utc};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::collections::HashMap;
@@ -59,6 +63,7 @@ pub enum PendingAction {
     package: String,
     force: bool,
   },
+  Untap { tap: String },
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +72,12 @@ pub enum ModalState {
   Logs { title: String, lines: Vec<String> },
   Success { title: String, message: String },
   Error { title: String, message: String },
+  /// brew 的 tap 列表；`input` 为正在输入的待添加 tap 名
+  Taps {
+    taps: Vec<BrewTap>,
+    selected: usize,
+    input: Option<String>,
+  },
 }
 
 impl App {
@@ -138,12 +149,18 @@ impl App {
         let cached_packages: Vec<Package> =
           cache.get(cache_key).await.unwrap_or(None).unwrap_or_default();
         let outdated_count = cached_packages.iter().filter(|pkg| pkg.outdated).count();
+        let cache_size = DiskUsage::new(cache.clone())
+          .cached_cache_usage(name)
+          .await
+          .and_then(|usage| usage.reclaimable);
         managers.push(ManagerStatus {
           name: name.to_string(),
           version: "".to_string(),
           available: true, // 假设可用，稍后检查
           package_count: cached_packages.len(),
           outdated_count,
+          installed_size: total_size(&cached_packages),
+          cache_size,
         });
       } else {
         managers.push(ManagerStatus {
@@ -152,6 +169,8 @@ impl App {
           available: false,
           package_count: 0,
           outdated_count: 0,
+          installed_size: None,
+          cache_size: None,
         });
       }
     }
@@ -180,7 +199,6 @@ impl App {
       .map(|name| {
         let cache = cache.clone();
         let manager_name = name.to_string();
-        let global = global;
         tokio::spawn(async move {
           let manager = create_manager(&manager_name, cache.clone(), global);
           if let Some(mgr) = manager {
@@ -194,12 +212,18 @@ impl App {
             let cached_packages: Vec<Package> =
               cache.get(cache_key).await.unwrap_or(None).unwrap_or_default();
             let outdated_count = cached_packages.iter().filter(|pkg| pkg.outdated).count();
+            let cache_size = DiskUsage::new(cache.clone())
+              .cached_cache_usage(&manager_name)
+              .await
+              .and_then(|usage| usage.reclaimable);
             ManagerStatus {
               name: manager_name,
               version: "".to_string(),
               available,
               package_count: cached_packages.len(),
               outdated_count,
+              installed_size: total_size(&cached_packages),
+              cache_size,
             }
           } else {
             ManagerStatus {
@@ -208,6 +232,8 @@ impl App {
               available: false,
               package_count: 0,
               outdated_count: 0,
+              installed_size: None,
+              cache_size: None,
             }
           }
         })
@@ -366,9 +392,36 @@ impl App {
         .iter()
         .filter(|pkg| pkg.outdated)
         .count();
+      manager.installed_size = total_size(&self.packages_all);
     }
   }
 
+  // 后台统计各管理器下载缓存的占用，完成后更新仪表盘
+  pub fn schedule_cache_usage(&mut self, handle: Arc<Mutex<App>>) {
+    let cache = self.cache.clone();
+    let global = self.global;
+    let names: Vec<String> = self
+      .managers
+      .iter()
+      .filter(|status| status.available)
+      .map(|status| status.name.clone())
+      .collect();
+    tokio::spawn(async move {
+      let usage = DiskUsage::new(cache.clone());
+      for name in names {
+        let Some(manager) = create_manager(&name, cache.clone(), global) else {
+          continue;
+        };
+        let result = usage.cache_usage(manager.as_ref()).await;
+        let mut app = handle.lock().await;
+        if let Some(status) = app.managers.iter_mut().find(|status| status.name == name) {
+          status.cache_size = result.reclaimable;
+          app.should_redraw = true;
+        }
+      }
+    });
+  }
+
   fn apply_search_filter(&mut self) {
     if self.search_query.is_empty() {
       self.packages = self.packages_all.clone();
@@ -551,6 +604,48 @@ impl App {
     false
   }
 
+  /// 用当前搜索关键词跨包管理器搜索远程仓库，结果显示在弹窗中
+  fn schedule_remote_search(&mut self, handle: Arc<Mutex<App>>) {
+    let query = self.search_query.trim().to_string();
+    if query.is_empty() {
+      self.status_message = "请先按 / 输入关键词 / Type a query with / first".to_string();
+      return;
+    }
+    self.status_message = format!("正在搜索 {} ...", query);
+    let cache = self.cache.clone();
+    let global = self.global;
+    tokio::spawn(async move {
+      let managers = MANAGER_NAMES
+        .iter()
+        .filter_map(|name| create_manager(name, cache.clone(), global))
+        .filter(|manager| manager.supports(Capability::SearchRemote))
+        .collect();
+      let outcome = search_all(managers, &query, Duration::from_secs(30)).await;
+
+      let mut lines = Vec::new();
+      for result in outcome.results.iter().take(50) {
+        lines.push(format!("{} [{}]", result.name, result.managers.join(", ")));
+        if let Some(desc) = &result.description {
+          lines.push(format!("    {}", desc));
+        }
+      }
+      for (manager, error) in &outcome.errors {
+        lines.push(format!("⚠ {}: {}", manager, error));
+      }
+      if lines.is_empty() {
+        lines.push("未找到匹配的包 / No results".to_string());
+      }
+
+      let mut app = handle.lock().await;
+      app.modal = Some(ModalState::Logs {
+        title: format!("搜索 / Search: {}", query),
+        lines,
+      });
+      app.status_message = format!("找到 {} 个结果", outcome.results.len());
+      app.should_redraw = true;
+    });
+  }
+
   pub fn show_logs_modal(&mut self) {
     if let Some(job) = self.current_job.as_ref() {
       self.modal = Some(ModalState::Logs {
@@ -574,6 +669,12 @@ impl App {
       return;
     }
 
+    if let Some(ModalState::Taps { input: Some(_), .. }) = self.modal {
+      self.handle_tap_input_key(key, handle);
+      self.should_redraw = true;
+      return;
+    }
+
     if key.code == KeyCode::Char('q') {
       self.should_quit = true;
       self.should_redraw = true;
@@ -615,22 +716,22 @@ impl App {
         self.schedule_load_packages(handle);
       }
       KeyCode::Char('/') => self.enter_search_mode(),
-      KeyCode::Char('a') => {
-        if self.selected_package().is_some() {
-          self.enter_action_menu();
-        }
-      },
+      KeyCode::Char('s') => self.schedule_remote_search(handle),
+      KeyCode::Char('a') if self.selected_package().is_some() => self.enter_action_menu(),
       KeyCode::Char('u') => self.request_update_selected(),
       KeyCode::Char('d') => self.request_uninstall_selected(false),
       KeyCode::Char('c') => self.cancel_current_job(),
       KeyCode::Char('r') => {
         let _ = self.refresh_manager_availability().await;
+        self.schedule_cache_usage(handle.clone());
         self.schedule_load_packages(handle);
       }
       KeyCode::Char('f') => {
         // 强制刷新当前管理器的包列表（清除缓存）
         self.schedule_force_refresh_packages(handle);
       }
+      // toggle_global 会修改状态，不能放进 match 守卫
+      #[allow(clippy::collapsible_match)]
       KeyCode::Char('g') => {
         if self.toggle_global() {
           // 切换成功，刷新所有管理器的统计数据，然后重新加载当前管理器的包列表
@@ -654,6 +755,9 @@ impl App {
         }
       },
       KeyCode::Char('m') => self.open_manager_detail(),
+      KeyCode::Char('t') if self.selected_manager_name() == Some("brew") => {
+        self.schedule_load_taps(handle);
+      }
       KeyCode::Char('L') => {
         self.show_logs_modal();
       }
@@ -666,6 +770,7 @@ impl App {
       KeyCode::Char('j') | KeyCode::Down => self.select_next_package(),
       KeyCode::Char('k') | KeyCode::Up => self.select_previous_package(),
       KeyCode::Char('/') => self.enter_search_mode(),
+      KeyCode::Char('s') => self.schedule_remote_search(handle),
       KeyCode::Char('a') | KeyCode::Enter => {
         if self.selected_package().is_some() {
           self.enter_action_menu();
@@ -677,6 +782,9 @@ impl App {
       KeyCode::Char('d') => self.request_uninstall_selected(false),
       KeyCode::Char('c') => self.cancel_current_job(),
       KeyCode::Char('b') | KeyCode::Esc => self.close_detail_view(),
+      KeyCode::Char('t') if self.selected_manager_name() == Some("brew") => {
+        self.schedule_load_taps(handle);
+      }
       KeyCode::Char('r') => {
         self.schedule_load_packages(handle);
       }
@@ -684,6 +792,8 @@ impl App {
         // 强制刷新当前管理器的包列表（清除缓存）
         self.schedule_force_refresh_packages(handle);
       }
+      // toggle_global 会修改状态，不能放进 match 守卫
+      #[allow(clippy::collapsible_match)]
       KeyCode::Char('g') => {
         if self.toggle_global() {
           // 切换成功，刷新所有管理器的统计数据，然后重新加载当前管理器的包列表
@@ -718,11 +828,9 @@ impl App {
         self.search_query.pop();
         self.apply_search_filter();
       }
-      KeyCode::Char(c) => {
-        if !key.modifiers.contains(KeyModifiers::CONTROL) {
-          self.search_query.push(c);
-          self.apply_search_filter();
-        }
+      KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+        self.search_query.push(c);
+        self.apply_search_filter();
       }
       _ => {}
     }
@@ -762,15 +870,140 @@ impl App {
         // 成功/失败提示：按任意键关闭
         self.close_modal();
       }
-      Some(ModalState::Logs { .. }) => {
-        // 日志查看：按 Esc 关闭
-        if key.code == KeyCode::Esc {
-          self.close_modal();
+      // 日志查看：按 Esc 关闭
+      Some(ModalState::Logs { .. }) if key.code == KeyCode::Esc => {
+        self.close_modal();
+      }
+      Some(ModalState::Taps { .. }) => self.handle_taps_key(key),
+      _ => {}
+    }
+    self.should_redraw = true;
+  }
+
+  fn handle_taps_key(&mut self, key: KeyEvent) {
+    let Some(ModalState::Taps {
+      taps,
+      selected,
+      input,
+    }) = self.modal.as_mut()
+    else {
+      return;
+    };
+    match key.code {
+      KeyCode::Esc => self.close_modal(),
+      KeyCode::Char('j') | KeyCode::Down if !taps.is_empty() => {
+        *selected = (*selected + 1) % taps.len();
+      }
+      KeyCode::Char('k') | KeyCode::Up if !taps.is_empty() => {
+        *selected = selected.checked_sub(1).unwrap_or(taps.len() - 1);
+      }
+      KeyCode::Char('a') => {
+        *input = Some(String::new());
+        self.status_message = "输入 tap 名（如 user/repo），Enter 添加，Esc 取消".to_string();
+      }
+      KeyCode::Char('d') => {
+        let Some(tap) = taps.get(*selected).map(|tap| tap.name.clone()) else {
+          return;
+        };
+        self.pending_action = Some(PendingAction::Untap { tap: tap.clone() });
+        self.modal = Some(ModalState::Confirm {
+          title: "Remove Tap".to_string(),
+          message: format!("Untap {}?", tap),
+        });
+      }
+      _ => {}
+    }
+  }
+
+  fn handle_tap_input_key(&mut self, key: KeyEvent, handle: Arc<Mutex<App>>) {
+    let Some(ModalState::Taps {
+      input: Some(input), ..
+    }) = self.modal.as_mut()
+    else {
+      return;
+    };
+    match key.code {
+      KeyCode::Esc => {
+        if let Some(ModalState::Taps { input, .. }) = self.modal.as_mut() {
+          *input = None;
+        }
+        self.status_message = "已取消添加 tap".to_string();
+      }
+      KeyCode::Enter => {
+        let tap = input.trim().to_string();
+        if tap.is_empty() {
+          return;
         }
+        self.spawn_tap_operation(handle, tap, true);
       }
-      None => {}
+      KeyCode::Backspace => {
+        input.pop();
+      }
+      KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => input.push(c),
+      _ => {}
     }
+  }
+
+  /// 读取 brew 的 tap 列表，显示在弹窗中
+  fn schedule_load_taps(&mut self, handle: Arc<Mutex<App>>) {
+    self.status_message = "正在读取 tap...".to_string();
+    let cache = self.cache.clone();
+    tokio::spawn(async move {
+      let result = BrewManager::new(cache, BrewScope::All).taps().await;
+      let mut app = handle.lock().await;
+      match result {
+        Ok(taps) => {
+          app.status_message = format!("{} 个 tap", taps.len());
+          app.modal = Some(ModalState::Taps {
+            taps,
+            selected: 0,
+            input: None,
+          });
+        }
+        Err(err) => {
+          app.modal = Some(ModalState::Error {
+            title: "读取 tap 失败 / Failed to load taps".to_string(),
+            message: err.to_string(),
+          });
+        }
+      }
+      app.should_redraw = true;
+    });
+  }
+
+  /// 添加或移除 tap，完成后重新显示 tap 列表
+  fn spawn_tap_operation(&mut self, handle: Arc<Mutex<App>>, tap: String, add: bool) {
+    self.modal = None;
+    self.status_message = format!("正在{} tap {} ...", if add { "添加" } else { "移除" }, tap);
     self.should_redraw = true;
+    let cache = self.cache.clone();
+    let executor = self.executor.clone();
+    tokio::spawn(async move {
+      let result = executor
+        .execute("brew", || async {
+          let manager = BrewManager::new(cache.clone(), BrewScope::All);
+          if add {
+            manager.tap(&tap, None).await
+          } else {
+            manager.untap(&tap, false).await
+          }
+        })
+        .await;
+      let mut app = handle.lock().await;
+      match result {
+        Ok(()) => {
+          app.status_message = format!("已{} tap {}", if add { "添加" } else { "移除" }, tap);
+          app.schedule_load_taps(handle.clone());
+        }
+        Err(err) => {
+          app.modal = Some(ModalState::Error {
+            title: format!("{} tap 失败 / Tap operation failed", if add { "添加" } else { "移除" }),
+            message: format!("{}: {}", tap, err),
+          });
+        }
+      }
+      app.should_redraw = true;
+    });
   }
 
   fn request_update_selected(&mut self) {
@@ -827,6 +1060,7 @@ impl App {
           .spawn_job(handle, manager, Operation::Uninstall, package, force)
           .await;
       }
+      PendingAction::Untap { tap } => self.spawn_tap_operation(handle, tap, false),
     }
 
     Ok(())
@@ -870,6 +1104,11 @@ impl App {
       let cache_key = create_manager(&manager, cache.clone(), global)
         .map(|mgr| mgr.cache_key().to_string())
         .unwrap_or_else(|| manager.clone());
+      // 记录操作前的已安装包，完成后写入差异
+      let before = match create_manager(&manager, cache.clone(), global) {
+        Some(mgr) => snapshot_installed(mgr.as_ref(), &cache).await.ok(),
+        None => None,
+      };
       let mut progress: f64 = 0.0;
       let mut ticker = interval(Duration::from_secs(1));
 
@@ -881,8 +1120,10 @@ impl App {
             Operation::Uninstall => {
               // 执行卸载
               mgr.uninstall(&target, force).await?;
-              // 自动清理缓存（忽略错误，不中断卸载）
-              let _ = mgr.clean_cache().await;
+              // 自动清理缓存（忽略错误，不中断卸载）；共享缓存只在显式清理时删除
+              if mgr.clean_cache_on_uninstall() {
+                let _ = mgr.clean_cache().await;
+              }
               Ok(())
             },
             Operation::Install => Ok(()),
@@ -927,6 +1168,13 @@ impl App {
 
       let status_for_completion = status.clone();
 
+      let mut changes = Vec::new();
+      if let (Some(before), Some(mgr)) = (before, create_manager(&manager, cache.clone(), global)) {
+        if let Ok(after) = snapshot_installed(mgr.as_ref(), &cache).await {
+          changes = PackageDiff::between(&manager, &before, &after).lines();
+        }
+      }
+
       let mut app = handle.lock().await;
       if let Some(job) = app.jobs.iter_mut().find(|job| job.id == job_id_for_task) {
         let step = if status == JobStatus::Succeeded {
@@ -944,6 +1192,10 @@ impl App {
         } else {
           job.logs.push("Completed".to_string());
         }
+        if !changes.is_empty() {
+          job.logs.push("变更 / Changes:".to_string());
+          job.logs.extend(changes);
+        }
       }
 
       if let Some(current) = app.current_job.as_mut() {
@@ -1072,3 +1324,10 @@ async fn fetch_packages(
 
   Ok((packages, outdated_map))
 }
+
+// 汇总已知大小的包占用，没有任何大小信息时返回 None
+fn total_size(packages: &[Package]) -> Option<u64> {
+  let mut sizes = packages.iter().filter_map(|pkg| pkg.size).peekable();
+  sizes.peek()?;
+  Some(sizes.sum())
+}
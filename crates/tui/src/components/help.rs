@@ -27,6 +27,7 @@ pub fn render_help(f: &mut Frame, area: Rect) {
       Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
     )]),
     Line::from("  /             进入搜索模式 / Enter search mode"),
+    Line::from("  s             跨包管理器搜索远程仓库 / Search all managers"),
     Line::from("  Esc           退出搜索 / Exit search"),
     Line::from(""),
     Line::from(vec![Span::styled(
@@ -40,6 +41,7 @@ pub fn render_help(f: &mut Frame, area: Rect) {
     Line::from("    Esc         取消菜单 / Cancel menu"),
     Line::from("  u             更新选中的包 / Update selected package"),
     Line::from("  d             卸载选中的包 / Uninstall selected package"),
+    Line::from("  t             管理 brew 的 tap / Manage brew taps"),
     Line::from(""),
     Line::from(vec![Span::styled(
       "任务管理 / Job Management",
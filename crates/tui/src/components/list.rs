@@ -4,11 +4,13 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::Line;
 use ratatui::widgets::{Block, Borders, List, ListItem, ListState, StatefulWidget};
 
+type RenderItem<T> = Box<dyn Fn(&T, bool) -> Line + Send + Sync>;
+
 pub struct ListWidget<'a, T> {
   items: &'a [T],
   selected: usize,
   visible_height: usize,
-  render_item: Box<dyn Fn(&T, bool) -> Line + Send + Sync>,
+  render_item: RenderItem<T>,
   title: String,
 }
 
@@ -34,11 +36,7 @@ impl<'a, T> ListWidget<'a, T> {
       return;
     }
     let visible_height = self.visible_height.max(1);
-    let offset = if self.selected + 1 > visible_height {
-      self.selected + 1 - visible_height
-    } else {
-      0
-    };
+    let offset = (self.selected + 1).saturating_sub(visible_height);
 
     let end = (offset + visible_height).min(self.items.len());
     let visible_items = self.items.iter().skip(offset).take(end - offset);
@@ -70,6 +70,7 @@ async fn main() -> Result<()> {
         app.status_message = format!("加载包列表失败: {}", err);
         app.should_redraw = true;
       }
+      app.schedule_cache_usage(app_handle.clone());
     });
   }
 
@@ -1,8 +1,9 @@
-use boxy_brew::BrewManager;
+use boxy_brew::{BrewManager, BrewScope};
 use boxy_bun::BunManager;
 use boxy_cache::Cache;
 use boxy_cargo::CargoManager;
 use boxy_core::manager::PackageManager;
+use boxy_core::python::PythonScope;
 use boxy_mas::MasManager;
 use boxy_npm::{NpmManager, NpmScope};
 use boxy_pip::PipManager;
@@ -28,8 +29,10 @@ pub fn create_manager(name: &str, cache: Arc<Cache>, global: bool) -> Option<Box
   } else {
     env::current_dir().ok()
   };
+  // TUI 没有显式的本地目录，pip、uv 不自动使用当前目录中的虚拟环境
+  let python_scope = PythonScope::Default;
   match name {
-    "brew" => Some(Box::new(BrewManager::new(cache))),
+    "brew" => Some(Box::new(BrewManager::new(cache, BrewScope::All))),
     "npm" => Some(Box::new(NpmManager::new(
       cache,
       if global { NpmScope::Global } else { NpmScope::Local },
@@ -38,10 +41,10 @@ pub fn create_manager(name: &str, cache: Arc<Cache>, global: bool) -> Option<Box
     "pnpm" => Some(Box::new(PnpmManager::new(cache, global, local_workdir.clone()))),
     "yarn" => Some(Box::new(YarnManager::new(cache, global, local_workdir.clone()))),
     "bun" => Some(Box::new(BunManager::new(cache, global, local_workdir.clone()))),
-    "pip" => Some(Box::new(PipManager::new(cache, global))),
+    "pip" => Some(Box::new(PipManager::new(cache, python_scope))),
     "pipx" => Some(Box::new(PipxManager::new(cache))),
-    "uv" => Some(Box::new(UvManager::new(cache, global))),
-    "cargo" => Some(Box::new(CargoManager::new(cache, global))),
+    "uv" => Some(Box::new(UvManager::new(cache, python_scope, None))),
+    "cargo" => Some(Box::new(CargoManager::new(cache, None))),
     "mas" => Some(Box::new(MasManager::new(cache))),
     _ => None,
   }
@@ -1,6 +1,7 @@
 use crate::app::App;
 use crate::components::list::ListWidget;
 use crate::managers::supports_global;
+use boxy_core::format_size;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
@@ -62,6 +63,16 @@ fn draw_manager_list(f: &mut Frame, app: &mut App, area: Rect) {
       } else {
         String::new()
       };
+      // 磁盘占用：已安装大小与可回收的下载缓存
+      let installed = item
+        .installed_size
+        .map(|size| format!(" {}", format_size(size)))
+        .unwrap_or_default();
+      let cache = item
+        .cache_size
+        .filter(|size| *size > 0)
+        .map(|size| format!(" ♻{}", format_size(size)))
+        .unwrap_or_default();
 
       Line::from(vec![
         Span::styled(status, Style::default().fg(status_color)),
@@ -71,6 +82,8 @@ fn draw_manager_list(f: &mut Frame, app: &mut App, area: Rect) {
         Span::styled(count, Style::default().fg(Color::Cyan)),
         Span::raw(")"),
         Span::styled(outdated, Style::default().fg(Color::Yellow)),
+        Span::styled(installed, Style::default().fg(Color::Gray)),
+        Span::styled(cache, Style::default().fg(Color::DarkGray)),
       ])
     },
   );
@@ -80,6 +80,10 @@ fn draw_package_detail(f: &mut Frame, app: &mut App, area: Rect) {
     if !pkg.version.is_empty() {
       lines.push(Line::from(format!("Version: {}", pkg.version)));
     }
+    if let Some(tap) = &pkg.tap {
+      let kind = if pkg.cask { "cask" } else { "formula" };
+      lines.push(Line::from(format!("Tap: {} ({})", tap, kind)));
+    }
     if let Some(desc) = &pkg.description {
       lines.push(Line::from(""));
       lines.push(Line::from(desc.as_str()));
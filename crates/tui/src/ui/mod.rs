@@ -34,7 +34,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
   // 根据当前状态显示不同的操作提示
   let manager_name = app.selected_manager_name();
-  let supports_global_mode = manager_name.map(|n| supports_global(n)).unwrap_or(false);
+  let supports_global_mode = manager_name.map(supports_global).unwrap_or(false);
   
   // 构建全局/本地切换提示，确保始终显示（即使不支持也显示，但提示不可用）
   let global_hint = if supports_global_mode {
@@ -44,6 +44,8 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     "  [g] 切换模式(仅npm/pnpm/yarn/bun)".to_string()
   };
   
+  let tap_hint = if manager_name == Some("brew") { "  [t] tap" } else { "" };
+
   let usage_text = match app.input_mode {
     crate::app::InputMode::ActionMenu => {
       "[j/k] 选择操作  [Enter] 执行  [Esc] 取消菜单  [q/Ctrl+C] 退出应用".to_string()
@@ -56,7 +58,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
           } else {
             "[j/k] 移动  [h/l] 切换管理器  [/] 搜索  [r] 刷新  [c] 取消"
           };
-          format!("{}{}  [?] 帮助  [q/Ctrl+C] 退出应用", base, global_hint)
+          format!("{}{}{}  [?] 帮助  [q/Ctrl+C] 退出应用", base, global_hint, tap_hint)
         }
         crate::app::View::ManagerDetail(_) => {
           let base = if app.selected_package().is_some() {
@@ -64,7 +66,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
           } else {
             "[j/k] 移动  [/] 搜索  [r] 刷新  [c] 取消"
           };
-          format!("{}{}  [b/Esc] 退回上一级  [q/Ctrl+C] 退出应用", base, global_hint)
+          format!(
+            "{}{}{}  [b/Esc] 退回上一级  [q/Ctrl+C] 退出应用",
+            base, global_hint, tap_hint
+          )
         }
         crate::app::View::PackageDetail(_) => {
           "[b/Esc] 退回上一级  [q/Ctrl+C] 退出应用".to_string()
@@ -25,6 +25,49 @@ pub fn render_modal(f: &mut Frame, area: Rect, modal: &ModalState) {
       }
       (title.clone(), Text::from(body_lines), Color::Yellow)
     }
+    ModalState::Taps {
+      taps,
+      selected,
+      input,
+    } => {
+      let mut lines = Vec::new();
+      if taps.is_empty() {
+        lines.push(Line::from("没有添加任何 tap / No taps"));
+      }
+      for (index, tap) in taps.iter().enumerate() {
+        let style = if index == *selected {
+          Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+          Style::default()
+        };
+        let marker = if index == *selected { "> " } else { "  " };
+        lines.push(Line::from(vec![
+          Span::styled(format!("{}{}", marker, tap.name), style),
+          Span::styled(
+            format!(
+              "  {} formula, {} cask",
+              tap.formula_names.len(),
+              tap.cask_tokens.len()
+            ),
+            Style::default().fg(Color::Gray),
+          ),
+        ]));
+        if let Some(remote) = tap.remote.as_ref().filter(|_| index == *selected) {
+          lines.push(Line::from(Span::styled(
+            format!("    {}", remote),
+            Style::default().fg(Color::Gray),
+          )));
+        }
+      }
+      lines.push(Line::from(""));
+      match input {
+        Some(input) => lines.push(Line::from(format!("添加 tap / Add tap: {}_", input))),
+        None => lines.push(Line::from(
+          "[j/k] 移动  [a] 添加  [d] 移除  [Esc] 关闭 / Move, Add, Remove, Close",
+        )),
+      }
+      ("Homebrew Taps".to_string(), Text::from(lines), Color::Cyan)
+    }
     ModalState::Success { title, message } => {
       let lines = vec![
         Line::from(vec![Span::styled(
@@ -41,7 +84,7 @@ pub fn render_modal(f: &mut Frame, area: Rect, modal: &ModalState) {
     ModalState::Error { title, message } => {
       let lines: Vec<Line> = message
         .split('\n')
-        .map(|s| Line::from(s))
+        .map(Line::from)
         .collect();
       let mut body_lines = vec![
         Line::from(vec![Span::styled(
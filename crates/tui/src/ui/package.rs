@@ -18,6 +18,13 @@ pub fn draw_package_detail(f: &mut Frame, app: &mut App, area: Rect) {
       lines.push(Line::from(format!("Version: {}", pkg.version)));
     }
     lines.push(Line::from(format!("Manager: {}", pkg.manager)));
+    if let Some(tap) = &pkg.tap {
+      let kind = if pkg.cask { "cask" } else { "formula" };
+      lines.push(Line::from(format!("Tap: {} ({})", tap, kind)));
+    }
+    for flag in &pkg.flags {
+      lines.push(Line::from(flag.description()));
+    }
 
     if let Some(desc) = &pkg.description {
       lines.push(Line::from(""));
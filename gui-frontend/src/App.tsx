@@ -14,17 +14,20 @@ import { usePackageStore } from "./store/packageStore";
 import { useTaskStore } from "./store/taskStore";
 import { useI18n } from "./lib/i18n";
 import {
+  addBrewTap,
   getAppLogPath,
   getAppLogs,
   getPackageInfo,
   isTauri,
+  listBrewTaps,
   openExternalUrl,
+  removeBrewTap,
   searchPackages,
   uninstallPackage,
   updateOutdatedPackages,
   updatePackage
 } from "./lib/api";
-import type { Job, ManagerStatus, Package } from "./types";
+import type { BrewTap, Job, ManagerStatus, Package } from "./types";
 
 const NAV_ITEMS = [
   { id: "dashboard", labelKey: "nav.dashboard" },
@@ -51,6 +54,10 @@ const formatSize = (bytes: number): string => {
   return `${size.toFixed(size >= 10 ? 0 : 1)} ${units[unitIndex]}`;
 };
 
+// Homebrew 第三方 tap 中的包，官方 homebrew/* tap 不单独标出
+const thirdPartyTap = (pkg: Package): string | null =>
+  pkg.tap && !pkg.tap.startsWith("homebrew/") ? pkg.tap : null;
+
 const isGitLink = (url: string): boolean => {
   const lower = url.toLowerCase();
   return (
@@ -1138,6 +1145,12 @@ export const App: React.FC = () => {
                       License: {selectedPackageInfo.license}
                     </span>
                   )}
+                  {selectedPackageInfo?.tap && (
+                    <span className="right-rail-text">
+                      Tap: {selectedPackageInfo.tap}
+                      {selectedPackageInfo.cask ? " · cask" : ""}
+                    </span>
+                  )}
                   {typeof selectedPackageInfo?.size === "number" && (
                     <span className="right-rail-text">
                       大小: {formatSize(selectedPackageInfo.size)}
@@ -1528,6 +1541,8 @@ const ManagerView: React.FC<{
         </div>
       </div>
 
+      {selectedManager === "brew" && <BrewTapsCard />}
+
       <div className="card">
         <div className="card-header">
           <h2>Packages</h2>
@@ -1580,6 +1595,7 @@ const ManagerView: React.FC<{
                 <span className="package-name">{pkg.name}</span>
                 <span className="package-version">
                   {pkg.version} · {pkg.manager}
+                  {thirdPartyTap(pkg) ? ` · ${thirdPartyTap(pkg)}` : ""}
                   {typeof pkg.size === "number" ? ` · ${formatSize(pkg.size)}` : ""}
                 </span>
               </div>
@@ -1621,6 +1637,7 @@ const ManagerView: React.FC<{
                       </button>
                     </div>
                   )}
+                {pkg.cask && <span className="badge">Cask</span>}
                 {pkg.outdated && <span className="badge badge-warn">Outdated</span>}
                 {selectedPackage?.name === pkg.name &&
                   selectedPackage?.manager === pkg.manager && (
@@ -1659,6 +1676,159 @@ const ManagerView: React.FC<{
   );
 };
 
+const BrewTapsCard: React.FC = () => {
+  const [taps, setTaps] = useState<BrewTap[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [nameInput, setNameInput] = useState("");
+  const [urlInput, setUrlInput] = useState("");
+  const [actionKey, setActionKey] = useState<string | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const loadTaps = useCallback(async () => {
+    setLoading(true);
+    try {
+      setTaps(await listBrewTaps());
+    } catch (error) {
+      console.error("Load brew taps failed:", error);
+      setMessage(`读取 tap 失败：${String(error)}`);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    void loadTaps();
+  }, [loadTaps]);
+
+  const onAddTap = async () => {
+    const name = nameInput.trim();
+    if (!name || actionKey) {
+      return;
+    }
+    setActionKey(`add:${name}`);
+    setMessage(null);
+    try {
+      await addBrewTap(name, urlInput.trim() || undefined);
+      setNameInput("");
+      setUrlInput("");
+      setMessage(`已添加 tap ${name}`);
+      await loadTaps();
+    } catch (error) {
+      console.error("Add brew tap failed:", error);
+      setMessage(`添加 tap 失败：${String(error)}`);
+    } finally {
+      setActionKey(null);
+    }
+  };
+
+  const onRemoveTap = async (tap: BrewTap) => {
+    if (actionKey) {
+      return;
+    }
+    if (!window.confirm(`移除 tap ${tap.name}？`)) {
+      return;
+    }
+    setActionKey(`remove:${tap.name}`);
+    setMessage(null);
+    try {
+      await removeBrewTap(tap.name);
+      setMessage(`已移除 tap ${tap.name}`);
+      await loadTaps();
+    } catch (error) {
+      // 仍有已安装的包时 brew untap 会拒绝，确认后强制移除
+      const forced = window.confirm(
+        `移除 tap ${tap.name} 失败：${String(error)}\n是否强制移除？`
+      );
+      if (!forced) {
+        setMessage(`移除 tap 失败：${String(error)}`);
+        return;
+      }
+      try {
+        await removeBrewTap(tap.name, true);
+        setMessage(`已强制移除 tap ${tap.name}`);
+        await loadTaps();
+      } catch (forceError) {
+        console.error("Remove brew tap failed:", forceError);
+        setMessage(`移除 tap 失败：${String(forceError)}`);
+      }
+    } finally {
+      setActionKey(null);
+    }
+  };
+
+  return (
+    <div className="card">
+      <div className="card-header">
+        <h2>Taps</h2>
+        <div className="control-buttons">
+          <button
+            className="chip"
+            type="button"
+            onClick={() => void loadTaps()}
+            disabled={loading}
+          >
+            {loading ? "加载中..." : "刷新"}
+          </button>
+        </div>
+      </div>
+      <div className="manager-scope-input">
+        <input
+          className="scope-input"
+          type="text"
+          value={nameInput}
+          onChange={(event) => setNameInput(event.target.value)}
+          placeholder="tap 名称，例如 hashicorp/tap"
+        />
+        <input
+          className="scope-input"
+          type="text"
+          value={urlInput}
+          onChange={(event) => setUrlInput(event.target.value)}
+          placeholder="可选：自定义 git 地址"
+        />
+        <button
+          className="chip"
+          type="button"
+          onClick={() => void onAddTap()}
+          disabled={!nameInput.trim() || actionKey !== null}
+        >
+          {actionKey?.startsWith("add:") ? "添加中..." : "添加"}
+        </button>
+      </div>
+      <div className="package-list">
+        {taps.map((tap) => (
+          <div key={tap.name} className="package-item">
+            <div className="package-meta">
+              <span className="package-name">{tap.name}</span>
+              <span className="package-version">
+                {tap.formula_names.length} formula · {tap.cask_tokens.length} cask
+                {tap.remote ? ` · ${tap.remote}` : ""}
+                {tap.last_commit ? ` · ${tap.last_commit}` : ""}
+              </span>
+            </div>
+            <div className="package-actions">
+              {tap.official && <span className="badge">Official</span>}
+              {tap.custom_remote && <span className="badge">Custom remote</span>}
+              <button
+                className="chip chip-danger"
+                type="button"
+                onClick={() => void onRemoveTap(tap)}
+                disabled={actionKey !== null}
+              >
+                {actionKey === `remove:${tap.name}` ? "移除中..." : "移除"}
+              </button>
+            </div>
+          </div>
+        ))}
+        {!loading && taps.length === 0 && (
+          <div className="control-hint">未添加任何 tap</div>
+        )}
+        {message && <div className="control-hint">{message}</div>}
+      </div>
+    </div>
+  );
+};
+
 const TasksView: React.FC<{
   tasks: Job[];
   onCancelTask: (taskId: string) => void;
@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Job, ManagerStatus, Package } from "../types";
+import type { BrewTap, Job, ManagerStatus, Package } from "../types";
 import {
+  mockBrewTaps,
   mockManagers,
   mockPackages,
   clearMockTasks,
@@ -83,6 +84,27 @@ export async function getPackageInfo(
   });
 }
 
+export async function listBrewTaps(): Promise<BrewTap[]> {
+  if (!isTauri()) {
+    return mockBrewTaps;
+  }
+  return invoke<BrewTap[]>("list_brew_taps");
+}
+
+export async function addBrewTap(name: string, url?: string): Promise<void> {
+  if (!isTauri()) {
+    return;
+  }
+  await invoke("add_brew_tap", { name, url });
+}
+
+export async function removeBrewTap(name: string, force = false): Promise<void> {
+  if (!isTauri()) {
+    return;
+  }
+  await invoke("remove_brew_tap", { name, force });
+}
+
 export async function installPackage(
   manager: string,
   packageName: string,
@@ -1,4 +1,4 @@
-import type { Job, ManagerStatus, Package } from "../types";
+import type { BrewTap, Job, ManagerStatus, Package } from "../types";
 
 export const mockManagers: ManagerStatus[] = [
   {
@@ -46,7 +46,8 @@ export const mockPackages: Package[] = [
     description: "JavaScript runtime",
     size: 52428800,
     outdated: true,
-    latest_version: "20.11.0"
+    latest_version: "20.11.0",
+    tap: "homebrew/core"
   },
   {
     name: "npm",
@@ -70,7 +71,29 @@ export const mockPackages: Package[] = [
     manager: "brew",
     description: "Python language",
     size: 73400320,
-    outdated: false
+    outdated: false,
+    tap: "homebrew/core"
+  },
+  {
+    name: "terraform",
+    version: "1.9.8",
+    manager: "brew",
+    description: "Terraform",
+    size: 94371840,
+    outdated: false,
+    tap: "hashicorp/tap"
+  }
+];
+
+export const mockBrewTaps: BrewTap[] = [
+  {
+    name: "hashicorp/tap",
+    remote: "https://github.com/hashicorp/homebrew-tap",
+    custom_remote: false,
+    official: false,
+    formula_names: ["hashicorp/tap/terraform", "hashicorp/tap/vault"],
+    cask_tokens: [],
+    last_commit: "3 days ago"
   }
 ];
 
@@ -4,4 +4,17 @@ export type ManagerStatus = {
   available: boolean;
   package_count: number;
   outdated_count: number;
+  installed_size?: number;
+  cache_size?: number;
+};
+
+export type BrewTap = {
+  name: string;
+  remote?: string | null;
+  custom_remote: boolean;
+  official: boolean;
+  path?: string | null;
+  formula_names: string[];
+  cask_tokens: string[];
+  last_commit?: string | null;
 };
@@ -10,4 +10,6 @@ export type Package = {
   size?: number | null;
   outdated: boolean;
   latest_version?: string | null;
+  tap?: string | null;
+  cask?: boolean;
 };